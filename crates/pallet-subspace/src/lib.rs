@@ -51,11 +51,11 @@ use sp_consensus_subspace::consensus::{is_proof_of_time_valid, verify_solution};
 use sp_consensus_subspace::digests::CompatibleDigestItem;
 use sp_consensus_subspace::offence::{OffenceDetails, OffenceError, OnOffenceHandler};
 use sp_consensus_subspace::{
-    EquivocationProof, FarmerPublicKey, FarmerSignature, PotParameters, PotParametersChange,
-    SignedVote, Vote, WrappedPotOutput,
+    EquivocationProof, FarmerPublicKey, FarmerSignature, NetworkSpaceEstimate, PotParameters,
+    PotParametersChange, SignedVote, SolutionRangeHistory, Vote, WrappedPotOutput,
 };
 use sp_runtime::generic::DigestItem;
-use sp_runtime::traits::{BlockNumberProvider, CheckedSub, Hash, One, Zero};
+use sp_runtime::traits::{BlockNumberProvider, CheckedSub, Hash, One, UniqueSaturatedInto, Zero};
 use sp_runtime::transaction_validity::{
     InvalidTransaction, TransactionPriority, TransactionSource, TransactionValidity,
     TransactionValidityError, ValidTransaction,
@@ -65,9 +65,9 @@ use sp_std::collections::btree_map::BTreeMap;
 use sp_std::prelude::*;
 use subspace_core_primitives::crypto::Scalar;
 use subspace_core_primitives::{
-    ArchivedHistorySegment, BlockHash, HistorySize, PieceOffset, PublicKey, RewardSignature,
-    SectorId, SectorIndex, SegmentHeader, SegmentIndex, SlotNumber, SolutionRange,
-    REWARD_SIGNING_CONTEXT,
+    solution_range_to_sectors, ArchivedHistorySegment, BlockHash, HistorySize, Piece, PieceOffset,
+    PublicKey, RewardSignature, SectorId, SectorIndex, SegmentHeader, SegmentIndex, SlotNumber,
+    SolutionRange, REWARD_SIGNING_CONTEXT,
 };
 use subspace_verification::{
     check_reward_signature, derive_next_solution_range, derive_pot_entropy, PieceCheckParams,
@@ -111,7 +111,9 @@ pub mod pallet {
     use sp_consensus_slots::Slot;
     use sp_consensus_subspace::digests::CompatibleDigestItem;
     use sp_consensus_subspace::inherents::{InherentError, InherentType, INHERENT_IDENTIFIER};
-    use sp_consensus_subspace::{EquivocationProof, FarmerPublicKey, FarmerSignature, SignedVote};
+    use sp_consensus_subspace::{
+        EquivocationProof, EraSolutionRanges, FarmerPublicKey, FarmerSignature, SignedVote,
+    };
     use sp_runtime::DigestItem;
     use sp_std::collections::btree_map::BTreeMap;
     use sp_std::num::NonZeroU32;
@@ -227,6 +229,9 @@ pub mod pallet {
         type MaxPiecesInSector: Get<u16>;
 
         type ShouldAdjustSolutionRange: Get<bool>;
+        /// Number of most recent eras for which solution ranges are kept in history.
+        #[pallet::constant]
+        type SolutionRangeHistoryLength: Get<u32>;
         /// Subspace requires some logic to be triggered on every block to query for whether an era
         /// has ended and to perform the transition to the next era.
         ///
@@ -405,6 +410,14 @@ pub mod pallet {
     #[pallet::storage]
     pub type EraStartSlot<T> = StorageValue<_, Slot>;
 
+    /// Solution ranges of the most recent eras, oldest first.
+    #[pallet::storage]
+    pub(super) type PastEraSolutionRanges<T: Config> = StorageValue<
+        _,
+        BoundedVec<EraSolutionRanges<BlockNumberFor<T>>, T::SolutionRangeHistoryLength>,
+        ValueQuery,
+    >;
+
     /// A set of blocked farmers keyed by their public key.
     #[pallet::storage]
     pub(super) type BlockList<T> = StorageMap<_, Twox64Concat, FarmerPublicKey, ()>;
@@ -763,6 +776,28 @@ impl<T: Config> Pallet<T> {
         });
 
         EraStartSlot::<T>::put(current_slot);
+
+        let solution_ranges = SolutionRanges::<T>::get();
+        let era_solution_ranges = EraSolutionRanges {
+            start_block: frame_system::Pallet::<T>::current_block_number() + One::one(),
+            start_slot: current_slot,
+            solution_range: solution_ranges
+                .next
+                .expect("Next solution range was set above; qed"),
+            voting_solution_range: solution_ranges
+                .voting_next
+                .expect("Next voting solution range was set above; qed"),
+            history_size: Self::history_size(),
+        };
+        PastEraSolutionRanges::<T>::mutate(|past_era_solution_ranges| {
+            let max_length = T::SolutionRangeHistoryLength::get() as usize;
+            if past_era_solution_ranges.len() >= max_length && !past_era_solution_ranges.is_empty()
+            {
+                past_era_solution_ranges.remove(0);
+            }
+            // Only fails if history length is zero, in which case nothing should be stored anyway
+            let _ = past_era_solution_ranges.try_push(era_solution_ranges);
+        });
     }
 
     fn do_initialize(block_number: BlockNumberFor<T>) {
@@ -1188,6 +1223,44 @@ impl<T: Config> Pallet<T> {
 
         u64::from(archived_segments) * ArchivedHistorySegment::SIZE as u64
     }
+
+    /// Space pledged to the network, in bytes, that corresponds to provided solution range.
+    pub fn solution_range_to_space(solution_range: SolutionRange) -> u128 {
+        let max_pieces_in_sector = T::MaxPiecesInSector::get();
+        let sectors = solution_range_to_sectors(
+            solution_range.max(1),
+            T::SlotProbability::get(),
+            max_pieces_in_sector,
+        );
+
+        u128::from(sectors) * u128::from(max_pieces_in_sector) * Piece::SIZE as u128
+    }
+
+    /// History of solution ranges of recent eras and network space estimated from them.
+    pub fn solution_range_history() -> SolutionRangeHistory<BlockNumberFor<T>> {
+        // Solution range is derived from the time it took to produce blocks of the whole era
+        let era_duration = T::EraDuration::get().unique_saturated_into();
+        let network_space_estimate = |solution_range| {
+            NetworkSpaceEstimate::new(Self::solution_range_to_space(solution_range), era_duration)
+        };
+        let solution_ranges = SolutionRanges::<T>::get();
+
+        SolutionRangeHistory {
+            eras: PastEraSolutionRanges::<T>::get()
+                .into_iter()
+                .map(|era_solution_ranges| {
+                    (
+                        era_solution_ranges,
+                        network_space_estimate(era_solution_ranges.solution_range),
+                    )
+                })
+                .collect(),
+            solution_ranges,
+            solution_range_adjustment_enabled: ShouldAdjustSolutionRange::<T>::get(),
+            network_space: network_space_estimate(solution_ranges.current),
+            history_size: Self::history_size(),
+        }
+    }
 }
 
 impl<T> Pallet<T>
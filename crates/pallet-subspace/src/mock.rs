@@ -194,6 +194,7 @@ impl Config for Test {
     type ExpectedVotesPerBlock = ExpectedVotesPerBlock;
     type MaxPiecesInSector = ConstU16<{ MAX_PIECES_IN_SECTOR }>;
     type ShouldAdjustSolutionRange = ShouldAdjustSolutionRange;
+    type SolutionRangeHistoryLength = ConstU32<2>;
     type EraChangeTrigger = NormalEraChange;
 
     type HandleEquivocation = EquivocationHandler<OffencesSubspace, ReportLongevity>;
@@ -34,7 +34,9 @@ use frame_system::{EventRecord, Phase};
 use rand::prelude::*;
 use schnorrkel::Keypair;
 use sp_consensus_slots::Slot;
-use sp_consensus_subspace::{FarmerPublicKey, FarmerSignature, PotExtension, SolutionRanges};
+use sp_consensus_subspace::{
+    FarmerPublicKey, FarmerSignature, NetworkSpaceEstimate, PotExtension, SolutionRanges,
+};
 use sp_core::crypto::UncheckedFrom;
 use sp_runtime::traits::{BlockNumberProvider, Header};
 use sp_runtime::transaction_validity::{
@@ -45,7 +47,7 @@ use std::assert_matches::assert_matches;
 use std::collections::BTreeMap;
 use std::sync::{Arc, Mutex};
 use subspace_core_primitives::crypto::Scalar;
-use subspace_core_primitives::{PieceOffset, PotOutput, SegmentIndex, SolutionRange};
+use subspace_core_primitives::{Piece, PieceOffset, PotOutput, SegmentIndex, SolutionRange};
 use subspace_runtime_primitives::{FindBlockRewardAddress, FindVotingRewardAddresses};
 
 #[test]
@@ -131,6 +133,80 @@ fn can_update_solution_range_on_era_change() {
     })
 }
 
+#[test]
+fn solution_range_history_is_recorded_on_era_change() {
+    new_test_ext(allow_all_pot_extension()).execute_with(|| {
+        let keypair = Keypair::generate();
+
+        assert_eq!(<Test as Config>::EraDuration::get(), 4);
+        assert_eq!(<Test as Config>::SolutionRangeHistoryLength::get(), 2);
+        assert_ok!(Subspace::enable_solution_range_adjustment(
+            RuntimeOrigin::root(),
+            None,
+            None
+        ));
+
+        progress_to_block(&keypair, 3, 1);
+        let solution_range_history = Subspace::solution_range_history();
+        assert!(solution_range_history.eras.is_empty());
+        assert!(solution_range_history.solution_range_adjustment_enabled);
+        assert_eq!(
+            solution_range_history.network_space.estimate,
+            Subspace::solution_range_to_space(INITIAL_SOLUTION_RANGE)
+        );
+
+        // Era edge
+        progress_to_block(&keypair, 4, 1);
+        let next_solution_ranges = Subspace::solution_ranges();
+        let solution_range_history = Subspace::solution_range_history();
+        assert_eq!(solution_range_history.eras.len(), 1);
+        let (era_solution_ranges, network_space) = solution_range_history.eras[0];
+        assert_eq!(era_solution_ranges.start_block, 5);
+        assert_eq!(era_solution_ranges.start_slot, Subspace::current_slot());
+        assert_eq!(
+            Some(era_solution_ranges.solution_range),
+            next_solution_ranges.next
+        );
+        assert_eq!(
+            Some(era_solution_ranges.voting_solution_range),
+            next_solution_ranges.voting_next
+        );
+        // Solution range decreased, so estimated space must increase
+        assert!(network_space.estimate > solution_range_history.network_space.estimate);
+        assert!(network_space.lower_bound < network_space.estimate);
+        assert!(network_space.upper_bound > network_space.estimate);
+
+        // Only the most recent eras are kept
+        progress_to_block(&keypair, 8, 1);
+        progress_to_block(&keypair, 12, 1);
+        let eras = Subspace::solution_range_history().eras;
+        assert_eq!(eras.len(), 2);
+        assert_eq!(eras[0].0.start_block, 9);
+        assert_eq!(eras[1].0.start_block, 13);
+    })
+}
+
+#[test]
+fn network_space_estimate_confidence_interval() {
+    // Relative error is `2 / sqrt(blocks)`
+    let estimate = NetworkSpaceEstimate::new(1_000_000, 100);
+    assert_eq!(estimate.estimate, 1_000_000);
+    assert_eq!(estimate.lower_bound, 800_000);
+    assert_eq!(estimate.upper_bound, 1_200_000);
+
+    // Estimate from a single block is very imprecise
+    let estimate = NetworkSpaceEstimate::new(1_000_000, 1);
+    assert_eq!(estimate.lower_bound, 0);
+    assert_eq!(estimate.upper_bound, 3_000_000);
+
+    // Halving solution range doubles estimated space, up to sector granularity
+    let space = Subspace::solution_range_to_space(INITIAL_SOLUTION_RANGE);
+    let doubled_space = Subspace::solution_range_to_space(INITIAL_SOLUTION_RANGE / 2);
+    let sector_space = u128::from(<Test as Config>::MaxPiecesInSector::get()) * Piece::SIZE as u128;
+    assert!(space > 0);
+    assert!(doubled_space.abs_diff(space * 2) <= sector_space * 2);
+}
+
 #[test]
 fn can_override_solution_range_update() {
     new_test_ext(allow_all_pot_extension()).execute_with(|| {
@@ -19,8 +19,11 @@
 
 #![feature(try_blocks)]
 
+#[cfg(test)]
+mod tests;
+
 use futures::channel::mpsc;
-use futures::{future, FutureExt, StreamExt};
+use futures::{future, stream, FutureExt, StreamExt};
 use jsonrpsee::core::{async_trait, Error as JsonRpseeError, RpcResult};
 use jsonrpsee::proc_macros::rpc;
 use jsonrpsee::types::{SubscriptionEmptyError, SubscriptionResult};
@@ -32,22 +35,25 @@ use sc_client_api::{AuxStore, BlockBackend};
 use sc_consensus_subspace::archiver::{
     recreate_genesis_segment, ArchivedSegmentNotification, SegmentHeadersStore,
 };
+use sc_consensus_subspace::equivocation::{EquivocationEvidence, EquivocationEvidenceStore};
 use sc_consensus_subspace::notification::SubspaceNotificationStream;
+use sc_consensus_subspace::object_mappings::{ObjectMappingsIndex, ObjectMappingsNotification};
 use sc_consensus_subspace::slot_worker::{
-    NewSlotNotification, RewardSigningNotification, SubspaceSyncOracle,
+    NewSlotNotification, RewardSigningNotification, SolutionOutcome, SolutionOutcomeNotification,
+    SolutionRejectionReason, SubspaceSyncOracle,
 };
 use sc_rpc::{DenyUnsafe, SubscriptionTaskExecutor};
-use sc_utils::mpsc::TracingUnboundedSender;
-use sp_api::{ApiError, ProvideRuntimeApi};
+use sc_utils::mpsc::{tracing_unbounded, TracingUnboundedSender};
+use sp_api::{ApiError, ApiExt, ProvideRuntimeApi};
 use sp_blockchain::HeaderBackend;
 use sp_consensus::SyncOracle;
 use sp_consensus_subspace::{
     ChainConstants, FarmerPublicKey, FarmerSignature, SubspaceApi as SubspaceRuntimeApi,
 };
 use sp_core::crypto::ByteArray;
-use sp_core::H256;
+use sp_core::{Bytes, H256};
 use sp_objects::ObjectsApi;
-use sp_runtime::traits::Block as BlockT;
+use sp_runtime::traits::{Block as BlockT, Header as HeaderT, UniqueSaturatedInto};
 use std::collections::hash_map::Entry;
 use std::collections::HashMap;
 use std::marker::PhantomData;
@@ -57,14 +63,19 @@ use std::sync::{Arc, Weak};
 use std::time::Duration;
 use subspace_archiving::archiver::NewArchivedSegment;
 use subspace_core_primitives::crypto::kzg::Kzg;
+use subspace_core_primitives::objects::GlobalObject;
 use subspace_core_primitives::{
-    BlockHash, HistorySize, PieceIndex, PublicKey, SegmentHeader, SegmentIndex, SlotNumber,
-    Solution,
+    BlockHash, BlockNumber, HistorySize, PieceIndex, PublicKey, SegmentHeader, SegmentIndex,
+    SlotNumber, Solution,
 };
 use subspace_farmer_components::FarmerProtocolInfo;
 use subspace_networking::libp2p::Multiaddr;
+use subspace_networking::utils::object_fetcher::{ObjectFetcher, ObjectPieceGetter};
 use subspace_rpc_primitives::{
-    FarmerAppInfo, RewardSignatureResponse, RewardSigningInfo, SlotInfo, SolutionResponse,
+    EquivocationEvidenceInfo, EraSolutionRangesInfo, FarmerAppInfo, NetworkSpaceEstimate,
+    ObjectMappingItem, ObjectMappingsInfo, RewardSignatureResponse, RewardSigningInfo, SlotInfo,
+    SolutionOutcome as RpcSolutionOutcome, SolutionOutcomeInfo, SolutionRangeHistoryInfo,
+    SolutionRejectionReason as RpcSolutionRejectionReason, SolutionResponse,
     MAX_SEGMENT_HEADERS_PER_REQUEST,
 };
 use tracing::{debug, error, warn};
@@ -81,6 +92,10 @@ pub trait SubspaceRpcApi {
     #[method(name = "subspace_getFarmerAppInfo")]
     fn get_farmer_app_info(&self) -> RpcResult<FarmerAppInfo>;
 
+    /// Get history of solution ranges of recent eras along with network space estimated from them
+    #[method(name = "subspace_solutionRangeHistory")]
+    fn solution_range_history(&self) -> RpcResult<SolutionRangeHistoryInfo>;
+
     #[method(name = "subspace_submitSolutionResponse")]
     fn submit_solution_response(&self, solution_response: SolutionResponse) -> RpcResult<()>;
 
@@ -100,6 +115,15 @@ pub trait SubspaceRpcApi {
     )]
     fn subscribe_reward_signing(&self);
 
+    /// Outcomes of submitted solutions subscription, only outcomes of solutions with provided
+    /// public keys are sent unless the list is empty
+    #[subscription(
+        name = "subspace_subscribeSolutionOutcomes" => "subspace_solution_outcome",
+        unsubscribe = "subspace_unsubscribeSolutionOutcomes",
+        item = SolutionOutcomeInfo,
+    )]
+    fn subscribe_solution_outcomes(&self, public_keys: Vec<PublicKey>);
+
     #[method(name = "subspace_submitRewardSignature")]
     fn submit_reward_signature(&self, reward_signature: RewardSignatureResponse) -> RpcResult<()>;
 
@@ -128,6 +152,31 @@ pub trait SubspaceRpcApi {
 
     #[method(name = "subspace_lastSegmentHeaders")]
     async fn last_segment_headers(&self, limit: u64) -> RpcResult<Vec<Option<SegmentHeader>>>;
+
+    /// Fetch object from archived history by its mapping, object is verified against its hash and
+    /// returned as hex-encoded bytes
+    #[method(name = "subspace_fetchObject")]
+    async fn fetch_object(
+        &self,
+        object_hash: H256,
+        global_object: GlobalObject,
+    ) -> RpcResult<Bytes>;
+
+    /// Find location of the object in archived history by its hash
+    #[method(name = "subspace_getObjectMapping")]
+    fn get_object_mapping(&self, object_hash: H256) -> RpcResult<Option<GlobalObject>>;
+
+    /// Evidence of equivocations detected by the node, from the oldest to the newest
+    #[method(name = "subspace_equivocationEvidence")]
+    fn equivocation_evidence(&self) -> RpcResult<Vec<EquivocationEvidenceInfo>>;
+
+    /// Object mappings of newly indexed segments subscription
+    #[subscription(
+        name = "subspace_subscribeObjectMappings" => "subspace_object_mappings",
+        unsubscribe = "subspace_unsubscribeObjectMappings",
+        item = ObjectMappingsInfo,
+    )]
+    fn subscribe_object_mappings(&self);
 }
 
 #[derive(Default)]
@@ -176,6 +225,9 @@ where
     pub new_slot_notification_stream: SubspaceNotificationStream<NewSlotNotification>,
     /// Reward signing notification stream
     pub reward_signing_notification_stream: SubspaceNotificationStream<RewardSigningNotification>,
+    /// Solution outcome notification stream
+    pub solution_outcome_notification_stream:
+        SubspaceNotificationStream<SolutionOutcomeNotification>,
     /// Archived segment notification stream
     pub archived_segment_notification_stream:
         SubspaceNotificationStream<ArchivedSegmentNotification>,
@@ -189,6 +241,12 @@ where
     pub deny_unsafe: DenyUnsafe,
     /// Kzg instance
     pub kzg: Kzg,
+    /// Object fetcher used for `subspace_fetchObject`, `None` disables object fetching
+    pub object_fetcher: Option<Arc<ObjectFetcher<Arc<dyn ObjectPieceGetter + Send + Sync>>>>,
+    /// Object mappings index used for object mapping queries, `None` disables them
+    pub object_mappings_index: Option<ObjectMappingsIndex<AS>>,
+    /// Store of detected equivocations, `None` if equivocation reporting is disabled
+    pub equivocation_evidence_store: Option<EquivocationEvidenceStore<AS>>,
 }
 
 /// Implements the [`SubspaceRpcApiServer`] trait for interacting with Subspace.
@@ -201,11 +259,15 @@ where
     subscription_executor: SubscriptionTaskExecutor,
     new_slot_notification_stream: SubspaceNotificationStream<NewSlotNotification>,
     reward_signing_notification_stream: SubspaceNotificationStream<RewardSigningNotification>,
+    solution_outcome_notification_stream: SubspaceNotificationStream<SolutionOutcomeNotification>,
     archived_segment_notification_stream: SubspaceNotificationStream<ArchivedSegmentNotification>,
     #[allow(clippy::type_complexity)]
     solution_response_senders:
         Arc<Mutex<LruCache<SlotNumber, mpsc::Sender<Solution<PublicKey, PublicKey>>>>>,
     reward_signature_senders: Arc<Mutex<BlockSignatureSenders>>,
+    /// Senders of solution outcomes subscriptions for solutions rejected by RPC itself, before
+    /// they reach slot worker
+    solution_outcome_senders: Arc<Mutex<Vec<TracingUnboundedSender<SolutionOutcomeInfo>>>>,
     dsn_bootstrap_nodes: Vec<Multiaddr>,
     segment_headers_store: SegmentHeadersStore<AS>,
     cached_archived_segment: Arc<Mutex<Option<CachedArchivedSegment>>>,
@@ -217,6 +279,9 @@ where
     chain_constants: ChainConstants,
     max_pieces_in_sector: u16,
     kzg: Kzg,
+    object_fetcher: Option<Arc<ObjectFetcher<Arc<dyn ObjectPieceGetter + Send + Sync>>>>,
+    object_mappings_index: Option<ObjectMappingsIndex<AS>>,
+    equivocation_evidence_store: Option<EquivocationEvidenceStore<AS>>,
     deny_unsafe: DenyUnsafe,
     _block: PhantomData<Block>,
 }
@@ -259,11 +324,13 @@ where
             subscription_executor: config.subscription_executor,
             new_slot_notification_stream: config.new_slot_notification_stream,
             reward_signing_notification_stream: config.reward_signing_notification_stream,
+            solution_outcome_notification_stream: config.solution_outcome_notification_stream,
             archived_segment_notification_stream: config.archived_segment_notification_stream,
             solution_response_senders: Arc::new(Mutex::new(LruCache::new(
                 solution_response_senders_capacity,
             ))),
             reward_signature_senders: Arc::default(),
+            solution_outcome_senders: Arc::default(),
             dsn_bootstrap_nodes: config.dsn_bootstrap_nodes,
             segment_headers_store: config.segment_headers_store,
             cached_archived_segment: Arc::default(),
@@ -274,6 +341,9 @@ where
             chain_constants,
             max_pieces_in_sector,
             kzg: config.kzg,
+            object_fetcher: config.object_fetcher,
+            object_mappings_index: config.object_mappings_index,
+            equivocation_evidence_store: config.equivocation_evidence_store,
             deny_unsafe: config.deny_unsafe,
             _block: PhantomData,
         })
@@ -290,7 +360,7 @@ where
         + Send
         + Sync
         + 'static,
-    Client::Api: ObjectsApi<Block>,
+    Client::Api: SubspaceRuntimeApi<Block, FarmerPublicKey> + ObjectsApi<Block>,
     SO: SyncOracle + Send + Sync + Clone + 'static,
     AS: AuxStore + Send + Sync + 'static,
 {
@@ -328,23 +398,93 @@ where
         })
     }
 
+    fn solution_range_history(&self) -> RpcResult<SolutionRangeHistoryInfo> {
+        let best_hash = self.client.info().best_hash;
+        let runtime_api = self.client.runtime_api();
+        let solution_range_history: Result<_, ApiError> = try {
+            let subspace_api_version = runtime_api
+                .api_version::<dyn SubspaceRuntimeApi<Block, FarmerPublicKey>>(best_hash)?
+                // Safe to return default version as 1 since there will always be version 1
+                .unwrap_or(1);
+
+            if subspace_api_version < 2 {
+                return Err(JsonRpseeError::Custom(
+                    "Solution range history is not supported by the runtime".to_string(),
+                ));
+            }
+
+            runtime_api.solution_range_history(best_hash)?
+        };
+        let solution_range_history = solution_range_history.map_err(|error| {
+            error!("Failed to get data from runtime API: {}", error);
+            JsonRpseeError::Custom("Internal error".to_string())
+        })?;
+
+        Ok(SolutionRangeHistoryInfo {
+            eras: solution_range_history
+                .eras
+                .into_iter()
+                .map(
+                    |(era_solution_ranges, network_space)| EraSolutionRangesInfo {
+                        start_block: era_solution_ranges.start_block.unique_saturated_into(),
+                        start_slot: era_solution_ranges.start_slot.into(),
+                        solution_range: era_solution_ranges.solution_range,
+                        voting_solution_range: era_solution_ranges.voting_solution_range,
+                        history_size: era_solution_ranges.history_size,
+                        network_space: network_space_estimate_to_rpc(network_space),
+                    },
+                )
+                .collect(),
+            solution_range: solution_range_history.solution_ranges.current,
+            voting_solution_range: solution_range_history.solution_ranges.voting_current,
+            solution_range_adjustment_enabled: solution_range_history
+                .solution_range_adjustment_enabled,
+            network_space: network_space_estimate_to_rpc(solution_range_history.network_space),
+            history_size: solution_range_history.history_size,
+        })
+    }
+
     fn submit_solution_response(&self, solution_response: SolutionResponse) -> RpcResult<()> {
         self.deny_unsafe.check_if_safe()?;
 
         let slot = solution_response.slot_number;
         let mut solution_response_senders = self.solution_response_senders.lock();
 
-        let success = solution_response_senders
-            .peek_mut(&slot)
-            .and_then(|sender| sender.try_send(solution_response.solution).ok())
-            .is_some();
+        let result = match solution_response_senders.peek_mut(&slot) {
+            Some(sender) => sender
+                .try_send(solution_response.solution)
+                .map_err(|error| {
+                    let reason = if error.is_full() {
+                        RpcSolutionRejectionReason::TooManySolutions
+                    } else {
+                        RpcSolutionRejectionReason::TooLate
+                    };
+                    (error.into_inner(), reason)
+                }),
+            None => Err((
+                solution_response.solution,
+                RpcSolutionRejectionReason::UnknownSlot,
+            )),
+        };
 
-        if !success {
+        if let Err((solution, reason)) = result {
             warn!(
                 %slot,
+                ?reason,
                 "Solution was ignored, likely because farmer was too slow"
             );
 
+            let solution_outcome_info = SolutionOutcomeInfo {
+                slot_number: slot,
+                public_key: solution.public_key.into(),
+                sector_index: solution.sector_index,
+                piece_offset: solution.piece_offset,
+                outcome: RpcSolutionOutcome::Rejected { reason },
+            };
+            self.solution_outcome_senders
+                .lock()
+                .retain(|sender| sender.unbounded_send(solution_outcome_info.clone()).is_ok());
+
             return Err(JsonRpseeError::Custom("Solution was ignored".to_string()));
         }
 
@@ -539,6 +679,61 @@ where
         Ok(())
     }
 
+    fn subscribe_solution_outcomes(
+        &self,
+        mut sink: SubscriptionSink,
+        public_keys: Vec<PublicKey>,
+    ) -> SubscriptionResult {
+        // Solutions are only accepted when unsafe APIs are allowed
+        self.deny_unsafe
+            .check_if_safe()
+            .map_err(|_error| SubscriptionEmptyError)?;
+
+        let slot_worker_stream = self.solution_outcome_notification_stream.subscribe().map(
+            |solution_outcome_notification| {
+                let SolutionOutcomeNotification {
+                    slot,
+                    public_key,
+                    sector_index,
+                    piece_offset,
+                    outcome,
+                } = solution_outcome_notification;
+
+                SolutionOutcomeInfo {
+                    slot_number: slot.into(),
+                    public_key: PublicKey::from(&public_key).into(),
+                    sector_index,
+                    piece_offset,
+                    outcome: solution_outcome_to_rpc(outcome),
+                }
+            },
+        );
+        // Solutions rejected by RPC never reach slot worker, hence are reported separately
+        let (rpc_sender, rpc_stream) =
+            tracing_unbounded("subspace_solution_outcome_rpc_stream", 100);
+        self.solution_outcome_senders.lock().push(rpc_sender);
+
+        let stream =
+            stream::select(slot_worker_stream, rpc_stream).filter(move |solution_outcome_info| {
+                future::ready(
+                    public_keys.is_empty()
+                        || public_keys.contains(&PublicKey::from(solution_outcome_info.public_key)),
+                )
+            });
+
+        let fut = async move {
+            sink.pipe_from_stream(stream).await;
+        };
+
+        self.subscription_executor.spawn(
+            "subspace-solution-outcome-subscription",
+            Some("rpc"),
+            fut.boxed(),
+        );
+
+        Ok(())
+    }
+
     fn submit_reward_signature(&self, reward_signature: RewardSignatureResponse) -> RpcResult<()> {
         self.deny_unsafe.check_if_safe()?;
 
@@ -782,4 +977,197 @@ where
 
         Ok(last_segment_headers)
     }
+
+    async fn fetch_object(
+        &self,
+        object_hash: H256,
+        global_object: GlobalObject,
+    ) -> RpcResult<Bytes> {
+        self.deny_unsafe.check_if_safe()?;
+
+        let Some(object_fetcher) = &self.object_fetcher else {
+            return Err(JsonRpseeError::Custom(
+                "Object fetching is not enabled".to_string(),
+            ));
+        };
+
+        object_fetcher
+            .fetch_object(global_object, object_hash.to_fixed_bytes())
+            .await
+            .map(Bytes::from)
+            .map_err(|error| {
+                debug!(%error, %object_hash, ?global_object, "Failed to fetch object");
+
+                JsonRpseeError::Custom(format!("Failed to fetch object: {error}"))
+            })
+    }
+
+    fn get_object_mapping(&self, object_hash: H256) -> RpcResult<Option<GlobalObject>> {
+        find_object_mapping(self.object_mappings_index.as_ref(), object_hash)
+    }
+
+    fn subscribe_object_mappings(&self, mut sink: SubscriptionSink) -> SubscriptionResult {
+        let Some(object_mappings_index) = &self.object_mappings_index else {
+            return Err(SubscriptionEmptyError);
+        };
+
+        let stream = object_mappings_index
+            .object_mappings_notification_stream()
+            .subscribe()
+            .map(|object_mappings_notification| {
+                let ObjectMappingsNotification {
+                    segment_index,
+                    object_mappings,
+                } = object_mappings_notification;
+
+                ObjectMappingsInfo {
+                    segment_index,
+                    object_mappings: object_mappings
+                        .iter()
+                        .map(|object_mapping| ObjectMappingItem {
+                            hash: object_mapping.hash,
+                            global_object: object_mapping.global_object,
+                        })
+                        .collect(),
+                }
+            });
+
+        let fut = async move {
+            sink.pipe_from_stream(stream).await;
+        };
+
+        self.subscription_executor.spawn(
+            "subspace-object-mappings-subscription",
+            Some("rpc"),
+            fut.boxed(),
+        );
+
+        Ok(())
+    }
+
+    fn equivocation_evidence(&self) -> RpcResult<Vec<EquivocationEvidenceInfo>> {
+        let Some(equivocation_evidence_store) = &self.equivocation_evidence_store else {
+            return Err(JsonRpseeError::Custom(
+                "Equivocation reporting is not enabled".to_string(),
+            ));
+        };
+
+        let evidence = equivocation_evidence_store
+            .evidence::<Block::Header>()
+            .map_err(|error| {
+                error!(%error, "Failed to get equivocation evidence");
+
+                JsonRpseeError::Custom("Internal error".to_string())
+            })?;
+
+        Ok(evidence
+            .into_iter()
+            .map(|EquivocationEvidence { proof, reported }| {
+                let (first_block_number, first_block_hash, first_header) =
+                    header_to_rpc(&proof.first_header);
+                let (second_block_number, second_block_hash, second_header) =
+                    header_to_rpc(&proof.second_header);
+
+                EquivocationEvidenceInfo {
+                    slot_number: proof.slot.into(),
+                    offender: PublicKey::from(&proof.offender).into(),
+                    first_block_number,
+                    first_block_hash,
+                    first_header,
+                    second_block_number,
+                    second_block_hash,
+                    second_header,
+                    reported,
+                }
+            })
+            .collect())
+    }
+}
+
+/// Location of the object in archived history, fails if object mappings indexing is not enabled
+fn find_object_mapping<AS>(
+    object_mappings_index: Option<&ObjectMappingsIndex<AS>>,
+    object_hash: H256,
+) -> RpcResult<Option<GlobalObject>>
+where
+    AS: AuxStore,
+{
+    let Some(object_mappings_index) = object_mappings_index else {
+        return Err(JsonRpseeError::Custom(
+            "Object mappings indexing is not enabled".to_string(),
+        ));
+    };
+
+    object_mappings_index
+        .get_object_mapping(object_hash.as_fixed_bytes())
+        .map_err(|error| {
+            error!(%error, %object_hash, "Failed to get object mapping");
+
+            JsonRpseeError::Custom("Internal error".to_string())
+        })
+}
+
+/// Block number, block hash and SCALE-encoded header
+fn header_to_rpc<Header>(header: &Header) -> (BlockNumber, BlockHash, Vec<u8>)
+where
+    Header: HeaderT,
+{
+    let block_hash = BlockHash::try_from(header.hash().as_ref())
+        .expect("Block hash must always be convertable into BlockHash; qed");
+
+    (
+        (*header.number()).unique_saturated_into(),
+        block_hash,
+        header.encode(),
+    )
+}
+
+fn network_space_estimate_to_rpc(
+    network_space: sp_consensus_subspace::NetworkSpaceEstimate,
+) -> NetworkSpaceEstimate {
+    NetworkSpaceEstimate {
+        estimate: network_space.estimate,
+        lower_bound: network_space.lower_bound,
+        upper_bound: network_space.upper_bound,
+    }
+}
+
+fn solution_outcome_to_rpc(outcome: SolutionOutcome) -> RpcSolutionOutcome {
+    let reason = match outcome {
+        SolutionOutcome::ClaimedBlock => {
+            return RpcSolutionOutcome::ClaimedBlock;
+        }
+        SolutionOutcome::ClaimedVote => {
+            return RpcSolutionOutcome::ClaimedVote;
+        }
+        SolutionOutcome::Rejected(reason) => reason,
+    };
+
+    let reason = match reason {
+        SolutionRejectionReason::NotRootPlotPublicKey => {
+            RpcSolutionRejectionReason::NotRootPlotPublicKey
+        }
+        SolutionRejectionReason::InBlockList => RpcSolutionRejectionReason::InBlockList,
+        SolutionRejectionReason::SegmentCommitmentNotFound { segment_index } => {
+            RpcSolutionRejectionReason::SegmentCommitmentNotFound { segment_index }
+        }
+        SolutionRejectionReason::InvalidSectorExpirationCheck => {
+            RpcSolutionRejectionReason::InvalidSectorExpirationCheck
+        }
+        SolutionRejectionReason::InvalidSolution(error) => {
+            RpcSolutionRejectionReason::InvalidSolution {
+                error: error.to_string(),
+            }
+        }
+        SolutionRejectionReason::BlockAlreadyClaimed => {
+            RpcSolutionRejectionReason::BlockAlreadyClaimed
+        }
+        SolutionRejectionReason::VoteOnGenesisBlock => {
+            RpcSolutionRejectionReason::VoteOnGenesisBlock
+        }
+        SolutionRejectionReason::SlotNotClaimed => RpcSolutionRejectionReason::SlotNotClaimed,
+        SolutionRejectionReason::TooLate => RpcSolutionRejectionReason::TooLate,
+    };
+
+    RpcSolutionOutcome::Rejected { reason }
 }
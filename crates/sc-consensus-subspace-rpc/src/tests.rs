@@ -0,0 +1,49 @@
+use crate::find_object_mapping;
+use sc_client_api::in_mem::Backend;
+use sc_consensus_subspace::object_mappings::{ObjectMapping, ObjectMappingsIndex};
+use sp_core::H256;
+use sp_runtime::traits::BlakeTwo256;
+use sp_runtime::OpaqueExtrinsic;
+use std::sync::Arc;
+use subspace_core_primitives::objects::GlobalObject;
+use subspace_core_primitives::SegmentIndex;
+
+type Block =
+    sp_runtime::generic::Block<sp_runtime::generic::Header<u32, BlakeTwo256>, OpaqueExtrinsic>;
+
+#[test]
+fn object_mapping_lookup() {
+    let object_hash = H256::repeat_byte(1);
+
+    // Lookup is not available unless indexing is enabled
+    assert!(find_object_mapping::<Backend<Block>>(None, object_hash).is_err());
+
+    let object_mappings_index = ObjectMappingsIndex::new(Arc::new(Backend::<Block>::new()));
+    assert_eq!(
+        find_object_mapping(Some(&object_mappings_index), object_hash).unwrap(),
+        None
+    );
+
+    let global_object = GlobalObject::V0 {
+        piece_index: SegmentIndex::ONE.source_piece_index(3),
+        offset: 42,
+    };
+    object_mappings_index
+        .index_object_mappings(
+            SegmentIndex::ONE,
+            vec![ObjectMapping {
+                hash: object_hash.to_fixed_bytes(),
+                global_object,
+            }],
+        )
+        .unwrap();
+
+    assert_eq!(
+        find_object_mapping(Some(&object_mappings_index), object_hash).unwrap(),
+        Some(global_object)
+    );
+    assert_eq!(
+        find_object_mapping(Some(&object_mappings_index), H256::repeat_byte(2)).unwrap(),
+        None
+    );
+}
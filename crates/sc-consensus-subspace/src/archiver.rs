@@ -46,6 +46,10 @@
 //! [`encode_block`] and [`decode_block`] are symmetric encoding/decoding functions turning
 //! [`SignedBlock`]s into bytes and back.
 
+#[cfg(test)]
+mod tests;
+
+use crate::aux_schema::{load_archiver_checkpoint, write_archiver_checkpoint};
 use crate::block_import::BlockImportingNotification;
 use crate::slot_worker::SubspaceSyncOracle;
 use crate::{SubspaceLink, SubspaceNotificationSender};
@@ -66,13 +70,13 @@ use sp_consensus_subspace::{FarmerPublicKey, SubspaceApi, SubspaceJustification}
 use sp_objects::ObjectsApi;
 use sp_runtime::generic::SignedBlock;
 use sp_runtime::traits::{Block as BlockT, CheckedSub, Header, NumberFor, One, Zero};
-use sp_runtime::{Justifications, Saturating};
+use sp_runtime::{Justifications, SaturatedConversion, Saturating};
 use std::error::Error;
 use std::future::Future;
 use std::slice;
 use std::sync::atomic::{AtomicU16, Ordering};
 use std::sync::Arc;
-use subspace_archiving::archiver::{Archiver, NewArchivedSegment};
+use subspace_archiving::archiver::{Archiver, ArchiverState, NewArchivedSegment};
 use subspace_core_primitives::crypto::kzg::Kzg;
 use subspace_core_primitives::objects::BlockObjectMapping;
 use subspace_core_primitives::{BlockNumber, RecordedHistorySegment, SegmentHeader, SegmentIndex};
@@ -359,6 +363,135 @@ where
     Ok(Some(new_archived_segment))
 }
 
+/// Archiver state persisted in aux storage after every archived segment, allows resuming archiving
+/// on restart without reading the last archived block again.
+#[derive(Encode, Decode)]
+struct ArchiverCheckpoint<Block>
+where
+    Block: BlockT,
+{
+    best_archived_block: (Block::Hash, NumberFor<Block>),
+    archiver_state: ArchiverState,
+}
+
+/// Load archiver checkpoint and verify it against segment headers and the current chain.
+///
+/// Returns restored archiver alongside the last segment header and best archived block, `None` is
+/// returned if there is no checkpoint, it is not usable or resuming from the last archived segment
+/// would require less work.
+fn load_verified_archiver_checkpoint<Block, Client, AS>(
+    client: &Client,
+    segment_headers_store: &SegmentHeadersStore<AS>,
+    kzg: Kzg,
+    best_block_to_archive: NumberFor<Block>,
+) -> Option<(Archiver, SegmentHeader, (Block::Hash, NumberFor<Block>))>
+where
+    Block: BlockT,
+    Client: HeaderBackend<Block> + AuxStore,
+    AS: AuxStore,
+{
+    let checkpoint = match load_archiver_checkpoint::<_, ArchiverCheckpoint<Block>>(client) {
+        Ok(Some(checkpoint)) => checkpoint,
+        Ok(None) => {
+            return None;
+        }
+        Err(error) => {
+            warn!(%error, "Failed to load archiver checkpoint, ignoring");
+            return None;
+        }
+    };
+
+    let (best_archived_block_hash, best_archived_block_number) = checkpoint.best_archived_block;
+    let archiver_state = checkpoint.archiver_state;
+
+    if best_archived_block_number > best_block_to_archive {
+        debug!(
+            %best_archived_block_number,
+            %best_block_to_archive,
+            "Archiver checkpoint is ahead of the chain, ignoring"
+        );
+        return None;
+    }
+
+    if client.hash(best_archived_block_number).ok().flatten() != Some(best_archived_block_hash) {
+        debug!(
+            %best_archived_block_number,
+            %best_archived_block_hash,
+            "Archiver checkpoint is not on the canonical chain, ignoring"
+        );
+        return None;
+    }
+
+    let Some(last_segment_index) = archiver_state
+        .segment_index()
+        .checked_sub(SegmentIndex::ONE)
+    else {
+        return None;
+    };
+    let Some(last_segment_header) = segment_headers_store.get_segment_header(last_segment_index)
+    else {
+        warn!(
+            %last_segment_index,
+            "Segment header from archiver checkpoint is not known, ignoring checkpoint"
+        );
+        return None;
+    };
+
+    if last_segment_header.hash() != archiver_state.prev_segment_header_hash()
+        || last_segment_header.last_archived_block() != archiver_state.last_archived_block()
+    {
+        warn!(
+            %last_segment_index,
+            "Archiver checkpoint doesn't match segment header, ignoring checkpoint"
+        );
+        return None;
+    }
+
+    if let Some(next_segment_header) =
+        segment_headers_store.get_segment_header(archiver_state.segment_index())
+    {
+        if NumberFor::<Block>::from(next_segment_header.last_archived_block().number)
+            <= best_block_to_archive
+        {
+            debug!(
+                segment_index = %archiver_state.segment_index(),
+                "Newer segment header is available, ignoring archiver checkpoint"
+            );
+            return None;
+        }
+    }
+
+    match Archiver::from_state(kzg, archiver_state) {
+        Ok(archiver) => Some((
+            archiver,
+            last_segment_header,
+            (best_archived_block_hash, best_archived_block_number),
+        )),
+        Err(error) => {
+            warn!(%error, "Archiver checkpoint is invalid, ignoring");
+            None
+        }
+    }
+}
+
+fn write_checkpoint<Block, Client>(
+    client: &Client,
+    archiver: &Archiver,
+    best_archived_block: (Block::Hash, NumberFor<Block>),
+) -> sp_blockchain::Result<()>
+where
+    Block: BlockT,
+    Client: AuxStore,
+{
+    write_archiver_checkpoint(
+        client,
+        &ArchiverCheckpoint::<Block> {
+            best_archived_block,
+            archiver_state: archiver.state(),
+        },
+    )
+}
+
 struct InitializedArchiver<Block>
 where
     Block: BlockT,
@@ -439,6 +572,39 @@ where
     SignedBlock::<Block>::decode(&mut encoded_block)
 }
 
+/// Insert segment header of the last archived segment such that it is included in the correct block
+/// when archiver is resumed
+fn insert_last_segment_header<Block>(
+    subspace_link: &SubspaceLink<Block>,
+    last_segment_header: SegmentHeader,
+    confirmation_depth_k: BlockNumber,
+) where
+    Block: BlockT,
+{
+    if last_segment_header.segment_index() == SegmentIndex::ZERO {
+        // Due to sync from DSN it is possible that the very first segment header is known even
+        // though only genesis block exists, in this case there is nothing else left to archive and
+        // we need to insert segment header to be included in the block 1 explicitly here or else
+        // it'll be missing and block import will fail.
+        //
+        // Checking for segment index instead of best block number ensures we support hypothetical
+        // reorgs of the early blocks within confirmation depth distance from genesis.
+        subspace_link
+            .segment_headers
+            .lock()
+            .put(One::one(), vec![last_segment_header]);
+    } else {
+        // Otherwise segment header is expected to be included in `+ confirmation_depth_k + 1`'s
+        // block (+1 because we will archive it in when processing block `+ confirmation_depth_k`
+        // and corresponding segment header will be included in the next block after that
+        let last_archived_block_number = last_segment_header.last_archived_block().number;
+        subspace_link.segment_headers.lock().put(
+            (last_archived_block_number + confirmation_depth_k + 1).into(),
+            vec![last_segment_header],
+        );
+    }
+}
+
 fn initialize_archiver<Block, Client, AS>(
     segment_headers_store: &SegmentHeadersStore<AS>,
     subspace_link: &SubspaceLink<Block>,
@@ -459,13 +625,22 @@ where
         .chain_constants(best_block_hash)?
         .confirmation_depth_k();
 
-    let maybe_last_archived_block = find_last_archived_block(
+    let best_block_to_archive = best_block_number.saturating_sub(confirmation_depth_k.into());
+    let maybe_checkpoint = load_verified_archiver_checkpoint(
         client,
         segment_headers_store,
-        best_block_number.saturating_sub(confirmation_depth_k.into()),
-    )?;
-    let have_last_segment_header = maybe_last_archived_block.is_some();
+        subspace_link.kzg().clone(),
+        best_block_to_archive,
+    );
+    let maybe_last_archived_block = if maybe_checkpoint.is_some() {
+        None
+    } else {
+        find_last_archived_block(client, segment_headers_store, best_block_to_archive)?
+    };
+    let have_last_segment_header =
+        maybe_checkpoint.is_some() || maybe_last_archived_block.is_some();
     let mut best_archived_block = None;
+    let mut blocks_to_archive_from = None;
 
     let mut archiver =
         if let Some((last_segment_header, last_archived_block, block_object_mappings)) =
@@ -495,31 +670,26 @@ where
             )
             .expect("Incorrect parameters for archiver");
 
-            if last_segment_header.segment_index() == SegmentIndex::ZERO {
-                // Due to sync from DSN it is possible that the very first segment header is known
-                // even though only genesis block exists, in this case there is nothing else left to
-                // archive and we need to insert segment header to be included in the block 1
-                // explicitly here or else it'll be missing and block import will fail.
-                //
-                // Checking for segment index instead of best block number ensures we support
-                // hypothetical reorgs of the early blocks within confirmation depth distance from
-                // genesis.
-                subspace_link
-                    .segment_headers
-                    .lock()
-                    .put(One::one(), vec![last_segment_header]);
-            } else {
-                // Otherwise segment header is expected to be included in
-                // `+ confirmation_depth_k + 1`'s block (+1 because we will archive it in when
-                // processing block `+ confirmation_depth_k` and corresponding segment header will
-                // be included in the next block after that
-                subspace_link.segment_headers.lock().put(
-                    (last_archived_block_number + confirmation_depth_k + 1).into(),
-                    vec![last_segment_header],
-                );
-            }
+            insert_last_segment_header(subspace_link, last_segment_header, confirmation_depth_k);
 
             archiver
+        } else if let Some((
+            checkpoint_archiver,
+            last_segment_header,
+            (checkpoint_block_hash, checkpoint_block_number),
+        )) = maybe_checkpoint
+        {
+            info!(
+                best_archived_block_number = %checkpoint_block_number,
+                "Resuming archiver from checkpoint",
+            );
+
+            best_archived_block.replace((checkpoint_block_hash, checkpoint_block_number));
+            blocks_to_archive_from
+                .replace(checkpoint_block_number.saturated_into::<BlockNumber>() + 1);
+            insert_last_segment_header(subspace_link, last_segment_header, confirmation_depth_k);
+
+            checkpoint_archiver
         } else {
             info!("Starting archiving from genesis");
 
@@ -530,10 +700,12 @@ where
 
     // Process blocks since last fully archived block (or genesis) up to the current head minus K
     {
-        let blocks_to_archive_from = archiver
-            .last_archived_block_number()
-            .map(|n| n + 1)
-            .unwrap_or_default();
+        let blocks_to_archive_from = blocks_to_archive_from.unwrap_or_else(|| {
+            archiver
+                .last_archived_block_number()
+                .map(|n| n + 1)
+                .unwrap_or_default()
+        });
         let blocks_to_archive_to =
             TryInto::<BlockNumber>::try_into(best_block_number)
                 .unwrap_or_else(|_| {
@@ -704,7 +876,10 @@ fn finalize_block<Block, Backend, Client>(
 ///
 /// Archiver is only able to move forward and doesn't support reorgs. Upon restart it will check
 /// [`SegmentHeadersStore`] and chain history to reconstruct "current" state it was in before last
-/// shutdown and continue incrementally archiving blockchain history from there.
+/// shutdown and continue incrementally archiving blockchain history from there. Archiver state is
+/// also checkpointed to aux storage after every archived segment (when only the remainder of the
+/// last block is buffered, so checkpoint is small), in which case archiving resumes from verified
+/// checkpoint without reading the last archived block again.
 ///
 /// Archiving is triggered by block importing notification ([`SubspaceLink::block_importing_notification_stream`])
 /// and tries to archive the block at [`ChainConstants::confirmation_depth_k`](sp_consensus_subspace::ChainConstants::confirmation_depth_k)
@@ -892,6 +1067,12 @@ where
                         block_number_to_finalize,
                     );
                 }
+
+                write_checkpoint(
+                    client.as_ref(),
+                    &archiver,
+                    (best_archived_block_hash, best_archived_block_number),
+                )?;
             }
         }
 
@@ -0,0 +1,201 @@
+use crate::archiver::{load_verified_archiver_checkpoint, write_checkpoint, SegmentHeadersStore};
+use sc_client_api::in_mem::Backend;
+use sc_client_api::AuxStore;
+use sp_blockchain::{BlockStatus, HeaderBackend, Info};
+use sp_core::H256;
+use sp_runtime::traits::BlakeTwo256;
+use sp_runtime::OpaqueExtrinsic;
+use std::sync::Arc;
+use subspace_archiving::archiver::Archiver;
+use subspace_core_primitives::crypto::kzg::{embedded_kzg_settings, Kzg};
+use subspace_core_primitives::objects::BlockObjectMapping;
+use subspace_core_primitives::{BlockNumber, RecordedHistorySegment, SegmentHeader, SegmentIndex};
+
+type Header = sp_runtime::generic::Header<BlockNumber, BlakeTwo256>;
+type Block = sp_runtime::generic::Block<Header, OpaqueExtrinsic>;
+
+/// Client that only knows hashes of canonical blocks and has aux storage
+struct TestClient {
+    aux_store: Backend<Block>,
+    block_hashes: Vec<H256>,
+}
+
+impl AuxStore for TestClient {
+    fn insert_aux<
+        'a,
+        'b: 'a,
+        'c: 'a,
+        I: IntoIterator<Item = &'a (&'c [u8], &'c [u8])>,
+        D: IntoIterator<Item = &'a &'b [u8]>,
+    >(
+        &self,
+        insert: I,
+        delete: D,
+    ) -> sp_blockchain::Result<()> {
+        self.aux_store.insert_aux(insert, delete)
+    }
+
+    fn get_aux(&self, key: &[u8]) -> sp_blockchain::Result<Option<Vec<u8>>> {
+        self.aux_store.get_aux(key)
+    }
+}
+
+impl HeaderBackend<Block> for TestClient {
+    fn header(&self, _hash: H256) -> sp_blockchain::Result<Option<Header>> {
+        unimplemented!("Not used in tests")
+    }
+
+    fn info(&self) -> Info<Block> {
+        unimplemented!("Not used in tests")
+    }
+
+    fn status(&self, _hash: H256) -> sp_blockchain::Result<BlockStatus> {
+        unimplemented!("Not used in tests")
+    }
+
+    fn number(&self, _hash: H256) -> sp_blockchain::Result<Option<BlockNumber>> {
+        unimplemented!("Not used in tests")
+    }
+
+    fn hash(&self, number: BlockNumber) -> sp_blockchain::Result<Option<H256>> {
+        Ok(self.block_hashes.get(number as usize).copied())
+    }
+}
+
+/// Block that takes two thirds of the segment, such that segments are produced every 1-2 blocks
+fn block(number: BlockNumber) -> Vec<u8> {
+    vec![number as u8; RecordedHistorySegment::SIZE / 3 * 2]
+}
+
+fn block_hash(number: BlockNumber) -> H256 {
+    H256::repeat_byte(number as u8 + 1)
+}
+
+fn load_checkpoint(
+    client: &TestClient,
+    segment_headers_store: &SegmentHeadersStore<Backend<Block>>,
+    kzg: Kzg,
+    best_block_to_archive: BlockNumber,
+) -> Option<(Archiver, SegmentHeader, (H256, BlockNumber))> {
+    load_verified_archiver_checkpoint::<Block, _, _>(
+        client,
+        segment_headers_store,
+        kzg,
+        best_block_to_archive,
+    )
+}
+
+/// Archive blocks the same way archiver task does, checkpoint is written after every archived
+/// segment
+fn archive_blocks(
+    client: &TestClient,
+    segment_headers_store: &SegmentHeadersStore<Backend<Block>>,
+    archiver: &mut Archiver,
+    block_numbers: impl Iterator<Item = BlockNumber>,
+) -> Vec<SegmentHeader> {
+    let mut segment_headers = Vec::new();
+
+    for block_number in block_numbers {
+        let new_segment_headers = archiver
+            .add_block(block(block_number), BlockObjectMapping::default(), true)
+            .into_iter()
+            .map(|archived_segment| archived_segment.segment_header)
+            .collect::<Vec<_>>();
+
+        if !new_segment_headers.is_empty() {
+            segment_headers_store
+                .add_segment_headers(&new_segment_headers)
+                .unwrap();
+            write_checkpoint::<Block, _>(
+                client,
+                archiver,
+                (block_hash(block_number), block_number),
+            )
+            .unwrap();
+        }
+
+        segment_headers.extend(new_segment_headers);
+    }
+
+    segment_headers
+}
+
+#[test]
+fn restart_from_checkpoint() {
+    let kzg = Kzg::new(embedded_kzg_settings());
+    let client = TestClient {
+        aux_store: Backend::<Block>::new(),
+        block_hashes: (0..4).map(block_hash).collect(),
+    };
+    let segment_headers_store =
+        SegmentHeadersStore::new(Arc::new(Backend::<Block>::new())).unwrap();
+    let mut archiver = Archiver::new(kzg.clone()).unwrap();
+
+    // There is no checkpoint yet
+    assert!(load_checkpoint(&client, &segment_headers_store, kzg.clone(), 3).is_none());
+
+    let segment_headers = archive_blocks(&client, &segment_headers_store, &mut archiver, 0..=1);
+    assert_eq!(segment_headers.len(), 1);
+    assert_eq!(segment_headers[0].segment_index(), SegmentIndex::ZERO);
+
+    // Restart with checkpoint created after the first segment
+    let (mut restored_archiver, last_segment_header, best_archived_block) =
+        load_checkpoint(&client, &segment_headers_store, kzg.clone(), 3).unwrap();
+    assert_eq!(last_segment_header, segment_headers[0]);
+    assert_eq!(best_archived_block, (block_hash(1), 1));
+
+    // Restored archiver produces the same segments as the one that was never restarted
+    for block_number in 2..=3 {
+        assert_eq!(
+            restored_archiver.add_block(block(block_number), BlockObjectMapping::default(), true),
+            archiver.add_block(block(block_number), BlockObjectMapping::default(), true),
+        );
+    }
+}
+
+#[test]
+fn unusable_checkpoint_is_ignored() {
+    let kzg = Kzg::new(embedded_kzg_settings());
+    let client = TestClient {
+        aux_store: Backend::<Block>::new(),
+        block_hashes: (0..4).map(block_hash).collect(),
+    };
+    let segment_headers_store =
+        SegmentHeadersStore::new(Arc::new(Backend::<Block>::new())).unwrap();
+    let mut archiver = Archiver::new(kzg.clone()).unwrap();
+    archive_blocks(&client, &segment_headers_store, &mut archiver, 0..=1);
+
+    // Checkpoint is ahead of blocks that can be archived
+    assert!(load_checkpoint(&client, &segment_headers_store, kzg.clone(), 0).is_none());
+
+    // Checkpoint block is not canonical anymore after reorg
+    let mut reorged_client = TestClient {
+        aux_store: Backend::<Block>::new(),
+        block_hashes: client.block_hashes.clone(),
+    };
+    reorged_client.block_hashes[1] = H256::zero();
+    *reorged_client.aux_store.storage.lock() = client.aux_store.storage.lock().clone();
+    assert!(load_checkpoint(&reorged_client, &segment_headers_store, kzg.clone(), 3).is_none());
+
+    // Segment header of the checkpoint is not known
+    let empty_segment_headers_store =
+        SegmentHeadersStore::new(Arc::new(Backend::<Block>::new())).unwrap();
+    assert!(load_checkpoint(&client, &empty_segment_headers_store, kzg.clone(), 3).is_none());
+
+    // Newer segment header is known (for example, after sync from DSN), resuming from it is
+    // preferred
+    let newer_segment_headers = archiver
+        .add_block(block(2), BlockObjectMapping::default(), true)
+        .into_iter()
+        .map(|archived_segment| archived_segment.segment_header)
+        .collect::<Vec<_>>();
+    assert_eq!(newer_segment_headers.len(), 1);
+    segment_headers_store
+        .add_segment_headers(&newer_segment_headers)
+        .unwrap();
+    assert!(
+        load_checkpoint(&client, &segment_headers_store, kzg.clone(), 1).is_some(),
+        "Newer segment header is not yet archivable at block 1"
+    );
+    assert!(load_checkpoint(&client, &segment_headers_store, kzg, 3).is_none());
+}
@@ -15,7 +15,7 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
-//! Schema for Subspace block weight in the aux-db.
+//! Schema for Subspace block weight and archiver checkpoint in the aux-db.
 
 use codec::{Decode, Encode};
 use sc_client_api::backend::AuxStore;
@@ -61,3 +61,26 @@ pub(crate) fn load_block_weight<H: Encode, B: AuxStore>(
 ) -> ClientResult<Option<BlockWeight>> {
     load_decode(backend, block_weight_key(block_hash).as_slice())
 }
+
+/// The aux storage key used to store the archiver checkpoint.
+const ARCHIVER_CHECKPOINT_KEY: &[u8] = b"archiver_checkpoint";
+
+/// Write archiver checkpoint to aux storage, replacing the previous one.
+pub(crate) fn write_archiver_checkpoint<B, T>(backend: &B, checkpoint: &T) -> ClientResult<()>
+where
+    B: AuxStore,
+    T: Encode,
+{
+    checkpoint.using_encoded(|encoded_checkpoint| {
+        backend.insert_aux(&[(ARCHIVER_CHECKPOINT_KEY, encoded_checkpoint)], &[])
+    })
+}
+
+/// Load archiver checkpoint from aux storage.
+pub(crate) fn load_archiver_checkpoint<B, T>(backend: &B) -> ClientResult<Option<T>>
+where
+    B: AuxStore,
+    T: Decode,
+{
+    load_decode(backend, ARCHIVER_CHECKPOINT_KEY)
+}
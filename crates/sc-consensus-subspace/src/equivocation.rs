@@ -0,0 +1,461 @@
+// Copyright (C) 2024 Subspace Labs, Inc.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Optional equivocation detection and reporting.
+//!
+//! Block import only checks for equivocation of blocks that go through the verifier of this
+//! particular node. This module contains [`create_equivocation_reporter`] that tracks headers by
+//! slot and farmer public key, both from block import and from block announcements received over
+//! the network (see [`EquivocationBlockAnnounceValidator`]), such that equivocation is detected
+//! even if conflicting block was never imported locally.
+//!
+//! Detected equivocations are reported to the runtime with an unsigned extrinsic and persisted in
+//! [`EquivocationEvidenceStore`] for monitoring purposes.
+//!
+//! Headers are not tracked during major sync, the same way verifier doesn't check blocks imported
+//! during initial sync for equivocation, since those are most likely stale.
+
+#[cfg(test)]
+mod tests;
+
+use codec::{Decode, Encode};
+use futures::channel::mpsc;
+use futures::{stream, StreamExt};
+use sc_client_api::{AuxStore, BlockchainEvents};
+use sc_transaction_pool_api::OffchainTransactionPoolFactory;
+use sp_api::{ApiError, ProvideRuntimeApi};
+use sp_blockchain::HeaderBackend;
+use sp_consensus::block_validation::{
+    BlockAnnounceValidator, DefaultBlockAnnounceValidator, Validation,
+};
+use sp_consensus::{BlockOrigin, SyncOracle};
+use sp_consensus_slots::Slot;
+use sp_consensus_subspace::digests::extract_pre_digest;
+use sp_consensus_subspace::{
+    is_equivocation_proof_valid, is_seal_signature_valid, EquivocationProof, FarmerPublicKey,
+    SubspaceApi,
+};
+use sp_runtime::traits::{Block as BlockT, Header as HeaderT};
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use tracing::{debug, error, info, warn};
+
+/// How many slots behind the highest imported slot are tracked
+const TRACKED_SLOTS: u64 = 1_000;
+/// How far ahead of the highest imported slot announced headers are accepted
+const MAX_FUTURE_SLOTS: u64 = 10;
+/// Max number of equivocation evidences kept in the store, older evidence is removed
+const MAX_STORED_EVIDENCE: usize = 1_000;
+/// Capacity of the channel for announced headers, headers are dropped if reporter falls behind
+pub const ANNOUNCED_HEADERS_CHANNEL_CAPACITY: usize = 1_000;
+
+/// Evidence of equivocation detected by this node.
+#[derive(Debug, Clone, Encode, Decode)]
+pub struct EquivocationEvidence<Header>
+where
+    Header: HeaderT,
+{
+    /// Equivocation proof
+    pub proof: EquivocationProof<Header>,
+    /// Whether equivocation report was submitted to transaction pool by this node
+    pub reported: bool,
+}
+
+/// Range of keys of stored equivocation evidence
+#[derive(Debug, Default, Copy, Clone, Encode, Decode)]
+struct EvidenceIndex {
+    /// Key index of the oldest stored evidence
+    first: u64,
+    /// Key index where next evidence will be stored
+    next: u64,
+}
+
+/// Persistent store of equivocation evidence detected by this node.
+///
+/// Each evidence is stored under its own key, small index with the range of stored keys is stored
+/// separately, such that adding evidence doesn't require rewriting previously stored evidence.
+pub struct EquivocationEvidenceStore<AS> {
+    aux_store: Arc<AS>,
+}
+
+impl<AS> Clone for EquivocationEvidenceStore<AS> {
+    fn clone(&self) -> Self {
+        Self {
+            aux_store: Arc::clone(&self.aux_store),
+        }
+    }
+}
+
+impl<AS> EquivocationEvidenceStore<AS>
+where
+    AS: AuxStore,
+{
+    const INDEX_KEY: &'static [u8] = b"equivocation-evidence-index";
+    const KEY_PREFIX: &'static [u8] = b"equivocation-evidence";
+
+    /// Create new instance
+    pub fn new(aux_store: Arc<AS>) -> Self {
+        Self { aux_store }
+    }
+
+    /// Get stored equivocation evidence, from the oldest to the newest
+    pub fn evidence<Header>(&self) -> sp_blockchain::Result<Vec<EquivocationEvidence<Header>>>
+    where
+        Header: HeaderT,
+    {
+        let index = self.index()?;
+
+        (index.first..index.next)
+            .map(|key_index| {
+                let evidence = self
+                    .aux_store
+                    .get_aux(&Self::key(key_index))?
+                    .ok_or_else(|| {
+                        sp_blockchain::Error::Application(
+                            format!("Equivocation evidence {key_index} is missing").into(),
+                        )
+                    })?;
+
+                EquivocationEvidence::decode(&mut evidence.as_slice()).map_err(|error| {
+                    sp_blockchain::Error::Application(
+                        format!("Failed to decode equivocation evidence: {error}").into(),
+                    )
+                })
+            })
+            .collect()
+    }
+
+    /// Store new evidence, removing the oldest one if there are too many
+    fn add_evidence<Header>(
+        &self,
+        evidence: EquivocationEvidence<Header>,
+    ) -> sp_blockchain::Result<()>
+    where
+        Header: HeaderT,
+    {
+        let mut index = self.index()?;
+        let key = Self::key(index.next);
+        index.next += 1;
+
+        let mut delete = Vec::new();
+        if index.next - index.first > MAX_STORED_EVIDENCE as u64 {
+            delete.push(Self::key(index.first));
+            index.first += 1;
+        }
+
+        let evidence = evidence.encode();
+        let index = index.encode();
+        self.aux_store.insert_aux(
+            &[
+                (key.as_slice(), evidence.as_slice()),
+                (Self::INDEX_KEY, index.as_slice()),
+            ],
+            &delete.iter().map(Vec::as_slice).collect::<Vec<_>>(),
+        )
+    }
+
+    fn index(&self) -> sp_blockchain::Result<EvidenceIndex> {
+        self.aux_store
+            .get_aux(Self::INDEX_KEY)?
+            .map(|index| {
+                EvidenceIndex::decode(&mut index.as_slice()).map_err(|error| {
+                    sp_blockchain::Error::Application(
+                        format!("Failed to decode equivocation evidence index: {error}").into(),
+                    )
+                })
+            })
+            .transpose()
+            .map(Option::unwrap_or_default)
+    }
+
+    fn key(key_index: u64) -> Vec<u8> {
+        (Self::KEY_PREFIX, key_index.to_le_bytes()).encode()
+    }
+}
+
+/// Block announce validator that forwards announced headers to equivocation reporter and
+/// otherwise behaves like [`DefaultBlockAnnounceValidator`].
+pub struct EquivocationBlockAnnounceValidator<Block>
+where
+    Block: BlockT,
+{
+    announced_headers_sender: mpsc::Sender<Block::Header>,
+}
+
+impl<Block> EquivocationBlockAnnounceValidator<Block>
+where
+    Block: BlockT,
+{
+    /// Create new instance, headers are sent to [`create_equivocation_reporter`] through provided
+    /// sender (see [`ANNOUNCED_HEADERS_CHANNEL_CAPACITY`])
+    pub fn new(announced_headers_sender: mpsc::Sender<Block::Header>) -> Self {
+        Self {
+            announced_headers_sender,
+        }
+    }
+}
+
+impl<Block> BlockAnnounceValidator<Block> for EquivocationBlockAnnounceValidator<Block>
+where
+    Block: BlockT,
+{
+    fn validate(
+        &mut self,
+        header: &Block::Header,
+        data: &[u8],
+    ) -> Pin<Box<dyn Future<Output = Result<Validation, Box<dyn Error + Send>>> + Send>> {
+        // Block announcement must not be slowed down or blocked by equivocation reporter, header is
+        // simply dropped if reporter is not keeping up
+        if let Err(error) = self.announced_headers_sender.try_send(header.clone()) {
+            debug!(%error, "Failed to forward announced header to equivocation reporter");
+        }
+
+        BlockAnnounceValidator::<Block>::validate(&mut DefaultBlockAnnounceValidator, header, data)
+    }
+}
+
+/// Where header was received from
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum HeaderSource {
+    BlockImport,
+    BlockAnnouncement,
+}
+
+#[derive(Debug)]
+struct SeenHeader<Header> {
+    header: Header,
+    /// Equivocation at this slot was already detected
+    equivocated: bool,
+}
+
+/// Tracks headers by slot and farmer public key
+#[derive(Debug)]
+struct EquivocationDetector<Header> {
+    headers: BTreeMap<Slot, HashMap<FarmerPublicKey, SeenHeader<Header>>>,
+    highest_imported_slot: Slot,
+}
+
+impl<Header> EquivocationDetector<Header>
+where
+    Header: HeaderT,
+{
+    fn new(highest_imported_slot: Slot) -> Self {
+        Self {
+            headers: BTreeMap::new(),
+            highest_imported_slot,
+        }
+    }
+
+    /// Move tracking window forward if slot of imported block is higher than previously seen,
+    /// headers that are too old are pruned
+    fn on_imported_slot(&mut self, slot: Slot) {
+        if slot <= self.highest_imported_slot {
+            return;
+        }
+
+        self.highest_imported_slot = slot;
+        let oldest_tracked_slot =
+            Slot::from(self.highest_imported_slot.saturating_sub(TRACKED_SLOTS));
+        self.headers = self.headers.split_off(&oldest_tracked_slot);
+    }
+
+    /// Track header, returns equivocation proof if another header for the same slot by the same
+    /// farmer was seen before
+    fn on_header(
+        &mut self,
+        source: HeaderSource,
+        header: Header,
+    ) -> Option<EquivocationProof<Header>> {
+        let pre_digest = extract_pre_digest(&header).ok()?;
+        let slot = pre_digest.slot();
+        let offender = pre_digest.solution().public_key.clone();
+
+        if source == HeaderSource::BlockImport {
+            self.on_imported_slot(slot);
+        }
+
+        // Anyone can announce arbitrary headers, only track those that may be relevant
+        if slot.saturating_add(TRACKED_SLOTS) < self.highest_imported_slot
+            || slot > self.highest_imported_slot.saturating_add(MAX_FUTURE_SLOTS)
+        {
+            return None;
+        }
+
+        // Header must be signed by the farmer, otherwise anyone could occupy the slot with
+        // invalid header and prevent detection of actual equivocation
+        if !is_seal_signature_valid(header.clone(), &offender) {
+            debug!(%slot, hash = ?header.hash(), ?source, "Ignoring header with invalid seal");
+            return None;
+        }
+
+        let seen_header = match self
+            .headers
+            .entry(slot)
+            .or_default()
+            .entry(offender.clone())
+        {
+            Entry::Vacant(entry) => {
+                entry.insert(SeenHeader {
+                    header,
+                    equivocated: false,
+                });
+                return None;
+            }
+            Entry::Occupied(entry) => entry.into_mut(),
+        };
+
+        if seen_header.equivocated || seen_header.header.hash() == header.hash() {
+            return None;
+        }
+
+        let proof = EquivocationProof {
+            offender,
+            slot,
+            first_header: seen_header.header.clone(),
+            second_header: header,
+        };
+
+        // Same farmer is allowed to produce blocks with solutions from different sectors
+        if !is_equivocation_proof_valid::<_, FarmerPublicKey>(&proof) {
+            return None;
+        }
+
+        seen_header.equivocated = true;
+
+        Some(proof)
+    }
+}
+
+/// Create a task that detects equivocations in headers from block import and block announcements
+/// (received from [`EquivocationBlockAnnounceValidator`]), reports them to the runtime and stores
+/// the evidence in [`EquivocationEvidenceStore`].
+pub fn create_equivocation_reporter<Block, Client, AS, SO>(
+    evidence_store: EquivocationEvidenceStore<AS>,
+    client: Arc<Client>,
+    offchain_tx_pool_factory: OffchainTransactionPoolFactory<Block>,
+    sync_oracle: SO,
+    announced_headers: mpsc::Receiver<Block::Header>,
+) -> impl Future<Output = ()> + Send + 'static
+where
+    Block: BlockT,
+    Client: ProvideRuntimeApi<Block>
+        + HeaderBackend<Block>
+        + BlockchainEvents<Block>
+        + Send
+        + Sync
+        + 'static,
+    Client::Api: SubspaceApi<Block, FarmerPublicKey>,
+    AS: AuxStore + Send + Sync + 'static,
+    SO: SyncOracle + Send + Sync + 'static,
+{
+    let imported_headers = client.import_notification_stream().map(|notification| {
+        (
+            HeaderSource::BlockImport,
+            notification.origin == BlockOrigin::NetworkInitialSync,
+            notification.header,
+        )
+    });
+    let announced_headers =
+        announced_headers.map(|header| (HeaderSource::BlockAnnouncement, false, header));
+
+    async move {
+        let mut headers = stream::select(imported_headers, announced_headers);
+        // Announced headers are checked against the highest imported slot, hence start with the
+        // slot of the best block
+        let best_slot = client
+            .header(client.info().best_hash)
+            .ok()
+            .flatten()
+            .and_then(|header| extract_pre_digest(&header).ok())
+            .map(|pre_digest| pre_digest.slot())
+            .unwrap_or_default();
+        let mut detector = EquivocationDetector::new(best_slot);
+
+        while let Some((source, initial_sync, header)) = headers.next().await {
+            // Don't track headers during major sync, they are most likely stale, but keep tracking
+            // window up to date
+            if initial_sync || sync_oracle.is_major_syncing() {
+                if source == HeaderSource::BlockImport
+                    && let Ok(pre_digest) = extract_pre_digest(&header)
+                {
+                    detector.on_imported_slot(pre_digest.slot());
+                }
+                continue;
+            }
+
+            let Some(proof) = detector.on_header(source, header) else {
+                continue;
+            };
+
+            info!(
+                offender = %proof.offender,
+                slot = %proof.slot,
+                first_header = ?proof.first_header.hash(),
+                second_header = ?proof.second_header.hash(),
+                ?source,
+                "Detected equivocation"
+            );
+
+            let reported =
+                match submit_equivocation_report(&*client, &offchain_tx_pool_factory, &proof) {
+                    Ok(reported) => reported,
+                    Err(error) => {
+                        warn!(%error, offender = %proof.offender, "Failed to report equivocation");
+                        false
+                    }
+                };
+
+            if let Err(error) =
+                evidence_store.add_evidence(EquivocationEvidence { proof, reported })
+            {
+                error!(%error, "Failed to store equivocation evidence");
+            }
+        }
+    }
+}
+
+/// Submit equivocation report to transaction pool, returns `false` if offender is already in block
+/// list and doesn't need to be reported
+fn submit_equivocation_report<Block, Client>(
+    client: &Client,
+    offchain_tx_pool_factory: &OffchainTransactionPoolFactory<Block>,
+    proof: &EquivocationProof<Block::Header>,
+) -> Result<bool, ApiError>
+where
+    Block: BlockT,
+    Client: ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+    Client::Api: SubspaceApi<Block, FarmerPublicKey>,
+{
+    let best_hash = client.info().best_hash;
+    let mut runtime_api = client.runtime_api();
+
+    if runtime_api.is_in_block_list(best_hash, &proof.offender)? {
+        debug!(offender = %proof.offender, "Offender is already in block list");
+        return Ok(false);
+    }
+
+    // Register the offchain tx pool to be able to use it from the runtime.
+    runtime_api.register_extension(offchain_tx_pool_factory.offchain_transaction_pool(best_hash));
+    runtime_api.submit_report_equivocation_extrinsic(best_hash, proof.clone())?;
+
+    info!(offender = %proof.offender, "Submitted equivocation report");
+
+    Ok(true)
+}
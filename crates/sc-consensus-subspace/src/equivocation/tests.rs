@@ -0,0 +1,328 @@
+use crate::equivocation::{
+    EquivocationDetector, EquivocationEvidence, EquivocationEvidenceStore, HeaderSource,
+    MAX_FUTURE_SLOTS, MAX_STORED_EVIDENCE, TRACKED_SLOTS,
+};
+use sc_client_api::in_mem::Backend;
+use schnorrkel::Keypair;
+use sp_consensus_slots::Slot;
+use sp_consensus_subspace::digests::{CompatibleDigestItem, PreDigest, PreDigestPotInfo};
+use sp_consensus_subspace::{EquivocationProof, FarmerPublicKey, FarmerSignature};
+use sp_core::crypto::UncheckedFrom;
+use sp_runtime::traits::{BlakeTwo256, Header as HeaderT};
+use sp_runtime::{Digest, DigestItem, OpaqueExtrinsic};
+use std::num::NonZeroU64;
+use std::sync::Arc;
+use subspace_core_primitives::{HistorySize, PieceOffset, Solution, REWARD_SIGNING_CONTEXT};
+
+type Header = sp_runtime::generic::Header<u32, BlakeTwo256>;
+type Block = sp_runtime::generic::Block<Header, OpaqueExtrinsic>;
+
+/// Create header at `slot` signed by `keypair`, different `parent_byte` results in different
+/// header for the same slot
+fn signed_header(keypair: &Keypair, slot: u64, parent_byte: u8, sector_index: u16) -> Header {
+    let public_key = FarmerPublicKey::unchecked_from(keypair.public.to_bytes());
+    let solution = Solution {
+        public_key: public_key.clone(),
+        reward_address: public_key,
+        sector_index,
+        history_size: HistorySize::from(NonZeroU64::new(1).unwrap()),
+        piece_offset: PieceOffset::default(),
+        record_commitment: Default::default(),
+        record_witness: Default::default(),
+        chunk: Default::default(),
+        chunk_witness: Default::default(),
+        proof_of_space: Default::default(),
+    };
+
+    let mut header = Header {
+        parent_hash: [parent_byte; 32].into(),
+        number: 1,
+        state_root: Default::default(),
+        extrinsics_root: Default::default(),
+        digest: Digest {
+            logs: vec![DigestItem::subspace_pre_digest(&PreDigest::V0 {
+                slot: Slot::from(slot),
+                solution,
+                pot_info: PreDigestPotInfo::V0 {
+                    proof_of_time: Default::default(),
+                    future_proof_of_time: Default::default(),
+                },
+            })],
+        },
+    };
+    let signature = keypair.sign(
+        schnorrkel::context::signing_context(REWARD_SIGNING_CONTEXT)
+            .bytes(header.hash().as_bytes()),
+    );
+    header
+        .digest
+        .logs
+        .push(DigestItem::subspace_seal(FarmerSignature::unchecked_from(
+            signature.to_bytes(),
+        )));
+
+    header
+}
+
+#[test]
+fn same_header_is_not_equivocation() {
+    let keypair = Keypair::generate();
+    let mut detector = EquivocationDetector::new(Slot::from(10));
+    let header = signed_header(&keypair, 10, 0, 0);
+
+    assert!(detector
+        .on_header(HeaderSource::BlockImport, header.clone())
+        .is_none());
+    assert!(detector
+        .on_header(HeaderSource::BlockAnnouncement, header)
+        .is_none());
+}
+
+#[test]
+fn different_headers_are_equivocation() {
+    let keypair = Keypair::generate();
+    let mut detector = EquivocationDetector::new(Slot::from(10));
+    let first_header = signed_header(&keypair, 10, 0, 0);
+    let second_header = signed_header(&keypair, 10, 1, 0);
+
+    assert!(detector
+        .on_header(HeaderSource::BlockImport, first_header.clone())
+        .is_none());
+    let proof = detector
+        .on_header(HeaderSource::BlockAnnouncement, second_header.clone())
+        .unwrap();
+    assert_eq!(
+        proof.offender,
+        FarmerPublicKey::unchecked_from(keypair.public.to_bytes())
+    );
+    assert_eq!(proof.slot, Slot::from(10));
+    assert_eq!(proof.first_header, first_header);
+    assert_eq!(proof.second_header, second_header);
+
+    // Equivocation at the same slot is only reported once
+    assert!(detector
+        .on_header(
+            HeaderSource::BlockAnnouncement,
+            signed_header(&keypair, 10, 2, 0)
+        )
+        .is_none());
+
+    // Different farmers at the same slot are not equivocating
+    let other_keypair = Keypair::generate();
+    assert!(detector
+        .on_header(
+            HeaderSource::BlockAnnouncement,
+            signed_header(&other_keypair, 10, 3, 0)
+        )
+        .is_none());
+
+    // Same farmer is allowed to produce blocks with solutions from different sectors
+    let mut detector = EquivocationDetector::new(Slot::from(10));
+    assert!(detector
+        .on_header(HeaderSource::BlockImport, signed_header(&keypair, 10, 0, 0))
+        .is_none());
+    assert!(detector
+        .on_header(
+            HeaderSource::BlockAnnouncement,
+            signed_header(&keypair, 10, 1, 1)
+        )
+        .is_none());
+}
+
+#[test]
+fn headers_with_invalid_seal_are_ignored() {
+    let keypair = Keypair::generate();
+    let mut detector = EquivocationDetector::new(Slot::from(10));
+
+    // Header claims to be produced by the farmer, but is signed by someone else
+    let mut forged_header = signed_header(&keypair, 10, 0, 0);
+    forged_header.digest.logs.pop();
+    let other_keypair = Keypair::generate();
+    let signature = other_keypair.sign(
+        schnorrkel::context::signing_context(REWARD_SIGNING_CONTEXT)
+            .bytes(forged_header.hash().as_bytes()),
+    );
+    forged_header
+        .digest
+        .logs
+        .push(DigestItem::subspace_seal(FarmerSignature::unchecked_from(
+            signature.to_bytes(),
+        )));
+
+    assert!(detector
+        .on_header(HeaderSource::BlockAnnouncement, forged_header)
+        .is_none());
+
+    // Forged header didn't occupy the slot, hence actual equivocation is still detected
+    assert!(detector
+        .on_header(HeaderSource::BlockImport, signed_header(&keypair, 10, 1, 0))
+        .is_none());
+    assert!(detector
+        .on_header(
+            HeaderSource::BlockAnnouncement,
+            signed_header(&keypair, 10, 2, 0)
+        )
+        .is_some());
+}
+
+#[test]
+fn headers_outside_of_window_are_ignored() {
+    let keypair = Keypair::generate();
+    let highest_imported_slot = TRACKED_SLOTS * 2;
+    let mut detector = EquivocationDetector::new(Slot::from(highest_imported_slot));
+
+    for slot in [
+        highest_imported_slot - TRACKED_SLOTS - 1,
+        highest_imported_slot + MAX_FUTURE_SLOTS + 1,
+    ] {
+        assert!(detector
+            .on_header(
+                HeaderSource::BlockAnnouncement,
+                signed_header(&keypair, slot, 0, 0)
+            )
+            .is_none());
+        assert!(detector
+            .on_header(
+                HeaderSource::BlockAnnouncement,
+                signed_header(&keypair, slot, 1, 0)
+            )
+            .is_none());
+    }
+    assert!(detector.headers.is_empty());
+
+    // Edges of the window are tracked
+    for slot in [
+        highest_imported_slot - TRACKED_SLOTS,
+        highest_imported_slot + MAX_FUTURE_SLOTS,
+    ] {
+        assert!(detector
+            .on_header(
+                HeaderSource::BlockAnnouncement,
+                signed_header(&keypair, slot, 0, 0)
+            )
+            .is_none());
+        assert!(detector
+            .on_header(
+                HeaderSource::BlockAnnouncement,
+                signed_header(&keypair, slot, 1, 0)
+            )
+            .is_some());
+    }
+}
+
+#[test]
+fn headers_at_extreme_slots_do_not_overflow() {
+    let keypair = Keypair::generate();
+
+    // Announced slot is not authenticated in any way before the window check
+    let mut detector = EquivocationDetector::new(Slot::from(10));
+    assert!(detector
+        .on_header(
+            HeaderSource::BlockAnnouncement,
+            signed_header(&keypair, u64::MAX, 0, 0)
+        )
+        .is_none());
+    assert!(detector.headers.is_empty());
+
+    let mut detector = EquivocationDetector::new(Slot::from(u64::MAX));
+    assert!(detector
+        .on_header(
+            HeaderSource::BlockImport,
+            signed_header(&keypair, u64::MAX, 0, 0)
+        )
+        .is_none());
+    assert!(detector
+        .on_header(
+            HeaderSource::BlockAnnouncement,
+            signed_header(&keypair, u64::MAX, 1, 0)
+        )
+        .is_some());
+}
+
+#[test]
+fn old_headers_are_pruned() {
+    let keypair = Keypair::generate();
+    let mut detector = EquivocationDetector::new(Slot::from(10));
+
+    let old_header = signed_header(&keypair, 10, 0, 0);
+    assert!(detector
+        .on_header(HeaderSource::BlockImport, old_header)
+        .is_none());
+    assert!(detector.headers.contains_key(&Slot::from(10)));
+
+    // Announced headers don't move the window
+    assert!(detector
+        .on_header(
+            HeaderSource::BlockAnnouncement,
+            signed_header(&keypair, 10 + MAX_FUTURE_SLOTS, 0, 0)
+        )
+        .is_none());
+    assert_eq!(detector.highest_imported_slot, Slot::from(10));
+    assert!(detector.headers.contains_key(&Slot::from(10)));
+
+    // Imported headers do
+    let new_slot = 10 + TRACKED_SLOTS + 1;
+    detector.on_imported_slot(Slot::from(new_slot - 1));
+    assert!(detector.headers.contains_key(&Slot::from(10)));
+    assert!(detector
+        .on_header(
+            HeaderSource::BlockImport,
+            signed_header(&keypair, new_slot, 0, 0)
+        )
+        .is_none());
+    assert_eq!(detector.highest_imported_slot, Slot::from(new_slot));
+    assert!(!detector.headers.contains_key(&Slot::from(10)));
+    assert!(detector
+        .headers
+        .keys()
+        .all(|slot| **slot + TRACKED_SLOTS >= new_slot));
+
+    // Older imported slot doesn't move the window back
+    detector.on_imported_slot(Slot::from(10));
+    assert_eq!(detector.highest_imported_slot, Slot::from(new_slot));
+
+    // Conflicting header for pruned slot is not detected anymore
+    assert!(detector
+        .on_header(
+            HeaderSource::BlockAnnouncement,
+            signed_header(&keypair, 10, 1, 0)
+        )
+        .is_none());
+}
+
+#[test]
+fn evidence_store_keeps_latest_evidence() {
+    let keypair = Keypair::generate();
+    let evidence_store = EquivocationEvidenceStore::new(Arc::new(Backend::<Block>::new()));
+    assert!(evidence_store.evidence::<Header>().unwrap().is_empty());
+
+    let evidence = |slot: u64| EquivocationEvidence {
+        proof: EquivocationProof {
+            offender: FarmerPublicKey::unchecked_from(keypair.public.to_bytes()),
+            slot: Slot::from(slot),
+            first_header: signed_header(&keypair, slot, 0, 0),
+            second_header: signed_header(&keypair, slot, 1, 0),
+        },
+        reported: slot % 2 == 0,
+    };
+
+    let total = MAX_STORED_EVIDENCE as u64 + 2;
+    for slot in 0..total {
+        evidence_store.add_evidence(evidence(slot)).unwrap();
+    }
+
+    let stored_evidence = evidence_store.evidence::<Header>().unwrap();
+    assert_eq!(stored_evidence.len(), MAX_STORED_EVIDENCE);
+    // Oldest evidence is removed, the rest is returned from the oldest to the newest
+    for (stored_evidence, slot) in stored_evidence.iter().zip(2..total) {
+        assert_eq!(stored_evidence.proof.slot, Slot::from(slot));
+        assert_eq!(stored_evidence.reported, slot % 2 == 0);
+    }
+
+    // Evidence survives re-opening the store
+    let evidence_store = EquivocationEvidenceStore::new(Arc::clone(&evidence_store.aux_store));
+    assert_eq!(
+        evidence_store.evidence::<Header>().unwrap().len(),
+        MAX_STORED_EVIDENCE
+    );
+}
@@ -29,7 +29,9 @@
 pub mod archiver;
 pub mod aux_schema;
 pub mod block_import;
+pub mod equivocation;
 pub mod notification;
+pub mod object_mappings;
 pub mod slot_worker;
 #[cfg(test)]
 mod tests;
@@ -38,7 +40,9 @@ pub mod verifier;
 use crate::archiver::{ArchivedSegmentNotification, FINALIZATION_DEPTH_IN_SEGMENTS};
 use crate::block_import::BlockImportingNotification;
 use crate::notification::{SubspaceNotificationSender, SubspaceNotificationStream};
-use crate::slot_worker::{NewSlotNotification, RewardSigningNotification};
+use crate::slot_worker::{
+    NewSlotNotification, RewardSigningNotification, SolutionOutcomeNotification,
+};
 use lru::LruCache;
 use parking_lot::Mutex;
 use sp_consensus_subspace::ChainConstants;
@@ -55,6 +59,8 @@ pub struct SubspaceLink<Block: BlockT> {
     new_slot_notification_stream: SubspaceNotificationStream<NewSlotNotification>,
     reward_signing_notification_sender: SubspaceNotificationSender<RewardSigningNotification>,
     reward_signing_notification_stream: SubspaceNotificationStream<RewardSigningNotification>,
+    solution_outcome_notification_sender: SubspaceNotificationSender<SolutionOutcomeNotification>,
+    solution_outcome_notification_stream: SubspaceNotificationStream<SolutionOutcomeNotification>,
     archived_segment_notification_sender: SubspaceNotificationSender<ArchivedSegmentNotification>,
     archived_segment_notification_stream: SubspaceNotificationStream<ArchivedSegmentNotification>,
     block_importing_notification_sender:
@@ -75,6 +81,8 @@ impl<Block: BlockT> SubspaceLink<Block> {
             notification::channel("subspace_new_slot_notification_stream");
         let (reward_signing_notification_sender, reward_signing_notification_stream) =
             notification::channel("subspace_reward_signing_notification_stream");
+        let (solution_outcome_notification_sender, solution_outcome_notification_stream) =
+            notification::channel("subspace_solution_outcome_notification_stream");
         let (archived_segment_notification_sender, archived_segment_notification_stream) =
             notification::channel("subspace_archived_segment_notification_stream");
         let (block_importing_notification_sender, block_importing_notification_stream) =
@@ -85,6 +93,8 @@ impl<Block: BlockT> SubspaceLink<Block> {
             new_slot_notification_stream,
             reward_signing_notification_sender,
             reward_signing_notification_stream,
+            solution_outcome_notification_sender,
+            solution_outcome_notification_stream,
             archived_segment_notification_sender,
             archived_segment_notification_stream,
             block_importing_notification_sender,
@@ -111,6 +121,14 @@ impl<Block: BlockT> SubspaceLink<Block> {
         self.reward_signing_notification_stream.clone()
     }
 
+    /// Get stream with notifications about outcomes of solutions submitted by farmers, including
+    /// reasons why solutions were not used.
+    pub fn solution_outcome_notification_stream(
+        &self,
+    ) -> SubspaceNotificationStream<SolutionOutcomeNotification> {
+        self.solution_outcome_notification_stream.clone()
+    }
+
     /// Get stream with notifications about archived segment creation
     pub fn archived_segment_notification_stream(
         &self,
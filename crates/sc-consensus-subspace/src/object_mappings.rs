@@ -0,0 +1,414 @@
+// Copyright (C) 2024 Subspace Labs, Inc.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Optional persistent index of object mappings.
+//!
+//! Archiver produces object mappings for every archived segment, but doesn't persist them. This
+//! module contains [`ObjectMappingsIndex`] that stores mapping from object hash to its location in
+//! archived history ([`GlobalObject`]) in auxiliary storage of the node, such that applications can
+//! locate their objects without running their own indexer.
+//!
+//! [`create_object_mappings_indexer`] creates a task that indexes newly archived segments, while
+//! [`backfill_object_mappings`] re-creates and indexes segments that were archived before indexing
+//! was enabled. Backfilling re-archives blocks starting with the beginning of requested segment,
+//! hence it requires corresponding blocks and their state to be available in the database.
+
+#[cfg(test)]
+mod tests;
+
+use crate::archiver::{encode_block, ArchivedSegmentNotification, SegmentHeadersStore};
+use crate::notification::{self, SubspaceNotificationSender, SubspaceNotificationStream};
+use crate::SubspaceLink;
+use codec::{Decode, Encode};
+use futures::StreamExt;
+use parking_lot::Mutex;
+use sc_client_api::{AuxStore, BlockBackend};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_objects::ObjectsApi;
+use sp_runtime::generic::SignedBlock;
+use sp_runtime::traits::{Block as BlockT, Header, Zero};
+use std::future::Future;
+use std::sync::Arc;
+use subspace_archiving::archiver::{Archiver, NewArchivedSegment};
+use subspace_core_primitives::crypto::kzg::Kzg;
+use subspace_core_primitives::objects::{BlockObjectMapping, GlobalObject};
+use subspace_core_primitives::{Blake3Hash, SegmentIndex};
+use tracing::{debug, info};
+
+/// Location of the object with specified hash in archived history.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ObjectMapping {
+    /// Object hash
+    pub hash: Blake3Hash,
+    /// Object location
+    pub global_object: GlobalObject,
+}
+
+/// Notification with object mappings of newly indexed segment.
+#[derive(Debug, Clone)]
+pub struct ObjectMappingsNotification {
+    /// Segment index that was indexed
+    pub segment_index: SegmentIndex,
+    /// Object mappings contained in the segment
+    pub object_mappings: Arc<Vec<ObjectMapping>>,
+}
+
+struct ObjectMappingsIndexInner<AS> {
+    aux_store: Arc<AS>,
+    /// Indexer and backfilling may process the same objects concurrently, checking and updating
+    /// of the stored mappings must not interleave between them
+    index_lock: Mutex<()>,
+    notification_sender: SubspaceNotificationSender<ObjectMappingsNotification>,
+    notification_stream: SubspaceNotificationStream<ObjectMappingsNotification>,
+}
+
+/// Persistent index of object mappings, allows to find location of the object in archived history
+/// by its hash.
+///
+/// If the same object was archived multiple times, location in the latest segment is stored,
+/// regardless of the order in which segments were indexed.
+pub struct ObjectMappingsIndex<AS> {
+    inner: Arc<ObjectMappingsIndexInner<AS>>,
+}
+
+impl<AS> Clone for ObjectMappingsIndex<AS> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: Arc::clone(&self.inner),
+        }
+    }
+}
+
+impl<AS> ObjectMappingsIndex<AS>
+where
+    AS: AuxStore,
+{
+    const KEY_PREFIX: &'static [u8] = b"object-mapping";
+
+    /// Create new instance
+    pub fn new(aux_store: Arc<AS>) -> Self {
+        let (notification_sender, notification_stream) =
+            notification::channel("subspace_object_mappings_notification_stream");
+
+        Self {
+            inner: Arc::new(ObjectMappingsIndexInner {
+                aux_store,
+                index_lock: Mutex::default(),
+                notification_sender,
+                notification_stream,
+            }),
+        }
+    }
+
+    /// Get location of the object by its hash
+    pub fn get_object_mapping(
+        &self,
+        hash: &Blake3Hash,
+    ) -> sp_blockchain::Result<Option<GlobalObject>> {
+        self.inner
+            .aux_store
+            .get_aux(&Self::key(hash))?
+            .map(|global_object| {
+                GlobalObject::decode(&mut global_object.as_slice()).map_err(|error| {
+                    sp_blockchain::Error::Application(
+                        format!("Failed to decode object mapping: {error}").into(),
+                    )
+                })
+            })
+            .transpose()
+    }
+
+    /// Get stream with notifications about object mappings of newly indexed segments
+    pub fn object_mappings_notification_stream(
+        &self,
+    ) -> SubspaceNotificationStream<ObjectMappingsNotification> {
+        self.inner.notification_stream.clone()
+    }
+
+    /// Store object mappings of archived segment and notify subscribers about them
+    fn index_archived_segment(
+        &self,
+        archived_segment: &NewArchivedSegment,
+    ) -> sp_blockchain::Result<()> {
+        self.index_object_mappings(
+            archived_segment.segment_header.segment_index(),
+            object_mappings(archived_segment),
+        )
+    }
+
+    /// Store object mappings of the segment unless objects were already indexed in later segments
+    /// (which happens during backfilling) and notify subscribers about them
+    pub fn index_object_mappings(
+        &self,
+        segment_index: SegmentIndex,
+        object_mappings: Vec<ObjectMapping>,
+    ) -> sp_blockchain::Result<()> {
+        debug!(
+            %segment_index,
+            object_mappings = %object_mappings.len(),
+            "Indexing object mappings"
+        );
+
+        if object_mappings.is_empty() {
+            return Ok(());
+        }
+
+        let index_guard = self.inner.index_lock.lock();
+        let mut insert_data = Vec::with_capacity(object_mappings.len());
+        for object_mapping in &object_mappings {
+            if let Some(global_object) = self.get_object_mapping(&object_mapping.hash)?
+                && global_object.piece_index().segment_index() > segment_index
+            {
+                continue;
+            }
+
+            insert_data.push((
+                Self::key(&object_mapping.hash),
+                object_mapping.global_object.encode(),
+            ));
+        }
+        self.inner.aux_store.insert_aux(
+            &insert_data
+                .iter()
+                .map(|(key, value)| (key.as_slice(), value.as_slice()))
+                .collect::<Vec<_>>(),
+            &[],
+        )?;
+        drop(index_guard);
+
+        let object_mappings = Arc::new(object_mappings);
+        self.inner
+            .notification_sender
+            .notify(move || ObjectMappingsNotification {
+                segment_index,
+                object_mappings,
+            });
+
+        Ok(())
+    }
+
+    fn key(hash: &Blake3Hash) -> Vec<u8> {
+        (Self::KEY_PREFIX, hash).encode()
+    }
+}
+
+/// Convert object mappings of source pieces in archived segment into global object mappings
+fn object_mappings(archived_segment: &NewArchivedSegment) -> Vec<ObjectMapping> {
+    let segment_index = archived_segment.segment_header.segment_index();
+
+    archived_segment
+        .object_mapping
+        .iter()
+        .enumerate()
+        .flat_map(move |(source_position, piece_object_mapping)| {
+            let piece_index = segment_index.source_piece_index(source_position as u32);
+
+            piece_object_mapping
+                .objects
+                .iter()
+                .map(move |piece_object| ObjectMapping {
+                    hash: piece_object.hash(),
+                    global_object: GlobalObject::V0 {
+                        piece_index,
+                        offset: piece_object.offset(),
+                    },
+                })
+        })
+        .collect()
+}
+
+/// Create a task that indexes object mappings of newly archived segments.
+///
+/// Subscription to archived segments happens immediately, such that segments archived during
+/// archiver initialization are indexed too. Archiver waits for segment to be indexed before
+/// proceeding.
+pub fn create_object_mappings_indexer<Block, AS>(
+    object_mappings_index: ObjectMappingsIndex<AS>,
+    subspace_link: &SubspaceLink<Block>,
+) -> impl Future<Output = sp_blockchain::Result<()>> + Send + 'static
+where
+    Block: BlockT,
+    AS: AuxStore + Send + Sync + 'static,
+{
+    let mut archived_segment_notification_stream = subspace_link
+        .archived_segment_notification_stream
+        .subscribe();
+
+    async move {
+        while let Some(ArchivedSegmentNotification {
+            archived_segment,
+            // Archiving will not continue until mappings are persisted
+            acknowledgement_sender: _acknowledgement_sender,
+        }) = archived_segment_notification_stream.next().await
+        {
+            object_mappings_index.index_archived_segment(&archived_segment)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Re-create segments starting with `from_segment_index` and index their object mappings.
+///
+/// Backfilling stops at the last segment known to segment headers store or once blocks are no
+/// longer available (segment headers store may know about segments that are not imported yet).
+/// This is a blocking function that should be called on a blocking thread.
+pub fn backfill_object_mappings<Block, Client, AS>(
+    object_mappings_index: &ObjectMappingsIndex<AS>,
+    segment_headers_store: &SegmentHeadersStore<AS>,
+    client: &Client,
+    kzg: Kzg,
+    from_segment_index: SegmentIndex,
+) -> sp_blockchain::Result<()>
+where
+    Block: BlockT,
+    Client: ProvideRuntimeApi<Block> + BlockBackend<Block> + HeaderBackend<Block>,
+    Client::Api: ObjectsApi<Block>,
+    AS: AuxStore,
+{
+    let Some(to_segment_index) = segment_headers_store.max_segment_index() else {
+        return Ok(());
+    };
+
+    if from_segment_index > to_segment_index {
+        return Ok(());
+    }
+
+    info!(
+        %from_segment_index,
+        %to_segment_index,
+        "Backfilling object mappings"
+    );
+
+    let mut archiver = if from_segment_index == SegmentIndex::ZERO {
+        Archiver::new(kzg)
+    } else {
+        let previous_segment_index = from_segment_index - SegmentIndex::ONE;
+        let previous_segment_header = segment_headers_store
+            .get_segment_header(previous_segment_index)
+            .ok_or_else(|| {
+                sp_blockchain::Error::Application(
+                    format!("Segment header {previous_segment_index} is missing").into(),
+                )
+            })?;
+
+        let last_archived_block_number = previous_segment_header.last_archived_block().number;
+        let Some(last_archived_block) = block_by_number(client, last_archived_block_number)? else {
+            return Err(sp_blockchain::Error::Application(
+                format!("Last archived block {last_archived_block_number} is not available").into(),
+            ));
+        };
+        let block_object_mappings = block_object_mappings(client, &last_archived_block)?;
+
+        Archiver::with_initial_state(
+            kzg,
+            previous_segment_header,
+            &encode_block(last_archived_block),
+            block_object_mappings,
+        )
+    }
+    .map_err(|error| {
+        sp_blockchain::Error::Application(
+            format!("Failed to instantiate archiver for backfilling: {error}").into(),
+        )
+    })?;
+
+    let mut block_number = archiver
+        .last_archived_block_number()
+        .map(|block_number| block_number + 1)
+        .unwrap_or_default();
+
+    loop {
+        let Some(block) = block_by_number(client, block_number)? else {
+            info!(
+                %block_number,
+                "Block is not available, stopping object mappings backfilling"
+            );
+
+            return Ok(());
+        };
+        let block_object_mappings = block_object_mappings(client, &block)?;
+
+        for archived_segment in
+            archiver.add_block(encode_block(block), block_object_mappings, false)
+        {
+            let segment_index = archived_segment.segment_header.segment_index();
+
+            if segment_index < from_segment_index {
+                continue;
+            }
+
+            object_mappings_index.index_archived_segment(&archived_segment)?;
+
+            if segment_index >= to_segment_index {
+                info!(%segment_index, "Finished object mappings backfilling");
+
+                return Ok(());
+            }
+        }
+
+        block_number += 1;
+    }
+}
+
+fn block_by_number<Block, Client>(
+    client: &Client,
+    block_number: u32,
+) -> sp_blockchain::Result<Option<SignedBlock<Block>>>
+where
+    Block: BlockT,
+    Client: BlockBackend<Block> + HeaderBackend<Block>,
+{
+    let Some(block_hash) = client.hash(block_number.into())? else {
+        return Ok(None);
+    };
+
+    client.block(block_hash)
+}
+
+fn block_object_mappings<Block, Client>(
+    client: &Client,
+    block: &SignedBlock<Block>,
+) -> sp_blockchain::Result<BlockObjectMapping>
+where
+    Block: BlockT,
+    Client: ProvideRuntimeApi<Block>,
+    Client::Api: ObjectsApi<Block>,
+{
+    let header = block.block.header();
+
+    // Genesis block has no parent to extract object mappings with
+    if header.number().is_zero() {
+        return Ok(BlockObjectMapping::default());
+    }
+
+    client
+        .runtime_api()
+        .validated_object_call_hashes(header.hash())
+        .and_then(|calls| {
+            client.runtime_api().extract_block_object_mapping(
+                *header.parent_hash(),
+                block.block.clone(),
+                calls,
+            )
+        })
+        .map_err(|error| {
+            sp_blockchain::Error::Application(
+                format!("Failed to retrieve block object mappings: {error}").into(),
+            )
+        })
+}
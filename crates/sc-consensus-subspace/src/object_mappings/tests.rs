@@ -0,0 +1,161 @@
+use crate::object_mappings::{ObjectMapping, ObjectMappingsIndex, ObjectMappingsNotification};
+use futures::{FutureExt, StreamExt};
+use sc_client_api::in_mem::Backend;
+use sp_runtime::traits::BlakeTwo256;
+use sp_runtime::OpaqueExtrinsic;
+use std::sync::Arc;
+use subspace_core_primitives::objects::GlobalObject;
+use subspace_core_primitives::{Blake3Hash, SegmentIndex};
+
+type Block =
+    sp_runtime::generic::Block<sp_runtime::generic::Header<u32, BlakeTwo256>, OpaqueExtrinsic>;
+
+fn object_mapping(hash: Blake3Hash, segment_index: u64, source_position: u32) -> ObjectMapping {
+    ObjectMapping {
+        hash,
+        global_object: GlobalObject::V0 {
+            piece_index: SegmentIndex::from(segment_index).source_piece_index(source_position),
+            offset: 100,
+        },
+    }
+}
+
+#[test]
+fn object_mappings_are_indexed() {
+    let object_mappings_index = ObjectMappingsIndex::new(Arc::new(Backend::<Block>::new()));
+    let mut object_mappings_notifications = object_mappings_index
+        .object_mappings_notification_stream()
+        .subscribe();
+
+    let object_mappings = vec![object_mapping([1; 32], 1, 0), object_mapping([2; 32], 1, 5)];
+    object_mappings_index
+        .index_object_mappings(SegmentIndex::ONE, object_mappings.clone())
+        .unwrap();
+
+    for object_mapping in &object_mappings {
+        assert_eq!(
+            object_mappings_index
+                .get_object_mapping(&object_mapping.hash)
+                .unwrap(),
+            Some(object_mapping.global_object)
+        );
+    }
+    assert_eq!(
+        object_mappings_index.get_object_mapping(&[3; 32]).unwrap(),
+        None
+    );
+
+    let ObjectMappingsNotification {
+        segment_index,
+        object_mappings: notified_object_mappings,
+    } = object_mappings_notifications
+        .next()
+        .now_or_never()
+        .unwrap()
+        .unwrap();
+    assert_eq!(segment_index, SegmentIndex::ONE);
+    assert_eq!(*notified_object_mappings, object_mappings);
+
+    // Segments without objects are not announced
+    object_mappings_index
+        .index_object_mappings(SegmentIndex::from(2), Vec::new())
+        .unwrap();
+    assert!(object_mappings_notifications
+        .next()
+        .now_or_never()
+        .is_none());
+}
+
+#[test]
+fn backfilling_preserves_newer_object_mappings() {
+    let object_mappings_index = ObjectMappingsIndex::new(Arc::new(Backend::<Block>::new()));
+
+    // Object is indexed as part of newly archived segment first
+    let new_object_mapping = object_mapping([1; 32], 5, 3);
+    object_mappings_index
+        .index_object_mappings(SegmentIndex::from(5), vec![new_object_mapping])
+        .unwrap();
+
+    // Then older segments that contain the same object are backfilled
+    let old_object_mapping = object_mapping([1; 32], 2, 7);
+    let other_object_mapping = object_mapping([2; 32], 2, 8);
+    object_mappings_index
+        .index_object_mappings(
+            SegmentIndex::from(2),
+            vec![old_object_mapping, other_object_mapping],
+        )
+        .unwrap();
+
+    assert_eq!(
+        object_mappings_index
+            .get_object_mapping(&new_object_mapping.hash)
+            .unwrap(),
+        Some(new_object_mapping.global_object)
+    );
+    assert_eq!(
+        object_mappings_index
+            .get_object_mapping(&other_object_mapping.hash)
+            .unwrap(),
+        Some(other_object_mapping.global_object)
+    );
+
+    // Another location in the same segment replaces previous one
+    let same_segment_object_mapping = object_mapping([1; 32], 5, 9);
+    object_mappings_index
+        .index_object_mappings(SegmentIndex::from(5), vec![same_segment_object_mapping])
+        .unwrap();
+    assert_eq!(
+        object_mappings_index
+            .get_object_mapping(&new_object_mapping.hash)
+            .unwrap(),
+        Some(same_segment_object_mapping.global_object)
+    );
+
+    // And location in later segment replaces it too
+    let newer_object_mapping = object_mapping([1; 32], 6, 0);
+    object_mappings_index
+        .index_object_mappings(SegmentIndex::from(6), vec![newer_object_mapping])
+        .unwrap();
+    assert_eq!(
+        object_mappings_index
+            .get_object_mapping(&new_object_mapping.hash)
+            .unwrap(),
+        Some(newer_object_mapping.global_object)
+    );
+}
+
+#[test]
+fn concurrent_indexing_preserves_newer_object_mappings() {
+    let object_mappings_index = ObjectMappingsIndex::new(Arc::new(Backend::<Block>::new()));
+    let hashes = (0..=u8::MAX).map(|byte| [byte; 32]).collect::<Vec<_>>();
+
+    // Newly archived segment and backfilled segment with the same objects are indexed at the same
+    // time, like it happens with indexer and backfilling running concurrently
+    std::thread::scope(|scope| {
+        for segment_index in [2, 5] {
+            let object_mappings_index = &object_mappings_index;
+            let object_mappings = hashes
+                .iter()
+                .map(|&hash| object_mapping(hash, segment_index, 0))
+                .collect::<Vec<_>>();
+
+            scope.spawn(move || {
+                for object_mapping in object_mappings {
+                    object_mappings_index
+                        .index_object_mappings(
+                            SegmentIndex::from(segment_index),
+                            vec![object_mapping],
+                        )
+                        .unwrap();
+                }
+            });
+        }
+    });
+
+    for hash in &hashes {
+        assert_eq!(
+            object_mappings_index.get_object_mapping(hash).unwrap(),
+            Some(object_mapping(*hash, 5, 0).global_object)
+        );
+    }
+}
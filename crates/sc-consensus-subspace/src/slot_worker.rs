@@ -31,7 +31,11 @@
 //! to the base Substrate behavior where major syncing is assumed to not happen in case authoring is
 //! forced.
 
+#[cfg(test)]
+mod tests;
+
 use crate::archiver::SegmentHeadersStore;
+use crate::notification::SubspaceNotificationSender;
 use crate::SubspaceLink;
 use futures::channel::mpsc;
 use futures::{StreamExt, TryFutureExt};
@@ -65,12 +69,13 @@ use sp_runtime::{DigestItem, Justification, Justifications};
 use std::collections::BTreeMap;
 use std::future::Future;
 use std::marker::PhantomData;
+use std::mem;
 use std::pin::Pin;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use subspace_core_primitives::{
-    BlockNumber, PotCheckpoints, PotOutput, PublicKey, RewardSignature, SectorId, Solution,
-    SolutionRange, REWARD_SIGNING_CONTEXT,
+    BlockNumber, PieceOffset, PotCheckpoints, PotOutput, PublicKey, RewardSignature, SectorId,
+    SectorIndex, SegmentIndex, Solution, SolutionRange, REWARD_SIGNING_CONTEXT,
 };
 use subspace_proof_of_space::Table;
 use subspace_verification::{
@@ -152,6 +157,86 @@ pub struct NewSlotNotification {
     /// Sender that can be used to send solutions for the slot.
     pub solution_sender: mpsc::Sender<Solution<FarmerPublicKey, FarmerPublicKey>>,
 }
+
+/// Reason why solution submitted by farmer didn't result in block or vote.
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum SolutionRejectionReason {
+    /// Only solutions of root plot public key are accepted
+    #[error("Only solutions of root plot public key are accepted")]
+    NotRootPlotPublicKey,
+    /// Farmer is in block list
+    #[error("Farmer is in block list")]
+    InBlockList,
+    /// Segment commitment of the segment solution refers to is not known to the node
+    #[error("Segment commitment of segment {segment_index} not found")]
+    SegmentCommitmentNotFound {
+        /// Segment index
+        segment_index: SegmentIndex,
+    },
+    /// Sector expiration check segment can't be derived from solution's history size
+    #[error("Sector expiration check segment can't be derived from history size")]
+    InvalidSectorExpirationCheck,
+    /// Solution failed verification
+    #[error("Invalid solution: {0}")]
+    InvalidSolution(subspace_verification::Error),
+    /// Solution has quality sufficient for block, but block was already claimed with another
+    /// solution at this slot
+    #[error("Block was already claimed at this slot")]
+    BlockAlreadyClaimed,
+    /// Votes are not created on top of genesis block
+    #[error("Votes are not created on top of genesis block")]
+    VoteOnGenesisBlock,
+    /// Solution arrived for slot that node didn't try to claim, for example because it was behind
+    /// the best block already
+    #[error("Slot was not claimed")]
+    SlotNotClaimed,
+    /// Solution arrived after slot was already claimed
+    #[error("Solution arrived after slot was already claimed")]
+    TooLate,
+}
+
+/// Outcome of the solution submitted by farmer.
+#[derive(Debug, Clone)]
+pub enum SolutionOutcome {
+    /// Solution was used to claim block
+    ClaimedBlock,
+    /// Solution was used to claim vote
+    ClaimedVote,
+    /// Solution was not used
+    Rejected(SolutionRejectionReason),
+}
+
+/// Notification about outcome of the solution submitted by farmer.
+#[derive(Debug, Clone)]
+pub struct SolutionOutcomeNotification {
+    /// Slot for which solution was submitted
+    pub slot: Slot,
+    /// Public key of the farmer
+    pub public_key: FarmerPublicKey,
+    /// Index of the sector where solution was found
+    pub sector_index: SectorIndex,
+    /// Piece offset within sector
+    pub piece_offset: PieceOffset,
+    /// Outcome of the solution
+    pub outcome: SolutionOutcome,
+}
+
+impl SolutionOutcomeNotification {
+    fn new(
+        slot: Slot,
+        solution: &Solution<FarmerPublicKey, FarmerPublicKey>,
+        outcome: SolutionOutcome,
+    ) -> Self {
+        Self {
+            slot,
+            public_key: solution.public_key.clone(),
+            sector_index: solution.sector_index,
+            piece_offset: solution.piece_offset,
+            outcome,
+        }
+    }
+}
+
 /// Notification with a hash that needs to be signed to receive reward and sender for signature.
 #[derive(Debug, Clone)]
 pub struct RewardSigningNotification {
@@ -231,12 +316,131 @@ where
     /// Solution receivers for challenges that were sent to farmers and expected to be received
     /// eventually
     pending_solutions: BTreeMap<Slot, mpsc::Receiver<Solution<FarmerPublicKey, FarmerPublicKey>>>,
+    /// Solution receivers for slots that were already claimed, solutions that arrive there are
+    /// rejected as late
+    late_solutions: BTreeMap<Slot, mpsc::Receiver<Solution<FarmerPublicKey, FarmerPublicKey>>>,
     /// Collection of PoT slots that can be retrieved later if needed by block production
     pot_checkpoints: BTreeMap<Slot, PotCheckpoints>,
     pot_verifier: PotVerifier,
     _pos_table: PhantomData<PosTable>,
 }
 
+impl<PosTable, Block, Client, E, SO, L, BS, AS>
+    SubspaceSlotWorker<PosTable, Block, Client, E, SO, L, BS, AS>
+where
+    Block: BlockT,
+    SO: SyncOracle + Send + Sync,
+{
+    fn notify_solution_outcome(
+        &self,
+        slot: Slot,
+        solution: &Solution<FarmerPublicKey, FarmerPublicKey>,
+        outcome: SolutionOutcome,
+    ) {
+        self.subspace_link
+            .solution_outcome_notification_sender
+            .notify(|| SolutionOutcomeNotification::new(slot, solution, outcome));
+    }
+}
+
+/// Take solution receiver for `slot`, solutions that arrived for older slots (that were never
+/// claimed) are rejected and their receivers are removed.
+fn take_solution_receiver(
+    pending_solutions: &mut BTreeMap<
+        Slot,
+        mpsc::Receiver<Solution<FarmerPublicKey, FarmerPublicKey>>,
+    >,
+    slot: Slot,
+    solution_outcome_notification_sender: &SubspaceNotificationSender<SolutionOutcomeNotification>,
+) -> Option<mpsc::Receiver<Solution<FarmerPublicKey, FarmerPublicKey>>> {
+    let newer_solutions = pending_solutions.split_off(&slot);
+    let outdated_solutions = mem::replace(pending_solutions, newer_solutions);
+    for (outdated_slot, mut solution_receiver) in outdated_solutions {
+        solution_receiver.close();
+        while let Ok(Some(solution)) = solution_receiver.try_next() {
+            debug!(
+                slot = %outdated_slot,
+                public_key = %solution.public_key,
+                "Solution arrived for slot that was not claimed"
+            );
+            solution_outcome_notification_sender.notify(|| {
+                SolutionOutcomeNotification::new(
+                    outdated_slot,
+                    &solution,
+                    SolutionOutcome::Rejected(SolutionRejectionReason::SlotNotClaimed),
+                )
+            });
+        }
+    }
+
+    pending_solutions.remove(&slot)
+}
+
+/// Reject solutions that arrived for already claimed slots, receivers are kept for
+/// block authoring delay slots since farmers are not expected to be slower than that.
+fn reject_late_solutions(
+    late_solutions: &mut BTreeMap<Slot, mpsc::Receiver<Solution<FarmerPublicKey, FarmerPublicKey>>>,
+    slot: Slot,
+    block_authoring_delay: Slot,
+    solution_outcome_notification_sender: &SubspaceNotificationSender<SolutionOutcomeNotification>,
+) {
+    for (claimed_slot, mut solution_receiver) in mem::take(late_solutions) {
+        let keep = claimed_slot + block_authoring_delay >= slot;
+        if !keep {
+            solution_receiver.close();
+        }
+
+        while let Ok(Some(solution)) = solution_receiver.try_next() {
+            debug!(
+                slot = %claimed_slot,
+                public_key = %solution.public_key,
+                "Solution arrived after slot was already claimed"
+            );
+            solution_outcome_notification_sender.notify(|| {
+                SolutionOutcomeNotification::new(
+                    claimed_slot,
+                    &solution,
+                    SolutionOutcome::Rejected(SolutionRejectionReason::TooLate),
+                )
+            });
+        }
+
+        if keep {
+            late_solutions.insert(claimed_slot, solution_receiver);
+        }
+    }
+}
+
+/// Decide what solution is used for based on the result of its verification against voting
+/// solution range.
+fn verified_solution_outcome(
+    solution_verification_result: Result<SolutionRange, subspace_verification::Error>,
+    solution_range: SolutionRange,
+    block_already_claimed: bool,
+    parent_is_genesis: bool,
+) -> SolutionOutcome {
+    match solution_verification_result {
+        Ok(solution_distance) => {
+            // If solution is of high enough quality and block pre-digest wasn't produced yet,
+            // block reward is claimed
+            if solution_distance <= solution_range / 2 {
+                if block_already_claimed {
+                    SolutionOutcome::Rejected(SolutionRejectionReason::BlockAlreadyClaimed)
+                } else {
+                    SolutionOutcome::ClaimedBlock
+                }
+            } else if parent_is_genesis {
+                // Not sending vote on top of genesis block since segment headers since piece
+                // verification wouldn't be possible due to missing (for now) segment commitment
+                SolutionOutcome::Rejected(SolutionRejectionReason::VoteOnGenesisBlock)
+            } else {
+                SolutionOutcome::ClaimedVote
+            }
+        }
+        Err(error) => SolutionOutcome::Rejected(SolutionRejectionReason::InvalidSolution(error)),
+    }
+}
+
 impl<PosTable, Block, Client, E, SO, L, BS, AS> PotSlotWorker<Block>
     for SubspaceSlotWorker<PosTable, Block, Client, E, SO, L, BS, AS>
 where
@@ -252,6 +456,13 @@ where
 
         self.pot_checkpoints.insert(slot, checkpoints);
 
+        reject_late_solutions(
+            &mut self.late_solutions,
+            slot,
+            self.subspace_link.chain_constants().block_authoring_delay(),
+            &self.subspace_link.solution_outcome_notification_sender,
+        );
+
         if self.sync_oracle.is_major_syncing() {
             debug!("Skipping farming slot {slot} due to sync");
             return;
@@ -502,24 +713,27 @@ where
             (proof_of_time, future_proof_of_time, pot_justification)
         };
 
-        let mut solution_receiver = {
-            // Remove receivers for old slots we will not need anymore
-            self.pending_solutions
-                .retain(|&stored_slot, _solution_receiver| stored_slot >= slot);
-
-            let mut solution_receiver = self.pending_solutions.remove(&slot)?;
-            // Time is out, we will not accept any more solutions
-            solution_receiver.close();
-            solution_receiver
-        };
+        // Remove receivers for old slots we will not need anymore
+        let mut solution_receiver = take_solution_receiver(
+            &mut self.pending_solutions,
+            slot,
+            &self.subspace_link.solution_outcome_notification_sender,
+        )?;
 
         let mut maybe_pre_digest = None;
 
-        while let Some(solution) = solution_receiver.next().await {
+        // Time is out, only solutions that have already arrived are considered, the rest will be
+        // rejected as late
+        while let Ok(Some(solution)) = solution_receiver.try_next() {
             if let Some(root_plot_public_key) = &maybe_root_plot_public_key {
                 if &solution.public_key != root_plot_public_key {
                     // Only root plot public key is allowed, no need to even try to claim block or
                     // vote.
+                    self.notify_solution_outcome(
+                        slot,
+                        &solution,
+                        SolutionOutcome::Rejected(SolutionRejectionReason::NotRootPlotPublicKey),
+                    );
                     continue;
                 }
             }
@@ -535,6 +749,11 @@ where
                     public_key = %solution.public_key,
                     "Ignoring solution provided by farmer in block list",
                 );
+                self.notify_solution_outcome(
+                    slot,
+                    &solution,
+                    SolutionOutcome::Rejected(SolutionRejectionReason::InBlockList),
+                );
 
                 continue;
             }
@@ -569,6 +788,13 @@ where
                         %segment_index,
                         "Segment commitment not found",
                     );
+                    self.notify_solution_outcome(
+                        slot,
+                        &solution,
+                        SolutionOutcome::Rejected(
+                            SolutionRejectionReason::SegmentCommitmentNotFound { segment_index },
+                        ),
+                    );
                     continue;
                 }
             };
@@ -578,6 +804,13 @@ where
             {
                 Some(sector_expiration_check) => sector_expiration_check.segment_index(),
                 None => {
+                    self.notify_solution_outcome(
+                        slot,
+                        &solution,
+                        SolutionOutcome::Rejected(
+                            SolutionRejectionReason::InvalidSectorExpirationCheck,
+                        ),
+                    );
                     continue;
                 }
             };
@@ -604,44 +837,30 @@ where
                 &self.subspace_link.kzg,
             );
 
-            match solution_verification_result {
-                Ok(solution_distance) => {
-                    // If solution is of high enough quality and block pre-digest wasn't produced yet,
-                    // block reward is claimed
-                    if solution_distance <= solution_range / 2 {
-                        if maybe_pre_digest.is_none() {
-                            info!(%slot, "🚜 Claimed block at slot");
-                            maybe_pre_digest.replace(PreDigest::V0 {
-                                slot,
-                                solution,
-                                pot_info: PreDigestPotInfo::V0 {
-                                    proof_of_time,
-                                    future_proof_of_time,
-                                },
-                            });
-                        } else {
-                            info!(
-                                %slot,
-                                "Skipping solution that has quality sufficient for block because \
-                                block pre-digest was already created",
-                            );
-                        }
-                    } else if !parent_header.number().is_zero() {
-                        // Not sending vote on top of genesis block since segment headers since piece
-                        // verification wouldn't be possible due to missing (for now) segment commitment
-                        info!(%slot, "🗳️ Claimed vote at slot");
-
-                        self.create_vote(
-                            parent_header,
-                            slot,
-                            solution,
-                            proof_of_time,
-                            future_proof_of_time,
-                        )
-                        .await;
-                    }
+            let solution_outcome = verified_solution_outcome(
+                solution_verification_result,
+                solution_range,
+                maybe_pre_digest.is_some(),
+                parent_header.number().is_zero(),
+            );
+
+            match &solution_outcome {
+                SolutionOutcome::ClaimedBlock => {
+                    info!(%slot, "🚜 Claimed block at slot");
+                }
+                SolutionOutcome::ClaimedVote => {
+                    info!(%slot, "🗳️ Claimed vote at slot");
+                }
+                SolutionOutcome::Rejected(SolutionRejectionReason::BlockAlreadyClaimed) => {
+                    info!(
+                        %slot,
+                        "Skipping solution that has quality sufficient for block because \
+                        block pre-digest was already created",
+                    );
                 }
-                Err(error @ subspace_verification::Error::OutsideSolutionRange { .. }) => {
+                SolutionOutcome::Rejected(SolutionRejectionReason::InvalidSolution(
+                    error @ subspace_verification::Error::OutsideSolutionRange { .. },
+                )) => {
                     // Solution range might have just adjusted, but when farmer was auditing they
                     // didn't know about this, so downgrade warning to debug message
                     if runtime_api
@@ -663,16 +882,45 @@ where
                         );
                     }
                 }
-                Err(error) => {
+                SolutionOutcome::Rejected(SolutionRejectionReason::InvalidSolution(error)) => {
                     warn!(
                         %slot,
                         %error,
                         "Invalid solution received",
                     );
                 }
+                SolutionOutcome::Rejected(_) => {}
+            }
+
+            self.notify_solution_outcome(slot, &solution, solution_outcome.clone());
+
+            match solution_outcome {
+                SolutionOutcome::ClaimedBlock => {
+                    maybe_pre_digest.replace(PreDigest::V0 {
+                        slot,
+                        solution,
+                        pot_info: PreDigestPotInfo::V0 {
+                            proof_of_time,
+                            future_proof_of_time,
+                        },
+                    });
+                }
+                SolutionOutcome::ClaimedVote => {
+                    self.create_vote(
+                        parent_header,
+                        slot,
+                        solution,
+                        proof_of_time,
+                        future_proof_of_time,
+                    )
+                    .await;
+                }
+                SolutionOutcome::Rejected(_) => {}
             }
         }
 
+        self.late_solutions.insert(slot, solution_receiver);
+
         maybe_pre_digest.map(|pre_digest| (pre_digest, pot_justification))
     }
 
@@ -825,6 +1073,7 @@ where
             offchain_tx_pool_factory,
             segment_headers_store,
             pending_solutions: Default::default(),
+            late_solutions: Default::default(),
             pot_checkpoints: Default::default(),
             pot_verifier,
             _pos_table: PhantomData::<PosTable>,
@@ -0,0 +1,202 @@
+use crate::notification;
+use crate::slot_worker::{
+    reject_late_solutions, take_solution_receiver, verified_solution_outcome, SolutionOutcome,
+    SolutionOutcomeNotification, SolutionRejectionReason,
+};
+use futures::channel::mpsc;
+use futures::{FutureExt, StreamExt};
+use sp_consensus_slots::Slot;
+use sp_consensus_subspace::FarmerPublicKey;
+use sp_core::crypto::UncheckedFrom;
+use std::collections::BTreeMap;
+use subspace_core_primitives::{SectorIndex, Solution};
+
+/// Solution that is identified by its sector index
+fn solution(sector_index: SectorIndex) -> Solution<FarmerPublicKey, FarmerPublicKey> {
+    let public_key = FarmerPublicKey::unchecked_from([1; 32]);
+    let mut solution = Solution::genesis_solution(public_key.clone(), public_key);
+    solution.sector_index = sector_index;
+    solution
+}
+
+/// Slot and sector index of solutions with rejection reasons that are available in the stream
+fn rejected_solutions<S>(stream: &mut S) -> Vec<(Slot, SectorIndex, SolutionRejectionReason)>
+where
+    S: futures::Stream<Item = SolutionOutcomeNotification> + Unpin,
+{
+    let mut rejected_solutions = Vec::new();
+    while let Some(Some(notification)) = stream.next().now_or_never() {
+        match notification.outcome {
+            SolutionOutcome::Rejected(reason) => {
+                rejected_solutions.push((notification.slot, notification.sector_index, reason));
+            }
+            outcome => {
+                panic!("Unexpected outcome {outcome:?}");
+            }
+        }
+    }
+    rejected_solutions
+}
+
+#[test]
+fn solutions_for_wrong_slot_are_rejected() {
+    let (sender, stream) = notification::channel("test_solution_outcome_notification_stream");
+    let mut stream = stream.subscribe();
+    let mut pending_solutions = BTreeMap::new();
+    let mut solution_senders = Vec::new();
+    for slot in 1..=4 {
+        let (solution_sender, solution_receiver) = mpsc::channel(10);
+        pending_solutions.insert(Slot::from(slot), solution_receiver);
+        solution_senders.push(solution_sender);
+    }
+    // Solutions for slots 1 and 2, sector index is used to distinguish them
+    solution_senders[0].try_send(solution(1)).unwrap();
+    solution_senders[1].try_send(solution(2)).unwrap();
+    solution_senders[2].try_send(solution(3)).unwrap();
+
+    let mut solution_receiver =
+        take_solution_receiver(&mut pending_solutions, Slot::from(3), &sender).unwrap();
+
+    // Solutions for slots before the one that is claimed are rejected
+    let rejected = rejected_solutions(&mut stream);
+    assert_eq!(rejected.len(), 2);
+    for ((slot, sector_index, reason), expected_slot) in rejected.into_iter().zip([1, 2]) {
+        assert_eq!(slot, Slot::from(expected_slot));
+        assert_eq!(sector_index, expected_slot as SectorIndex);
+        assert!(matches!(reason, SolutionRejectionReason::SlotNotClaimed));
+    }
+    // Their receivers are closed, hence farmers will not be able to send solutions there anymore
+    assert!(solution_senders[0].try_send(solution(1)).is_err());
+    assert!(solution_senders[1].try_send(solution(2)).is_err());
+
+    // Solution for claimed slot is returned and receivers for future slots are kept
+    assert_eq!(
+        solution_receiver.try_next().unwrap().unwrap().sector_index,
+        3
+    );
+    assert_eq!(
+        pending_solutions.keys().copied().collect::<Vec<_>>(),
+        vec![Slot::from(4)]
+    );
+
+    // Slot that farmers were never challenged for can't be claimed
+    assert!(take_solution_receiver(&mut pending_solutions, Slot::from(5), &sender).is_none());
+    assert!(pending_solutions.is_empty());
+    assert!(rejected_solutions(&mut stream).is_empty());
+}
+
+#[test]
+fn late_solutions_are_rejected() {
+    let (sender, stream) = notification::channel("test_solution_outcome_notification_stream");
+    let mut stream = stream.subscribe();
+    let block_authoring_delay = Slot::from(4);
+    let claimed_slot = Slot::from(10);
+    let mut late_solutions = BTreeMap::new();
+    let (mut solution_sender, solution_receiver) = mpsc::channel(10);
+    late_solutions.insert(claimed_slot, solution_receiver);
+
+    // Nothing arrived yet
+    reject_late_solutions(
+        &mut late_solutions,
+        claimed_slot + Slot::from(1),
+        block_authoring_delay,
+        &sender,
+    );
+    assert!(rejected_solutions(&mut stream).is_empty());
+    assert!(late_solutions.contains_key(&claimed_slot));
+
+    // Solutions that arrived after slot was claimed are rejected as late while within block
+    // authoring delay
+    solution_sender.try_send(solution(1)).unwrap();
+    reject_late_solutions(
+        &mut late_solutions,
+        claimed_slot + block_authoring_delay,
+        block_authoring_delay,
+        &sender,
+    );
+    let rejected = rejected_solutions(&mut stream);
+    assert_eq!(rejected.len(), 1);
+    assert_eq!(rejected[0].0, claimed_slot);
+    assert_eq!(rejected[0].1, 1);
+    assert!(matches!(rejected[0].2, SolutionRejectionReason::TooLate));
+    assert!(late_solutions.contains_key(&claimed_slot));
+
+    // After that receiver is dropped, but whatever arrived before that is still rejected
+    solution_sender.try_send(solution(2)).unwrap();
+    reject_late_solutions(
+        &mut late_solutions,
+        claimed_slot + block_authoring_delay + Slot::from(1),
+        block_authoring_delay,
+        &sender,
+    );
+    let rejected = rejected_solutions(&mut stream);
+    assert_eq!(rejected.len(), 1);
+    assert_eq!(rejected[0].1, 2);
+    assert!(matches!(rejected[0].2, SolutionRejectionReason::TooLate));
+    assert!(late_solutions.is_empty());
+    assert!(solution_sender.try_send(solution(3)).is_err());
+}
+
+#[test]
+fn verified_solution_outcomes() {
+    let solution_range = 100;
+
+    // Solution within half of solution range claims block, but only the first one
+    assert!(matches!(
+        verified_solution_outcome(Ok(50), solution_range, false, false),
+        SolutionOutcome::ClaimedBlock
+    ));
+    assert!(matches!(
+        verified_solution_outcome(Ok(50), solution_range, true, false),
+        SolutionOutcome::Rejected(SolutionRejectionReason::BlockAlreadyClaimed)
+    ));
+    // Block is claimed on top of genesis block too
+    assert!(matches!(
+        verified_solution_outcome(Ok(0), solution_range, false, true),
+        SolutionOutcome::ClaimedBlock
+    ));
+
+    // Otherwise solution that passed verification against voting solution range claims vote
+    assert!(matches!(
+        verified_solution_outcome(Ok(51), solution_range, false, false),
+        SolutionOutcome::ClaimedVote
+    ));
+    assert!(matches!(
+        verified_solution_outcome(Ok(51), solution_range, true, false),
+        SolutionOutcome::ClaimedVote
+    ));
+    assert!(matches!(
+        verified_solution_outcome(Ok(51), solution_range, false, true),
+        SolutionOutcome::Rejected(SolutionRejectionReason::VoteOnGenesisBlock)
+    ));
+
+    // Solution outside of voting solution range is rejected with verification error
+    let outside_solution_range = subspace_verification::Error::OutsideSolutionRange {
+        half_solution_range: 200,
+        solution_distance: 201,
+    };
+    match verified_solution_outcome(
+        Err(outside_solution_range.clone()),
+        solution_range,
+        false,
+        false,
+    ) {
+        SolutionOutcome::Rejected(SolutionRejectionReason::InvalidSolution(error)) => {
+            assert_eq!(error, outside_solution_range);
+        }
+        outcome => {
+            panic!("Unexpected outcome {outcome:?}");
+        }
+    }
+    assert!(matches!(
+        verified_solution_outcome(
+            Err(subspace_verification::Error::InvalidProofOfSpace),
+            solution_range,
+            false,
+            false,
+        ),
+        SolutionOutcome::Rejected(SolutionRejectionReason::InvalidSolution(
+            subspace_verification::Error::InvalidProofOfSpace
+        ))
+    ));
+}
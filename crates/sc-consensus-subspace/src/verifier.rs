@@ -15,7 +15,6 @@
 
 use futures::lock::Mutex;
 use rand::prelude::*;
-use rayon::prelude::*;
 use sc_client_api::backend::AuxStore;
 use sc_consensus::block_import::BlockImportParams;
 use sc_consensus::import_queue::Verifier;
@@ -313,7 +312,7 @@ where
                 slot_iterations,
                 seed,
             };
-            // Collect all the data we will use for verification so we can process it in parallel
+            // Collect all the data we will use for verification so we can process it at once
             let checkpoints_verification_input = iter::once((
                 pot_input,
                 *checkpoints
@@ -333,35 +332,34 @@ where
                 }))
                 .collect::<Vec<_>>();
 
-            // All checkpoints must be valid, at least according to the seed included in
-            // justifications, search for the first error
             let pot_verifier = &self.pot_verifier;
-            checkpoints_verification_input
-                .into_par_iter()
-                .find_map_first(|(pot_input, checkpoints)| {
-                    if full_pot_verification {
-                        // Try to find invalid checkpoints
-                        if !pot_verifier.verify_checkpoints(
-                            pot_input.seed,
-                            pot_input.slot_iterations,
-                            &checkpoints,
-                        ) {
-                            return Some(VerificationError::InvalidProofOfTime);
-                        }
-                    } else {
-                        // We inject verified checkpoints in order to avoid full proving when votes
-                        // included in the block will inevitably be verified during block execution
-                        pot_verifier.inject_verified_checkpoints(
-                            pot_input.seed,
-                            pot_input.slot_iterations,
-                            checkpoints,
-                        );
-                    }
-
-                    // We search for errors
-                    None
-                })
-                .map_or(Ok(()), Err)?;
+            if full_pot_verification {
+                // All checkpoints must be valid, at least according to the seed included in
+                // justifications, checkpoints of all slots are verified together
+                let checkpoints_verification_input = checkpoints_verification_input
+                    .into_iter()
+                    .map(|(pot_input, checkpoints)| {
+                        (pot_input.seed, pot_input.slot_iterations, checkpoints)
+                    })
+                    .collect::<Vec<_>>();
+                if !pot_verifier
+                    .verify_checkpoints_batch(&checkpoints_verification_input)
+                    .into_iter()
+                    .all(|valid| valid)
+                {
+                    return Err(VerificationError::InvalidProofOfTime);
+                }
+            } else {
+                // We inject verified checkpoints in order to avoid full proving when votes included
+                // in the block will inevitably be verified during block execution
+                for (pot_input, checkpoints) in checkpoints_verification_input {
+                    pot_verifier.inject_verified_checkpoints(
+                        pot_input.seed,
+                        pot_input.slot_iterations,
+                        checkpoints,
+                    );
+                }
+            }
         }
 
         // Verify that block is signed properly
@@ -0,0 +1,54 @@
+//! Schema for proof of time data in the aux-db.
+
+use parity_scale_codec::{Decode, Encode};
+use sc_client_api::backend::AuxStore;
+use sp_blockchain::{Error as ClientError, Result as ClientResult};
+use std::num::NonZeroU32;
+use subspace_core_primitives::{PotCheckpoints, PotSeed};
+
+/// The aux storage key used to store verified checkpoints.
+const VERIFIED_CHECKPOINTS_KEY: &[u8] = b"pot_verified_checkpoints";
+
+/// Checkpoints that are known to be correct for seed and slot iterations
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Encode, Decode)]
+pub(crate) struct VerifiedCheckpoints {
+    pub(crate) seed: PotSeed,
+    pub(crate) slot_iterations: NonZeroU32,
+    pub(crate) checkpoints: PotCheckpoints,
+}
+
+/// Write verified checkpoints to aux storage, replacing previously written ones.
+pub(crate) fn write_verified_checkpoints<AS>(
+    aux_store: &AS,
+    verified_checkpoints: &[VerifiedCheckpoints],
+) -> ClientResult<()>
+where
+    AS: AuxStore,
+{
+    verified_checkpoints.using_encoded(|encoded_verified_checkpoints| {
+        aux_store.insert_aux(
+            &[(VERIFIED_CHECKPOINTS_KEY, encoded_verified_checkpoints)],
+            &[],
+        )
+    })
+}
+
+/// Load verified checkpoints from aux storage.
+pub(crate) fn load_verified_checkpoints<AS>(
+    aux_store: &AS,
+) -> ClientResult<Vec<VerifiedCheckpoints>>
+where
+    AS: AuxStore,
+{
+    match aux_store.get_aux(VERIFIED_CHECKPOINTS_KEY)? {
+        Some(encoded_verified_checkpoints) => {
+            Vec::<VerifiedCheckpoints>::decode(&mut encoded_verified_checkpoints.as_slice())
+                .map_err(|error| {
+                    ClientError::Backend(format!(
+                        "Proof of time DB is corrupted. Decode error: {error}"
+                    ))
+                })
+        }
+        None => Ok(Vec::new()),
+    }
+}
@@ -2,6 +2,7 @@
 
 #![feature(const_option, extract_if, let_chains)]
 
+mod aux_schema;
 mod slots;
 pub mod source;
 pub mod verifier;
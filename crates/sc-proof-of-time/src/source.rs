@@ -10,7 +10,7 @@ use core_affinity::CoreId;
 use derive_more::{Deref, DerefMut};
 use futures::channel::mpsc;
 use futures::{select, StreamExt};
-use sc_client_api::BlockchainEvents;
+use sc_client_api::{AuxStore, BlockchainEvents};
 use sc_network::{NotificationService, PeerId};
 use sc_network_gossip::{Network as GossipNetwork, Syncing as GossipSyncing};
 use sp_api::{ApiError, ProvideRuntimeApi};
@@ -28,6 +28,7 @@ use std::marker::PhantomData;
 use std::sync::Arc;
 use std::thread;
 use subspace_core_primitives::PotCheckpoints;
+use substrate_prometheus_endpoint::Registry;
 use thread_priority::{set_current_thread_priority, ThreadPriority};
 use tracing::{debug, error, trace, warn};
 
@@ -35,6 +36,11 @@ const LOCAL_PROOFS_CHANNEL_CAPACITY: usize = 10;
 const SLOTS_CHANNEL_CAPACITY: usize = 10;
 const GOSSIP_OUTGOING_CHANNEL_CAPACITY: usize = 10;
 const GOSSIP_INCOMING_CHANNEL_CAPACITY: usize = 10;
+/// How often (in slots) verified checkpoints are persisted, such that they don't need to be
+/// verified again after restart
+const CHECKPOINTS_PERSISTENCE_INTERVAL: u64 = 100;
+/// Max number of most recently used verified checkpoints to persist
+const MAX_PERSISTED_CHECKPOINTS: usize = 4_096;
 
 /// Proof of time slot information
 pub struct PotSlotInfo {
@@ -58,6 +64,8 @@ pub struct PotSourceWorker<Block, Client, SO> {
     client: Arc<Client>,
     sync_oracle: SO,
     chain_constants: ChainConstants,
+    pot_verifier: PotVerifier,
+    last_persisted_slot: Slot,
     timekeeper_proofs_receiver: mpsc::Receiver<TimekeeperProof>,
     to_gossip_sender: mpsc::Sender<ToGossipMessage>,
     from_gossip_receiver: mpsc::Receiver<(PeerId, GossipProof)>,
@@ -70,7 +78,7 @@ pub struct PotSourceWorker<Block, Client, SO> {
 impl<Block, Client, SO> PotSourceWorker<Block, Client, SO>
 where
     Block: BlockT,
-    Client: BlockchainEvents<Block> + HeaderBackend<Block> + ProvideRuntimeApi<Block>,
+    Client: AuxStore + BlockchainEvents<Block> + HeaderBackend<Block> + ProvideRuntimeApi<Block>,
     Client::Api: SubspaceRuntimeApi<Block, FarmerPublicKey>,
     SO: SyncOracle + Clone + Send + Sync + 'static,
 {
@@ -85,6 +93,7 @@ where
         notification_service: Box<dyn NotificationService>,
         sync: Arc<GossipSync>,
         sync_oracle: SO,
+        prometheus_registry: Option<&Registry>,
     ) -> Result<(Self, PotGossipWorker<Block>, PotSlotInfoStream), ApiError>
     where
         Network: GossipNetwork<Block> + Send + Sync + Clone + 'static,
@@ -175,18 +184,21 @@ where
         let gossip_worker = PotGossipWorker::new(
             to_gossip_receiver,
             from_gossip_sender,
-            pot_verifier,
+            pot_verifier.clone(),
             Arc::clone(&state),
             network,
             notification_service,
             sync,
             sync_oracle.clone(),
+            prometheus_registry,
         );
 
         let source_worker = Self {
             client,
             sync_oracle,
             chain_constants,
+            pot_verifier,
+            last_persisted_slot: parent_slot,
             timekeeper_proofs_receiver,
             to_gossip_sender,
             from_gossip_receiver,
@@ -289,6 +301,8 @@ where
             // We don't care if block production is too slow or block production is not enabled on this
             // node at all
             let _ = self.slot_sender.try_send(PotSlotInfo { slot, checkpoints });
+
+            self.maybe_persist_verified_checkpoints(slot);
         }
     }
 
@@ -316,6 +330,8 @@ where
                     slot: proof.slot,
                     checkpoints: proof.checkpoints,
                 });
+
+                self.maybe_persist_verified_checkpoints(proof.slot);
             }
 
             if self
@@ -332,6 +348,21 @@ where
         }
     }
 
+    /// Persist verified checkpoints every [`CHECKPOINTS_PERSISTENCE_INTERVAL`] slots
+    fn maybe_persist_verified_checkpoints(&mut self, slot: Slot) {
+        if slot < self.last_persisted_slot + Slot::from(CHECKPOINTS_PERSISTENCE_INTERVAL) {
+            return;
+        }
+        self.last_persisted_slot = slot;
+
+        if let Err(error) = self
+            .pot_verifier
+            .persist_verified_checkpoints(self.client.as_ref(), MAX_PERSISTED_CHECKPOINTS)
+        {
+            warn!(%error, %slot, "Failed to persist verified proof of time checkpoints");
+        }
+    }
+
     fn handle_block_import_notification(
         &mut self,
         block_hash: Block::Hash,
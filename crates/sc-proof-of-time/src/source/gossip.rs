@@ -1,5 +1,8 @@
 //! PoT gossip functionality.
 
+mod peer_scores;
+
+use crate::source::gossip::peer_scores::{PeerOffence, PeerScoreMetrics, PeerScores};
 use crate::source::state::PotState;
 use crate::verifier::PotVerifier;
 use futures::channel::mpsc;
@@ -23,7 +26,9 @@ use std::future::poll_fn;
 use std::num::{NonZeroU32, NonZeroUsize};
 use std::pin::pin;
 use std::sync::{atomic, Arc};
+use std::time::Instant;
 use subspace_core_primitives::{PotCheckpoints, PotSeed};
+use substrate_prometheus_endpoint::Registry;
 use tracing::{debug, error, trace, warn};
 
 /// How many slots can proof be before it is too far
@@ -64,9 +69,15 @@ mod rep {
     /// matching slot inputs.
     pub(super) const GOSSIP_SLOT_INPUT_MISMATCH: ReputationChange =
         ReputationChange::new(-(1 << 5), "PoT: slot input mismatch");
+    /// Reputation change when a peer sends us more than one proof for the same slot.
+    pub(super) const GOSSIP_DUPLICATE_PROOF: ReputationChange =
+        ReputationChange::new(-(1 << 5), "PoT: duplicate proof");
     /// Reputation change when a peer sends us an invalid proof.
     pub(super) const GOSSIP_INVALID_PROOF: ReputationChange =
         ReputationChange::new_fatal("PoT: Invalid proof");
+    /// Reputation change when a peer accumulates too many offences and gets banned locally.
+    pub(super) const GOSSIP_BANNED: ReputationChange =
+        ReputationChange::new_fatal("PoT: Too many offences");
 }
 
 const GOSSIP_PROTOCOL: &str = "/subspace/subspace-proof-of-time/1";
@@ -105,17 +116,64 @@ pub(super) enum ToGossipMessage {
     NextSlotInput(PotNextSlotInput),
 }
 
+/// Handles shared between gossip worker and proof verification offloaded to other threads
+struct GossipHandles<Block>
+where
+    Block: BlockT,
+{
+    engine: Arc<Mutex<GossipEngine<Block>>>,
+    network: Arc<dyn NetworkPeers + Send + Sync>,
+    pot_verifier: PotVerifier,
+    peer_scores: Arc<Mutex<PeerScores>>,
+}
+
+impl<Block> Clone for GossipHandles<Block>
+where
+    Block: BlockT,
+{
+    fn clone(&self) -> Self {
+        Self {
+            engine: Arc::clone(&self.engine),
+            network: Arc::clone(&self.network),
+            pot_verifier: self.pot_verifier.clone(),
+            peer_scores: Arc::clone(&self.peer_scores),
+        }
+    }
+}
+
+impl<Block> GossipHandles<Block>
+where
+    Block: BlockT,
+{
+    /// Punish senders of invalid proof, disconnecting those that got banned as the result
+    fn report_invalid_proof<I>(&self, senders: I)
+    where
+        I: IntoIterator<Item = PeerId>,
+    {
+        let engine = self.engine.lock();
+        let mut peer_scores = self.peer_scores.lock();
+        let now = Instant::now();
+        for sender in senders {
+            engine.report(sender, rep::GOSSIP_INVALID_PROOF);
+
+            if peer_scores.report(sender, PeerOffence::InvalidProof, now) {
+                debug!(%sender, "Peer banned due to too many offences");
+
+                self.network.report_peer(sender, rep::GOSSIP_BANNED);
+            }
+        }
+    }
+}
+
 /// PoT gossip worker
 #[must_use = "Gossip worker doesn't do anything unless run() method is called"]
 pub struct PotGossipWorker<Block>
 where
     Block: BlockT,
 {
-    engine: Arc<Mutex<GossipEngine<Block>>>,
-    network: Arc<dyn NetworkPeers + Send + Sync>,
+    handles: GossipHandles<Block>,
     topic: Block::Hash,
     state: Arc<PotState>,
-    pot_verifier: PotVerifier,
     gossip_cache: LruCache<PeerId, VecDeque<GossipProof>>,
     to_gossip_receiver: mpsc::Receiver<ToGossipMessage>,
     from_gossip_sender: mpsc::Sender<(PeerId, GossipProof)>,
@@ -137,6 +195,7 @@ where
         notification_service: Box<dyn NotificationService>,
         sync: Arc<GossipSync>,
         sync_oracle: SO,
+        prometheus_registry: Option<&Registry>,
     ) -> Self
     where
         Network: GossipNetwork<Block> + NetworkPeers + Send + Sync + Clone + 'static,
@@ -145,11 +204,22 @@ where
     {
         let topic = <<Block::Header as HeaderT>::Hashing as HashT>::hash(b"proofs");
 
+        let peer_score_metrics =
+            prometheus_registry.and_then(|registry| match PeerScoreMetrics::new(registry) {
+                Ok(peer_score_metrics) => Some(peer_score_metrics),
+                Err(error) => {
+                    warn!(%error, "Failed to register PoT gossip peer score metrics");
+                    None
+                }
+            });
+        let peer_scores = Arc::new(Mutex::new(PeerScores::new(peer_score_metrics)));
+
         let validator = Arc::new(PotGossipValidator::new(
             Arc::clone(&state),
             topic,
             sync_oracle,
             network.clone(),
+            Arc::clone(&peer_scores),
         ));
         let engine = GossipEngine::new(
             network.clone(),
@@ -161,11 +231,14 @@ where
         );
 
         Self {
-            engine: Arc::new(Mutex::new(engine)),
-            network: Arc::new(network),
+            handles: GossipHandles {
+                engine: Arc::new(Mutex::new(engine)),
+                network: Arc::new(network),
+                pot_verifier,
+                peer_scores,
+            },
             topic,
             state,
-            pot_verifier,
             gossip_cache: LruCache::new(GOSSIP_CACHE_PEER_COUNT),
             to_gossip_receiver,
             from_gossip_sender,
@@ -177,7 +250,7 @@ where
     /// NOTE: Even though this function is async, it might do blocking operations internally and
     /// should be running on a dedicated thread.
     pub async fn run(mut self) {
-        let message_receiver = self.engine.lock().messages_for(self.topic);
+        let message_receiver = self.handles.engine.lock().messages_for(self.topic);
         let incoming_unverified_messages =
             pin!(message_receiver.filter_map(|notification| async move {
                 notification.sender.map(|sender| {
@@ -190,7 +263,8 @@ where
         let mut incoming_unverified_messages = incoming_unverified_messages.fuse();
 
         loop {
-            let mut gossip_engine_poll = poll_fn(|cx| self.engine.lock().poll_unpin(cx)).fuse();
+            let mut gossip_engine_poll =
+                poll_fn(|cx| self.handles.engine.lock().poll_unpin(cx)).fuse();
 
             futures::select! {
                 (sender, proof) = incoming_unverified_messages.select_next_some() => {
@@ -220,6 +294,7 @@ where
                 );
 
                 if let Some(verified_checkpoints) = self
+                    .handles
                     .pot_verifier
                     .try_get_checkpoints(proof.slot_iterations, proof.seed)
                 {
@@ -230,11 +305,12 @@ where
                             "Invalid old proof, punishing sender",
                         );
 
-                        self.engine.lock().report(sender, rep::GOSSIP_INVALID_PROOF);
+                        self.handles.report_invalid_proof([sender]);
                     }
                 } else {
                     // We didn't use it, but also didn't bother verifying
-                    self.engine
+                    self.handles
+                        .engine
                         .lock()
                         .report(sender, rep::GOSSIP_OUTDATED_PROOF);
                 }
@@ -251,7 +327,8 @@ where
                         "Proof with next slot mismatch, ignoring",
                     );
 
-                    self.engine
+                    self.handles
+                        .engine
                         .lock()
                         .report(sender, rep::GOSSIP_NEXT_SLOT_MISMATCH);
                     return;
@@ -277,7 +354,8 @@ where
                             "Too many proofs stored from peer",
                         );
 
-                        self.engine
+                        self.handles
+                            .engine
                             .lock()
                             .report(sender, rep::GOSSIP_TOO_MANY_PROOFS);
                     }
@@ -287,14 +365,15 @@ where
             }
         }
 
-        if self.pot_verifier.verify_checkpoints(
+        if self.handles.pot_verifier.verify_checkpoints(
             proof.seed,
             proof.slot_iterations,
             &proof.checkpoints,
         ) {
             debug!(%sender, slot = %proof.slot, "Full verification succeeded");
 
-            self.engine
+            self.handles
+                .engine
                 .lock()
                 .gossip_message(self.topic, proof.encode(), false);
 
@@ -303,14 +382,15 @@ where
             }
         } else {
             debug!(%sender, slot = %proof.slot, "Full verification failed");
-            self.engine.lock().report(sender, rep::GOSSIP_INVALID_PROOF);
+            self.handles.report_invalid_proof([sender]);
         }
     }
 
     async fn handle_to_gossip_messages(&mut self, message: ToGossipMessage) {
         match message {
             ToGossipMessage::Proof(proof) => {
-                self.engine
+                self.handles
+                    .engine
                     .lock()
                     .gossip_message(self.topic, proof.encode(), false);
             }
@@ -340,23 +420,24 @@ where
         for (proof, senders) in old_proofs {
             if proof.slot != next_slot_input.slot {
                 let invalid_proof = self
+                    .handles
                     .pot_verifier
                     .try_get_checkpoints(proof.slot_iterations, proof.seed)
                     .map(|verified_checkpoints| verified_checkpoints != proof.checkpoints)
                     .unwrap_or_default();
 
-                let engine = self.engine.lock();
                 if invalid_proof {
-                    for sender in senders {
+                    for sender in &senders {
                         trace!(
                             %sender,
                             slot = %proof.slot,
                             "Proof ended up being invalid",
                         );
-
-                        engine.report(sender, rep::GOSSIP_INVALID_PROOF);
                     }
+
+                    self.handles.report_invalid_proof(senders);
                 } else {
+                    let engine = self.handles.engine.lock();
                     for sender in senders {
                         trace!(
                             %sender,
@@ -374,7 +455,7 @@ where
             if !(proof.seed == next_slot_input.seed
                 && proof.slot_iterations == next_slot_input.slot_iterations)
             {
-                let engine = self.engine.lock();
+                let engine = self.handles.engine.lock();
                 for sender in senders {
                     trace!(
                         %sender,
@@ -393,9 +474,7 @@ where
 
         // Avoid blocking gossip for too long
         rayon::spawn({
-            let engine = Arc::clone(&self.engine);
-            let network = Arc::clone(&self.network);
-            let pot_verifier = self.pot_verifier.clone();
+            let handles = self.handles.clone();
             let from_gossip_sender = self.from_gossip_sender.clone();
             let topic = self.topic;
 
@@ -403,9 +482,7 @@ where
                 Self::handle_potentially_matching_proofs(
                     next_slot_input,
                     potentially_matching_proofs,
-                    engine,
-                    network.as_ref(),
-                    &pot_verifier,
+                    &handles,
                     from_gossip_sender,
                     topic,
                 );
@@ -416,9 +493,7 @@ where
     fn handle_potentially_matching_proofs(
         next_slot_input: PotNextSlotInput,
         mut potentially_matching_proofs: Vec<(GossipProof, Vec<PeerId>)>,
-        engine: Arc<Mutex<GossipEngine<Block>>>,
-        network: &dyn NetworkPeers,
-        pot_verifier: &PotVerifier,
+        handles: &GossipHandles<Block>,
         mut from_gossip_sender: mpsc::Sender<(PeerId, GossipProof)>,
         topic: Block::Hash,
     ) {
@@ -431,7 +506,7 @@ where
         potentially_matching_proofs.sort_by_cached_key(|(_proof, peer_ids)| {
             peer_ids
                 .iter()
-                .map(|peer_id| network.peer_reputation(peer_id))
+                .map(|peer_id| handles.network.peer_reputation(peer_id))
                 .max()
         });
 
@@ -442,7 +517,7 @@ where
 
             // Verify all proofs, starting with those sent by most reputable peers
             for (proof, _senders) in potentially_matching_proofs.iter().rev() {
-                if pot_verifier.verify_checkpoints(
+                if handles.pot_verifier.verify_checkpoints(
                     proof.seed,
                     proof.slot_iterations,
                     &proof.checkpoints,
@@ -459,7 +534,7 @@ where
                 .last()
                 .expect("Guaranteed to be non-empty; qed");
 
-            if pot_verifier.verify_checkpoints(
+            if handles.pot_verifier.verify_checkpoints(
                 proof.seed,
                 proof.slot_iterations,
                 &proof.checkpoints,
@@ -499,7 +574,10 @@ where
                     }
                     sent = true;
 
-                    engine.lock().gossip_message(topic, proof.encode(), false);
+                    handles
+                        .engine
+                        .lock()
+                        .gossip_message(topic, proof.encode(), false);
 
                     if let Err(error) =
                         futures::executor::block_on(from_gossip_sender.send((sender, proof)))
@@ -512,11 +590,11 @@ where
                     }
                 }
             } else {
-                let engine = engine.lock();
-                for sender in senders {
+                for sender in &senders {
                     debug!(%sender, slot = %proof.slot, "Next slot proof is invalid");
-                    engine.report(sender, rep::GOSSIP_INVALID_PROOF);
                 }
+
+                handles.report_invalid_proof(senders);
             }
         }
     }
@@ -531,20 +609,42 @@ where
     topic: Block::Hash,
     sync_oracle: SO,
     network: Network,
+    peer_scores: Arc<Mutex<PeerScores>>,
 }
 
 impl<Block, SO, Network> PotGossipValidator<Block, SO, Network>
 where
     Block: BlockT,
     SO: SyncOracle,
+    Network: NetworkPeers,
 {
     /// Creates the validator.
-    fn new(state: Arc<PotState>, topic: Block::Hash, sync_oracle: SO, network: Network) -> Self {
+    fn new(
+        state: Arc<PotState>,
+        topic: Block::Hash,
+        sync_oracle: SO,
+        network: Network,
+        peer_scores: Arc<Mutex<PeerScores>>,
+    ) -> Self {
         Self {
             state,
             topic,
             sync_oracle,
             network,
+            peer_scores,
+        }
+    }
+
+    /// Record offence committed by peer, disconnecting it if it got banned as the result
+    fn report_offence(&self, sender: &PeerId, offence: PeerOffence) {
+        if self
+            .peer_scores
+            .lock()
+            .report(*sender, offence, Instant::now())
+        {
+            debug!(%sender, ?offence, "Peer banned due to too many offences");
+
+            self.network.report_peer(*sender, rep::GOSSIP_BANNED);
         }
     }
 }
@@ -566,6 +666,11 @@ where
             return ValidationResult::Discard;
         }
 
+        if self.peer_scores.lock().is_banned(sender, Instant::now()) {
+            trace!(%sender, "Received proof from banned peer, ignoring");
+            return ValidationResult::Discard;
+        }
+
         match GossipProof::decode(&mut data) {
             Ok(proof) => {
                 let next_slot_input = self.state.next_slot_input(atomic::Ordering::Relaxed);
@@ -590,6 +695,7 @@ where
 
                     self.network
                         .report_peer(*sender, rep::GOSSIP_TOO_FAR_IN_THE_FUTURE);
+                    self.report_offence(sender, PeerOffence::TooFarInTheFuture);
                     return ValidationResult::Discard;
                 }
                 // Next slot matches expectations, but other inputs are not
@@ -628,6 +734,23 @@ where
                     return ValidationResult::Discard;
                 }
 
+                if self
+                    .peer_scores
+                    .lock()
+                    .is_duplicate(*sender, proof.slot, Instant::now())
+                {
+                    debug!(
+                        %sender,
+                        slot = %proof.slot,
+                        "Received more than one proof for the same slot, ignoring"
+                    );
+
+                    self.network
+                        .report_peer(*sender, rep::GOSSIP_DUPLICATE_PROOF);
+                    self.report_offence(sender, PeerOffence::DuplicateProof);
+                    return ValidationResult::Discard;
+                }
+
                 trace!(%sender, slot = %proof.slot, "Superficial verification succeeded");
 
                 // We will fully validate and re-gossip it explicitly later if necessary
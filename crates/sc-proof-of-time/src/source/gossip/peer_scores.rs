@@ -0,0 +1,213 @@
+//! Local scoring of PoT gossip peers.
+//!
+//! Substrate's reputation is shared by all protocols and recovers quickly, so peers that keep
+//! sending bad proofs are additionally tracked here and their gossip is ignored for some time
+//! once accumulated penalty crosses the threshold.
+
+#[cfg(test)]
+mod tests;
+
+use lru::LruCache;
+use sc_network::PeerId;
+use sp_consensus_slots::Slot;
+use std::collections::VecDeque;
+use std::num::NonZeroUsize;
+use std::time::{Duration, Instant};
+use substrate_prometheus_endpoint::{
+    register, Counter, CounterVec, Opts, PrometheusError, Registry, U64,
+};
+
+/// Accumulated penalty at which peer is banned
+pub(super) const BAN_THRESHOLD: u32 = 100;
+/// For how long gossip from banned peer is ignored
+pub(super) const BAN_DURATION: Duration = Duration::from_secs(10 * 60);
+/// Accumulated penalty is halved after this much time
+pub(super) const PENALTY_HALF_LIFE: Duration = Duration::from_secs(60);
+const TRACKED_PEERS: NonZeroUsize = NonZeroUsize::new(1_000).expect("Not zero; qed");
+/// How many recent slots to remember per peer for duplicate detection
+const RECENT_SLOTS_PER_PEER: usize = 20;
+
+/// Misbehavior of a gossip peer
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub(super) enum PeerOffence {
+    /// Proof failed verification
+    InvalidProof,
+    /// More than one proof for the same slot
+    DuplicateProof,
+    /// Proof for slot that is too far in the future
+    TooFarInTheFuture,
+}
+
+impl PeerOffence {
+    fn penalty(&self) -> u32 {
+        match self {
+            Self::InvalidProof => BAN_THRESHOLD,
+            Self::DuplicateProof => BAN_THRESHOLD / 4,
+            Self::TooFarInTheFuture => BAN_THRESHOLD / 10,
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            Self::InvalidProof => "invalid_proof",
+            Self::DuplicateProof => "duplicate_proof",
+            Self::TooFarInTheFuture => "too_far_in_the_future",
+        }
+    }
+}
+
+/// Metrics of PoT gossip peer scoring
+#[derive(Debug, Clone)]
+pub(super) struct PeerScoreMetrics {
+    offences: CounterVec<U64>,
+    bans: Counter<U64>,
+    ignored_proofs: Counter<U64>,
+}
+
+impl PeerScoreMetrics {
+    pub(super) fn new(registry: &Registry) -> Result<Self, PrometheusError> {
+        Ok(Self {
+            offences: register(
+                CounterVec::new(
+                    Opts::new(
+                        "subspace_pot_gossip_peer_offences",
+                        "Number of offences committed by PoT gossip peers",
+                    ),
+                    &["offence"],
+                )?,
+                registry,
+            )?,
+            bans: register(
+                Counter::new(
+                    "subspace_pot_gossip_peer_bans",
+                    "Number of times PoT gossip peers were banned",
+                )?,
+                registry,
+            )?,
+            ignored_proofs: register(
+                Counter::new(
+                    "subspace_pot_gossip_ignored_proofs",
+                    "Number of proofs ignored because they were received from banned peers",
+                )?,
+                registry,
+            )?,
+        })
+    }
+}
+
+#[derive(Debug)]
+struct PeerScore {
+    penalty: u32,
+    last_update: Instant,
+    banned_until: Option<Instant>,
+    recent_slots: VecDeque<Slot>,
+}
+
+impl PeerScore {
+    fn new(now: Instant) -> Self {
+        Self {
+            penalty: 0,
+            last_update: now,
+            banned_until: None,
+            recent_slots: VecDeque::with_capacity(RECENT_SLOTS_PER_PEER),
+        }
+    }
+
+    /// Decay penalty according to time passed since last update
+    fn decay(&mut self, now: Instant) {
+        let half_lives =
+            now.saturating_duration_since(self.last_update).as_secs() / PENALTY_HALF_LIFE.as_secs();
+        if half_lives > 0 {
+            self.penalty = self
+                .penalty
+                .checked_shr(half_lives as u32)
+                .unwrap_or_default();
+            self.last_update += PENALTY_HALF_LIFE * half_lives as u32;
+        }
+    }
+}
+
+/// Scores of PoT gossip peers
+#[derive(Debug)]
+pub(super) struct PeerScores {
+    peers: LruCache<PeerId, PeerScore>,
+    metrics: Option<PeerScoreMetrics>,
+}
+
+impl PeerScores {
+    pub(super) fn new(metrics: Option<PeerScoreMetrics>) -> Self {
+        Self {
+            peers: LruCache::new(TRACKED_PEERS),
+            metrics,
+        }
+    }
+
+    /// Check whether gossip from peer should be ignored, counts ignored proof if it does
+    pub(super) fn is_banned(&mut self, peer_id: &PeerId, now: Instant) -> bool {
+        let Some(peer_score) = self.peers.get_mut(peer_id) else {
+            return false;
+        };
+        let Some(banned_until) = peer_score.banned_until else {
+            return false;
+        };
+
+        if banned_until <= now {
+            peer_score.banned_until.take();
+            return false;
+        }
+
+        if let Some(metrics) = &self.metrics {
+            metrics.ignored_proofs.inc();
+        }
+
+        true
+    }
+
+    /// Register proof for slot received from peer, returns `true` if peer has already sent proof
+    /// for this slot before.
+    pub(super) fn is_duplicate(&mut self, peer_id: PeerId, slot: Slot, now: Instant) -> bool {
+        let peer_score = self
+            .peers
+            .get_or_insert_mut(peer_id, || PeerScore::new(now));
+
+        if peer_score.recent_slots.contains(&slot) {
+            return true;
+        }
+
+        if peer_score.recent_slots.len() == RECENT_SLOTS_PER_PEER {
+            peer_score.recent_slots.pop_front();
+        }
+        peer_score.recent_slots.push_back(slot);
+
+        false
+    }
+
+    /// Record offence committed by peer, returns `true` if peer got banned as the result.
+    pub(super) fn report(&mut self, peer_id: PeerId, offence: PeerOffence, now: Instant) -> bool {
+        if let Some(metrics) = &self.metrics {
+            metrics.offences.with_label_values(&[offence.label()]).inc();
+        }
+
+        let peer_score = self
+            .peers
+            .get_or_insert_mut(peer_id, || PeerScore::new(now));
+        peer_score.decay(now);
+        peer_score.penalty = peer_score.penalty.saturating_add(offence.penalty());
+
+        let already_banned = peer_score
+            .banned_until
+            .is_some_and(|banned_until| banned_until > now);
+        if already_banned || peer_score.penalty < BAN_THRESHOLD {
+            return false;
+        }
+
+        peer_score.banned_until.replace(now + BAN_DURATION);
+        peer_score.penalty = 0;
+
+        if let Some(metrics) = &self.metrics {
+            metrics.bans.inc();
+        }
+
+        true
+    }
+}
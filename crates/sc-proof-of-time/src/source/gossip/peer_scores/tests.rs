@@ -0,0 +1,70 @@
+use crate::source::gossip::peer_scores::{
+    PeerOffence, PeerScores, BAN_DURATION, PENALTY_HALF_LIFE,
+};
+use sc_network::PeerId;
+use sp_consensus_slots::Slot;
+use std::time::Instant;
+
+#[test]
+fn invalid_proof_bans_immediately() {
+    let mut peer_scores = PeerScores::new(None);
+    let peer_id = PeerId::random();
+    let other_peer_id = PeerId::random();
+    let now = Instant::now();
+
+    assert!(!peer_scores.is_banned(&peer_id, now));
+    assert!(peer_scores.report(peer_id, PeerOffence::InvalidProof, now));
+    assert!(peer_scores.is_banned(&peer_id, now));
+    assert!(!peer_scores.is_banned(&other_peer_id, now));
+
+    // Already banned peer is not banned again
+    assert!(!peer_scores.report(peer_id, PeerOffence::InvalidProof, now));
+
+    // Ban expires eventually
+    assert!(peer_scores.is_banned(&peer_id, now + BAN_DURATION / 2));
+    assert!(!peer_scores.is_banned(&peer_id, now + BAN_DURATION));
+}
+
+#[test]
+fn duplicate_proofs() {
+    let mut peer_scores = PeerScores::new(None);
+    let peer_id = PeerId::random();
+    let other_peer_id = PeerId::random();
+    let now = Instant::now();
+
+    assert!(!peer_scores.is_duplicate(peer_id, Slot::from(1), now));
+    assert!(!peer_scores.is_duplicate(peer_id, Slot::from(2), now));
+    // Different peers can send proofs for the same slot
+    assert!(!peer_scores.is_duplicate(other_peer_id, Slot::from(1), now));
+    assert!(peer_scores.is_duplicate(peer_id, Slot::from(1), now));
+
+    // Four duplicates result in a ban
+    for _ in 0..3 {
+        assert!(!peer_scores.report(peer_id, PeerOffence::DuplicateProof, now));
+        assert!(!peer_scores.is_banned(&peer_id, now));
+    }
+    assert!(peer_scores.report(peer_id, PeerOffence::DuplicateProof, now));
+    assert!(peer_scores.is_banned(&peer_id, now));
+    assert!(!peer_scores.is_banned(&other_peer_id, now));
+}
+
+#[test]
+fn penalty_decays() {
+    let mut peer_scores = PeerScores::new(None);
+    let peer_id = PeerId::random();
+    let mut now = Instant::now();
+
+    // Penalty of occasional offences decays before reaching the threshold
+    for _ in 0..20 {
+        assert!(!peer_scores.report(peer_id, PeerOffence::TooFarInTheFuture, now));
+        now += PENALTY_HALF_LIFE;
+    }
+    assert!(!peer_scores.is_banned(&peer_id, now));
+
+    // But not when they happen in a burst
+    let banned = (0..10)
+        .map(|_| peer_scores.report(peer_id, PeerOffence::TooFarInTheFuture, now))
+        .any(|banned| banned);
+    assert!(banned);
+    assert!(peer_scores.is_banned(&peer_id, now));
+}
@@ -3,13 +3,19 @@
 #[cfg(test)]
 mod tests;
 
+use crate::aux_schema::{
+    load_verified_checkpoints, write_verified_checkpoints, VerifiedCheckpoints,
+};
 use lru::LruCache;
 use parking_lot::Mutex;
+use sc_client_api::backend::AuxStore;
+use sp_blockchain::Result as ClientResult;
 use sp_consensus_slots::Slot;
 use sp_consensus_subspace::{PotNextSlotInput, PotParametersChange};
 use std::num::{NonZeroU32, NonZeroUsize};
 use std::sync::Arc;
 use subspace_core_primitives::{PotCheckpoints, PotOutput, PotSeed};
+use subspace_proof_of_time::PotVerificationInput;
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 struct CacheKey {
@@ -55,6 +61,60 @@ impl PotVerifier {
         );
     }
 
+    /// Inject checkpoints previously persisted with [`Self::persist_verified_checkpoints()`] into
+    /// verifier, returns number of injected checkpoints
+    pub fn load_persisted_checkpoints<AS>(&self, aux_store: &AS) -> ClientResult<usize>
+    where
+        AS: AuxStore,
+    {
+        let verified_checkpoints = load_verified_checkpoints(aux_store)?;
+        let count = verified_checkpoints.len();
+
+        // Persisted checkpoints are ordered from the least to the most recently used, which is
+        // also the order in which they need to be inserted into LRU cache
+        for VerifiedCheckpoints {
+            seed,
+            slot_iterations,
+            checkpoints,
+        } in verified_checkpoints
+        {
+            self.inject_verified_checkpoints(seed, slot_iterations, checkpoints);
+        }
+
+        Ok(count)
+    }
+
+    /// Persist up to `limit` most recently used verified checkpoints, replacing previously
+    /// persisted checkpoints
+    pub fn persist_verified_checkpoints<AS>(&self, aux_store: &AS, limit: usize) -> ClientResult<()>
+    where
+        AS: AuxStore,
+    {
+        let mut verified_checkpoints = self.verified_checkpoints(limit);
+        verified_checkpoints.reverse();
+
+        write_verified_checkpoints(aux_store, &verified_checkpoints)
+    }
+
+    /// Up to `limit` verified checkpoints, starting with the most recently used, checkpoints that
+    /// are being verified or proven right now are skipped
+    fn verified_checkpoints(&self, limit: usize) -> Vec<VerifiedCheckpoints> {
+        self.cache
+            .lock()
+            .iter()
+            .filter_map(|(cache_key, cache_value)| {
+                let checkpoints = cache_value.checkpoints.try_lock()?.as_ref().copied()?;
+
+                Some(VerifiedCheckpoints {
+                    seed: cache_key.seed,
+                    slot_iterations: cache_key.slot_iterations,
+                    checkpoints,
+                })
+            })
+            .take(limit)
+            .collect()
+    }
+
     /// Get genesis seed
     pub fn genesis_seed(&self) -> PotSeed {
         self.genesis_seed
@@ -234,6 +294,64 @@ impl PotVerifier {
         self.verify_checkpoints_internal(seed, slot_iterations, checkpoints)
     }
 
+    /// Verify proof of time checkpoints of many slots at once, results are in the same order as
+    /// inputs.
+    ///
+    /// Checkpoints that are not in cache yet are verified together with
+    /// [`subspace_proof_of_time::verify_batch()`], successfully verified checkpoints are cached.
+    pub fn verify_checkpoints_batch(
+        &self,
+        inputs: &[(PotSeed, NonZeroU32, PotCheckpoints)],
+    ) -> Vec<bool> {
+        let mut results = vec![false; inputs.len()];
+        let mut uncached_indices = Vec::new();
+
+        for (index, &(seed, slot_iterations, checkpoints)) in inputs.iter().enumerate() {
+            let cache_key = CacheKey {
+                seed,
+                slot_iterations,
+            };
+            let maybe_cache_value = self.cache.lock().get(&cache_key).cloned();
+            let maybe_correct_checkpoints = maybe_cache_value.and_then(|cache_value| {
+                let correct_checkpoints = *cache_value.checkpoints.lock();
+                correct_checkpoints
+            });
+
+            match maybe_correct_checkpoints {
+                Some(correct_checkpoints) => {
+                    results[index] = checkpoints == correct_checkpoints;
+                }
+                None => {
+                    uncached_indices.push(index);
+                }
+            }
+        }
+
+        let batch_inputs = uncached_indices
+            .iter()
+            .map(|&index| {
+                let (seed, slot_iterations, checkpoints) = &inputs[index];
+
+                PotVerificationInput {
+                    seed: *seed,
+                    iterations: *slot_iterations,
+                    checkpoints: checkpoints.as_slice(),
+                }
+            })
+            .collect::<Vec<_>>();
+        let batch_results = subspace_proof_of_time::verify_batch(&batch_inputs);
+
+        for (index, result) in uncached_indices.into_iter().zip(batch_results) {
+            if result.unwrap_or_default() {
+                let (seed, slot_iterations, checkpoints) = inputs[index];
+                self.inject_verified_checkpoints(seed, slot_iterations, checkpoints);
+                results[index] = true;
+            }
+        }
+
+        results
+    }
+
     fn verify_checkpoints_internal(
         &self,
         seed: PotSeed,
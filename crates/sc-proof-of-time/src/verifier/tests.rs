@@ -1,10 +1,16 @@
 use crate::verifier::PotVerifier;
+use sc_client_api::in_mem::Backend;
 use sp_consensus_slots::Slot;
 use sp_consensus_subspace::{PotNextSlotInput, PotParametersChange};
+use sp_runtime::traits::BlakeTwo256;
+use sp_runtime::OpaqueExtrinsic;
 use std::mem;
 use std::num::{NonZeroU32, NonZeroUsize};
 use subspace_core_primitives::{Blake3Hash, PotSeed};
 
+type Block =
+    sp_runtime::generic::Block<sp_runtime::generic::Header<u32, BlakeTwo256>, OpaqueExtrinsic>;
+
 const SEED: [u8; 16] = [
     0xd6, 0x66, 0xcc, 0xd8, 0xd5, 0x93, 0xc2, 0x3d, 0xa8, 0xdb, 0x6b, 0x5b, 0x14, 0x13, 0xb1, 0x3a,
 ];
@@ -229,3 +235,90 @@ fn parameters_change() {
         })
     ));
 }
+
+#[test]
+fn persisted_checkpoints() {
+    let genesis_seed = PotSeed::from(SEED);
+    let slot_iterations = NonZeroU32::new(512).unwrap();
+    let aux_store = Backend::<Block>::new();
+
+    let verifier = PotVerifier::new(genesis_seed, NonZeroUsize::new(1000).unwrap());
+
+    // Nothing persisted yet
+    assert_eq!(verifier.load_persisted_checkpoints(&aux_store).unwrap(), 0);
+
+    let checkpoints_1 = verifier
+        .get_checkpoints(slot_iterations, genesis_seed)
+        .unwrap();
+    let seed_2 = checkpoints_1.output().seed();
+    let checkpoints_2 = verifier.get_checkpoints(slot_iterations, seed_2).unwrap();
+    let seed_3 = checkpoints_2.output().seed();
+    let checkpoints_3 = verifier.get_checkpoints(slot_iterations, seed_3).unwrap();
+
+    // Only two most recently used checkpoints are persisted
+    verifier
+        .persist_verified_checkpoints(&aux_store, 2)
+        .unwrap();
+
+    let verifier = PotVerifier::new(genesis_seed, NonZeroUsize::new(1000).unwrap());
+    assert_eq!(verifier.load_persisted_checkpoints(&aux_store).unwrap(), 2);
+
+    // Persisted checkpoints are available without proving
+    assert!(verifier
+        .try_get_checkpoints(slot_iterations, genesis_seed)
+        .is_none());
+    assert_eq!(
+        verifier.try_get_checkpoints(slot_iterations, seed_2),
+        Some(checkpoints_2)
+    );
+    assert_eq!(
+        verifier.try_get_checkpoints(slot_iterations, seed_3),
+        Some(checkpoints_3)
+    );
+
+    // Order of persisted checkpoints is preserved, so the least recently used one is evicted first
+    let verifier = PotVerifier::new(genesis_seed, NonZeroUsize::new(1).unwrap());
+    assert_eq!(verifier.load_persisted_checkpoints(&aux_store).unwrap(), 2);
+    assert!(verifier
+        .try_get_checkpoints(slot_iterations, seed_2)
+        .is_none());
+    assert_eq!(
+        verifier.try_get_checkpoints(slot_iterations, seed_3),
+        Some(checkpoints_3)
+    );
+}
+
+#[test]
+fn verify_checkpoints_batch() {
+    let genesis_seed = PotSeed::from(SEED);
+    let slot_iterations = NonZeroU32::new(512).unwrap();
+    let checkpoints_1 = subspace_proof_of_time::prove(genesis_seed, slot_iterations).unwrap();
+    let seed_1 = checkpoints_1.output().seed();
+    let checkpoints_2 = subspace_proof_of_time::prove(seed_1, slot_iterations).unwrap();
+
+    let verifier = PotVerifier::new(genesis_seed, NonZeroUsize::new(1000).unwrap());
+
+    // The first slot is already in cache
+    assert!(verifier.verify_checkpoints(genesis_seed, slot_iterations, &checkpoints_1));
+
+    let double_slot_iterations = slot_iterations
+        .checked_mul(NonZeroU32::new(2).unwrap())
+        .unwrap();
+    assert_eq!(
+        verifier.verify_checkpoints_batch(&[
+            (genesis_seed, slot_iterations, checkpoints_1),
+            (seed_1, slot_iterations, checkpoints_2),
+            // Invalid number of iterations
+            (seed_1, double_slot_iterations, checkpoints_2),
+            // Invalid seed
+            (genesis_seed, slot_iterations, checkpoints_2),
+        ]),
+        [true, true, false, false]
+    );
+
+    // Successfully verified checkpoints are cached
+    assert_eq!(
+        verifier.try_get_checkpoints(slot_iterations, seed_1),
+        Some(checkpoints_2)
+    );
+}
@@ -40,10 +40,13 @@ use sp_consensus_slots::{Slot, SlotDuration};
 use sp_core::crypto::KeyTypeId;
 use sp_core::H256;
 use sp_io::hashing;
-use sp_runtime::traits::{Block as BlockT, Header as HeaderT};
+use sp_runtime::traits::{
+    AtLeast32BitUnsigned, Block as BlockT, CheckedSub, Header as HeaderT, IntegerSquareRoot, Zero,
+};
 use sp_runtime::{ConsensusEngineId, Justification};
 use sp_runtime_interface::pass_by::PassBy;
 use sp_runtime_interface::{pass_by, runtime_interface};
+use sp_std::collections::btree_map::BTreeMap;
 use sp_std::num::NonZeroU32;
 #[cfg(feature = "std")]
 use subspace_core_primitives::crypto::kzg::Kzg;
@@ -60,7 +63,7 @@ use subspace_proof_of_space::shim::ShimTable;
 use subspace_proof_of_space::PosTableType;
 #[cfg(feature = "std")]
 use subspace_proof_of_space::Table;
-use subspace_verification::{check_reward_signature, VerifySolutionParams};
+use subspace_verification::{check_reward_signature, derive_pot_entropy, VerifySolutionParams};
 
 /// Key type for Subspace pallet.
 const KEY_TYPE: KeyTypeId = KeyTypeId(*b"sub_");
@@ -209,6 +212,124 @@ pub struct PotParametersChange {
     pub entropy: Blake3Hash,
 }
 
+/// Parameters of proof of time entropy injection.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Decode, Encode, TypeInfo)]
+pub enum PotEntropyInjectionParameters<Number> {
+    /// V0 of proof of time entropy injection parameters.
+    #[codec(index = 0)]
+    V0 {
+        /// Interval, in blocks, between blockchain entropy injection into proof of time chain.
+        interval: Number,
+        /// Interval, in entropy injection intervals, where to take entropy for injection from.
+        lookback_depth: u8,
+        /// Delay after block, in slots, when entropy injection takes effect.
+        delay: Slot,
+    },
+}
+
+impl<Number> PotEntropyInjectionParameters<Number>
+where
+    Number: Copy,
+{
+    /// Interval, in blocks, between blockchain entropy injection into proof of time chain.
+    pub fn interval(&self) -> Number {
+        let Self::V0 { interval, .. } = self;
+        *interval
+    }
+
+    /// Interval, in entropy injection intervals, where to take entropy for injection from.
+    pub fn lookback_depth(&self) -> u8 {
+        let Self::V0 { lookback_depth, .. } = self;
+        *lookback_depth
+    }
+
+    /// Delay after block, in slots, when entropy injection takes effect.
+    pub fn delay(&self) -> Slot {
+        let Self::V0 { delay, .. } = self;
+        *delay
+    }
+}
+
+/// Proof of time entropy collected at some block.
+#[derive(Debug, Copy, Clone, Encode, Decode, Eq, PartialEq, TypeInfo)]
+pub struct PotEntropyValue {
+    /// Slot at which entropy will be injected, known once entropy injection is scheduled.
+    pub target_slot: Option<Slot>,
+    /// Entropy derived from the block.
+    pub entropy: Blake3Hash,
+}
+
+/// Collect proof of time entropy of the block the same way runtime does and return parameters
+/// change that runtime is expected to include in that block.
+///
+/// `pot_entropy` is entropy collected by ancestors of the block, keyed by block number it was
+/// collected at, it is updated to include entropy of the block and to exclude entropy that was
+/// already injected. `slot_iterations` are proof of time slot iterations of the block.
+pub fn collect_pot_entropy<Number, PublicKey, RewardAddress>(
+    injection_parameters: &PotEntropyInjectionParameters<Number>,
+    pot_entropy: &mut BTreeMap<Number, PotEntropyValue>,
+    number: Number,
+    pre_digest: &PreDigest<PublicKey, RewardAddress>,
+    slot_iterations: NonZeroU32,
+) -> Option<PotParametersChange>
+where
+    Number: AtLeast32BitUnsigned + Copy,
+{
+    let injection_interval = injection_parameters.interval();
+    let lookback_in_blocks =
+        injection_interval * Number::from(injection_parameters.lookback_depth());
+    let last_entropy_injection_block = number / injection_interval * injection_interval;
+    let maybe_entropy_source_block_number =
+        last_entropy_injection_block.checked_sub(&lookback_in_blocks);
+
+    // Collect entropy every `interval` blocks
+    if (number % injection_interval).is_zero() {
+        pot_entropy.insert(
+            number,
+            PotEntropyValue {
+                target_slot: None,
+                entropy: derive_pot_entropy(
+                    pre_digest.solution().chunk,
+                    pre_digest.pot_info().proof_of_time(),
+                ),
+            },
+        );
+
+        // Schedule injection of entropy collected `lookback_in_blocks` ago
+        if let Some(entropy_source_block_number) = maybe_entropy_source_block_number
+            && let Some(entropy_value) = pot_entropy.get_mut(&entropy_source_block_number)
+        {
+            entropy_value.target_slot.replace(
+                pre_digest
+                    .slot()
+                    .saturating_add(injection_parameters.delay()),
+            );
+        }
+    }
+
+    let expected_parameters_change = maybe_entropy_source_block_number
+        .and_then(|entropy_source_block_number| pot_entropy.get(&entropy_source_block_number))
+        .and_then(|entropy_value| {
+            Some(PotParametersChange {
+                slot: entropy_value.target_slot?,
+                slot_iterations,
+                entropy: entropy_value.entropy,
+            })
+        });
+
+    // Clean up entropy that was already injected
+    if let Some(entry) = pot_entropy.first_entry()
+        && entry
+            .get()
+            .target_slot
+            .is_some_and(|target_slot| target_slot < pre_digest.slot())
+    {
+        entry.remove();
+    }
+
+    expected_parameters_change
+}
+
 /// An consensus log item for Subspace.
 #[derive(Debug, Decode, Encode, Clone, PartialEq, Eq)]
 enum ConsensusLog {
@@ -305,7 +426,8 @@ where
         .find_map(|log| log.as_subspace_pre_digest())
 }
 
-fn is_seal_signature_valid<Header>(mut header: Header, offender: &FarmerPublicKey) -> bool
+/// Verifies that the header is sealed by the farmer with provided public key.
+pub fn is_seal_signature_valid<Header>(mut header: Header, offender: &FarmerPublicKey) -> bool
 where
     Header: HeaderT,
 {
@@ -405,6 +527,69 @@ impl Default for SolutionRanges {
     }
 }
 
+/// Solution ranges that were set at the start of an era.
+#[derive(Decode, Encode, MaxEncodedLen, PartialEq, Eq, Clone, Copy, Debug, TypeInfo)]
+pub struct EraSolutionRanges<Number> {
+    /// First block of the era.
+    pub start_block: Number,
+    /// Slot at which solution ranges for the era were derived.
+    pub start_slot: Slot,
+    /// Solution range used for block authoring during the era.
+    pub solution_range: SolutionRange,
+    /// Solution range used for voting during the era.
+    pub voting_solution_range: SolutionRange,
+    /// Size of the blockchain history at the start of the era.
+    pub history_size: HistorySize,
+}
+
+/// Estimated total space pledged to the network, in bytes.
+#[derive(Decode, Encode, PartialEq, Eq, Clone, Copy, Debug, TypeInfo)]
+pub struct NetworkSpaceEstimate {
+    /// Point estimate.
+    pub estimate: u128,
+    /// Lower bound of ~95% confidence interval.
+    pub lower_bound: u128,
+    /// Upper bound of ~95% confidence interval.
+    pub upper_bound: u128,
+}
+
+impl NetworkSpaceEstimate {
+    /// Create estimate from space that corresponds to solution range derived from the time it took
+    /// to produce `blocks` blocks.
+    ///
+    /// Time to produce a fixed number of blocks is Gamma-distributed, so relative standard error of
+    /// the estimate is `1 / sqrt(blocks)` and confidence interval spans two standard errors in each
+    /// direction. Solution range adjustment is clamped, so estimates are only meaningful after
+    /// solution range has converged.
+    pub fn new(estimate: u128, blocks: u32) -> Self {
+        let blocks_sqrt = u128::from(blocks.integer_sqrt().max(1));
+        let error = estimate.saturating_mul(2) / blocks_sqrt;
+
+        Self {
+            estimate,
+            lower_bound: estimate.saturating_sub(error),
+            upper_bound: estimate.saturating_add(error),
+        }
+    }
+}
+
+/// History of solution ranges and network space estimated from them.
+#[derive(Decode, Encode, PartialEq, Eq, Clone, Debug, TypeInfo)]
+pub struct SolutionRangeHistory<Number> {
+    /// Solution ranges of the most recent eras in ascending order along with network space
+    /// estimated from them.
+    pub eras: Vec<(EraSolutionRanges<Number>, NetworkSpaceEstimate)>,
+    /// Current solution ranges.
+    pub solution_ranges: SolutionRanges,
+    /// Whether solution range adjustment is enabled, network space can't be estimated from
+    /// solution range otherwise.
+    pub solution_range_adjustment_enabled: bool,
+    /// Network space estimated from current solution range.
+    pub network_space: NetworkSpaceEstimate,
+    /// Current size of the blockchain history.
+    pub history_size: HistorySize,
+}
+
 /// Subspace blockchain constants.
 #[derive(Debug, Encode, Decode, PartialEq, Eq, Clone, Copy, TypeInfo)]
 pub enum ChainConstants {
@@ -695,6 +880,7 @@ impl PotParameters {
 
 sp_api::decl_runtime_apis! {
     /// API necessary for block authorship with Subspace.
+    #[api_version(2)]
     pub trait SubspaceApi<RewardAddress: Encode + Decode> {
         /// Proof of time parameters
         fn pot_parameters() -> PotParameters;
@@ -748,5 +934,15 @@ sp_api::decl_runtime_apis! {
 
         /// Get Subspace blockchain constants
         fn chain_constants() -> ChainConstants;
+
+        /// History of solution ranges of recent eras and network space estimated from them
+        #[api_version(2)]
+        fn solution_range_history()
+            -> SolutionRangeHistory<<<Block as BlockT>::Header as HeaderT>::Number>;
+
+        /// Parameters of proof of time entropy injection
+        #[api_version(2)]
+        fn pot_entropy_injection_parameters()
+            -> PotEntropyInjectionParameters<<<Block as BlockT>::Header as HeaderT>::Number>;
     }
 }
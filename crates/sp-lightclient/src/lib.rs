@@ -19,11 +19,9 @@
 #![warn(rust_2018_idioms, missing_docs)]
 #![cfg_attr(not(feature = "std"), no_std)]
 
-// TODO: Unlock tests for PoT as well once PoT implementation settled (there are multiple items with
-//  this conditional compilation in the file
-#[cfg(all(test, not(feature = "pot")))]
+#[cfg(test)]
 mod mock;
-#[cfg(all(test, not(feature = "pot")))]
+#[cfg(test)]
 mod tests;
 
 use codec::{Decode, Encode};
@@ -36,18 +34,23 @@ use sp_consensus_subspace::digests::{
     Error as DigestError, ErrorDigestType, NextDigestsVerificationParams, PreDigest,
     SubspaceDigestItems,
 };
-use sp_consensus_subspace::{FarmerPublicKey, FarmerSignature};
+use sp_consensus_subspace::{
+    collect_pot_entropy, FarmerPublicKey, FarmerSignature, PotEntropyInjectionParameters,
+    PotEntropyValue, PotNextSlotInput, SubspaceJustification,
+};
 use sp_runtime::traits::Header as HeaderT;
 use sp_runtime::ArithmeticError;
+use sp_std::boxed::Box;
 use sp_std::cmp::Ordering;
 use sp_std::collections::btree_map::BTreeMap;
+use sp_std::fmt;
 use sp_std::marker::PhantomData;
-use sp_std::num::NonZeroU64;
-#[cfg(not(feature = "pot"))]
-use subspace_core_primitives::Randomness;
+use sp_std::num::{NonZeroU32, NonZeroU64};
+use sp_std::vec::Vec;
 use subspace_core_primitives::{
-    ArchivedHistorySegment, BlockWeight, HistorySize, PublicKey, RewardSignature, SectorId,
-    SegmentCommitment, SegmentIndex, SolutionRange, REWARD_SIGNING_CONTEXT,
+    ArchivedHistorySegment, BlockWeight, HistorySize, PotOutput, PotSeed, PublicKey,
+    RewardSignature, SectorId, SegmentCommitment, SegmentIndex, SolutionRange,
+    REWARD_SIGNING_CONTEXT,
 };
 use subspace_verification::{
     calculate_block_weight, check_reward_signature, PieceCheckParams, VerifySolutionParams,
@@ -66,9 +69,18 @@ pub struct ChainConstants<Header: HeaderT> {
     /// When Block #1 is finalized, these segment commitments are present in Block #1 are stored in
     /// the storage.
     pub genesis_segment_commitments: BTreeMap<SegmentIndex, SegmentCommitment>,
-    /// Defines interval at which randomness is updated.
-    #[cfg(not(feature = "pot"))]
-    pub global_randomness_interval: NumberOf<Header>,
+    /// Seed of the proof of time chain, used to verify checkpoints of Block #1.
+    pub genesis_pot_seed: PotSeed,
+    /// Number of proof of time iterations per slot at genesis.
+    pub genesis_pot_slot_iterations: NonZeroU32,
+    /// Number of slots between slot arrival and when corresponding block can be produced.
+    pub block_authoring_delay: Slot,
+    /// Interval, expressed in blocks, of proof of time entropy injection.
+    pub pot_entropy_injection_interval: NumberOf<Header>,
+    /// Depth, expressed in number of injection intervals, at which entropy is taken for injection.
+    pub pot_entropy_injection_lookback_depth: u8,
+    /// Delay after block, expressed in slots, after which entropy injection takes effect.
+    pub pot_entropy_injection_delay: Slot,
     /// Era duration at which solution range is updated.
     pub era_duration: NumberOf<Header>,
     /// Slot probability.
@@ -111,8 +123,14 @@ pub struct HeaderExt<Header> {
     pub maybe_next_solution_range_override: Option<SolutionRange>,
     /// Restrict block authoring to this public key.
     pub maybe_root_plot_public_key: Option<FarmerPublicKey>,
-
-    #[cfg(all(test, not(feature = "pot")))]
+    /// Verified proofs of time for slots after the slot of this header up to its future slot,
+    /// used to verify proof of time of the immediate descendant.
+    pub recent_proofs_of_time: BTreeMap<Slot, PotOutput>,
+    /// Proof of time entropy collected on chain until this header, keyed by block number it was
+    /// collected at, mirrors the state of the runtime.
+    pub pot_entropy: BTreeMap<NumberOf<Header>, PotEntropyValue>,
+
+    #[cfg(test)]
     test_overrides: mock::TestOverrides,
 }
 
@@ -120,35 +138,24 @@ pub struct HeaderExt<Header> {
 /// descendant.
 #[derive(Default, Debug, Encode, Decode, Clone, TypeInfo)]
 pub struct NextDigestItems {
-    #[cfg(not(feature = "pot"))]
-    next_global_randomness: Randomness,
     next_solution_range: SolutionRange,
 }
 
 impl NextDigestItems {
     /// Constructs self with provided next digest items.
-    pub fn new(
-        #[cfg(not(feature = "pot"))] next_global_randomness: Randomness,
-        next_solution_range: SolutionRange,
-    ) -> Self {
+    pub fn new(next_solution_range: SolutionRange) -> Self {
         Self {
-            #[cfg(not(feature = "pot"))]
-            next_global_randomness,
             next_solution_range,
         }
     }
 }
 
 impl<Header: HeaderT> HeaderExt<Header> {
-    /// Extracts the next digest items Randomness, Solution range, and Salt present in the Header.
+    /// Extracts the next solution range present in the Header.
     /// If next digests are not present, then we fallback to the current ones.
     fn extract_next_digest_items(&self) -> Result<NextDigestItems, ImportError<Header>> {
         let SubspaceDigestItems {
-            #[cfg(not(feature = "pot"))]
-            global_randomness,
             solution_range,
-            #[cfg(not(feature = "pot"))]
-            next_global_randomness,
             next_solution_range,
             ..
         } = extract_subspace_digest_items::<_, FarmerPublicKey, FarmerPublicKey, FarmerSignature>(
@@ -160,7 +167,7 @@ impl<Header: HeaderT> HeaderExt<Header> {
             .maybe_current_solution_range_override
             .unwrap_or(solution_range);
 
-        #[cfg(all(test, not(feature = "pot")))]
+        #[cfg(test)]
         let solution_range = {
             if self.test_overrides.solution_range.is_some() {
                 self.test_overrides.solution_range.unwrap()
@@ -169,7 +176,7 @@ impl<Header: HeaderT> HeaderExt<Header> {
             }
         };
 
-        #[cfg(all(test, not(feature = "pot")))]
+        #[cfg(test)]
         let next_solution_range = {
             if self.test_overrides.next_solution_range.is_some() {
                 self.test_overrides.next_solution_range
@@ -179,8 +186,6 @@ impl<Header: HeaderT> HeaderExt<Header> {
         };
 
         Ok(NextDigestItems {
-            #[cfg(not(feature = "pot"))]
-            next_global_randomness: next_global_randomness.unwrap_or(global_randomness),
             next_solution_range: next_solution_range.unwrap_or(solution_range),
         })
     }
@@ -275,6 +280,10 @@ pub enum ImportError<Header: HeaderT> {
     EmptySegmentCommitmentHistory,
     /// Invalid history size
     InvalidHistorySize,
+    /// Proof of time justification doesn't match the header.
+    InvalidPotJustification,
+    /// Proof of time checkpoints or proof of time in the header are invalid.
+    InvalidProofOfTime,
 }
 
 impl<Header: HeaderT> From<DigestError> for ImportError<Header> {
@@ -284,24 +293,192 @@ impl<Header: HeaderT> From<DigestError> for ImportError<Header> {
     }
 }
 
+/// Notification about the change of the chain caused by header import.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChainNotification<Header: HeaderT> {
+    /// Best header has changed.
+    BestHeader {
+        /// Hash of the new best header.
+        hash: HashOf<Header>,
+        /// Number of the new best header.
+        number: NumberOf<Header>,
+        /// Headers that are no longer part of the best chain, from the previous best header
+        /// backwards. Empty unless there was a reorg.
+        retracted: Vec<HashOf<Header>>,
+        /// Headers that became part of the best chain, in ascending order ending with the new best
+        /// header.
+        enacted: Vec<HashOf<Header>>,
+    },
+    /// Header was finalized, notifications are sent for every finalized header in ascending order.
+    FinalizedHeader {
+        /// Hash of the finalized header.
+        hash: HashOf<Header>,
+        /// Number of the finalized header.
+        number: NumberOf<Header>,
+    },
+}
+
+/// Error while building canonical ancestry path.
+#[derive(Debug, PartialEq, Eq)]
+pub enum AncestryPathError<Header: HeaderT> {
+    /// Missing header associated with hash, it was either never imported or already pruned.
+    MissingHeader(HashOf<Header>),
+    /// Header is not part of the best chain.
+    NotCanonical(HashOf<Header>),
+    /// Header is not an ancestor of the descendant header.
+    NotAncestor(HashOf<Header>),
+}
+
+/// Returns headers of the best chain from `ancestor` to `descendant` (both inclusive) in ascending
+/// order.
+///
+/// Successful result proves that both headers are part of the best chain known to the storage and
+/// that `descendant` is buried under `ancestor` by `path.len() - 1` headers, using best header as
+/// `descendant` gives the depth of `ancestor`.
+pub fn canonical_ancestry_path<Header, Store>(
+    store: &Store,
+    ancestor: HashOf<Header>,
+    descendant: HashOf<Header>,
+) -> Result<Vec<Header>, AncestryPathError<Header>>
+where
+    Header: HeaderT,
+    Store: Storage<Header>,
+{
+    let header = |hash| {
+        store
+            .header(hash)
+            .map(|header_ext| header_ext.header)
+            .ok_or(AncestryPathError::MissingHeader(hash))
+    };
+
+    let descendant_header = header(descendant)?;
+    let descendant_number = *descendant_header.number();
+
+    // finalized headers never have forks, otherwise walk back from the best header
+    let is_canonical = if descendant_number <= *store.finalized_header().header.number() {
+        store
+            .headers_at_number(descendant_number)
+            .iter()
+            .any(|header_ext| header_ext.header.hash() == descendant)
+    } else {
+        let mut best_header = store.best_header().header;
+        while *best_header.number() > descendant_number {
+            best_header = header(*best_header.parent_hash())?;
+        }
+
+        best_header.hash() == descendant
+    };
+    if !is_canonical {
+        return Err(AncestryPathError::NotCanonical(descendant));
+    }
+
+    let ancestor_number = *header(ancestor)?.number();
+    if ancestor_number > descendant_number {
+        return Err(AncestryPathError::NotAncestor(ancestor));
+    }
+
+    let mut path = Vec::new();
+    let mut current_header = descendant_header;
+    while *current_header.number() > ancestor_number {
+        let parent_header = header(*current_header.parent_hash())?;
+        path.push(current_header);
+        current_header = parent_header;
+    }
+    if current_header.hash() != ancestor {
+        return Err(AncestryPathError::NotAncestor(ancestor));
+    }
+    path.push(current_header);
+
+    path.reverse();
+    Ok(path)
+}
+
+type NotificationListener<Header> = Box<dyn FnMut(&ChainNotification<Header>) + Send>;
+
 /// Verifies and import headers.
-#[derive(Debug)]
 pub struct HeaderImporter<Header: HeaderT, Store: Storage<Header>> {
     store: Store,
+    notification_listeners: Vec<NotificationListener<Header>>,
+    /// Notifications of the header that is being imported, only sent once import succeeds
+    pending_notifications: Vec<ChainNotification<Header>>,
     _phantom: PhantomData<Header>,
 }
 
+impl<Header, Store> fmt::Debug for HeaderImporter<Header, Store>
+where
+    Header: HeaderT,
+    Store: Storage<Header> + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HeaderImporter")
+            .field("store", &self.store)
+            .finish_non_exhaustive()
+    }
+}
+
 impl<Header: HeaderT, Store: Storage<Header>> HeaderImporter<Header, Store> {
     /// Returns a new instance of HeaderImporter with provided Storage impls
     pub fn new(store: Store) -> Self {
         HeaderImporter {
             store,
+            notification_listeners: Vec::new(),
+            pending_notifications: Vec::new(),
             _phantom: Default::default(),
         }
     }
 
-    /// Verifies header, computes consensus values for block progress and stores the HeaderExt.
-    pub fn import_header(&mut self, mut header: Header) -> Result<(), ImportError<Header>> {
+    /// Registers listener that is called with every chain notification in the order in which
+    /// changes are applied to the storage.
+    ///
+    /// Notifications are only sent once header import succeeds, nothing is sent if import fails.
+    pub fn add_notification_listener<Listener>(&mut self, listener: Listener)
+    where
+        Listener: FnMut(&ChainNotification<Header>) + Send + 'static,
+    {
+        self.notification_listeners.push(Box::new(listener));
+    }
+
+    /// Storage headers are imported into, for instance to commit changes buffered by it.
+    pub fn store_mut(&mut self) -> &mut Store {
+        &mut self.store
+    }
+
+    /// Returns headers of the best chain from `ancestor` to `descendant` (both inclusive), see
+    /// [`canonical_ancestry_path`] for details.
+    pub fn canonical_ancestry_path(
+        &self,
+        ancestor: HashOf<Header>,
+        descendant: HashOf<Header>,
+    ) -> Result<Vec<Header>, AncestryPathError<Header>> {
+        canonical_ancestry_path(&self.store, ancestor, descendant)
+    }
+
+    /// Verifies header along with proof of time checkpoints from its justification, computes
+    /// consensus values for block progress and stores the HeaderExt.
+    pub fn import_header(
+        &mut self,
+        header: Header,
+        justification: SubspaceJustification,
+    ) -> Result<(), ImportError<Header>> {
+        let result = self.verify_and_store_header(header, justification);
+
+        let pending_notifications = sp_std::mem::take(&mut self.pending_notifications);
+        if result.is_ok() {
+            for notification in &pending_notifications {
+                for listener in &mut self.notification_listeners {
+                    listener(notification);
+                }
+            }
+        }
+
+        result
+    }
+
+    fn verify_and_store_header(
+        &mut self,
+        mut header: Header,
+        justification: SubspaceJustification,
+    ) -> Result<(), ImportError<Header>> {
         // check if the header is already imported
         match self.store.header(header.hash()) {
             Some(_) => Err(ImportError::HeaderAlreadyImported),
@@ -325,7 +502,7 @@ impl<Header: HeaderT, Store: Storage<Header>> HeaderImporter<Header, Store> {
             .header(*header.parent_hash())
             .ok_or_else(|| ImportError::MissingParent(header.hash()))?;
 
-        // verify global randomness and solution range from the parent header
+        // verify solution range and proof of time slot iterations from the parent header
         let header_digests = self.verify_header_digest_with_parent(&parent_header, &header)?;
 
         // verify next digest items
@@ -345,8 +522,6 @@ impl<Header: HeaderT, Store: Storage<Header>> HeaderImporter<Header, Store> {
         verify_next_digests::<Header>(NextDigestsVerificationParams {
             number: *header.number(),
             header_digests: &header_digests,
-            #[cfg(not(feature = "pot"))]
-            global_randomness_interval: constants.global_randomness_interval,
             era_duration: constants.era_duration,
             slot_probability: constants.slot_probability,
             era_start_slot: parent_header.era_start_slot,
@@ -358,6 +533,18 @@ impl<Header: HeaderT, Store: Storage<Header>> HeaderImporter<Header, Store> {
         // slot must be strictly increasing from the parent header
         Self::verify_slot(&parent_header.header, &header_digests.pre_digest)?;
 
+        // verify proof of time parameters change, it affects verification of checkpoints below
+        let pot_entropy = Self::verify_pot_parameters_change(
+            &constants,
+            &parent_header,
+            *header.number(),
+            &header_digests,
+        )?;
+
+        // verify proof of time checkpoints and proof of time in the header
+        let recent_proofs_of_time =
+            Self::verify_proof_of_time(&constants, &parent_header, &header_digests, justification)?;
+
         // verify block signature
         Self::verify_block_signature(
             &mut header,
@@ -405,9 +592,6 @@ impl<Header: HeaderT, Store: Storage<Header>> HeaderImporter<Header, Store> {
             header_digests.pre_digest.solution().into(),
             header_digests.pre_digest.slot().into(),
             (&VerifySolutionParams {
-                #[cfg(not(feature = "pot"))]
-                global_randomness: header_digests.global_randomness,
-                #[cfg(feature = "pot")]
                 proof_of_time: header_digests.pre_digest.pot_info().proof_of_time(),
                 solution_range: header_digests.solution_range,
                 piece_check_params: Some(PieceCheckParams {
@@ -458,6 +642,7 @@ impl<Header: HeaderT, Store: Storage<Header>> HeaderImporter<Header, Store> {
         }
 
         // store header
+        let (hash, number) = (header.hash(), *header.number());
         let header_ext = HeaderExt {
             header,
             total_weight,
@@ -466,8 +651,10 @@ impl<Header: HeaderT, Store: Storage<Header>> HeaderImporter<Header, Store> {
             maybe_current_solution_range_override,
             maybe_next_solution_range_override,
             maybe_root_plot_public_key,
+            recent_proofs_of_time,
+            pot_entropy,
 
-            #[cfg(all(test, not(feature = "pot")))]
+            #[cfg(test)]
             test_overrides: Default::default(),
         };
 
@@ -475,6 +662,16 @@ impl<Header: HeaderT, Store: Storage<Header>> HeaderImporter<Header, Store> {
 
         // finalize, prune forks, and ensure storage is bounded if the chain has progressed
         if is_best_header {
+            if !self.notification_listeners.is_empty() {
+                let (retracted, enacted) = self.tree_route(last_best_header.header.hash(), hash)?;
+                self.notify(ChainNotification::BestHeader {
+                    hash,
+                    number,
+                    retracted,
+                    enacted,
+                });
+            }
+
             self.finalize_header_at_k_depth()?;
             self.ensure_storage_bound();
         }
@@ -512,17 +709,25 @@ impl<Header: HeaderT, Store: Storage<Header>> HeaderImporter<Header, Store> {
         };
 
         // check the digest items against the next digest items from parent header
-        #[cfg(not(feature = "pot"))]
-        if pre_digest_items.global_randomness != next_digest_items.next_global_randomness {
-            return Err(ImportError::InvalidDigest(
-                ErrorDigestType::GlobalRandomness,
-            ));
-        }
-
         if pre_digest_items.solution_range != next_digest_items.next_solution_range {
             return Err(ImportError::InvalidDigest(ErrorDigestType::SolutionRange));
         }
 
+        // slot iterations are not adjusted on chain yet, so they must match the parent header
+        let expected_pot_slot_iterations = if header.number() == &One::one() {
+            self.store.chain_constants().genesis_pot_slot_iterations
+        } else {
+            extract_subspace_digest_items::<_, FarmerPublicKey, FarmerPublicKey, FarmerSignature>(
+                &parent_header.header,
+            )?
+            .pot_slot_iterations
+        };
+        if pre_digest_items.pot_slot_iterations != expected_pot_slot_iterations {
+            return Err(ImportError::InvalidDigest(
+                ErrorDigestType::PotSlotIterations,
+            ));
+        }
+
         Ok(pre_digest_items)
     }
 
@@ -540,6 +745,126 @@ impl<Header: HeaderT, Store: Storage<Header>> HeaderImporter<Header, Store> {
         Ok(())
     }
 
+    /// Verifies proof of time checkpoints from justification against the header and its parent.
+    ///
+    /// Checkpoints must cover all slots after the future slot of the parent header up to the
+    /// future slot of the header, start with the correct seed, end with the future proof of time
+    /// and include proof of time of the header. Returns verified proofs of time for slots after
+    /// the slot of the header.
+    fn verify_proof_of_time(
+        constants: &ChainConstants<Header>,
+        parent_header: &HeaderExt<Header>,
+        header_digests: &SubspaceDigestItems<FarmerPublicKey, FarmerPublicKey, FarmerSignature>,
+        justification: SubspaceJustification,
+    ) -> Result<BTreeMap<Slot, PotOutput>, ImportError<Header>> {
+        let SubspaceJustification::PotCheckpoints { seed, checkpoints } = justification;
+
+        let slot = header_digests.pre_digest.slot();
+        let future_slot = slot + constants.block_authoring_delay;
+
+        // genesis header doesn't have proof of time, so Block #1 starts from the genesis seed
+        let (expected_seed, parent_future_slot) = if parent_header.header.number().is_zero() {
+            (constants.genesis_pot_seed, Slot::from(0))
+        } else {
+            let parent_pre_digest = extract_pre_digest(&parent_header.header)?;
+            let parent_future_slot = parent_pre_digest.slot() + constants.block_authoring_delay;
+            let expected_input = PotNextSlotInput::derive(
+                header_digests.pot_slot_iterations,
+                parent_future_slot,
+                parent_pre_digest.pot_info().future_proof_of_time(),
+                &header_digests.pot_parameters_change,
+            );
+
+            (expected_input.seed, parent_future_slot)
+        };
+
+        if seed != expected_seed || checkpoints.len() as u64 != *future_slot - *parent_future_slot {
+            return Err(ImportError::InvalidPotJustification);
+        }
+
+        let first_slot = parent_future_slot + Slot::from(1);
+        let slot_iterations = header_digests
+            .pot_parameters_change
+            .as_ref()
+            .and_then(|parameters_change| {
+                (parameters_change.slot == first_slot).then_some(parameters_change.slot_iterations)
+            })
+            .unwrap_or(header_digests.pot_slot_iterations);
+
+        let mut recent_proofs_of_time = parent_header.recent_proofs_of_time.clone();
+        let mut pot_input = PotNextSlotInput {
+            slot: first_slot,
+            slot_iterations,
+            seed,
+        };
+        for (index, checkpoints) in checkpoints.iter().enumerate() {
+            if index > 0 {
+                pot_input = PotNextSlotInput::derive(
+                    pot_input.slot_iterations,
+                    pot_input.slot,
+                    recent_proofs_of_time[&pot_input.slot],
+                    &header_digests.pot_parameters_change,
+                );
+            }
+
+            let valid = subspace_proof_of_time::verify(
+                pot_input.seed,
+                pot_input.slot_iterations,
+                checkpoints.as_slice(),
+            )
+            .map_err(|_error| ImportError::InvalidProofOfTime)?;
+            if !valid {
+                return Err(ImportError::InvalidProofOfTime);
+            }
+
+            recent_proofs_of_time.insert(pot_input.slot, checkpoints.output());
+        }
+
+        let pot_info = header_digests.pre_digest.pot_info();
+        if recent_proofs_of_time.get(&future_slot) != Some(&pot_info.future_proof_of_time())
+            || recent_proofs_of_time.get(&slot) != Some(&pot_info.proof_of_time())
+        {
+            return Err(ImportError::InvalidProofOfTime);
+        }
+
+        // descendants can only have higher slots, older proofs are not needed anymore
+        recent_proofs_of_time.retain(|&recent_slot, _| recent_slot > slot);
+
+        Ok(recent_proofs_of_time)
+    }
+
+    /// Verifies proof of time parameters change in the header the same way runtime derives it
+    /// from proof of time entropy collected on chain. Returns updated proof of time entropy.
+    fn verify_pot_parameters_change(
+        constants: &ChainConstants<Header>,
+        parent_header: &HeaderExt<Header>,
+        number: NumberOf<Header>,
+        header_digests: &SubspaceDigestItems<FarmerPublicKey, FarmerPublicKey, FarmerSignature>,
+    ) -> Result<BTreeMap<NumberOf<Header>, PotEntropyValue>, ImportError<Header>> {
+        let injection_parameters = PotEntropyInjectionParameters::V0 {
+            interval: constants.pot_entropy_injection_interval,
+            lookback_depth: constants.pot_entropy_injection_lookback_depth,
+            delay: constants.pot_entropy_injection_delay,
+        };
+        let mut pot_entropy = parent_header.pot_entropy.clone();
+
+        let expected_pot_parameters_change = collect_pot_entropy(
+            &injection_parameters,
+            &mut pot_entropy,
+            number,
+            &header_digests.pre_digest,
+            header_digests.pot_slot_iterations,
+        );
+
+        if header_digests.pot_parameters_change != expected_pot_parameters_change {
+            return Err(ImportError::InvalidDigest(
+                ErrorDigestType::PotParametersChange,
+            ));
+        }
+
+        Ok(pot_entropy)
+    }
+
     /// Verifies the block signature present in the last digest log.
     fn verify_block_signature(
         header: &mut Header,
@@ -763,9 +1088,60 @@ impl<Header: HeaderT, Store: Storage<Header>> HeaderImporter<Header, Store> {
         // store the segment commitments present in the header digests
         self.store
             .store_segment_commitments(digests_items.segment_commitments);
+
+        self.notify(ChainNotification::FinalizedHeader {
+            hash: header.hash(),
+            number: *header.number(),
+        });
         Ok(())
     }
 
+    /// Queue notification to be sent once import of the current header succeeds
+    fn notify(&mut self, notification: ChainNotification<Header>) {
+        if !self.notification_listeners.is_empty() {
+            self.pending_notifications.push(notification);
+        }
+    }
+
+    /// Returns headers retracted from and enacted to the best chain when the best header changes
+    /// from `from` to `to`, see [`ChainNotification::BestHeader`] for details.
+    #[allow(clippy::type_complexity)]
+    fn tree_route(
+        &self,
+        from: HashOf<Header>,
+        to: HashOf<Header>,
+    ) -> Result<(Vec<HashOf<Header>>, Vec<HashOf<Header>>), ImportError<Header>> {
+        let header = |hash| {
+            self.store
+                .header(hash)
+                .map(|header_ext| header_ext.header)
+                .ok_or(ImportError::MissingHeader(hash))
+        };
+
+        let mut from_header = header(from)?;
+        let mut to_header = header(to)?;
+        let mut retracted = Vec::new();
+        let mut enacted = Vec::new();
+
+        while from_header.number() > to_header.number() {
+            retracted.push(from_header.hash());
+            from_header = header(*from_header.parent_hash())?;
+        }
+        while to_header.number() > from_header.number() {
+            enacted.push(to_header.hash());
+            to_header = header(*to_header.parent_hash())?;
+        }
+        while from_header.hash() != to_header.hash() {
+            retracted.push(from_header.hash());
+            enacted.push(to_header.hash());
+            from_header = header(*from_header.parent_hash())?;
+            to_header = header(*to_header.parent_hash())?;
+        }
+
+        enacted.reverse();
+        Ok((retracted, enacted))
+    }
+
     /// Finalize the header at K-depth from the best block and prune remaining forks at that number.
     /// We want to finalize the header from the current finalized header until the K-depth number of the best.
     /// 1. In an ideal scenario, the current finalized head is one number less than number to be finalized.
@@ -2,8 +2,6 @@ use crate::{ChainConstants, HashOf, HeaderExt, NumberOf, Storage};
 use codec::{Decode, Encode};
 use scale_info::TypeInfo;
 use sp_arithmetic::traits::Zero;
-#[cfg(feature = "pot")]
-use sp_consensus_subspace::PotExtension;
 use sp_consensus_subspace::{KzgExtension, PosExtension};
 use sp_io::TestExternalities;
 use sp_runtime::traits::{BlakeTwo256, Header as HeaderT};
@@ -212,10 +210,6 @@ pub fn new_test_ext() -> TestExternalities {
 
     ext.register_extension(KzgExtension::new(kzg_instance().clone()));
     ext.register_extension(PosExtension::new::<PosTable>());
-    #[cfg(feature = "pot")]
-    ext.register_extension(PotExtension::new(Box::new(
-        |parent_hash, slot, proof_of_time| todo!(),
-    )));
 
     ext
 }
@@ -1,7 +1,7 @@
 use crate::mock::{kzg_instance, new_test_ext, Header, MockStorage, PosTable};
 use crate::{
-    ChainConstants, DigestError, HashOf, HeaderExt, HeaderImporter, ImportError, NextDigestItems,
-    NumberOf, Storage, StorageBound,
+    AncestryPathError, ChainConstants, ChainNotification, DigestError, HashOf, HeaderExt,
+    HeaderImporter, ImportError, NextDigestItems, NumberOf, Storage, StorageBound,
 };
 use frame_support::{assert_err, assert_ok};
 use futures::executor::block_on;
@@ -9,25 +9,24 @@ use rand::rngs::StdRng;
 use rand::{Rng, SeedableRng};
 use schnorrkel::Keypair;
 use sp_consensus_slots::Slot;
-#[cfg(not(feature = "pot"))]
-use sp_consensus_subspace::digests::derive_next_global_randomness;
 use sp_consensus_subspace::digests::{
     derive_next_solution_range, extract_pre_digest, extract_subspace_digest_items,
     CompatibleDigestItem, DeriveNextSolutionRangeParams, ErrorDigestType, PreDigest,
+    PreDigestPotInfo,
+};
+use sp_consensus_subspace::{
+    FarmerPublicKey, FarmerSignature, PotNextSlotInput, PotParametersChange, SubspaceJustification,
 };
-use sp_consensus_subspace::{FarmerPublicKey, FarmerSignature};
 use sp_runtime::app_crypto::UncheckedFrom;
 use sp_runtime::testing::H256;
 use sp_runtime::traits::Header as HeaderT;
 use sp_runtime::{Digest, DigestItem};
 use std::iter;
-use std::num::{NonZeroU64, NonZeroUsize};
-use std::sync::OnceLock;
+use std::num::{NonZeroU32, NonZeroU64, NonZeroUsize};
+use std::sync::{Arc, Mutex, OnceLock};
 use subspace_archiving::archiver::{Archiver, NewArchivedSegment};
-#[cfg(feature = "pot")]
-use subspace_core_primitives::PotOutput;
 use subspace_core_primitives::{
-    BlockWeight, HistorySize, PublicKey, Randomness, Record, RecordedHistorySegment,
+    BlockWeight, HistorySize, PotOutput, PotSeed, PublicKey, Record, RecordedHistorySegment,
     SegmentCommitment, SegmentIndex, SlotNumber, Solution, SolutionRange, REWARD_SIGNING_CONTEXT,
 };
 use subspace_erasure_coding::ErasureCoding;
@@ -36,8 +35,6 @@ use subspace_farmer_components::plotting::plot_sector;
 use subspace_farmer_components::sector::{sector_size, SectorMetadataChecksummed};
 use subspace_farmer_components::FarmerProtocolInfo;
 use subspace_proof_of_space::Table;
-#[cfg(not(feature = "pot"))]
-use subspace_verification::derive_randomness;
 use subspace_verification::{calculate_block_weight, verify_solution, VerifySolutionParams};
 
 fn erasure_coding_instance() -> &'static ErasureCoding {
@@ -52,24 +49,24 @@ fn erasure_coding_instance() -> &'static ErasureCoding {
     })
 }
 
-#[cfg(not(feature = "pot"))]
-fn default_randomness() -> Randomness {
-    Randomness::from([1u8; 32])
-}
+// Small number of iterations for testing purposes
+const POT_SLOT_ITERATIONS: NonZeroU32 = NonZeroU32::new(16).expect("Not zero; qed");
 
 fn default_test_constants() -> ChainConstants<Header> {
-    #[cfg(not(feature = "pot"))]
-    let global_randomness = default_randomness();
     ChainConstants {
         k_depth: 7,
         genesis_digest_items: NextDigestItems {
-            #[cfg(not(feature = "pot"))]
-            next_global_randomness: global_randomness,
             next_solution_range: Default::default(),
         },
         genesis_segment_commitments: Default::default(),
-        #[cfg(not(feature = "pot"))]
-        global_randomness_interval: 20,
+        genesis_pot_seed: PotSeed::from([1u8; PotSeed::SIZE]),
+        genesis_pot_slot_iterations: POT_SLOT_ITERATIONS,
+        block_authoring_delay: Slot::from(2),
+        pot_entropy_injection_interval: 5,
+        pot_entropy_injection_lookback_depth: 2,
+        // Same invariants as in runtime: injection delay is bigger than block authoring delay + 1
+        // and smaller than injection interval
+        pot_entropy_injection_delay: Slot::from(4),
         era_duration: 20,
         slot_probability: (1, 6),
         storage_bound: Default::default(),
@@ -124,17 +121,104 @@ impl FarmerParameters {
     }
 }
 
+struct HeaderPot {
+    justification: SubspaceJustification,
+    proof_of_time: PotOutput,
+    future_proof_of_time: PotOutput,
+    pot_parameters_change: Option<PotParametersChange>,
+}
+
+fn pot_parameters_change(
+    constants: &ChainConstants<Header>,
+    parent_header: &HeaderExt<Header>,
+    number: NumberOf<Header>,
+    slot: Slot,
+) -> Option<PotParametersChange> {
+    let interval = constants.pot_entropy_injection_interval;
+    let lookback_in_blocks = interval * u32::from(constants.pot_entropy_injection_lookback_depth);
+    let entropy_source_block_number =
+        (number / interval * interval).checked_sub(lookback_in_blocks)?;
+    let entropy_value = parent_header
+        .pot_entropy
+        .get(&entropy_source_block_number)?;
+
+    Some(PotParametersChange {
+        // target slot is only missing when it is scheduled by this header
+        slot: entropy_value
+            .target_slot
+            .unwrap_or(slot + constants.pot_entropy_injection_delay),
+        slot_iterations: POT_SLOT_ITERATIONS,
+        entropy: entropy_value.entropy,
+    })
+}
+
+/// Proves proof of time for slots after the future slot of the parent header up to the future slot
+/// of the header at `slot`.
+fn prove_pot(store: &MockStorage, parent_hash: HashOf<Header>, slot: Slot) -> HeaderPot {
+    let constants = store.chain_constants();
+    let parent_header = store.header(parent_hash).unwrap();
+    let pot_parameters_change = pot_parameters_change(
+        &constants,
+        &parent_header,
+        parent_header.header.number + 1,
+        slot,
+    );
+
+    let future_slot = slot + constants.block_authoring_delay;
+    let (seed, parent_future_slot) = if parent_header.header.number == 0 {
+        (constants.genesis_pot_seed, Slot::from(0))
+    } else {
+        let parent_pre_digest = extract_pre_digest(&parent_header.header).unwrap();
+        let parent_future_slot = parent_pre_digest.slot() + constants.block_authoring_delay;
+        let pot_input = PotNextSlotInput::derive(
+            POT_SLOT_ITERATIONS,
+            parent_future_slot,
+            parent_pre_digest.pot_info().future_proof_of_time(),
+            &pot_parameters_change,
+        );
+
+        (pot_input.seed, parent_future_slot)
+    };
+
+    let mut proofs_of_time = parent_header.recent_proofs_of_time;
+    let mut checkpoints = Vec::new();
+    let mut pot_input = PotNextSlotInput {
+        slot: parent_future_slot + Slot::from(1),
+        slot_iterations: POT_SLOT_ITERATIONS,
+        seed,
+    };
+    loop {
+        let slot_checkpoints =
+            subspace_proof_of_time::prove(pot_input.seed, pot_input.slot_iterations).unwrap();
+        checkpoints.push(slot_checkpoints);
+        proofs_of_time.insert(pot_input.slot, slot_checkpoints.output());
+
+        if pot_input.slot == future_slot {
+            break;
+        }
+
+        pot_input = PotNextSlotInput::derive(
+            pot_input.slot_iterations,
+            pot_input.slot,
+            slot_checkpoints.output(),
+            &pot_parameters_change,
+        );
+    }
+
+    HeaderPot {
+        justification: SubspaceJustification::PotCheckpoints { seed, checkpoints },
+        proof_of_time: proofs_of_time[&slot],
+        future_proof_of_time: proofs_of_time[&future_slot],
+        pot_parameters_change,
+    }
+}
+
 struct ValidHeaderParams<'a> {
     parent_hash: HashOf<Header>,
     number: NumberOf<Header>,
     slot: u64,
     keypair: &'a Keypair,
-    #[cfg(not(feature = "pot"))]
-    global_randomness: Randomness,
-    #[cfg(feature = "pot")]
-    proof_of_time: PotOutput,
-    #[cfg(feature = "pot")]
-    future_proof_of_time: PotOutput,
+    pot: &'a HeaderPot,
     farmer_parameters: &'a FarmerParameters,
 }
 
@@ -152,12 +236,7 @@ fn valid_header(
         number,
         slot,
         keypair,
-        #[cfg(not(feature = "pot"))]
-        global_randomness,
-        #[cfg(feature = "pot")]
-        proof_of_time,
-        #[cfg(feature = "pot")]
-        future_proof_of_time,
+        pot,
         farmer_parameters,
     } = params;
 
@@ -190,8 +269,7 @@ fn valid_header(
         ))
         .unwrap();
 
-        #[cfg(feature = "pot")]
-        let global_randomness = proof_of_time.derive_global_randomness();
+        let global_randomness = pot.proof_of_time.derive_global_randomness();
         let global_challenge = global_randomness.derive_global_challenge(slot);
 
         let maybe_solution_candidates = audit_sector(
@@ -236,10 +314,7 @@ fn valid_header(
             &solution,
             slot,
             &VerifySolutionParams {
-                #[cfg(not(feature = "pot"))]
-                global_randomness,
-                #[cfg(feature = "pot")]
-                proof_of_time,
+                proof_of_time: pot.proof_of_time,
                 solution_range: SolutionRange::MAX,
                 piece_check_params: None,
             },
@@ -252,17 +327,19 @@ fn valid_header(
         let pre_digest = PreDigest::V0 {
             slot: slot.into(),
             solution,
-            #[cfg(feature = "pot")]
-            proof_of_time,
-            #[cfg(feature = "pot")]
-            future_proof_of_time,
+            pot_info: PreDigestPotInfo::V0 {
+                proof_of_time: pot.proof_of_time,
+                future_proof_of_time: pot.future_proof_of_time,
+            },
         };
-        let digests = vec![
-            #[cfg(not(feature = "pot"))]
-            DigestItem::global_randomness(global_randomness),
+        let mut digests = vec![
             DigestItem::solution_range(solution_range),
             DigestItem::subspace_pre_digest(&pre_digest),
+            DigestItem::pot_slot_iterations(POT_SLOT_ITERATIONS),
         ];
+        if let Some(pot_parameters_change) = pot.pot_parameters_change {
+            digests.push(DigestItem::pot_parameters_change(pot_parameters_change));
+        }
 
         let header = Header {
             parent_hash,
@@ -331,6 +408,8 @@ fn initialize_store(
         maybe_current_solution_range_override: None,
         maybe_next_solution_range_override: None,
         maybe_root_plot_public_key,
+        recent_proofs_of_time: Default::default(),
+        pot_entropy: Default::default(),
         test_overrides: Default::default(),
     };
 
@@ -348,14 +427,6 @@ fn add_next_digests(store: &MockStorage, number: NumberOf<Header>, header: &mut
         .unwrap();
 
     let digest_logs = header.digest_mut();
-    #[cfg(not(feature = "pot"))]
-    if let Some(next_randomness) = derive_next_global_randomness::<Header>(
-        number,
-        constants.global_randomness_interval,
-        &digests.pre_digest,
-    ) {
-        digest_logs.push(DigestItem::next_global_randomness(next_randomness));
-    }
 
     if let Some(next_solution_range) =
         derive_next_solution_range::<Header>(DeriveNextSolutionRangeParams {
@@ -409,9 +480,8 @@ fn add_headers_to_chain(
     let mut slot = next_slot(constants.slot_probability, slot);
     let mut best_header_hash = best_header_ext.header.hash();
     while number <= until_number {
-        let (global_randomness, override_next_solution) = if number == 1 {
-            let randomness = default_randomness();
-            (randomness, false)
+        let override_next_solution = if number == 1 {
+            false
         } else {
             let header = importer.store.header(parent_hash).unwrap();
             let digests = extract_subspace_digest_items::<
@@ -422,19 +492,17 @@ fn add_headers_to_chain(
             >(&header.header)
             .unwrap();
 
-            let randomness = digests
-                .next_global_randomness
-                .unwrap_or(digests.global_randomness);
-            (randomness, digests.next_global_randomness.is_some())
+            digests.next_solution_range.is_some()
         };
 
+        let pot = prove_pot(&importer.store, parent_hash, slot);
         let (mut header, solution_range, block_weight, segment_index, segment_commitment) =
             valid_header(ValidHeaderParams {
                 parent_hash,
                 number,
                 slot: slot.into(),
                 keypair,
-                global_randomness,
+                pot: &pot,
                 farmer_parameters,
             });
         importer.store.override_cumulative_weight(parent_hash, 0);
@@ -483,7 +551,7 @@ fn add_headers_to_chain(
         slot = next_slot(constants.slot_probability, slot);
         number += 1;
 
-        assert_ok!(importer.import_header(header.clone()));
+        assert_ok!(importer.import_header(header.clone(), pot.justification));
         if let Some(ForkAt {
             is_best: maybe_best,
             ..
@@ -535,21 +603,21 @@ fn test_header_import_missing_parent() {
         let farmer_parameters = FarmerParameters::new();
 
         let constants = default_test_constants();
-        let (mut store, _genesis_hash) = initialize_store(constants, true, None);
-        let global_randomness = default_randomness();
+        let (mut store, genesis_hash) = initialize_store(constants, true, None);
+        let pot = prove_pot(&store, genesis_hash, Slot::from(1));
         let (header, _solution_range, _block_weight, segment_index, segment_commitment) =
             valid_header(ValidHeaderParams {
                 parent_hash: Default::default(),
                 number: 1,
                 slot: 1,
                 keypair: &keypair,
-                global_randomness,
+                pot: &pot,
                 farmer_parameters: &farmer_parameters,
             });
         store.store_segment_commitment(segment_index, segment_commitment);
         let mut importer = HeaderImporter::new(store);
         assert_err!(
-            importer.import_header(header.clone()),
+            importer.import_header(header.clone(), pot.justification),
             ImportError::MissingParent(header.hash())
         );
     });
@@ -746,6 +814,216 @@ fn test_chain_reorg_to_heavier_chain() {
     });
 }
 
+#[test]
+fn test_chain_notifications() {
+    new_test_ext().execute_with(|| {
+        let keypair = Keypair::generate();
+        let farmer = FarmerParameters::new();
+
+        let mut constants = default_test_constants();
+        constants.k_depth = 4;
+        let (store, genesis_hash) = initialize_store(constants, true, None);
+        let mut importer = HeaderImporter::new(store);
+        let notifications = Arc::new(Mutex::new(Vec::new()));
+        importer.add_notification_listener({
+            let notifications = Arc::clone(&notifications);
+
+            move |notification| notifications.lock().unwrap().push(notification.clone())
+        });
+        let take_notifications = || std::mem::take(&mut *notifications.lock().unwrap());
+
+        // every header of the linear chain is enacted one by one
+        let hash_of_4 = add_headers_to_chain(&mut importer, &keypair, 4, None, &farmer);
+        let notifications_of_4 = take_notifications();
+        assert_eq!(notifications_of_4.len(), 4);
+        for (number, notification) in (1..).zip(notifications_of_4.iter()) {
+            let ChainNotification::BestHeader {
+                hash,
+                number: notified_number,
+                retracted,
+                enacted,
+            } = notification
+            else {
+                panic!("Expected best header notification, got {notification:?}");
+            };
+            assert_eq!(*notified_number, number);
+            assert!(retracted.is_empty());
+            assert_eq!(enacted, &vec![*hash]);
+        }
+        assert_eq!(
+            notifications_of_4.last(),
+            Some(&ChainNotification::BestHeader {
+                hash: hash_of_4,
+                number: 4,
+                retracted: vec![],
+                enacted: vec![hash_of_4],
+            })
+        );
+
+        // headers of the fork that doesn't become best are not notified
+        add_headers_to_chain(
+            &mut importer,
+            &keypair,
+            4,
+            Some(ForkAt {
+                parent_hash: genesis_hash,
+                is_best: Some(false),
+            }),
+            &farmer,
+        );
+        assert!(take_notifications().is_empty());
+
+        // new best header finalizes header at k-depth after best header notification
+        let hash_of_5 = add_headers_to_chain(&mut importer, &keypair, 1, None, &farmer);
+        let hash_of_1 = importer.store.headers_at_number(1)[0].header.hash();
+        assert_eq!(
+            take_notifications(),
+            vec![
+                ChainNotification::BestHeader {
+                    hash: hash_of_5,
+                    number: 5,
+                    retracted: vec![],
+                    enacted: vec![hash_of_5],
+                },
+                ChainNotification::FinalizedHeader {
+                    hash: hash_of_1,
+                    number: 1,
+                },
+            ]
+        );
+
+        let fork_hash_of_8 = add_headers_to_chain(
+            &mut importer,
+            &keypair,
+            4,
+            Some(ForkAt {
+                parent_hash: hash_of_4,
+                is_best: Some(false),
+            }),
+            &farmer,
+        );
+        assert!(take_notifications().is_empty());
+
+        // reorg retracts old best header and enacts the whole fork
+        let hash_of_9 = add_headers_to_chain(
+            &mut importer,
+            &keypair,
+            1,
+            Some(ForkAt {
+                parent_hash: fork_hash_of_8,
+                is_best: Some(true),
+            }),
+            &farmer,
+        );
+        let notifications_of_9 = take_notifications();
+        let Some(ChainNotification::BestHeader {
+            hash,
+            number,
+            retracted,
+            enacted,
+        }) = notifications_of_9.first()
+        else {
+            panic!("Expected best header notification, got {notifications_of_9:?}");
+        };
+        assert_eq!((*hash, *number), (hash_of_9, 9));
+        assert_eq!(retracted, &vec![hash_of_5]);
+        assert_eq!(enacted.len(), 5);
+        assert_eq!(enacted.last(), Some(&hash_of_9));
+        assert_eq!(
+            importer
+                .store
+                .header(enacted[0])
+                .unwrap()
+                .header
+                .parent_hash,
+            hash_of_4
+        );
+
+        // headers 2 to 5 are finalized in ascending order
+        let finalized = notifications_of_9[1..]
+            .iter()
+            .map(|notification| match notification {
+                ChainNotification::FinalizedHeader { hash, number } => {
+                    assert_eq!(
+                        importer.store.headers_at_number(*number)[0].header.hash(),
+                        *hash
+                    );
+                    *number
+                }
+                notification => {
+                    panic!("Expected finalized header notification, got {notification:?}")
+                }
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(finalized, vec![2, 3, 4, 5]);
+    });
+}
+
+#[test]
+fn test_canonical_ancestry_path() {
+    new_test_ext().execute_with(|| {
+        let keypair = Keypair::generate();
+        let farmer = FarmerParameters::new();
+
+        let mut constants = default_test_constants();
+        constants.k_depth = 10;
+        let (store, genesis_hash) = initialize_store(constants, true, None);
+        let mut importer = HeaderImporter::new(store);
+
+        let hash_of_4 = add_headers_to_chain(&mut importer, &keypair, 4, None, &farmer);
+        let fork_hash_of_2 = add_headers_to_chain(
+            &mut importer,
+            &keypair,
+            2,
+            Some(ForkAt {
+                parent_hash: genesis_hash,
+                is_best: Some(false),
+            }),
+            &farmer,
+        );
+
+        let path = importer
+            .canonical_ancestry_path(genesis_hash, hash_of_4)
+            .unwrap();
+        assert_eq!(path.len(), 5);
+        assert_eq!(path[0].hash(), genesis_hash);
+        assert_eq!(path[4].hash(), hash_of_4);
+        for (number, window) in (1..).zip(path.windows(2)) {
+            assert_eq!(window[1].number, number);
+            assert_eq!(window[1].parent_hash, window[0].hash());
+        }
+
+        // header is its own ancestor
+        let path = importer
+            .canonical_ancestry_path(hash_of_4, hash_of_4)
+            .unwrap();
+        assert_eq!(path.len(), 1);
+
+        assert_eq!(
+            importer.canonical_ancestry_path(genesis_hash, fork_hash_of_2),
+            Err(AncestryPathError::NotCanonical(fork_hash_of_2))
+        );
+        let fork_hash_of_1 = importer
+            .store
+            .header(fork_hash_of_2)
+            .unwrap()
+            .header
+            .parent_hash;
+        assert_eq!(
+            importer.canonical_ancestry_path(fork_hash_of_1, hash_of_4),
+            Err(AncestryPathError::NotAncestor(fork_hash_of_1))
+        );
+        assert_eq!(
+            importer.canonical_ancestry_path(hash_of_4, genesis_hash),
+            Err(AncestryPathError::NotAncestor(hash_of_4))
+        );
+        assert_eq!(
+            importer.canonical_ancestry_path(genesis_hash, H256::repeat_byte(1)),
+            Err(AncestryPathError::MissingHeader(H256::repeat_byte(1)))
+        );
+    });
+}
+
 #[test]
 fn test_reorg_to_heavier_smaller_chain() {
     new_test_ext().execute_with(|| {
@@ -782,20 +1060,15 @@ fn test_reorg_to_heavier_smaller_chain() {
                 &header_at_2.header,
             )
             .unwrap();
+        let slot = next_slot(constants.slot_probability, digests_at_2.pre_digest.slot());
+        let pot = prove_pot(&importer.store, header_at_2.header.hash(), slot);
         let (mut header, solution_range, block_weight, segment_index, segment_commitment) =
             valid_header(ValidHeaderParams {
                 parent_hash: header_at_2.header.hash(),
                 number: 3,
-                slot: next_slot(constants.slot_probability, digests_at_2.pre_digest.slot()).into(),
+                slot: slot.into(),
                 keypair: &keypair,
-                #[cfg(not(feature = "pot"))]
-                global_randomness: digests_at_2.global_randomness,
-                // TODO: Correct value
-                #[cfg(feature = "pot")]
-                proof_of_time: PotOutput::default(),
-                // TODO: Correct value
-                #[cfg(feature = "pot")]
-                future_proof_of_time: PotOutput::default(),
+                pot: &pot,
                 farmer_parameters: &farmer_parameters,
             });
         seal_header(&keypair, &mut header);
@@ -813,74 +1086,215 @@ fn test_reorg_to_heavier_smaller_chain() {
         importer
             .store
             .override_cumulative_weight(header_at_2.header.hash(), 0);
-        let res = importer.import_header(header);
+        let notifications = Arc::new(Mutex::new(Vec::new()));
+        importer.add_notification_listener({
+            let notifications = Arc::clone(&notifications);
+
+            move |notification| notifications.lock().unwrap().push(notification.clone())
+        });
+        let res = importer.import_header(header, pot.justification);
         assert_err!(res, ImportError::SwitchedToForkBelowArchivingDepth);
+        // header became best before finalization failed, but failed import is not notified
+        assert!(notifications.lock().unwrap().is_empty());
     });
 }
 
 #[test]
-fn test_next_global_randomness_digest() {
+fn test_pot_slot_iterations_digest() {
     new_test_ext().execute_with(|| {
         let keypair = Keypair::generate();
         let farmer_parameters = FarmerParameters::new();
 
         let mut constants = default_test_constants();
-        constants.global_randomness_interval = 5;
-        let (store, genesis_hash) = initialize_store(constants, true, None);
+        let (store, genesis_hash) = initialize_store(constants.clone(), true, None);
         let mut importer = HeaderImporter::new(store);
-        assert_eq!(
-            importer.store.finalized_header().header.hash(),
-            genesis_hash
-        );
-
-        let hash_of_4 = add_headers_to_chain(&mut importer, &keypair, 4, None, &farmer_parameters);
-        assert_eq!(importer.store.best_header().header.hash(), hash_of_4);
 
-        // try to import header with out next global randomness
-        let constants = importer.store.chain_constants();
-        let header_at_4 = importer.store.header(hash_of_4).unwrap();
-        let digests_at_4 =
-            extract_subspace_digest_items::<_, FarmerPublicKey, FarmerPublicKey, FarmerSignature>(
-                &header_at_4.header,
-            )
-            .unwrap();
+        // try to import header with slot iterations different from genesis
+        let pot = prove_pot(&importer.store, genesis_hash, Slot::from(1));
         let (mut header, solution_range, _block_weight, segment_index, segment_commitment) =
             valid_header(ValidHeaderParams {
-                parent_hash: header_at_4.header.hash(),
-                number: 5,
-                slot: next_slot(constants.slot_probability, digests_at_4.pre_digest.slot()).into(),
+                parent_hash: genesis_hash,
+                number: 1,
+                slot: 1,
                 keypair: &keypair,
-                global_randomness: digests_at_4.global_randomness,
+                pot: &pot,
                 farmer_parameters: &farmer_parameters,
             });
+        header
+            .digest
+            .logs
+            .retain(|log| log.as_pot_slot_iterations().is_none());
+        header.digest.logs.push(DigestItem::pot_slot_iterations(
+            POT_SLOT_ITERATIONS.saturating_mul(NonZeroU32::new(2).unwrap()),
+        ));
         seal_header(&keypair, &mut header);
-        importer
-            .store
-            .override_solution_range(header_at_4.header.hash(), solution_range);
+        constants.genesis_digest_items.next_solution_range = solution_range;
+        importer.store.override_constants(constants);
         importer
             .store
             .store_segment_commitment(segment_index, segment_commitment);
+        importer.store.override_cumulative_weight(genesis_hash, 0);
+        let res = importer.import_header(header, pot.justification);
+        assert_err!(
+            res,
+            ImportError::InvalidDigest(ErrorDigestType::PotSlotIterations)
+        );
+    });
+}
+
+#[test]
+fn test_pot_parameters_change_digest() {
+    new_test_ext().execute_with(|| {
+        let keypair = Keypair::generate();
+        let farmer_parameters = FarmerParameters::new();
+
+        let mut constants = default_test_constants();
+        let (store, genesis_hash) = initialize_store(constants.clone(), true, None);
+        let mut importer = HeaderImporter::new(store);
+
+        // try to import header with parameters change while there is no entropy to inject yet
+        let pot = prove_pot(&importer.store, genesis_hash, Slot::from(1));
+        let (mut header, solution_range, _block_weight, segment_index, segment_commitment) =
+            valid_header(ValidHeaderParams {
+                parent_hash: genesis_hash,
+                number: 1,
+                slot: 1,
+                keypair: &keypair,
+                pot: &pot,
+                farmer_parameters: &farmer_parameters,
+            });
+        header
+            .digest
+            .logs
+            .push(DigestItem::pot_parameters_change(PotParametersChange {
+                slot: Slot::from(2),
+                slot_iterations: POT_SLOT_ITERATIONS,
+                entropy: Default::default(),
+            }));
+        seal_header(&keypair, &mut header);
+        constants.genesis_digest_items.next_solution_range = solution_range;
+        importer.store.override_constants(constants.clone());
         importer
             .store
-            .override_cumulative_weight(header_at_4.header.hash(), 0);
-        let res = importer.import_header(header.clone());
+            .store_segment_commitment(segment_index, segment_commitment);
+        importer.store.override_cumulative_weight(genesis_hash, 0);
+        let res = importer.import_header(header, pot.justification);
         assert_err!(
             res,
-            ImportError::DigestError(DigestError::NextDigestVerificationError(
-                ErrorDigestType::NextGlobalRandomness
-            ))
+            ImportError::InvalidDigest(ErrorDigestType::PotParametersChange)
         );
-        assert_eq!(importer.store.best_header().header.hash(), hash_of_4);
 
-        // add next global randomness
-        remove_seal(&mut header);
-        let pre_digest = extract_pre_digest(&header).unwrap();
-        let randomness = derive_randomness(pre_digest.solution(), pre_digest.slot().into());
-        let digests = header.digest_mut();
-        digests.push(DigestItem::next_global_randomness(randomness));
+        // entropy collected at block 5 is injected starting with block 15
+        let hash_of_15 =
+            add_headers_to_chain(&mut importer, &keypair, 15, None, &farmer_parameters);
+        let header_at_14 = importer.store.headers_at_number(14).pop().unwrap();
+        let header_at_15 = importer.store.header(hash_of_15).unwrap();
+        let digests_at_14 =
+            extract_subspace_digest_items::<_, FarmerPublicKey, FarmerPublicKey, FarmerSignature>(
+                &header_at_14.header,
+            )
+            .unwrap();
+        let digests_at_15 =
+            extract_subspace_digest_items::<_, FarmerPublicKey, FarmerPublicKey, FarmerSignature>(
+                &header_at_15.header,
+            )
+            .unwrap();
+        assert_eq!(digests_at_14.pot_parameters_change, None);
+        let pot_parameters_change = digests_at_15.pot_parameters_change.unwrap();
+        assert_eq!(
+            pot_parameters_change.slot,
+            digests_at_15.pre_digest.slot() + constants.pot_entropy_injection_delay
+        );
+        assert_eq!(
+            Some(pot_parameters_change.entropy),
+            header_at_15
+                .pot_entropy
+                .get(&5)
+                .map(|entropy_value| entropy_value.entropy)
+        );
+    });
+}
+
+#[test]
+fn test_invalid_proof_of_time() {
+    new_test_ext().execute_with(|| {
+        let keypair = Keypair::generate();
+        let farmer_parameters = FarmerParameters::new();
+
+        let mut constants = default_test_constants();
+        let (store, genesis_hash) = initialize_store(constants.clone(), true, None);
+        let mut importer = HeaderImporter::new(store);
+
+        let pot = prove_pot(&importer.store, genesis_hash, Slot::from(1));
+        let (mut header, solution_range, _block_weight, segment_index, segment_commitment) =
+            valid_header(ValidHeaderParams {
+                parent_hash: genesis_hash,
+                number: 1,
+                slot: 1,
+                keypair: &keypair,
+                pot: &pot,
+                farmer_parameters: &farmer_parameters,
+            });
         seal_header(&keypair, &mut header);
-        let res = importer.import_header(header.clone());
-        assert_ok!(res);
+        constants.genesis_digest_items.next_solution_range = solution_range;
+        importer.store.override_constants(constants);
+        importer
+            .store
+            .store_segment_commitment(segment_index, segment_commitment);
+        importer.store.override_cumulative_weight(genesis_hash, 0);
+
+        let SubspaceJustification::PotCheckpoints { seed, checkpoints } = pot.justification.clone();
+
+        // seed must match genesis seed
+        let res = importer.import_header(
+            header.clone(),
+            SubspaceJustification::PotCheckpoints {
+                seed: PotSeed::default(),
+                checkpoints: checkpoints.clone(),
+            },
+        );
+        assert_err!(res, ImportError::InvalidPotJustification);
+
+        // checkpoints must cover all slots up to the future slot
+        let res = importer.import_header(
+            header.clone(),
+            SubspaceJustification::PotCheckpoints {
+                seed,
+                checkpoints: checkpoints[1..].to_vec(),
+            },
+        );
+        assert_err!(res, ImportError::InvalidPotJustification);
+
+        // checkpoints must be valid
+        let mut invalid_checkpoints = checkpoints.clone();
+        invalid_checkpoints[0][0] = PotOutput::default();
+        let res = importer.import_header(
+            header.clone(),
+            SubspaceJustification::PotCheckpoints {
+                seed,
+                checkpoints: invalid_checkpoints,
+            },
+        );
+        assert_err!(res, ImportError::InvalidProofOfTime);
+
+        // proof of time in the header must match checkpoints
+        let invalid_pot = HeaderPot {
+            proof_of_time: PotOutput::default(),
+            ..pot
+        };
+        let (mut invalid_header, ..) = valid_header(ValidHeaderParams {
+            parent_hash: genesis_hash,
+            number: 1,
+            slot: 1,
+            keypair: &keypair,
+            pot: &invalid_pot,
+            farmer_parameters: &farmer_parameters,
+        });
+        seal_header(&keypair, &mut invalid_header);
+        let res = importer.import_header(invalid_header, invalid_pot.justification.clone());
+        assert_err!(res, ImportError::InvalidProofOfTime);
+
+        assert_ok!(importer.import_header(header.clone(), invalid_pot.justification));
         assert_eq!(importer.store.best_header().header.hash(), header.hash());
     });
 }
@@ -911,13 +1325,15 @@ fn test_next_solution_range_digest_with_adjustment_enabled() {
                 &header_at_4.header,
             )
             .unwrap();
+        let slot = next_slot(constants.slot_probability, digests_at_4.pre_digest.slot());
+        let pot = prove_pot(&importer.store, header_at_4.header.hash(), slot);
         let (mut header, solution_range, _block_weight, segment_index, segment_commitment) =
             valid_header(ValidHeaderParams {
                 parent_hash: header_at_4.header.hash(),
                 number: 5,
-                slot: next_slot(constants.slot_probability, digests_at_4.pre_digest.slot()).into(),
+                slot: slot.into(),
                 keypair: &keypair,
-                global_randomness: digests_at_4.global_randomness,
+                pot: &pot,
                 farmer_parameters: &farmer_parameters,
             });
         seal_header(&keypair, &mut header);
@@ -931,7 +1347,7 @@ fn test_next_solution_range_digest_with_adjustment_enabled() {
             .store
             .override_cumulative_weight(header_at_4.header.hash(), 0);
         let pre_digest = extract_pre_digest(&header).unwrap();
-        let res = importer.import_header(header.clone());
+        let res = importer.import_header(header.clone(), pot.justification.clone());
         assert_err!(
             res,
             ImportError::DigestError(DigestError::NextDigestVerificationError(
@@ -952,7 +1368,7 @@ fn test_next_solution_range_digest_with_adjustment_enabled() {
         let digests = header.digest_mut();
         digests.push(DigestItem::next_solution_range(next_solution_range));
         seal_header(&keypair, &mut header);
-        let res = importer.import_header(header.clone());
+        let res = importer.import_header(header.clone(), pot.justification);
         assert_ok!(res);
         assert_eq!(importer.store.best_header().header.hash(), header.hash());
     });
@@ -984,13 +1400,15 @@ fn test_next_solution_range_digest_with_adjustment_disabled() {
                 &header_at_4.header,
             )
             .unwrap();
+        let slot = next_slot(constants.slot_probability, digests_at_4.pre_digest.slot());
+        let pot = prove_pot(&importer.store, header_at_4.header.hash(), slot);
         let (mut header, solution_range, _block_weight, segment_index, segment_commitment) =
             valid_header(ValidHeaderParams {
                 parent_hash: header_at_4.header.hash(),
                 number: 5,
-                slot: next_slot(constants.slot_probability, digests_at_4.pre_digest.slot()).into(),
+                slot: slot.into(),
                 keypair: &keypair,
-                global_randomness: digests_at_4.global_randomness,
+                pot: &pot,
                 farmer_parameters: &farmer_parameters,
             });
         importer
@@ -1009,7 +1427,7 @@ fn test_next_solution_range_digest_with_adjustment_disabled() {
         let digests = header.digest_mut();
         digests.push(DigestItem::next_solution_range(next_solution_range));
         seal_header(&keypair, &mut header);
-        let res = importer.import_header(header.clone());
+        let res = importer.import_header(header.clone(), pot.justification);
         assert_ok!(res);
         assert_eq!(importer.store.best_header().header.hash(), header.hash());
         assert!(!importer.store.best_header().should_adjust_solution_range);
@@ -1044,13 +1462,15 @@ fn test_enable_solution_range_adjustment_without_override() {
                 &header_at_4.header,
             )
             .unwrap();
+        let slot = next_slot(constants.slot_probability, digests_at_4.pre_digest.slot());
+        let pot = prove_pot(&importer.store, header_at_4.header.hash(), slot);
         let (mut header, solution_range, _block_weight, segment_index, segment_commitment) =
             valid_header(ValidHeaderParams {
                 parent_hash: header_at_4.header.hash(),
                 number: 5,
-                slot: next_slot(constants.slot_probability, digests_at_4.pre_digest.slot()).into(),
+                slot: slot.into(),
                 keypair: &keypair,
-                global_randomness: digests_at_4.global_randomness,
+                pot: &pot,
                 farmer_parameters: &farmer_parameters,
             });
         importer
@@ -1076,7 +1496,7 @@ fn test_enable_solution_range_adjustment_without_override() {
             None,
         ));
         seal_header(&keypair, &mut header);
-        let res = importer.import_header(header.clone());
+        let res = importer.import_header(header.clone(), pot.justification);
         assert_ok!(res);
         assert_eq!(importer.store.best_header().header.hash(), header.hash());
         assert!(importer.store.best_header().should_adjust_solution_range);
@@ -1113,13 +1533,15 @@ fn test_enable_solution_range_adjustment_with_override_between_update_intervals(
                 &header_at_3.header,
             )
             .unwrap();
+        let slot = next_slot(constants.slot_probability, digests_at_3.pre_digest.slot());
+        let pot = prove_pot(&importer.store, header_at_3.header.hash(), slot);
         let (mut header, solution_range, _block_weight, segment_index, segment_commitment) =
             valid_header(ValidHeaderParams {
                 parent_hash: header_at_3.header.hash(),
                 number: 4,
-                slot: next_slot(constants.slot_probability, digests_at_3.pre_digest.slot()).into(),
+                slot: slot.into(),
                 keypair: &keypair,
-                global_randomness: digests_at_3.global_randomness,
+                pot: &pot,
                 farmer_parameters: &farmer_parameters,
             });
         importer
@@ -1137,7 +1559,7 @@ fn test_enable_solution_range_adjustment_with_override_between_update_intervals(
             Some(solution_range_override),
         ));
         seal_header(&keypair, &mut header);
-        let res = importer.import_header(header.clone());
+        let res = importer.import_header(header.clone(), pot.justification);
         assert_ok!(res);
         let header_at_4 = importer.store.best_header();
         assert_eq!(header_at_4.header.hash(), header.hash());
@@ -1182,13 +1604,15 @@ fn test_enable_solution_range_adjustment_with_override_at_interval_change() {
                 &header_at_4.header,
             )
             .unwrap();
+        let slot = next_slot(constants.slot_probability, digests_at_4.pre_digest.slot());
+        let pot = prove_pot(&importer.store, header_at_4.header.hash(), slot);
         let (mut header, solution_range, _block_weight, segment_index, segment_commitment) =
             valid_header(ValidHeaderParams {
                 parent_hash: header_at_4.header.hash(),
                 number: 5,
-                slot: next_slot(constants.slot_probability, digests_at_4.pre_digest.slot()).into(),
+                slot: slot.into(),
                 keypair: &keypair,
-                global_randomness: digests_at_4.global_randomness,
+                pot: &pot,
                 farmer_parameters: &farmer_parameters,
             });
         importer
@@ -1208,7 +1632,7 @@ fn test_enable_solution_range_adjustment_with_override_at_interval_change() {
             Some(solution_range_override),
         ));
         seal_header(&keypair, &mut header);
-        let res = importer.import_header(header.clone());
+        let res = importer.import_header(header.clone(), pot.justification);
         assert_ok!(res);
         assert_eq!(importer.store.best_header().header.hash(), header.hash());
         assert!(importer.store.best_header().should_adjust_solution_range);
@@ -1243,13 +1667,15 @@ fn test_disallow_enable_solution_range_digest_when_solution_range_adjustment_is_
                 &header_at_4.header,
             )
             .unwrap();
+        let slot = next_slot(constants.slot_probability, digests_at_4.pre_digest.slot());
+        let pot = prove_pot(&importer.store, header_at_4.header.hash(), slot);
         let (mut header, solution_range, _block_weight, segment_index, segment_commitment) =
             valid_header(ValidHeaderParams {
                 parent_hash: header_at_4.header.hash(),
                 number: 5,
-                slot: next_slot(constants.slot_probability, digests_at_4.pre_digest.slot()).into(),
+                slot: slot.into(),
                 keypair: &keypair,
-                global_randomness: digests_at_4.global_randomness,
+                pot: &pot,
                 farmer_parameters: &farmer_parameters,
             });
         importer
@@ -1266,7 +1692,7 @@ fn test_disallow_enable_solution_range_digest_when_solution_range_adjustment_is_
             None,
         ));
         seal_header(&keypair, &mut header);
-        let res = importer.import_header(header.clone());
+        let res = importer.import_header(header.clone(), pot.justification);
         assert_err!(
             res,
             ImportError::DigestError(DigestError::NextDigestVerificationError(
@@ -1334,14 +1760,14 @@ fn test_block_author_different_farmer() {
 
         // try to import header authored by different farmer
         let keypair_disallowed = Keypair::generate();
-        let global_randomness = default_randomness();
+        let pot = prove_pot(&importer.store, genesis_hash, Slot::from(1));
         let (mut header, solution_range, _block_weight, segment_index, segment_commitment) =
             valid_header(ValidHeaderParams {
                 parent_hash: genesis_hash,
                 number: 1,
                 slot: 1,
                 keypair: &keypair_disallowed,
-                global_randomness,
+                pot: &pot,
                 farmer_parameters: &farmer_parameters,
             });
         seal_header(&keypair_disallowed, &mut header);
@@ -1351,7 +1777,7 @@ fn test_block_author_different_farmer() {
             .store
             .store_segment_commitment(segment_index, segment_commitment);
         importer.store.override_cumulative_weight(genesis_hash, 0);
-        let res = importer.import_header(header);
+        let res = importer.import_header(header, pot.justification);
         assert_err!(
             res,
             ImportError::IncorrectBlockAuthor(FarmerPublicKey::unchecked_from(
@@ -1373,14 +1799,14 @@ fn test_block_author_first_farmer() {
         let mut importer = HeaderImporter::new(store);
 
         // try import header with first farmer
-        let global_randomness = default_randomness();
+        let pot = prove_pot(&importer.store, genesis_hash, Slot::from(1));
         let (mut header, solution_range, _block_weight, segment_index, segment_commitment) =
             valid_header(ValidHeaderParams {
                 parent_hash: genesis_hash,
                 number: 1,
                 slot: 1,
                 keypair: &keypair,
-                global_randomness,
+                pot: &pot,
                 farmer_parameters: &farmer_parameters,
             });
         header
@@ -1396,7 +1822,7 @@ fn test_block_author_first_farmer() {
             .store
             .store_segment_commitment(segment_index, segment_commitment);
         importer.store.override_cumulative_weight(genesis_hash, 0);
-        let res = importer.import_header(header.clone());
+        let res = importer.import_header(header.clone(), pot.justification);
         assert_ok!(res);
         let best_header = importer.store.best_header();
         assert_eq!(header.hash(), best_header.header.hash());
@@ -1416,14 +1842,14 @@ fn test_block_author_allow_any_farmer() {
         let mut importer = HeaderImporter::new(store);
 
         // try to import header authored by different farmer
-        let global_randomness = default_randomness();
+        let pot = prove_pot(&importer.store, genesis_hash, Slot::from(1));
         let (mut header, solution_range, _block_weight, segment_index, segment_commitment) =
             valid_header(ValidHeaderParams {
                 parent_hash: genesis_hash,
                 number: 1,
                 slot: 1,
                 keypair: &keypair,
-                global_randomness,
+                pot: &pot,
                 farmer_parameters: &farmer_parameters,
             });
         header
@@ -1437,7 +1863,7 @@ fn test_block_author_allow_any_farmer() {
             .store
             .store_segment_commitment(segment_index, segment_commitment);
         importer.store.override_cumulative_weight(genesis_hash, 0);
-        let res = importer.import_header(header.clone());
+        let res = importer.import_header(header.clone(), pot.justification);
         assert_ok!(res);
         let best_header = importer.store.best_header();
         assert_eq!(header.hash(), best_header.header.hash());
@@ -1457,14 +1883,14 @@ fn test_disallow_root_plot_public_key_override() {
         let mut importer = HeaderImporter::new(store);
 
         // try to import header that contains root plot public key override
-        let global_randomness = default_randomness();
+        let pot = prove_pot(&importer.store, genesis_hash, Slot::from(1));
         let (mut header, solution_range, _block_weight, segment_index, segment_commitment) =
             valid_header(ValidHeaderParams {
                 parent_hash: genesis_hash,
                 number: 1,
                 slot: 1,
                 keypair: &keypair_allowed,
-                global_randomness,
+                pot: &pot,
                 farmer_parameters: &farmer_parameters,
             });
         let keypair_disallowed = Keypair::generate();
@@ -1480,7 +1906,7 @@ fn test_disallow_root_plot_public_key_override() {
             .store
             .store_segment_commitment(segment_index, segment_commitment);
         importer.store.override_cumulative_weight(genesis_hash, 0);
-        let res = importer.import_header(header);
+        let res = importer.import_header(header, pot.justification);
         assert_err!(
             res,
             ImportError::DigestError(DigestError::NextDigestVerificationError(
@@ -1,5 +1,7 @@
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
 use rand::{thread_rng, Rng};
+#[cfg(feature = "parallel")]
+use rayon::ThreadPoolBuilder;
 use subspace_archiving::archiver::Archiver;
 use subspace_core_primitives::crypto::kzg;
 use subspace_core_primitives::crypto::kzg::Kzg;
@@ -23,6 +25,23 @@ fn criterion_benchmark(c: &mut Criterion) {
         })
     });
 
+    #[cfg(feature = "parallel")]
+    {
+        let thread_pool = ThreadPoolBuilder::new().num_threads(1).build().unwrap();
+
+        c.bench_function("segment-archiving-large-block/single-threaded", |b| {
+            b.iter(|| {
+                thread_pool.install(|| {
+                    archiver.clone().add_block(
+                        black_box(input.clone()),
+                        black_box(Default::default()),
+                        black_box(true),
+                    );
+                })
+            })
+        });
+    }
+
     c.bench_function("segment-archiving-small-blocks/incremental", |b| {
         b.iter(|| {
             let mut archiver = archiver.clone();
@@ -18,7 +18,7 @@ mod incremental_record_commitments;
 extern crate alloc;
 
 use crate::archiver::incremental_record_commitments::{
-    update_record_commitments, IncrementalRecordCommitmentsState,
+    is_last_record_commitment_valid, update_record_commitments, IncrementalRecordCommitmentsState,
 };
 use alloc::collections::VecDeque;
 #[cfg(not(feature = "std"))]
@@ -39,8 +39,8 @@ use subspace_core_primitives::objects::{
 };
 use subspace_core_primitives::{
     ArchivedBlockProgress, ArchivedHistorySegment, Blake3Hash, BlockNumber, LastArchivedBlock,
-    PieceArray, RawRecord, RecordWitness, RecordedHistorySegment, SegmentCommitment, SegmentHeader,
-    SegmentIndex,
+    PieceArray, RawRecord, RecordCommitment, RecordWitness, RecordedHistorySegment,
+    SegmentCommitment, SegmentHeader, SegmentIndex,
 };
 use subspace_erasure_coding::ErasureCoding;
 
@@ -187,6 +187,16 @@ pub struct NewArchivedSegment {
     pub object_mapping: Vec<PieceObjectMapping>,
 }
 
+/// Archived segment as stored in exported segment file, the same as [`NewArchivedSegment`], but
+/// without object mappings
+#[derive(Debug, Clone, Eq, PartialEq, Decode, Encode)]
+pub struct ExportedSegment {
+    /// Segment header, stored first, such that it can be read without reading the whole file
+    pub segment_header: SegmentHeader,
+    /// Pieces of archived segment (both source and parity)
+    pub pieces: ArchivedHistorySegment,
+}
+
 /// Archiver instantiation error
 #[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
 #[cfg_attr(feature = "thiserror", derive(thiserror::Error))]
@@ -214,6 +224,57 @@ pub enum ArchiverInstantiationError {
         /// Already archived portion of the block
         archived_block_bytes: u32,
     },
+    /// Archiver state contains parent segment header that doesn't match previous segment header
+    /// hash
+    #[cfg_attr(
+        feature = "thiserror",
+        error("Archiver state contains parent segment header that doesn't match previous segment header hash")
+    )]
+    InvalidStateParentSegmentHeader,
+    /// Archiver state contains record commitments that don't correspond to buffered data
+    #[cfg_attr(
+        feature = "thiserror",
+        error("Archiver state contains record commitments that don't correspond to buffered data")
+    )]
+    InvalidStateRecordCommitments,
+}
+
+/// Segment item buffered by the archiver alongside object mapping, which is skipped during regular
+/// segment item encoding
+#[derive(Debug, Clone, Eq, PartialEq, Encode, Decode)]
+struct BufferedSegmentItem {
+    segment_item: SegmentItem,
+    object_mapping: BlockObjectMapping,
+}
+
+/// Snapshot of the internal state of the [`Archiver`].
+///
+/// It can be persisted and later used to restore archiver with [`Archiver::from_state()`] without
+/// re-processing blocks that were added since the last archived segment.
+#[derive(Debug, Clone, Eq, PartialEq, Encode, Decode)]
+pub struct ArchiverState {
+    segment_index: SegmentIndex,
+    prev_segment_header_hash: Blake3Hash,
+    last_archived_block: LastArchivedBlock,
+    buffer: Vec<BufferedSegmentItem>,
+    record_commitments: Vec<RecordCommitment>,
+}
+
+impl ArchiverState {
+    /// Index of the segment that archiver will produce next
+    pub fn segment_index(&self) -> SegmentIndex {
+        self.segment_index
+    }
+
+    /// Hash of the segment header of the previous segment
+    pub fn prev_segment_header_hash(&self) -> Blake3Hash {
+        self.prev_segment_header_hash
+    }
+
+    /// Last archived block as of the previous segment
+    pub fn last_archived_block(&self) -> LastArchivedBlock {
+        self.last_archived_block
+    }
 }
 
 /// Block archiver for Subspace blockchain.
@@ -336,6 +397,110 @@ impl Archiver {
         Ok(archiver)
     }
 
+    /// Snapshot of the internal state that can be used to restore archiver with
+    /// [`Self::from_state()`].
+    pub fn state(&self) -> ArchiverState {
+        ArchiverState {
+            segment_index: self.segment_index,
+            prev_segment_header_hash: self.prev_segment_header_hash,
+            last_archived_block: self.last_archived_block,
+            buffer: self
+                .buffer
+                .iter()
+                .map(|segment_item| {
+                    let object_mapping = match segment_item {
+                        SegmentItem::Block { object_mapping, .. }
+                        | SegmentItem::BlockStart { object_mapping, .. }
+                        | SegmentItem::BlockContinuation { object_mapping, .. } => {
+                            object_mapping.clone()
+                        }
+                        SegmentItem::Padding | SegmentItem::ParentSegmentHeader(_) => {
+                            BlockObjectMapping::default()
+                        }
+                    };
+
+                    BufferedSegmentItem {
+                        segment_item: segment_item.clone(),
+                        object_mapping,
+                    }
+                })
+                .collect(),
+            record_commitments: self
+                .incremental_record_commitments
+                .iter()
+                .map(|&commitment| RecordCommitment::from(commitment))
+                .collect(),
+        }
+    }
+
+    /// Create a new instance of the archiver from previously created state snapshot.
+    ///
+    /// State is verified to be consistent: parent segment header in the buffer must match previous
+    /// segment header hash and the last record commitment is re-created from buffered data.
+    pub fn from_state(kzg: Kzg, state: ArchiverState) -> Result<Self, ArchiverInstantiationError> {
+        let ArchiverState {
+            segment_index,
+            prev_segment_header_hash,
+            last_archived_block,
+            buffer,
+            record_commitments,
+        } = state;
+
+        let mut archiver = Self::new(kzg)?;
+
+        archiver.segment_index = segment_index;
+        archiver.prev_segment_header_hash = prev_segment_header_hash;
+        archiver.last_archived_block = last_archived_block;
+        archiver.buffer = buffer
+            .into_iter()
+            .map(
+                |BufferedSegmentItem {
+                     mut segment_item,
+                     object_mapping: buffered_object_mapping,
+                 }| {
+                    match &mut segment_item {
+                        SegmentItem::Block { object_mapping, .. }
+                        | SegmentItem::BlockStart { object_mapping, .. }
+                        | SegmentItem::BlockContinuation { object_mapping, .. } => {
+                            *object_mapping = buffered_object_mapping;
+                        }
+                        SegmentItem::Padding | SegmentItem::ParentSegmentHeader(_) => {
+                            // No object mapping
+                        }
+                    }
+
+                    segment_item
+                },
+            )
+            .collect();
+
+        if let Some(SegmentItem::ParentSegmentHeader(parent_segment_header)) =
+            archiver.buffer.front()
+        {
+            if parent_segment_header.hash() != prev_segment_header_hash
+                || parent_segment_header.segment_index() + SegmentIndex::ONE != segment_index
+            {
+                return Err(ArchiverInstantiationError::InvalidStateParentSegmentHeader);
+            }
+        }
+
+        for record_commitment in &record_commitments {
+            let commitment = Commitment::try_from(record_commitment)
+                .map_err(|_error| ArchiverInstantiationError::InvalidStateRecordCommitments)?;
+            archiver.incremental_record_commitments.push(commitment);
+        }
+
+        if !is_last_record_commitment_valid(
+            &archiver.incremental_record_commitments,
+            archiver.buffer.iter(),
+            &archiver.kzg,
+        ) {
+            return Err(ArchiverInstantiationError::InvalidStateRecordCommitments);
+        }
+
+        Ok(archiver)
+    }
+
     /// Get last archived block if there was any
     pub fn last_archived_block_number(&self) -> Option<BlockNumber> {
         if self.last_archived_block != INITIAL_LAST_ARCHIVED_BLOCK {
@@ -795,11 +960,13 @@ impl Archiver {
         );
 
         // Create witness for every record and write it to corresponding piece.
-        pieces
-            .iter_mut()
-            .zip(record_commitments)
-            .enumerate()
-            .for_each(|(position, (piece, commitment))| {
+        {
+            #[cfg(not(feature = "parallel"))]
+            let iter = pieces.iter_mut().zip(record_commitments).enumerate();
+            #[cfg(feature = "parallel")]
+            let iter = pieces.par_iter_mut().zip(record_commitments).enumerate();
+
+            iter.for_each(|(position, (piece, commitment))| {
                 let commitment_bytes = commitment.to_bytes();
                 let (_record, commitment, witness) = piece.split_mut();
                 commitment.copy_from_slice(&commitment_bytes);
@@ -816,6 +983,7 @@ impl Archiver {
                         .to_bytes(),
                 );
             });
+        }
 
         // Now produce segment header
         let segment_header = SegmentHeader::V0 {
@@ -1,7 +1,7 @@
 #[cfg(not(feature = "std"))]
 extern crate alloc;
 
-use crate::archiver::Segment;
+use crate::archiver::{Segment, SegmentItem};
 #[cfg(not(feature = "std"))]
 use alloc::vec::Vec;
 use core::ops::{Deref, DerefMut};
@@ -65,6 +65,83 @@ pub(super) fn update_record_commitments(
     ));
 }
 
+/// Check that the last record commitment in the state corresponds to provided segment items.
+///
+/// `segment_items` are encoded the same way as [`Segment::V0`] would, returns `false` if there are
+/// more record commitments than complete records in the segment.
+pub(super) fn is_last_record_commitment_valid<'a, I>(
+    incremental_record_commitments: &IncrementalRecordCommitmentsState,
+    segment_items: I,
+    kzg: &Kzg,
+) -> bool
+where
+    I: Iterator<Item = &'a SegmentItem>,
+{
+    let Some(last_commitment) = incremental_record_commitments.last() else {
+        return true;
+    };
+
+    let mut record_extractor = RecordExtractor {
+        skip_bytes: (incremental_record_commitments.len() - 1) * RawRecord::SIZE,
+        record_bytes: Vec::with_capacity(RawRecord::SIZE),
+    };
+    // Variant of `Segment::V0`
+    record_extractor.push_byte(0);
+    for segment_item in segment_items {
+        if record_extractor.record_bytes.len() == RawRecord::SIZE {
+            break;
+        }
+        segment_item.encode_to(&mut record_extractor);
+    }
+
+    if record_extractor.record_bytes.len() != RawRecord::SIZE {
+        return false;
+    }
+
+    create_record_commitment(&record_extractor.record_bytes, kzg) == *last_commitment
+}
+
+/// Create commitment to raw record bytes.
+fn create_record_commitment(raw_record_bytes: &[u8], kzg: &Kzg) -> Commitment {
+    let record_chunks = raw_record_bytes
+        .array_chunks::<{ Scalar::SAFE_BYTES }>()
+        .map(Scalar::from);
+    let number_of_chunks = record_chunks.len();
+    let mut scalars = Vec::with_capacity(number_of_chunks.next_power_of_two());
+
+    record_chunks.collect_into(&mut scalars);
+
+    // Number of scalars for KZG must be a power of two elements
+    scalars.resize(scalars.capacity(), Scalar::default());
+
+    let polynomial = kzg
+        .poly(&scalars)
+        .expect("KZG instance must be configured to support this many scalars; qed");
+    kzg.commit(&polynomial)
+        .expect("KZG instance must be configured to support this many scalars; qed")
+}
+
+/// Extracts bytes of a single record from encoded segment, skipping bytes before it
+struct RecordExtractor {
+    skip_bytes: usize,
+    record_bytes: Vec<u8>,
+}
+
+impl Output for RecordExtractor {
+    fn write(&mut self, mut bytes: &[u8]) {
+        if self.skip_bytes >= bytes.len() {
+            self.skip_bytes -= bytes.len();
+            return;
+        }
+
+        bytes = &bytes[self.skip_bytes..];
+        self.skip_bytes = 0;
+        let remaining = RawRecord::SIZE - self.record_bytes.len();
+        self.record_bytes
+            .extend_from_slice(&bytes[..bytes.len().min(remaining)]);
+    }
+}
+
 /// Processor is hidden to not expose unnecessary implementation details (like `Output` trait
 /// implementation)
 struct IncrementalRecordCommitmentsProcessor<'a> {
@@ -86,28 +163,7 @@ impl<'a> Drop for IncrementalRecordCommitmentsProcessor<'a> {
         let raw_records_bytes = self.buffer.par_chunks_exact(RawRecord::SIZE);
 
         let iter = raw_records_bytes
-            .map(|raw_record_bytes| {
-                raw_record_bytes
-                    .array_chunks::<{ Scalar::SAFE_BYTES }>()
-                    .map(Scalar::from)
-            })
-            .map(|record_chunks| {
-                let number_of_chunks = record_chunks.len();
-                let mut scalars = Vec::with_capacity(number_of_chunks.next_power_of_two());
-
-                record_chunks.collect_into(&mut scalars);
-
-                // Number of scalars for KZG must be a power of two elements
-                scalars.resize(scalars.capacity(), Scalar::default());
-
-                let polynomial = self
-                    .kzg
-                    .poly(&scalars)
-                    .expect("KZG instance must be configured to support this many scalars; qed");
-                self.kzg
-                    .commit(&polynomial)
-                    .expect("KZG instance must be configured to support this many scalars; qed")
-            });
+            .map(|raw_record_bytes| create_record_commitment(raw_record_bytes, self.kzg));
 
         #[cfg(not(feature = "parallel"))]
         iter.collect_into(&mut self.incremental_record_commitments.state);
@@ -80,6 +80,13 @@ impl PiecesReconstructor {
     ) -> Result<(ArchivedHistorySegment, Polynomial), ReconstructorError> {
         let mut reconstructed_pieces = ArchivedHistorySegment::default();
 
+        // Erasure pattern is the same for all record offsets, prepare recovery for it once
+        let present_shards = input_pieces.iter().map(Option::is_some).collect::<Vec<_>>();
+        let batch_recovery = self
+            .erasure_coding
+            .batch_recovery(&present_shards)
+            .map_err(ReconstructorError::DataShardsReconstruction)?;
+
         // Scratch buffer to avoid re-allocation
         let mut tmp_shards_scalars =
             Vec::<Option<Scalar>>::with_capacity(ArchivedHistorySegment::NUM_PIECES);
@@ -103,7 +110,7 @@ impl PiecesReconstructor {
                 tmp_shards_scalars.push(maybe_scalar);
             }
 
-            self.erasure_coding
+            batch_recovery
                 .recover(&tmp_shards_scalars)
                 .map_err(ReconstructorError::DataShardsReconstruction)?
                 .into_iter()
@@ -126,6 +126,16 @@ impl Reconstructor {
             // If not all data pieces are available, need to reconstruct data shards using erasure
             // coding.
 
+            // Erasure pattern is the same for all record offsets, prepare recovery for it once
+            let present_shards = segment_pieces
+                .iter()
+                .map(Option::is_some)
+                .collect::<Vec<_>>();
+            let batch_recovery = self
+                .erasure_coding
+                .batch_recovery(&present_shards)
+                .map_err(ReconstructorError::DataShardsReconstruction)?;
+
             // Scratch buffer to avoid re-allocation
             let mut tmp_shards_scalars =
                 Vec::<Option<Scalar>>::with_capacity(ArchivedHistorySegment::NUM_PIECES);
@@ -149,7 +159,7 @@ impl Reconstructor {
                     tmp_shards_scalars.push(maybe_scalar);
                 }
 
-                self.erasure_coding
+                batch_recovery
                     .recover(&tmp_shards_scalars)
                     .map_err(ReconstructorError::DataShardsReconstruction)?
                     .into_iter()
@@ -664,3 +664,94 @@ fn object_on_the_edge_of_segment() {
         mapped_bytes
     );
 }
+
+#[test]
+fn archiver_state() {
+    let kzg = Kzg::new(embedded_kzg_settings());
+    let mut archiver = Archiver::new(kzg.clone()).unwrap();
+
+    let mut block_0 = vec![0u8; RecordedHistorySegment::SIZE / 3 * 2];
+    thread_rng().fill(block_0.as_mut_slice());
+    assert!(archiver
+        .add_block(block_0, BlockObjectMapping::default(), true)
+        .is_empty());
+
+    // Spills over into the next segment, object ends up in the buffer of the archiver
+    let mut block_1 = vec![0u8; RecordedHistorySegment::SIZE / 3 * 2];
+    thread_rng().fill(block_1.as_mut_slice());
+    let block_1_object_mapping = BlockObjectMapping {
+        objects: vec![BlockObject::V0 {
+            hash: Blake3Hash::default(),
+            offset: RecordedHistorySegment::SIZE as u32 / 2,
+        }],
+    };
+    assert_eq!(
+        archiver
+            .add_block(block_1, block_1_object_mapping, true)
+            .len(),
+        1
+    );
+
+    let state = archiver.state();
+    assert_eq!(state.segment_index(), SegmentIndex::ONE);
+    assert_eq!(
+        state.last_archived_block().number,
+        1,
+        "Second block is partially archived"
+    );
+
+    let decoded_state = archiver::ArchiverState::decode(&mut state.encode().as_slice()).unwrap();
+    assert_eq!(decoded_state, state);
+    let mut restored_archiver = Archiver::from_state(kzg.clone(), decoded_state).unwrap();
+
+    let mut block_2 = vec![0u8; RecordedHistorySegment::SIZE];
+    thread_rng().fill(block_2.as_mut_slice());
+    assert_eq!(
+        restored_archiver.add_block(block_2.clone(), BlockObjectMapping::default(), true),
+        archiver.add_block(block_2, BlockObjectMapping::default(), true),
+    );
+
+    // Corrupted last record commitment must be detected
+    let mut encoded_state = archiver.state().encode();
+    let encoded_state_length = encoded_state.len();
+    encoded_state[encoded_state_length - 1] ^= 0xff;
+    let corrupted_state = archiver::ArchiverState::decode(&mut encoded_state.as_slice()).unwrap();
+    assert_matches!(
+        Archiver::from_state(kzg, corrupted_state),
+        Err(ArchiverInstantiationError::InvalidStateRecordCommitments),
+    );
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn parallel_archiving_is_deterministic() {
+    let kzg = Kzg::new(embedded_kzg_settings());
+    let thread_pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(1)
+        .build()
+        .unwrap();
+
+    let blocks = (0..3)
+        .map(|_| {
+            let mut block = vec![0u8; RecordedHistorySegment::SIZE / 2];
+            thread_rng().fill(block.as_mut_slice());
+            block
+        })
+        .collect::<Vec<_>>();
+
+    for incremental in [false, true] {
+        let archive = || {
+            let mut archiver = Archiver::new(kzg.clone()).unwrap();
+            blocks
+                .iter()
+                .flat_map(|block| {
+                    archiver.add_block(block.clone(), BlockObjectMapping::default(), incremental)
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let archived_segments = archive();
+        assert_eq!(archived_segments.len(), 1);
+        assert_eq!(archived_segments, thread_pool.install(archive));
+    }
+}
@@ -1,5 +1,5 @@
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
-use subspace_core_primitives::crypto::kzg::{embedded_kzg_settings, Kzg};
+use subspace_core_primitives::crypto::kzg::{embedded_kzg_settings, FixedBaseTables, Kzg};
 use subspace_core_primitives::crypto::Scalar;
 use subspace_core_primitives::RawRecord;
 
@@ -10,6 +10,22 @@ fn criterion_benchmark(c: &mut Criterion) {
 
     let kzg = Kzg::new(embedded_kzg_settings());
 
+    c.bench_function("fixed-base-tables", |b| {
+        b.iter(|| {
+            FixedBaseTables::new(
+                &embedded_kzg_settings(),
+                black_box(values.len()),
+                black_box(4),
+            )
+            .unwrap();
+        })
+    });
+
+    let kzg_with_tables = Kzg::with_fixed_base_tables(
+        embedded_kzg_settings(),
+        FixedBaseTables::new(&embedded_kzg_settings(), values.len(), 4).unwrap(),
+    );
+
     c.bench_function("create-polynomial", |b| {
         b.iter(|| {
             kzg.poly(black_box(&values)).unwrap();
@@ -24,6 +40,12 @@ fn criterion_benchmark(c: &mut Criterion) {
                 kzg.commit(black_box(&polynomial)).unwrap();
             })
         });
+
+        c.bench_function("commit/fixed-base", |b| {
+            b.iter(|| {
+                kzg_with_tables.commit(black_box(&polynomial)).unwrap();
+            })
+        });
     }
 
     let num_values = values.len();
@@ -37,6 +59,14 @@ fn criterion_benchmark(c: &mut Criterion) {
                     .unwrap();
             })
         });
+
+        c.bench_function("create-witness/fixed-base", |b| {
+            b.iter(|| {
+                kzg_with_tables
+                    .create_witness(black_box(&polynomial), black_box(num_values), black_box(0))
+                    .unwrap();
+            })
+        });
     }
 
     {
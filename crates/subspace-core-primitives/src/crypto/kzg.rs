@@ -1,17 +1,22 @@
 //! Tools for KZG commitment scheme
 
+mod fixed_base_tables;
 #[cfg(test)]
 mod tests;
 
 extern crate alloc;
 
 use crate::crypto::Scalar;
+#[cfg(feature = "std")]
+use crate::RawRecord;
 use alloc::collections::btree_map::Entry;
 use alloc::collections::BTreeMap;
 #[cfg(not(feature = "std"))]
 use alloc::string::{String, ToString};
 use alloc::sync::Arc;
 #[cfg(not(feature = "std"))]
+use alloc::vec;
+#[cfg(not(feature = "std"))]
 use alloc::vec::Vec;
 use core::mem;
 use derive_more::{AsMut, AsRef, Deref, DerefMut, From, Into};
@@ -20,14 +25,19 @@ use kzg::{FFTFr, FFTSettings, Fr, KZGSettings, G1, G2};
 #[cfg(feature = "std")]
 use parking_lot::Mutex;
 use rust_kzg_blst::types::fft_settings::FsFFTSettings;
+use rust_kzg_blst::types::fr::FsFr;
 use rust_kzg_blst::types::g1::FsG1;
 use rust_kzg_blst::types::g2::FsG2;
 use rust_kzg_blst::types::kzg_settings::FsKZGSettings;
 use rust_kzg_blst::types::poly::FsPoly;
 #[cfg(not(feature = "std"))]
 use spin::Mutex;
+#[cfg(feature = "std")]
+use std::path::Path;
 use tracing::debug;
 
+pub use fixed_base_tables::FixedBaseTables;
+
 /// Embedded KZG settings as bytes, too big for `no_std` in most cases
 /// Generated with with following command (using current Ethereum KZG Summoning Ceremony):
 /// ```bash
@@ -39,6 +49,9 @@ pub const EMBEDDED_KZG_SETTINGS_BYTES: &[u8] = include_bytes!("kzg/eth-public-pa
 pub const NUM_G1_POWERS: usize = 32_768;
 /// Number of G2 powers stored in [`EMBEDDED_KZG_SETTINGS_BYTES`]
 pub const NUM_G2_POWERS: usize = 65;
+/// Window size of fixed-base tables created by [`Kzg::with_cached_fixed_base_tables`]
+#[cfg(feature = "std")]
+const CACHED_FIXED_BASE_TABLES_WBITS: usize = 4;
 
 // Symmetric function is present in tests
 /// Function turns bytes into `FsKZGSettings`, it is up to the user to ensure that bytes make sense,
@@ -351,6 +364,7 @@ impl TryFrom<[u8; Self::SIZE]> for Witness {
 #[derive(Debug)]
 struct Inner {
     kzg_settings: FsKZGSettings,
+    fixed_base_tables: Option<FixedBaseTables>,
     fft_settings_cache: Mutex<BTreeMap<usize, Arc<FsFFTSettings>>>,
 }
 
@@ -368,12 +382,61 @@ impl Kzg {
     pub fn new(kzg_settings: FsKZGSettings) -> Self {
         let inner = Arc::new(Inner {
             kzg_settings,
+            fixed_base_tables: None,
             fft_settings_cache: Mutex::default(),
         });
 
         Self { inner }
     }
 
+    /// Create new instance with given KZG settings and precomputed fixed-base tables for them.
+    ///
+    /// Commitments and witnesses for polynomials with up to [`FixedBaseTables::num_points()`]
+    /// coefficients will use tables, larger polynomials fall back to generic multi-scalar
+    /// multiplication. Results are identical either way, tables only affect performance and memory
+    /// usage.
+    pub fn with_fixed_base_tables(
+        kzg_settings: FsKZGSettings,
+        fixed_base_tables: FixedBaseTables,
+    ) -> Self {
+        let inner = Arc::new(Inner {
+            kzg_settings,
+            fixed_base_tables: Some(fixed_base_tables),
+            fft_settings_cache: Mutex::default(),
+        });
+
+        Self { inner }
+    }
+
+    /// Create new instance with given KZG settings and fixed-base tables that cover record
+    /// polynomials ([`RawRecord::NUM_CHUNKS`] coefficients), which are committed to during
+    /// archiving and piece reconstruction.
+    ///
+    /// Tables are read from `path` if they were cached there before, otherwise they are built
+    /// (which takes a while) and cached at `path` for the next time.
+    #[cfg(feature = "std")]
+    pub fn with_cached_fixed_base_tables(
+        kzg_settings: FsKZGSettings,
+        path: &Path,
+    ) -> Result<Self, String> {
+        let fixed_base_tables = FixedBaseTables::read_or_build(
+            path,
+            &kzg_settings,
+            RawRecord::NUM_CHUNKS,
+            CACHED_FIXED_BASE_TABLES_WBITS,
+        )?;
+
+        Ok(Self::with_fixed_base_tables(
+            kzg_settings,
+            fixed_base_tables,
+        ))
+    }
+
+    /// Precomputed fixed-base tables, if instance was created with them
+    pub fn fixed_base_tables(&self) -> Option<&FixedBaseTables> {
+        self.inner.fixed_base_tables.as_ref()
+    }
+
     /// Create polynomial from data. Data must be multiple of 32 bytes, each containing up to 254
     /// bits of information.
     ///
@@ -389,6 +452,10 @@ impl Kzg {
 
     /// Computes a `Commitment` to `polynomial`
     pub fn commit(&self, polynomial: &Polynomial) -> Result<Commitment, String> {
+        if let Some(fixed_base_tables) = self.usable_fixed_base_tables(polynomial) {
+            return fixed_base_tables.msm(&polynomial.0.coeffs).map(Commitment);
+        }
+
         self.inner
             .kzg_settings
             .commit_to_poly(&polynomial.0)
@@ -405,6 +472,23 @@ impl Kzg {
         let x = self
             .get_fft_settings(num_values)?
             .get_expanded_roots_of_unity_at(index as usize);
+
+        if let Some(fixed_base_tables) = self.usable_fixed_base_tables(polynomial) {
+            let coefficients = &polynomial.0.coeffs;
+            // Quotient of division by `X - x` using synthetic division, remainder (evaluation at
+            // `x`) is not needed for witness
+            let mut quotient = vec![FsFr::zero(); coefficients.len().saturating_sub(1)];
+            let mut accumulator = FsFr::zero();
+            for (coefficient, quotient_coefficient) in
+                coefficients.iter().skip(1).zip(&mut quotient).rev()
+            {
+                accumulator = coefficient.add(&accumulator.mul(&x));
+                *quotient_coefficient = accumulator;
+            }
+
+            return fixed_base_tables.msm(&quotient).map(Witness);
+        }
+
         self.inner
             .kzg_settings
             .compute_proof_single(&polynomial.0, &x)
@@ -443,6 +527,13 @@ impl Kzg {
         }
     }
 
+    fn usable_fixed_base_tables(&self, polynomial: &Polynomial) -> Option<&FixedBaseTables> {
+        self.inner
+            .fixed_base_tables
+            .as_ref()
+            .filter(|fixed_base_tables| polynomial.0.coeffs.len() <= fixed_base_tables.num_points())
+    }
+
     /// Get FFT settings for specified number of values, uses internal cache to avoid derivation
     /// every time.
     pub fn get_fft_settings(&self, num_values: usize) -> Result<Arc<FsFFTSettings>, String> {
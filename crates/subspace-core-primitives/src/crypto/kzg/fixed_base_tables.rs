@@ -0,0 +1,317 @@
+//! Fixed-base window tables for KZG public parameters
+
+extern crate alloc;
+
+#[cfg(not(feature = "std"))]
+use alloc::format;
+#[cfg(not(feature = "std"))]
+use alloc::string::{String, ToString};
+#[cfg(not(feature = "std"))]
+use alloc::vec;
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+use blst::{
+    blst_p1, blst_p1_affine, blst_p1_affine_serialize, blst_p1_deserialize, blst_p1s_mult_wbits,
+    blst_p1s_mult_wbits_precompute, blst_p1s_mult_wbits_precompute_sizeof,
+    blst_p1s_mult_wbits_scratch_sizeof, blst_p1s_to_affine, blst_scalar, blst_scalar_from_fr,
+    limb_t, BLST_ERROR,
+};
+use core::{fmt, mem};
+use kzg::G1;
+#[cfg(feature = "std")]
+use kzg::{Fr, KZGSettings};
+use rust_kzg_blst::types::fr::FsFr;
+use rust_kzg_blst::types::g1::FsG1;
+use rust_kzg_blst::types::kzg_settings::FsKZGSettings;
+#[cfg(feature = "std")]
+use rust_kzg_blst::types::poly::FsPoly;
+#[cfg(feature = "std")]
+use std::path::Path;
+#[cfg(feature = "std")]
+use std::{fs, io};
+#[cfg(feature = "std")]
+use tracing::{debug, warn};
+
+/// Size of serialized affine point
+const AFFINE_POINT_SIZE: usize = 96;
+/// Size of the header of serialized tables: window size and number of points
+const HEADER_SIZE: usize = mem::size_of::<u32>() * 2;
+/// Number of bits in scalars
+const SCALAR_BITS: usize = 255;
+
+/// Fixed-base window tables for the first `num_points` G1 powers of KZG public parameters.
+///
+/// Tables are built once and replace generic multi-scalar multiplication in commitments and
+/// witnesses creation with fixed-base one. Table size grows linearly with number of points and
+/// exponentially with window size (`num_points * 2^(wbits - 1)` points of 96 bytes each), hence
+/// window size is a trade-off between memory usage and performance.
+#[derive(Clone)]
+pub struct FixedBaseTables {
+    wbits: usize,
+    num_points: usize,
+    table: Vec<blst_p1_affine>,
+}
+
+impl fmt::Debug for FixedBaseTables {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FixedBaseTables")
+            .field("wbits", &self.wbits)
+            .field("num_points", &self.num_points)
+            .finish_non_exhaustive()
+    }
+}
+
+impl FixedBaseTables {
+    /// Build tables with window size `wbits` for the first `num_points` G1 powers of KZG settings.
+    pub fn new(
+        kzg_settings: &FsKZGSettings,
+        num_points: usize,
+        wbits: usize,
+    ) -> Result<Self, String> {
+        if num_points == 0 || num_points > kzg_settings.secret_g1.len() {
+            return Err(format!(
+                "Number of points must be between 1 and {}, {num_points} given",
+                kzg_settings.secret_g1.len()
+            ));
+        }
+        if !(1..=SCALAR_BITS).contains(&wbits) {
+            return Err(format!("Window size must be between 1 and {SCALAR_BITS}"));
+        }
+
+        let points = kzg_settings.secret_g1[..num_points]
+            .iter()
+            .map(|point| &point.0 as *const blst_p1)
+            .collect::<Vec<_>>();
+        let mut affine_points = vec![blst_p1_affine::default(); num_points];
+        // SAFETY: Output has space for `num_points` points and there are `num_points` valid
+        // pointers to input points
+        unsafe {
+            blst_p1s_to_affine(affine_points.as_mut_ptr(), points.as_ptr(), num_points);
+        }
+
+        let affine_points = affine_points
+            .iter()
+            .map(|point| point as *const blst_p1_affine)
+            .collect::<Vec<_>>();
+        let mut table = vec![blst_p1_affine::default(); Self::table_len(num_points, wbits)];
+        // SAFETY: Table is allocated with size requested by the library and there are
+        // `num_points` valid pointers to input points
+        unsafe {
+            blst_p1s_mult_wbits_precompute(
+                table.as_mut_ptr(),
+                wbits,
+                affine_points.as_ptr(),
+                num_points,
+            );
+        }
+
+        Ok(Self {
+            wbits,
+            num_points,
+            table,
+        })
+    }
+
+    /// Window size in bits
+    pub fn wbits(&self) -> usize {
+        self.wbits
+    }
+
+    /// Number of points for which tables were built, multi-scalar multiplication with up to this
+    /// many scalars is supported
+    pub fn num_points(&self) -> usize {
+        self.num_points
+    }
+
+    /// Multi-scalar multiplication of the first `scalars.len()` points by `scalars`
+    pub(super) fn msm(&self, scalars: &[FsFr]) -> Result<FsG1, String> {
+        let num_points = scalars.len();
+        if num_points > self.num_points {
+            return Err(format!(
+                "Tables were built for {} points, {num_points} scalars given",
+                self.num_points
+            ));
+        }
+        if num_points == 0 {
+            return Ok(FsG1::identity());
+        }
+
+        let scalars = scalars
+            .iter()
+            .map(|scalar| {
+                let mut out = blst_scalar::default();
+                // SAFETY: Both pointers are valid
+                unsafe {
+                    blst_scalar_from_fr(&mut out, &scalar.0);
+                }
+                out
+            })
+            .collect::<Vec<_>>();
+        let scalars = scalars
+            .iter()
+            .map(|scalar| scalar.b.as_ptr())
+            .collect::<Vec<_>>();
+        // SAFETY: Pure function
+        let scratch_size = unsafe { blst_p1s_mult_wbits_scratch_sizeof(num_points) };
+        let mut scratch = vec![limb_t::default(); scratch_size.div_ceil(mem::size_of::<limb_t>())];
+
+        let mut out = blst_p1::default();
+        // SAFETY: Tables were built for at least `num_points` points with the same window size,
+        // there are `num_points` valid pointers to scalars and scratch space is allocated with
+        // size requested by the library
+        unsafe {
+            blst_p1s_mult_wbits(
+                &mut out,
+                self.table.as_ptr(),
+                self.wbits,
+                num_points,
+                scalars.as_ptr(),
+                SCALAR_BITS,
+                scratch.as_mut_ptr(),
+            );
+        }
+
+        Ok(FsG1(out))
+    }
+
+    /// Serialize tables into bytes, such that they can be cached (for example on disk) and loaded
+    /// with [`Self::from_bytes`] later instead of being built again
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(HEADER_SIZE + self.table.len() * AFFINE_POINT_SIZE);
+        bytes.extend_from_slice(&(self.wbits as u32).to_le_bytes());
+        bytes.extend_from_slice(&(self.num_points as u32).to_le_bytes());
+
+        for point in &self.table {
+            let mut point_bytes = [0u8; AFFINE_POINT_SIZE];
+            // SAFETY: Output has space for serialized point and input point is valid
+            unsafe {
+                blst_p1_affine_serialize(point_bytes.as_mut_ptr(), point);
+            }
+            bytes.extend_from_slice(&point_bytes);
+        }
+
+        bytes
+    }
+
+    /// Deserialize tables previously serialized with [`Self::to_bytes`].
+    ///
+    /// Points are checked to be valid, but not to correspond to specific KZG settings, hence bytes
+    /// must come from a trusted source.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String> {
+        if bytes.len() < HEADER_SIZE {
+            return Err("Not enough bytes for header".to_string());
+        }
+        let (header, table_bytes) = bytes.split_at(HEADER_SIZE);
+        let (wbits, num_points) = header.split_at(mem::size_of::<u32>());
+        let wbits = u32::from_le_bytes(wbits.try_into().expect("Correct length; qed")) as usize;
+        let num_points =
+            u32::from_le_bytes(num_points.try_into().expect("Correct length; qed")) as usize;
+
+        if num_points == 0 || !(1..=SCALAR_BITS).contains(&wbits) {
+            return Err("Invalid header".to_string());
+        }
+
+        let table_len = Self::table_len(num_points, wbits);
+        if table_bytes.len() != table_len * AFFINE_POINT_SIZE {
+            return Err("Invalid bytes length".to_string());
+        }
+
+        let table = table_bytes
+            .chunks_exact(AFFINE_POINT_SIZE)
+            .map(|point_bytes| {
+                let mut point = blst_p1_affine::default();
+                // SAFETY: Input has size of serialized point and output pointer is valid
+                let result = unsafe { blst_p1_deserialize(&mut point, point_bytes.as_ptr()) };
+
+                if result == BLST_ERROR::BLST_SUCCESS {
+                    Ok(point)
+                } else {
+                    Err(format!("Invalid point: {result:?}"))
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            wbits,
+            num_points,
+            table,
+        })
+    }
+
+    fn table_len(num_points: usize, wbits: usize) -> usize {
+        // SAFETY: Pure function
+        let table_size = unsafe { blst_p1s_mult_wbits_precompute_sizeof(wbits, num_points) };
+        table_size / mem::size_of::<blst_p1_affine>()
+    }
+}
+
+#[cfg(feature = "std")]
+impl FixedBaseTables {
+    /// Read tables from `path` if they were cached there before with the same number of points and
+    /// window size, otherwise build them with [`Self::new`] and cache at `path` for the next time.
+    ///
+    /// Tables read from `path` are checked against `kzg_settings` with a test commitment, tables
+    /// that don't match are built again.
+    pub fn read_or_build(
+        path: &Path,
+        kzg_settings: &FsKZGSettings,
+        num_points: usize,
+        wbits: usize,
+    ) -> Result<Self, String> {
+        match fs::read(path) {
+            Ok(bytes) => match Self::from_bytes(&bytes) {
+                Ok(tables) if tables.num_points == num_points && tables.wbits == wbits => {
+                    if tables.matches(kzg_settings)? {
+                        debug!(?path, "Fixed-base tables read from cache");
+
+                        return Ok(tables);
+                    }
+
+                    warn!(
+                        ?path,
+                        "Cached fixed-base tables don't match KZG settings, rebuilding"
+                    );
+                }
+                Ok(_) => {
+                    debug!(
+                        ?path,
+                        "Cached fixed-base tables have different parameters, rebuilding"
+                    );
+                }
+                Err(error) => {
+                    warn!(?path, %error, "Cached fixed-base tables are invalid, rebuilding");
+                }
+            },
+            Err(error) if error.kind() == io::ErrorKind::NotFound => {}
+            Err(error) => {
+                return Err(format!(
+                    "Failed to read fixed-base tables from {}: {error}",
+                    path.display()
+                ));
+            }
+        }
+
+        let tables = Self::new(kzg_settings, num_points, wbits)?;
+        fs::write(path, tables.to_bytes()).map_err(|error| {
+            format!(
+                "Failed to write fixed-base tables to {}: {error}",
+                path.display()
+            )
+        })?;
+
+        Ok(tables)
+    }
+
+    /// Check that tables correspond to `kzg_settings` by comparing commitment to a polynomial that
+    /// uses all points with the one created by generic multi-scalar multiplication
+    fn matches(&self, kzg_settings: &FsKZGSettings) -> Result<bool, String> {
+        let coeffs = (1..=self.num_points as u64)
+            .map(FsFr::from_u64)
+            .collect::<Vec<_>>();
+        let expected = kzg_settings.commit_to_poly(&FsPoly {
+            coeffs: coeffs.clone(),
+        })?;
+
+        Ok(self.msm(&coeffs)?.equals(&expected))
+    }
+}
@@ -1,5 +1,7 @@
-use crate::crypto::kzg::{embedded_kzg_settings, Kzg};
+use crate::crypto::kzg::{embedded_kzg_settings, FixedBaseTables, Kzg};
 use crate::crypto::Scalar;
+use std::fs;
+use tempfile::TempDir;
 
 #[test]
 fn basic() {
@@ -24,3 +26,104 @@ fn basic() {
         );
     }
 }
+
+#[test]
+fn fixed_base_tables() {
+    let values = (0..8)
+        .map(|_| Scalar::from(rand::random::<[u8; Scalar::SAFE_BYTES]>()))
+        .collect::<Vec<_>>();
+
+    let kzg = Kzg::new(embedded_kzg_settings());
+    let fixed_base_tables = FixedBaseTables::new(&embedded_kzg_settings(), 4, 4).unwrap();
+    let fixed_base_tables = FixedBaseTables::from_bytes(&fixed_base_tables.to_bytes()).unwrap();
+    assert_eq!(fixed_base_tables.num_points(), 4);
+    assert_eq!(fixed_base_tables.wbits(), 4);
+    let kzg_with_tables = Kzg::with_fixed_base_tables(embedded_kzg_settings(), fixed_base_tables);
+
+    let num_values = values.len();
+    let polynomial = kzg.poly(&values).unwrap();
+    let commitment = kzg.commit(&polynomial).unwrap();
+
+    // Polynomial is larger than tables, falls back to generic implementation
+    assert_eq!(kzg_with_tables.commit(&polynomial).unwrap(), commitment);
+
+    let small_values = &values[..4];
+    let small_polynomial = kzg.poly(small_values).unwrap();
+    let small_commitment = kzg.commit(&small_polynomial).unwrap();
+    assert_eq!(
+        kzg_with_tables.commit(&small_polynomial).unwrap(),
+        small_commitment
+    );
+
+    for (index, value) in small_values.iter().enumerate() {
+        let index = index.try_into().unwrap();
+
+        let witness = kzg
+            .create_witness(&small_polynomial, small_values.len(), index)
+            .unwrap();
+        let witness_with_tables = kzg_with_tables
+            .create_witness(&small_polynomial, small_values.len(), index)
+            .unwrap();
+
+        assert_eq!(witness_with_tables, witness, "failed on index {index}");
+        assert!(
+            kzg_with_tables.verify(
+                &small_commitment,
+                small_values.len(),
+                index,
+                value,
+                &witness_with_tables
+            ),
+            "failed on index {index}"
+        );
+    }
+
+    let witness = kzg.create_witness(&polynomial, num_values, 0).unwrap();
+    assert_eq!(
+        kzg_with_tables
+            .create_witness(&polynomial, num_values, 0)
+            .unwrap(),
+        witness
+    );
+
+    assert!(FixedBaseTables::from_bytes(&[]).is_err());
+}
+
+#[test]
+fn fixed_base_tables_cache() {
+    let directory = TempDir::new().unwrap();
+    let path = directory.path().join("fixed_base_tables.bin");
+    let kzg_settings = embedded_kzg_settings();
+
+    // Tables are built and cached on the first call
+    let fixed_base_tables = FixedBaseTables::read_or_build(&path, &kzg_settings, 4, 4).unwrap();
+    let cached_bytes = fs::read(&path).unwrap();
+    assert_eq!(cached_bytes, fixed_base_tables.to_bytes());
+
+    // And read from cache afterwards
+    let cached_fixed_base_tables =
+        FixedBaseTables::read_or_build(&path, &kzg_settings, 4, 4).unwrap();
+    assert_eq!(cached_fixed_base_tables.to_bytes(), cached_bytes);
+
+    // Different parameters result in tables being built again
+    let fixed_base_tables = FixedBaseTables::read_or_build(&path, &kzg_settings, 8, 4).unwrap();
+    assert_eq!(fixed_base_tables.num_points(), 8);
+    assert_eq!(fs::read(&path).unwrap(), fixed_base_tables.to_bytes());
+
+    // Tables that don't correspond to KZG settings are detected and built again, swapping two
+    // entries keeps all points valid, but makes tables produce wrong results
+    let mut bytes = fixed_base_tables.to_bytes();
+    let (header, table) = bytes.split_at_mut(8);
+    assert_eq!(header[4..], 8u32.to_le_bytes());
+    let (first, rest) = table.split_at_mut(96);
+    first.swap_with_slice(&mut rest[..96]);
+    fs::write(&path, &bytes).unwrap();
+    let rebuilt_tables = FixedBaseTables::read_or_build(&path, &kzg_settings, 8, 4).unwrap();
+    assert_eq!(rebuilt_tables.to_bytes(), fixed_base_tables.to_bytes());
+    assert_eq!(fs::read(&path).unwrap(), fixed_base_tables.to_bytes());
+
+    // Corrupted cache is replaced too
+    fs::write(&path, [1, 2, 3]).unwrap();
+    FixedBaseTables::read_or_build(&path, &kzg_settings, 8, 4).unwrap();
+    assert_eq!(fs::read(&path).unwrap(), fixed_base_tables.to_bytes());
+}
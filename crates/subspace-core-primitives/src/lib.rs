@@ -64,7 +64,9 @@ pub use pieces::{
 };
 use scale_info::TypeInfo;
 pub use segments::{
-    ArchivedHistorySegment, HistorySize, RecordedHistorySegment, SegmentCommitment, SegmentIndex,
+    verify_segment_headers_chain, verify_segment_headers_link, ArchivedHistorySegment, HistorySize,
+    RecordedHistorySegment, SegmentCommitment, SegmentHeaderProof, SegmentHeadersChainError,
+    SegmentIndex,
 };
 use static_assertions::{const_assert, const_assert_eq};
 
@@ -685,6 +687,62 @@ pub fn bidirectional_distance<T: WrappingSub + Ord>(a: &T, b: &T) -> T {
     diff.min(diff2)
 }
 
+/// Number of chunks in a sector that are audited in each slot, accounting for probability of
+/// hitting occupied s-bucket.
+pub const fn audited_chunks_per_sector(max_pieces_in_sector: u16) -> u64 {
+    let chunks =
+        max_pieces_in_sector as u64 * Record::NUM_CHUNKS as u64 / Record::NUM_S_BUCKETS as u64;
+
+    if chunks == 0 {
+        1
+    } else {
+        chunks
+    }
+}
+
+/// Solution range that results in expected slot probability for specified number of sectors.
+///
+/// Computes the following:
+/// ```text
+/// MAX * slot_probability / (pieces_in_sector * chunks / s_buckets) / sectors
+/// ```
+pub const fn sectors_to_solution_range(
+    sectors: u64,
+    slot_probability: (u64, u64),
+    max_pieces_in_sector: u16,
+) -> SolutionRange {
+    let solution_range = SolutionRange::MAX
+        // Account for slot probability
+        / slot_probability.1 * slot_probability.0
+        // Now take sector size and probability of hitting occupied s-bucket in sector into account
+        / audited_chunks_per_sector(max_pieces_in_sector);
+
+    // Take number of sectors into account
+    solution_range / sectors
+}
+
+/// Number of sectors that corresponds to provided solution range, inverse of
+/// [`sectors_to_solution_range()`].
+///
+/// Computes the following:
+/// ```text
+/// MAX * slot_probability / (pieces_in_sector * chunks / s_buckets) / solution_range
+/// ```
+pub const fn solution_range_to_sectors(
+    solution_range: SolutionRange,
+    slot_probability: (u64, u64),
+    max_pieces_in_sector: u16,
+) -> u64 {
+    let sectors = SolutionRange::MAX
+        // Account for slot probability
+        / slot_probability.1 * slot_probability.0
+        // Now take sector size and probability of hitting occupied s-bucket in sector into account
+        / audited_chunks_per_sector(max_pieces_in_sector);
+
+    // Take solution range into account
+    sectors / solution_range
+}
+
 #[allow(clippy::assign_op_pattern, clippy::ptr_offset_with_cast)]
 mod private_u256 {
     //! This module is needed to scope clippy allows
@@ -1,9 +1,12 @@
 use crate::crypto::kzg::Commitment;
 use crate::pieces::{FlatPieces, Piece, PieceIndex, RawRecord};
+use crate::{Blake3Hash, LastArchivedBlock, SegmentHeader};
 #[cfg(not(feature = "std"))]
 use alloc::boxed::Box;
 #[cfg(not(feature = "std"))]
 use alloc::string::String;
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
 use core::array::TryFromSliceError;
 use core::iter::Step;
 use core::mem;
@@ -87,6 +90,12 @@ impl SegmentIndex {
         PieceIndex::from((self.0 + 1) * ArchivedHistorySegment::NUM_PIECES as u64 - 1)
     }
 
+    /// Get piece index of the source piece at `source_position` among source pieces of this segment
+    /// (source pieces are interleaved with parity pieces).
+    pub fn source_piece_index(&self, source_position: u32) -> PieceIndex {
+        self.first_piece_index() + PieceIndex::from(u64::from(source_position) * 2)
+    }
+
     /// List of piece indexes that belong to this segment.
     pub fn segment_piece_indexes(&self) -> [PieceIndex; ArchivedHistorySegment::NUM_PIECES] {
         let mut piece_indices = [PieceIndex::ZERO; ArchivedHistorySegment::NUM_PIECES];
@@ -350,3 +359,196 @@ impl ArchivedHistorySegment {
     /// composed from [`crate::pieces::Record`]s together with corresponding commitments and witnesses.
     pub const SIZE: usize = Piece::SIZE * Self::NUM_PIECES;
 }
+
+/// Segment headers chain verification error
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "thiserror", derive(thiserror::Error))]
+pub enum SegmentHeadersChainError {
+    /// No segment headers to verify
+    #[cfg_attr(feature = "thiserror", error("No segment headers to verify"))]
+    Empty,
+    /// Unexpected segment index
+    #[cfg_attr(
+        feature = "thiserror",
+        error("Unexpected segment index: expected {expected}, actual {actual}")
+    )]
+    UnexpectedSegmentIndex {
+        /// Expected segment index
+        expected: SegmentIndex,
+        /// Actual segment index
+        actual: SegmentIndex,
+    },
+    /// Segment header doesn't link to the previous segment header
+    #[cfg_attr(
+        feature = "thiserror",
+        error("Segment header {segment_index} doesn't link to the previous segment header")
+    )]
+    InvalidPrevSegmentHeaderHash {
+        /// Segment index of the segment header with invalid link
+        segment_index: SegmentIndex,
+    },
+    /// Last archived block went backwards compared to the previous segment header
+    #[cfg_attr(
+        feature = "thiserror",
+        error("Last archived block of segment header {segment_index} went backwards")
+    )]
+    LastArchivedBlockWentBackwards {
+        /// Segment index of the segment header with invalid last archived block
+        segment_index: SegmentIndex,
+    },
+    /// Genesis segment header must not link to any previous segment header
+    #[cfg_attr(
+        feature = "thiserror",
+        error("Genesis segment header must not link to any previous segment header")
+    )]
+    InvalidGenesisSegmentHeader,
+}
+
+/// Verify that `segment_header` directly follows `prev_segment_header`: segment index is
+/// incremented by one, previous segment header hash matches and last archived block doesn't go
+/// backwards.
+pub fn verify_segment_headers_link(
+    prev_segment_header: &SegmentHeader,
+    segment_header: &SegmentHeader,
+) -> Result<(), SegmentHeadersChainError> {
+    let segment_index = segment_header.segment_index();
+    let expected_segment_index = prev_segment_header.segment_index() + SegmentIndex::ONE;
+    if segment_index != expected_segment_index {
+        return Err(SegmentHeadersChainError::UnexpectedSegmentIndex {
+            expected: expected_segment_index,
+            actual: segment_index,
+        });
+    }
+
+    if segment_header.prev_segment_header_hash() != prev_segment_header.hash() {
+        return Err(SegmentHeadersChainError::InvalidPrevSegmentHeaderHash { segment_index });
+    }
+
+    if segment_header.last_archived_block().number
+        < prev_segment_header.last_archived_block().number
+    {
+        return Err(SegmentHeadersChainError::LastArchivedBlockWentBackwards { segment_index });
+    }
+
+    Ok(())
+}
+
+/// Verify that `segment_headers` (in ascending order) form a chain.
+///
+/// If `prev_segment_header` is given (typically the last trusted segment header), the first segment
+/// header must directly follow it, otherwise the first segment header must be the genesis segment
+/// header.
+pub fn verify_segment_headers_chain(
+    prev_segment_header: Option<&SegmentHeader>,
+    segment_headers: &[SegmentHeader],
+) -> Result<(), SegmentHeadersChainError> {
+    let Some(first_segment_header) = segment_headers.first() else {
+        return Err(SegmentHeadersChainError::Empty);
+    };
+
+    match prev_segment_header {
+        Some(prev_segment_header) => {
+            verify_segment_headers_link(prev_segment_header, first_segment_header)?;
+        }
+        None => {
+            if first_segment_header.segment_index() != SegmentIndex::ZERO {
+                return Err(SegmentHeadersChainError::UnexpectedSegmentIndex {
+                    expected: SegmentIndex::ZERO,
+                    actual: first_segment_header.segment_index(),
+                });
+            }
+            if first_segment_header.prev_segment_header_hash() != Blake3Hash::default() {
+                return Err(SegmentHeadersChainError::InvalidGenesisSegmentHeader);
+            }
+        }
+    }
+
+    segment_headers
+        .windows(2)
+        .try_for_each(|pair| verify_segment_headers_link(&pair[0], &pair[1]))
+}
+
+/// Compact proof that segment header (and hence its segment commitment) is part of the archived
+/// history anchored at a trusted segment header.
+///
+/// Instead of full segment headers between proven and trusted segment headers, only segment
+/// commitments and last archived blocks are stored, the rest is derived during verification.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode, TypeInfo)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct SegmentHeaderProof {
+    /// Segment header that is being proven
+    pub segment_header: SegmentHeader,
+    /// Segment commitments and last archived blocks of segments that follow proven segment header,
+    /// up to (but excluding) the trusted segment header
+    pub following_segments: Vec<(SegmentCommitment, LastArchivedBlock)>,
+}
+
+impl SegmentHeaderProof {
+    /// Create proof from `segment_headers` (in ascending order) where the first one is the segment
+    /// header being proven and the rest are all segment headers that follow it up to (but
+    /// excluding) the trusted segment header.
+    pub fn new(segment_headers: &[SegmentHeader]) -> Result<Self, SegmentHeadersChainError> {
+        let Some((segment_header, following_segment_headers)) = segment_headers.split_first()
+        else {
+            return Err(SegmentHeadersChainError::Empty);
+        };
+
+        if !following_segment_headers.is_empty() {
+            verify_segment_headers_chain(Some(segment_header), following_segment_headers)?;
+        }
+
+        Ok(Self {
+            segment_header: *segment_header,
+            following_segments: following_segment_headers
+                .iter()
+                .map(|segment_header| {
+                    (
+                        segment_header.segment_commitment(),
+                        segment_header.last_archived_block(),
+                    )
+                })
+                .collect(),
+        })
+    }
+
+    /// Segment index of proven segment header
+    pub fn segment_index(&self) -> SegmentIndex {
+        self.segment_header.segment_index()
+    }
+
+    /// Segment commitment of proven segment header
+    pub fn segment_commitment(&self) -> SegmentCommitment {
+        self.segment_header.segment_commitment()
+    }
+
+    /// Verify proof against trusted segment header
+    pub fn verify(
+        &self,
+        trusted_segment_header: &SegmentHeader,
+    ) -> Result<(), SegmentHeadersChainError> {
+        if self.following_segments.is_empty() && self.segment_header == *trusted_segment_header {
+            return Ok(());
+        }
+
+        let mut prev_segment_header = self.segment_header;
+        for &(segment_commitment, last_archived_block) in &self.following_segments {
+            let segment_header = SegmentHeader::V0 {
+                segment_index: prev_segment_header.segment_index() + SegmentIndex::ONE,
+                segment_commitment,
+                prev_segment_header_hash: prev_segment_header.hash(),
+                last_archived_block,
+            };
+
+            if last_archived_block.number < prev_segment_header.last_archived_block().number {
+                return Err(SegmentHeadersChainError::LastArchivedBlockWentBackwards {
+                    segment_index: segment_header.segment_index(),
+                });
+            }
+
+            prev_segment_header = segment_header;
+        }
+
+        verify_segment_headers_link(&prev_segment_header, trusted_segment_header)
+    }
+}
@@ -1,5 +1,10 @@
 use crate::crypto::Scalar;
-use crate::U256;
+use crate::{
+    verify_segment_headers_chain, ArchivedBlockProgress, ArchivedHistorySegment, BlockNumber,
+    LastArchivedBlock, PieceIndex, SegmentCommitment, SegmentHeader, SegmentHeaderProof,
+    SegmentHeadersChainError, SegmentIndex, U256,
+};
+use parity_scale_codec::{Decode, Encode};
 use rand::thread_rng;
 use rand_core::RngCore;
 
@@ -8,6 +13,25 @@ fn piece_distance_middle() {
     assert_eq!(U256::MIDDLE, U256::MAX / 2);
 }
 
+#[test]
+fn source_piece_index() {
+    let segment_index = SegmentIndex::from(3);
+    let source_piece_indexes = (0..ArchivedHistorySegment::NUM_PIECES as u32 / 2)
+        .map(|source_position| segment_index.source_piece_index(source_position))
+        .collect::<Vec<_>>();
+
+    assert_eq!(source_piece_indexes[0], segment_index.first_piece_index());
+    assert_eq!(
+        source_piece_indexes[1],
+        segment_index.first_piece_index() + PieceIndex::from(2)
+    );
+    assert_eq!(
+        source_piece_indexes,
+        segment_index.segment_piece_indexes_source_first()
+            [..ArchivedHistorySegment::NUM_PIECES / 2]
+    );
+}
+
 #[test]
 fn bytes_scalars_conversion() {
     {
@@ -64,3 +88,106 @@ fn bytes_scalars_conversion() {
         }
     }
 }
+
+fn segment_headers_chain(len: usize) -> Vec<SegmentHeader> {
+    let mut segment_headers = Vec::<SegmentHeader>::with_capacity(len);
+    for index in 0..len {
+        let prev_segment_header_hash = segment_headers
+            .last()
+            .map(SegmentHeader::hash)
+            .unwrap_or_default();
+        let mut segment_commitment = SegmentCommitment::default();
+        thread_rng().fill_bytes(segment_commitment.as_mut());
+
+        segment_headers.push(SegmentHeader::V0 {
+            segment_index: SegmentIndex::from(index as u64),
+            segment_commitment,
+            prev_segment_header_hash,
+            last_archived_block: LastArchivedBlock {
+                number: index as BlockNumber * 10,
+                archived_progress: ArchivedBlockProgress::Partial(1),
+            },
+        });
+    }
+
+    segment_headers
+}
+
+#[test]
+fn segment_headers_chain_verification() {
+    let segment_headers = segment_headers_chain(5);
+
+    assert_eq!(verify_segment_headers_chain(None, &segment_headers), Ok(()));
+    assert_eq!(
+        verify_segment_headers_chain(Some(&segment_headers[1]), &segment_headers[2..]),
+        Ok(())
+    );
+    assert_eq!(
+        verify_segment_headers_chain(None, &[]),
+        Err(SegmentHeadersChainError::Empty)
+    );
+    assert_eq!(
+        verify_segment_headers_chain(None, &segment_headers[1..]),
+        Err(SegmentHeadersChainError::UnexpectedSegmentIndex {
+            expected: SegmentIndex::ZERO,
+            actual: SegmentIndex::ONE,
+        })
+    );
+    assert_eq!(
+        verify_segment_headers_chain(Some(&segment_headers[0]), &segment_headers[2..]),
+        Err(SegmentHeadersChainError::UnexpectedSegmentIndex {
+            expected: SegmentIndex::ONE,
+            actual: SegmentIndex::from(2),
+        })
+    );
+
+    let mut broken_segment_headers = segment_headers.clone();
+    let SegmentHeader::V0 {
+        segment_commitment, ..
+    } = &mut broken_segment_headers[2];
+    segment_commitment.as_mut()[0] ^= 1;
+    assert_eq!(
+        verify_segment_headers_chain(None, &broken_segment_headers),
+        Err(SegmentHeadersChainError::InvalidPrevSegmentHeaderHash {
+            segment_index: SegmentIndex::from(3),
+        })
+    );
+}
+
+#[test]
+fn segment_header_proof() {
+    let segment_headers = segment_headers_chain(6);
+    let trusted_segment_header = segment_headers[5];
+
+    for index in 0..segment_headers.len() - 1 {
+        let proof = SegmentHeaderProof::new(&segment_headers[index..5]).unwrap();
+        assert_eq!(proof.segment_index(), SegmentIndex::from(index as u64));
+        assert_eq!(
+            proof.segment_commitment(),
+            segment_headers[index].segment_commitment()
+        );
+        assert_eq!(proof.verify(&trusted_segment_header), Ok(()));
+
+        let proof = SegmentHeaderProof::decode(&mut proof.encode().as_slice()).unwrap();
+        assert_eq!(proof.verify(&trusted_segment_header), Ok(()));
+
+        // Proof must not verify against other segment headers
+        assert!(proof.verify(&segment_headers[0]).is_err());
+    }
+
+    // Trusted segment header proves itself
+    assert_eq!(
+        SegmentHeaderProof::new(&segment_headers[5..])
+            .unwrap()
+            .verify(&trusted_segment_header),
+        Ok(())
+    );
+
+    // Proof with tampered segment commitment must not verify
+    let mut proof = SegmentHeaderProof::new(&segment_headers[2..5]).unwrap();
+    proof.following_segments[0].0.as_mut()[0] ^= 1;
+    assert!(proof.verify(&trusted_segment_header).is_err());
+
+    // Proof can't be created from segment headers that do not form a chain
+    assert!(SegmentHeaderProof::new(&[segment_headers[1], segment_headers[3]]).is_err());
+}
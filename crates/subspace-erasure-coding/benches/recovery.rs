@@ -0,0 +1,58 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use std::num::NonZeroUsize;
+use subspace_core_primitives::crypto::Scalar;
+use subspace_core_primitives::ArchivedHistorySegment;
+use subspace_erasure_coding::ErasureCoding;
+
+/// Number of sets of shards with the same erasure pattern recovered in each iteration
+const NUM_RECORDS: usize = 64;
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let num_shards = ArchivedHistorySegment::NUM_PIECES;
+    let scale = NonZeroUsize::new(num_shards.ilog2() as usize)
+        .expect("Recorded history segment contains at very least one record; qed");
+    let ec = ErasureCoding::new(scale).unwrap();
+
+    // Every other source and parity shard is missing
+    let present_shards = (0..num_shards)
+        .map(|index| index % 4 < 2)
+        .collect::<Vec<_>>();
+
+    let records = (0..NUM_RECORDS)
+        .map(|_| {
+            let source_shards = (0..num_shards / 2)
+                .map(|_| Scalar::from(rand::random::<[u8; Scalar::SAFE_BYTES]>()))
+                .collect::<Vec<_>>();
+            let parity_shards = ec.extend(&source_shards).unwrap();
+
+            source_shards
+                .into_iter()
+                .zip(parity_shards)
+                .flat_map(|(source, parity)| [source, parity])
+                .zip(&present_shards)
+                .map(|(shard, present)| present.then_some(shard))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    c.bench_function("recover/per-record", |b| {
+        b.iter(|| {
+            for shards in &records {
+                ec.recover(black_box(shards)).unwrap();
+            }
+        })
+    });
+
+    c.bench_function("recover/batch", |b| {
+        b.iter(|| {
+            let batch_recovery = ec.batch_recovery(black_box(&present_shards)).unwrap();
+
+            for shards in &records {
+                batch_recovery.recover(black_box(shards)).unwrap();
+            }
+        })
+    });
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
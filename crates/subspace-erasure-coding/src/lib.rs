@@ -6,13 +6,16 @@ mod tests;
 extern crate alloc;
 
 #[cfg(not(feature = "std"))]
-use alloc::string::String;
+use alloc::format;
+#[cfg(not(feature = "std"))]
+use alloc::string::{String, ToString};
 use alloc::sync::Arc;
 #[cfg(not(feature = "std"))]
 use alloc::vec::Vec;
 use core::num::NonZeroUsize;
-use kzg::{FFTSettings, PolyRecover, DAS, FFTG1, G1};
+use kzg::{FFTFr, FFTSettings, Fr, PolyRecover, ZeroPoly, DAS, FFTG1, G1};
 use rust_kzg_blst::types::fft_settings::FsFFTSettings;
+use rust_kzg_blst::types::fr::FsFr;
 use rust_kzg_blst::types::g1::FsG1;
 use rust_kzg_blst::types::poly::FsPoly;
 use subspace_core_primitives::crypto::kzg::{Commitment, Polynomial};
@@ -93,6 +96,18 @@ impl ErasureCoding {
         Ok(self.recover(shards)?.into_iter().step_by(2))
     }
 
+    /// Prepare recovery of many sets of shards with the same erasure pattern, `present_shards`
+    /// indicates which shards will be `Some` (at least 1/2 should be `true`).
+    ///
+    /// Vanishing polynomial of missing shards and values derived from it are computed once here
+    /// instead of for every set of shards like in [`ErasureCoding::recover()`].
+    ///
+    /// Both in input and output source shards are interleaved with parity shards:
+    /// source, parity, source, parity, ...
+    pub fn batch_recovery(&self, present_shards: &[bool]) -> Result<BatchRecovery, String> {
+        BatchRecovery::new(Arc::clone(&self.fft_settings), present_shards)
+    }
+
     /// Extend commitments using erasure coding.
     ///
     /// Returns both source and parity commitments interleaved.
@@ -114,3 +129,197 @@ impl ErasureCoding {
             .map(Commitment::vec_from_repr)
     }
 }
+
+/// Scale factor used for polynomial evaluation over a coset, must not be a root of unity
+const SCALE_FACTOR: u64 = 5;
+
+/// Recovery of many sets of shards with the same erasure pattern.
+///
+/// Created with [`ErasureCoding::batch_recovery()`], produces the same results as corresponding
+/// methods of [`ErasureCoding`], but reuses all computations that only depend on the erasure
+/// pattern.
+#[derive(Debug, Clone)]
+pub struct BatchRecovery {
+    fft_settings: Arc<FsFFTSettings>,
+    present_shards: Vec<bool>,
+    /// Evaluations of vanishing polynomial of missing shards, `None` if nothing is missing
+    zero_eval: Option<Vec<FsFr>>,
+    /// Inverted evaluations of scaled vanishing polynomial of missing shards
+    inv_scaled_zero_eval: Vec<FsFr>,
+    /// Powers of scale factor
+    scale_powers: Vec<FsFr>,
+    /// Powers of inverted scale factor
+    inv_scale_powers: Vec<FsFr>,
+}
+
+impl BatchRecovery {
+    fn new(fft_settings: Arc<FsFFTSettings>, present_shards: &[bool]) -> Result<Self, String> {
+        let num_shards = present_shards.len();
+        if !num_shards.is_power_of_two() || num_shards > fft_settings.max_width {
+            return Err(format!(
+                "Number of shards must be a power of two not exceeding {}, {num_shards} given",
+                fft_settings.max_width
+            ));
+        }
+
+        let missing = present_shards
+            .iter()
+            .enumerate()
+            .filter_map(|(index, present)| (!present).then_some(index))
+            .collect::<Vec<_>>();
+
+        if missing.len() > num_shards / 2 {
+            return Err("Impossible to recover, too many shards are missing".to_string());
+        }
+
+        if missing.is_empty() {
+            return Ok(Self {
+                fft_settings,
+                present_shards: present_shards.to_vec(),
+                zero_eval: None,
+                inv_scaled_zero_eval: Vec::new(),
+                scale_powers: Vec::new(),
+                inv_scale_powers: Vec::new(),
+            });
+        }
+
+        let (mut zero_poly, zero_eval) =
+            fft_settings.zero_poly_via_multiplication(num_shards, &missing)?;
+
+        let scale_factor = FsFr::from_u64(SCALE_FACTOR);
+        let inv_scale_factor = scale_factor.inverse();
+        let mut scale_powers = Vec::with_capacity(num_shards);
+        let mut inv_scale_powers = Vec::with_capacity(num_shards);
+        let mut scale_power = FsFr::one();
+        let mut inv_scale_power = FsFr::one();
+        for _ in 0..num_shards {
+            scale_powers.push(scale_power);
+            inv_scale_powers.push(inv_scale_power);
+            scale_power = scale_power.mul(&scale_factor);
+            inv_scale_power = inv_scale_power.mul(&inv_scale_factor);
+        }
+
+        zero_poly.coeffs.resize(num_shards, FsFr::zero());
+        zero_poly.coeffs.iter_mut().zip(&inv_scale_powers).for_each(
+            |(coefficient, inv_scale_power)| {
+                *coefficient = coefficient.mul(inv_scale_power);
+            },
+        );
+        let inv_scaled_zero_eval = fft_settings
+            .fft_fr(&zero_poly.coeffs, false)?
+            .into_iter()
+            .map(|value| {
+                if value.is_zero() {
+                    Err("Vanishing polynomial is zero over coset".to_string())
+                } else {
+                    Ok(value.inverse())
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            fft_settings,
+            present_shards: present_shards.to_vec(),
+            zero_eval: Some(zero_eval),
+            inv_scaled_zero_eval,
+            scale_powers,
+            inv_scale_powers,
+        })
+    }
+
+    /// Recovery of missing shards from given shards, same as [`ErasureCoding::recover()`].
+    pub fn recover(&self, shards: &[Option<Scalar>]) -> Result<Vec<Scalar>, String> {
+        let Some(coeffs) = self.recover_coeffs(shards)? else {
+            return Ok(shards.iter().flatten().copied().collect());
+        };
+
+        let values = self.fft_settings.fft_fr(&coeffs, false)?;
+
+        if Scalar::slice_option_to_repr(shards)
+            .iter()
+            .zip(&values)
+            .any(|(shard, value)| shard.map_or(false, |shard| !shard.equals(value)))
+        {
+            return Err("Recovered data doesn't match given shards".to_string());
+        }
+
+        Ok(Scalar::vec_from_repr(values))
+    }
+
+    /// Recovery of missing shards from given shards in form of normalized polynomial, same as
+    /// [`ErasureCoding::recover_poly()`].
+    pub fn recover_poly(&self, shards: &[Option<Scalar>]) -> Result<Polynomial, String> {
+        let coeffs = match self.recover_coeffs(shards)? {
+            Some(coeffs) => coeffs,
+            None => self.fft_settings.fft_fr(
+                Scalar::slice_to_repr(&shards.iter().flatten().copied().collect::<Vec<_>>()),
+                true,
+            )?,
+        };
+
+        let mut poly = Polynomial::from(FsPoly { coeffs });
+
+        poly.normalize();
+
+        Ok(poly)
+    }
+
+    /// Recovery of source shards from given shards, same as [`ErasureCoding::recover_source()`].
+    pub fn recover_source(
+        &self,
+        shards: &[Option<Scalar>],
+    ) -> Result<impl ExactSizeIterator<Item = Scalar>, String> {
+        Ok(self.recover(shards)?.into_iter().step_by(2))
+    }
+
+    /// Returns coefficients of recovered polynomial or `None` if no shards are missing
+    fn recover_coeffs(&self, shards: &[Option<Scalar>]) -> Result<Option<Vec<FsFr>>, String> {
+        if shards.len() != self.present_shards.len()
+            || shards
+                .iter()
+                .zip(&self.present_shards)
+                .any(|(shard, present)| shard.is_some() != *present)
+        {
+            return Err("Shards do not match erasure pattern".to_string());
+        }
+
+        let Some(zero_eval) = &self.zero_eval else {
+            return Ok(None);
+        };
+
+        let evaluations_with_zero = Scalar::slice_option_to_repr(shards)
+            .iter()
+            .zip(zero_eval)
+            .map(|(shard, zero_eval)| match shard {
+                Some(shard) => shard.mul(zero_eval),
+                None => FsFr::zero(),
+            })
+            .collect::<Vec<_>>();
+
+        let mut coeffs = self.fft_settings.fft_fr(&evaluations_with_zero, true)?;
+        coeffs
+            .iter_mut()
+            .zip(&self.inv_scale_powers)
+            .for_each(|(coefficient, inv_scale_power)| {
+                *coefficient = coefficient.mul(inv_scale_power);
+            });
+
+        let mut evaluations = self.fft_settings.fft_fr(&coeffs, false)?;
+        evaluations
+            .iter_mut()
+            .zip(&self.inv_scaled_zero_eval)
+            .for_each(|(evaluation, inv_scaled_zero_eval)| {
+                *evaluation = evaluation.mul(inv_scaled_zero_eval);
+            });
+
+        let mut coeffs = self.fft_settings.fft_fr(&evaluations, true)?;
+        coeffs
+            .iter_mut()
+            .zip(&self.scale_powers)
+            .for_each(|(coefficient, scale_power)| {
+                *coefficient = coefficient.mul(scale_power);
+            });
+
+        Ok(Some(coeffs))
+    }
+}
@@ -135,3 +135,72 @@ fn not_enough_partial() {
         .replace(Scalar::default());
     assert!(ec.recover(&partial_shards).is_ok());
 }
+
+#[test]
+fn batch_recovery() {
+    let scale = NonZeroUsize::new(8).unwrap();
+    let num_shards = 2usize.pow(scale.get() as u32);
+    let ec = ErasureCoding::new(scale).unwrap();
+
+    let present_shards = (0..num_shards)
+        .map(|index| index % 4 != 1 && index >= num_shards / 8)
+        .collect::<Vec<_>>();
+    let batch_recovery = ec.batch_recovery(&present_shards).unwrap();
+
+    for _ in 0..4 {
+        let source_shards = (0..num_shards / 2)
+            .map(|_| rand::random::<[u8; Scalar::SAFE_BYTES]>())
+            .map(Scalar::from)
+            .collect::<Vec<_>>();
+        let parity_shards = ec.extend(&source_shards).unwrap();
+        let shards = concatenated_to_interleaved(
+            source_shards
+                .iter()
+                .chain(&parity_shards)
+                .copied()
+                .collect::<Vec<_>>(),
+        );
+
+        let partial_shards = shards
+            .iter()
+            .zip(&present_shards)
+            .map(|(shard, present)| present.then_some(*shard))
+            .collect::<Vec<_>>();
+
+        assert_eq!(batch_recovery.recover(&partial_shards).unwrap(), shards);
+        assert_eq!(
+            batch_recovery
+                .recover_source(&partial_shards)
+                .unwrap()
+                .collect::<Vec<_>>(),
+            source_shards
+        );
+        // Polynomial doesn't implement `PartialEq`, compare debug representation instead
+        assert_eq!(
+            format!(
+                "{:?}",
+                batch_recovery.recover_poly(&partial_shards).unwrap()
+            ),
+            format!("{:?}", ec.recover_poly(&partial_shards).unwrap())
+        );
+
+        // Shards must match erasure pattern
+        let mut mismatched_shards = partial_shards.clone();
+        mismatched_shards[0] = None;
+        assert!(batch_recovery.recover(&mismatched_shards).is_err());
+    }
+
+    // Nothing missing
+    let batch_recovery = ec.batch_recovery(&vec![true; num_shards]).unwrap();
+    let shards = vec![Some(Scalar::default()); num_shards];
+    assert_eq!(
+        batch_recovery.recover(&shards).unwrap(),
+        vec![Scalar::default(); num_shards]
+    );
+
+    // Less than half is not sufficient
+    let present_shards = (0..num_shards)
+        .map(|index| index < num_shards / 2 - 1)
+        .collect::<Vec<_>>();
+    assert!(ec.batch_recovery(&present_shards).is_err());
+}
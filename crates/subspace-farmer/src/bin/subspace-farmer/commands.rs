@@ -3,6 +3,7 @@ pub(crate) mod farm;
 mod info;
 mod scrub;
 mod shared;
+pub(crate) mod verify_pieces;
 
 pub(crate) use info::info;
 pub(crate) use scrub::scrub;
@@ -32,7 +32,9 @@ use subspace_farmer::single_disk_farm::{
     SingleDiskFarmError, SingleDiskFarmOptions,
 };
 use subspace_farmer::utils::farmer_piece_getter::{DsnCacheRetryPolicy, FarmerPieceGetter};
-use subspace_farmer::utils::piece_validator::SegmentCommitmentPieceValidator;
+use subspace_farmer::utils::piece_validator::{
+    NodeClientSegmentCommitments, SegmentCommitmentPieceValidator,
+};
 use subspace_farmer::utils::plotted_pieces::PlottedPieces;
 use subspace_farmer::utils::ss58::parse_ss58_reward_address;
 use subspace_farmer::utils::{
@@ -247,6 +249,10 @@ pub(crate) struct FarmingArgs {
     /// Disable farm locking, for example if file system doesn't support it
     #[arg(long)]
     disable_farm_locking: bool,
+    /// Use precomputed fixed-base tables for KZG commitments cached at specified path, speeds up
+    /// plotting at the cost of memory usage, tables are built if file doesn't exist
+    #[arg(long)]
+    kzg_fixed_base_tables_path: Option<PathBuf>,
 }
 
 fn cache_percentage_parser(s: &str) -> anyhow::Result<NonZeroU8> {
@@ -305,6 +311,10 @@ struct DsnArgs {
     /// Defines whether we should run blocking Kademlia bootstrap() operation before other requests.
     #[arg(long, default_value_t = false)]
     disable_bootstrap_on_start: bool,
+    /// Discover other farmers and nodes in local network using mDNS and prefer them for piece
+    /// retrieval.
+    #[arg(long, default_value_t = false)]
+    lan_peer_discovery: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -399,6 +409,7 @@ where
         replotting_cpu_cores,
         plotting_thread_priority,
         disable_farm_locking,
+        kzg_fixed_base_tables_path,
     } = farming_args;
 
     // Override flags with `--dev`
@@ -491,7 +502,13 @@ where
         None
     };
 
-    let kzg = Kzg::new(embedded_kzg_settings());
+    let kzg = match &kzg_fixed_base_tables_path {
+        Some(path) => tokio::task::block_in_place(|| {
+            Kzg::with_cached_fixed_base_tables(embedded_kzg_settings(), path)
+        })
+        .map_err(|error| anyhow!("Failed to load KZG fixed-base tables: {error}"))?,
+        None => Kzg::new(embedded_kzg_settings()),
+    };
     let erasure_coding = ErasureCoding::new(
         NonZeroUsize::new(Record::NUM_S_BUCKETS.next_power_of_two().ilog2() as usize)
             .expect("Not zero; qed"),
@@ -499,8 +516,8 @@ where
     .map_err(|error| anyhow::anyhow!(error))?;
     let validator = Some(SegmentCommitmentPieceValidator::new(
         node.clone(),
-        node_client.clone(),
         kzg.clone(),
+        NodeClientSegmentCommitments::new(node_client.clone()),
     ));
     let piece_provider = PieceProvider::new(node.clone(), validator.clone());
 
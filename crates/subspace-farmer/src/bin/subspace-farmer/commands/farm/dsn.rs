@@ -10,6 +10,7 @@ use subspace_farmer::utils::plotted_pieces::PlottedPieces;
 use subspace_farmer::{NodeClient, NodeRpcClient, KNOWN_PEERS_CACHE_SIZE};
 use subspace_networking::libp2p::identity::Keypair;
 use subspace_networking::libp2p::kad::RecordKey;
+use subspace_networking::libp2p::mdns::Config as MdnsConfig;
 use subspace_networking::libp2p::multiaddr::Protocol;
 use subspace_networking::utils::multihash::ToMultihash;
 use subspace_networking::utils::strip_peer_id;
@@ -42,6 +43,7 @@ pub(super) fn configure_dsn(
         pending_out_connections,
         external_addresses,
         disable_bootstrap_on_start,
+        lan_peer_discovery,
     }: DsnArgs,
     weak_plotted_pieces: Weak<Mutex<Option<PlottedPieces>>>,
     node_client: NodeRpcClient,
@@ -181,6 +183,7 @@ pub(super) fn configure_dsn(
         kademlia_mode: KademliaMode::Dynamic,
         external_addresses,
         disable_bootstrap_on_start,
+        mdns: lan_peer_discovery.then(MdnsConfig::default),
         ..default_config
     };
 
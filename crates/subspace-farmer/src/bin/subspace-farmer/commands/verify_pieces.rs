@@ -0,0 +1,387 @@
+use anyhow::anyhow;
+use clap::{Parser, ValueHint};
+use futures::{stream, StreamExt};
+use parity_scale_codec::{Decode, IoReader};
+use rayon::prelude::*;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use subspace_archiving::archiver::{is_piece_valid, ExportedSegment};
+use subspace_core_primitives::crypto::kzg::{embedded_kzg_settings, Kzg};
+use subspace_core_primitives::{PieceArray, PieceIndex, SegmentHeader, SegmentIndex};
+use subspace_farmer::node_client::NodeClientExt;
+use subspace_farmer::single_disk_farm::piece_cache::DiskPieceCache;
+use subspace_farmer::{NodeClient, NodeRpcClient};
+use subspace_networking::libp2p::identity::Keypair;
+use subspace_networking::libp2p::kad::Mode;
+use subspace_networking::libp2p::Multiaddr;
+use subspace_networking::utils::strip_peer_id;
+use subspace_networking::{
+    construct, Config, KademliaMode, PieceByIndexRequest, PieceByIndexRequestHandler,
+    PieceByIndexResponse,
+};
+use subspace_rpc_primitives::MAX_SEGMENT_HEADERS_PER_REQUEST;
+use tracing::{info, warn};
+
+/// How many pieces are requested from the peer concurrently
+const PEER_PIECE_REQUEST_CONCURRENCY: usize = 32;
+
+/// Arguments for pieces verification
+#[derive(Debug, Parser)]
+pub(crate) struct VerifyPiecesArgs {
+    /// WebSocket RPC URL of the Subspace node to get segment headers from
+    #[arg(
+        long,
+        value_hint = ValueHint::Url,
+        required_unless_present = "segment_headers_file",
+        conflicts_with = "segment_headers_file"
+    )]
+    node_rpc_url: Option<String>,
+    /// JSON file with an array of all segment headers starting with the first one (as returned by
+    /// `subspace_segmentHeaders` RPC method) to use instead of the node
+    #[arg(long, value_hint = ValueHint::FilePath)]
+    segment_headers_file: Option<PathBuf>,
+    /// Farm whose piece cache should be verified, multiple are supported
+    #[arg(long = "farm", value_hint = ValueHint::DirPath)]
+    farms: Vec<PathBuf>,
+    /// File with archived segment exported by `subspace-node export-segments`, multiple are
+    /// supported
+    #[arg(long = "segment-file", value_hint = ValueHint::FilePath)]
+    segment_files: Vec<PathBuf>,
+    /// Multiaddr of the peer (including `/p2p/<peer-id>` suffix) whose pieces should be verified
+    #[arg(long, requires = "peer_segment_indices")]
+    peer: Option<Multiaddr>,
+    /// Segment whose pieces should be requested from the peer, multiple are supported
+    #[arg(long = "peer-segment-index", requires = "peer")]
+    peer_segment_indices: Vec<u64>,
+    /// Protocol version for libp2p stack (genesis hash of the chain in hex), retrieved from the
+    /// node if not specified
+    #[arg(long)]
+    protocol_version: Option<String>,
+}
+
+/// Outcome of verification of all pieces
+#[derive(Debug, Default)]
+struct VerificationSummary {
+    valid: AtomicUsize,
+    invalid: AtomicUsize,
+    missing: AtomicUsize,
+}
+
+impl VerificationSummary {
+    fn valid(&self) {
+        self.valid.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn invalid(&self, source: &str, maybe_piece_index: Option<PieceIndex>, reason: &str) {
+        self.invalid.fetch_add(1, Ordering::Relaxed);
+        match maybe_piece_index {
+            Some(piece_index) => {
+                warn!(%source, %piece_index, %reason, "Invalid piece");
+            }
+            None => {
+                warn!(%source, %reason, "Invalid piece");
+            }
+        }
+    }
+
+    fn missing(&self) {
+        self.missing.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Verify pieces from various sources against segment headers, returns an error if any invalid
+/// pieces were found.
+pub(crate) async fn verify_pieces(verify_pieces_args: VerifyPiecesArgs) -> anyhow::Result<()> {
+    let VerifyPiecesArgs {
+        node_rpc_url,
+        segment_headers_file,
+        farms,
+        segment_files,
+        peer,
+        peer_segment_indices,
+        protocol_version,
+    } = verify_pieces_args;
+
+    let kzg = Kzg::new(embedded_kzg_settings());
+    let summary = VerificationSummary::default();
+
+    let (segment_headers, maybe_node_client) = match (node_rpc_url, segment_headers_file) {
+        (Some(node_rpc_url), _) => {
+            info!(url = %node_rpc_url, "Connecting to node RPC");
+            let node_client = NodeRpcClient::new(&node_rpc_url).await?;
+
+            (
+                download_segment_headers(&node_client).await?,
+                Some(node_client),
+            )
+        }
+        (None, Some(segment_headers_file)) => {
+            let segment_headers = serde_json::from_reader::<_, Vec<SegmentHeader>>(
+                BufReader::new(File::open(&segment_headers_file)?),
+            )?;
+
+            (segment_headers, None)
+        }
+        (None, None) => {
+            unreachable!("Required by CLI arguments parser; qed");
+        }
+    };
+
+    for (segment_index, segment_header) in segment_headers.iter().enumerate() {
+        if segment_header.segment_index() != SegmentIndex::from(segment_index as u64) {
+            return Err(anyhow!(
+                "Segment headers must be ordered by segment index starting from zero"
+            ));
+        }
+    }
+
+    info!(
+        segment_headers = %segment_headers.len(),
+        "Verifying pieces against segment headers"
+    );
+
+    for farm in &farms {
+        let source = farm.display().to_string();
+        info!(%source, "Verifying pieces in farm's piece cache");
+
+        DiskPieceCache::read_pieces(farm)?
+            .par_bridge()
+            .for_each(|(offset, result)| match result {
+                Ok((piece_index, piece)) => {
+                    verify_piece(
+                        &kzg,
+                        &segment_headers,
+                        &summary,
+                        &source,
+                        piece_index,
+                        &piece,
+                    );
+                }
+                Err(error) => {
+                    summary.invalid(&source, None, &format!("offset {offset}: {error}"));
+                }
+            });
+    }
+
+    for segment_file in &segment_files {
+        let source = segment_file.display().to_string();
+        info!(%source, "Verifying pieces in segment file");
+
+        let ExportedSegment {
+            segment_header,
+            pieces,
+        } = ExportedSegment::decode(&mut IoReader(BufReader::new(File::open(segment_file)?)))
+            .map_err(|error| anyhow!("Failed to decode segment file {source}: {error}"))?;
+
+        let segment_index = segment_header.segment_index();
+        if segment_headers.get(u64::from(segment_index) as usize) != Some(&segment_header) {
+            summary.invalid(
+                &source,
+                None,
+                &format!("segment header of segment {segment_index} doesn't match"),
+            );
+        }
+
+        segment_index
+            .segment_piece_indexes()
+            .into_par_iter()
+            .zip(pieces.par_iter())
+            .for_each(|(piece_index, piece)| {
+                verify_piece(
+                    &kzg,
+                    &segment_headers,
+                    &summary,
+                    &source,
+                    piece_index,
+                    piece,
+                );
+            });
+    }
+
+    if let Some(peer) = peer {
+        let protocol_version = match (protocol_version, &maybe_node_client) {
+            (Some(protocol_version), _) => protocol_version,
+            (None, Some(node_client)) => {
+                let farmer_app_info = node_client
+                    .farmer_app_info()
+                    .await
+                    .map_err(|error| anyhow!(error))?;
+
+                hex::encode(farmer_app_info.genesis_hash)
+            }
+            (None, None) => {
+                return Err(anyhow!(
+                    "Protocol version must be specified when node RPC URL is not"
+                ));
+            }
+        };
+
+        verify_peer_pieces(
+            &kzg,
+            &segment_headers,
+            &summary,
+            protocol_version,
+            peer,
+            peer_segment_indices,
+        )
+        .await?;
+    }
+
+    let valid = summary.valid.load(Ordering::Relaxed);
+    let invalid = summary.invalid.load(Ordering::Relaxed);
+    let missing = summary.missing.load(Ordering::Relaxed);
+
+    info!(%valid, %invalid, %missing, "Verification finished");
+
+    if invalid > 0 {
+        return Err(anyhow!("Found {invalid} invalid pieces"));
+    }
+
+    Ok(())
+}
+
+/// Download all segment headers from the node
+async fn download_segment_headers(
+    node_client: &NodeRpcClient,
+) -> anyhow::Result<Vec<SegmentHeader>> {
+    let Some(last_segment_header) = node_client
+        .last_segment_headers(1)
+        .await
+        .map_err(|error| anyhow!("Failed to get last segment header: {error}"))?
+        .into_iter()
+        .next()
+        .flatten()
+    else {
+        return Ok(Vec::new());
+    };
+
+    let mut segment_headers =
+        Vec::with_capacity(u64::from(last_segment_header.segment_index()) as usize + 1);
+    let segment_indices = (0..=u64::from(last_segment_header.segment_index()))
+        .map(SegmentIndex::from)
+        .collect::<Vec<_>>();
+
+    for segment_indices in segment_indices.chunks(MAX_SEGMENT_HEADERS_PER_REQUEST) {
+        let segment_headers_batch = node_client
+            .segment_headers(segment_indices.to_vec())
+            .await
+            .map_err(|error| anyhow!("Failed to get segment headers: {error}"))?;
+
+        for (segment_index, maybe_segment_header) in
+            segment_indices.iter().zip(segment_headers_batch)
+        {
+            let segment_header = maybe_segment_header
+                .ok_or_else(|| anyhow!("Node didn't return segment header {segment_index}"))?;
+            segment_headers.push(segment_header);
+        }
+    }
+
+    Ok(segment_headers)
+}
+
+/// Request pieces of specified segments from the peer and verify them
+async fn verify_peer_pieces(
+    kzg: &Kzg,
+    segment_headers: &[SegmentHeader],
+    summary: &VerificationSummary,
+    protocol_version: String,
+    peer: Multiaddr,
+    segment_indices: Vec<u64>,
+) -> anyhow::Result<()> {
+    let Some((peer_id, _address)) = strip_peer_id(vec![peer.clone()]).into_iter().next() else {
+        return Err(anyhow!("Peer address {peer} must end with peer ID"));
+    };
+
+    let config = Config {
+        bootstrap_addresses: vec![peer],
+        kademlia_mode: KademliaMode::Static(Mode::Client),
+        // Handler needs to be registered in order to be able to send requests
+        request_response_protocols: vec![PieceByIndexRequestHandler::create(|_, _| async { None })],
+        ..Config::new(protocol_version, Keypair::generate_ed25519(), (), None)
+    };
+    let (node, mut node_runner) = construct(config)?;
+    let node_runner_handle = tokio::spawn(async move {
+        node_runner.run().await;
+    });
+    node.bootstrap().await?;
+
+    let source = &peer_id.to_string();
+    for segment_index in segment_indices {
+        let segment_index = SegmentIndex::from(segment_index);
+        info!(%source, %segment_index, "Verifying pieces of the segment stored by peer");
+
+        let pieces = stream::iter(segment_index.segment_piece_indexes())
+            .map(|piece_index| {
+                let node = &node;
+
+                async move {
+                    let response = node
+                        .send_generic_request(peer_id, PieceByIndexRequest { piece_index })
+                        .await;
+
+                    (piece_index, response)
+                }
+            })
+            .buffer_unordered(PEER_PIECE_REQUEST_CONCURRENCY)
+            .filter_map(|(piece_index, response)| async move {
+                match response {
+                    Ok(PieceByIndexResponse { piece: Some(piece) }) => Some((piece_index, piece)),
+                    Ok(PieceByIndexResponse { piece: None }) => {
+                        summary.missing();
+                        None
+                    }
+                    Err(error) => {
+                        warn!(%source, %piece_index, %error, "Piece request failed");
+                        summary.missing();
+                        None
+                    }
+                }
+            })
+            .collect::<Vec<_>>()
+            .await;
+
+        pieces.par_iter().for_each(|(piece_index, piece)| {
+            verify_piece(kzg, segment_headers, summary, source, *piece_index, piece);
+        });
+    }
+
+    node_runner_handle.abort();
+
+    Ok(())
+}
+
+fn verify_piece(
+    kzg: &Kzg,
+    segment_headers: &[SegmentHeader],
+    summary: &VerificationSummary,
+    source: &str,
+    piece_index: PieceIndex,
+    piece: &PieceArray,
+) {
+    let segment_index = piece_index.segment_index();
+    let Some(segment_header) = segment_headers.get(u64::from(segment_index) as usize) else {
+        summary.invalid(
+            source,
+            Some(piece_index),
+            &format!("segment header {segment_index} is not known"),
+        );
+        return;
+    };
+
+    if is_piece_valid(
+        kzg,
+        piece,
+        &segment_header.segment_commitment(),
+        piece_index.position(),
+    ) {
+        summary.valid();
+    } else {
+        summary.invalid(
+            source,
+            Some(piece_index),
+            "commitment or witness doesn't match segment commitment",
+        );
+    }
+}
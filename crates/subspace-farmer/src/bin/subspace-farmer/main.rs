@@ -46,6 +46,9 @@ enum Command {
         #[arg(long)]
         disable_farm_locking: bool,
     },
+    /// Verifies pieces (in farm's piece cache, exported segment files or stored by a peer) against
+    /// segment headers, exits with an error if any invalid pieces were found
+    VerifyPieces(commands::verify_pieces::VerifyPiecesArgs),
     /// Wipes the farm
     Wipe {
         /// One or more farm located at specified path.
@@ -108,6 +111,9 @@ async fn main() -> anyhow::Result<()> {
                 commands::scrub(&disk_farms, disable_farm_locking);
             }
         }
+        Command::VerifyPieces(verify_pieces_args) => {
+            commands::verify_pieces::verify_pieces(verify_pieces_args).await?;
+        }
         Command::Wipe { disk_farms } => {
             for disk_farm in &disk_farms {
                 if !disk_farm.exists() {
@@ -5,8 +5,9 @@ mod tests;
 use crate::single_disk_farm::unbuffered_io_file_windows::UnbufferedIoFileWindows;
 use crate::single_disk_farm::unbuffered_io_file_windows::DISK_SECTOR_SIZE;
 use derive_more::Display;
+use std::fs::File;
 #[cfg(not(windows))]
-use std::fs::{File, OpenOptions};
+use std::fs::OpenOptions;
 use std::path::Path;
 use std::sync::Arc;
 use std::{fs, io, mem};
@@ -236,6 +237,48 @@ impl DiskPieceCache {
             .file
             .read_exact_at(element, u64::from(offset) * u64::from(Self::element_size()))?;
 
+        Self::parse_element(element)
+    }
+
+    /// Read all pieces stored in piece cache of the farm in `directory` without opening the farm
+    /// itself, for instance to verify pieces while farmer is not running.
+    ///
+    /// Empty elements are skipped, elements with checksum mismatch are returned as
+    /// [`DiskPieceCacheError::ChecksumMismatch`].
+    pub fn read_pieces(
+        directory: &Path,
+    ) -> Result<
+        impl Iterator<Item = (Offset, Result<(PieceIndex, Piece), DiskPieceCacheError>)>,
+        DiskPieceCacheError,
+    > {
+        let mut file = File::open(directory.join(Self::FILE_NAME))?;
+        // Error doesn't matter here
+        let _ = file.advise_sequential_access();
+        let num_elements = (file.size()? / u64::from(Self::element_size())) as u32;
+        let mut element = vec![0; Self::element_size() as usize];
+
+        Ok((0..num_elements).filter_map(move |offset| {
+            let result = file
+                .read_exact_at(
+                    &mut element,
+                    u64::from(offset) * u64::from(Self::element_size()),
+                )
+                .map_err(DiskPieceCacheError::from)
+                .and_then(|()| Self::parse_element(&element));
+
+            match result {
+                Ok(Some(piece_index)) => {
+                    let mut piece = Piece::default();
+                    piece.copy_from_slice(&element[PieceIndex::SIZE..][..Piece::SIZE]);
+                    Some((Offset(offset), Ok((piece_index, piece))))
+                }
+                Ok(None) => None,
+                Err(error) => Some((Offset(offset), Err(error))),
+            }
+        }))
+    }
+
+    fn parse_element(element: &[u8]) -> Result<Option<PieceIndex>, DiskPieceCacheError> {
         let (piece_index_bytes, remaining_bytes) = element.split_at(PieceIndex::SIZE);
         let (piece_bytes, expected_checksum) = remaining_bytes.split_at(Piece::SIZE);
 
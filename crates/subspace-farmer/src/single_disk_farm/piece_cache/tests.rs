@@ -2,6 +2,7 @@ use crate::single_disk_farm::piece_cache::{DiskPieceCache, DiskPieceCacheError,
 use rand::prelude::*;
 use std::assert_matches::assert_matches;
 use subspace_core_primitives::{Piece, PieceIndex};
+use subspace_farmer_components::file_ext::FileExt;
 use tempfile::tempdir;
 
 #[test]
@@ -141,3 +142,52 @@ fn basic() {
         );
     }
 }
+
+#[test]
+fn read_pieces() {
+    let path = tempdir().unwrap();
+    let disk_piece_cache = DiskPieceCache::open(path.as_ref(), 3).unwrap();
+
+    let piece = {
+        let mut piece = Piece::default();
+        thread_rng().fill(piece.as_mut());
+        piece
+    };
+    disk_piece_cache
+        .write_piece(Offset(0), PieceIndex::ONE, &piece)
+        .unwrap();
+    // Leave a gap, all elements are read regardless
+    disk_piece_cache
+        .write_piece(Offset(2), PieceIndex::from(10), &piece)
+        .unwrap();
+
+    let pieces = DiskPieceCache::read_pieces(path.as_ref())
+        .unwrap()
+        .map(|(_offset, result)| result.unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        pieces,
+        vec![
+            (PieceIndex::ONE, piece.clone()),
+            (PieceIndex::from(10), piece.clone())
+        ]
+    );
+
+    // Corrupt the first element
+    {
+        let file = std::fs::OpenOptions::new()
+            .write(true)
+            .open(path.as_ref().join(DiskPieceCache::FILE_NAME))
+            .unwrap();
+        file.write_all_at(&[!piece.as_ref()[0]], PieceIndex::SIZE as u64)
+            .unwrap();
+    }
+
+    let results = DiskPieceCache::read_pieces(path.as_ref())
+        .unwrap()
+        .map(|(_offset, result)| result)
+        .collect::<Vec<_>>();
+    assert_eq!(results.len(), 2);
+    assert_matches!(results[0], Err(DiskPieceCacheError::ChecksumMismatch));
+    assert!(results[1].is_ok());
+}
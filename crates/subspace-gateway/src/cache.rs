@@ -0,0 +1,68 @@
+//! In-memory cache of HTTP responses.
+
+use actix_web::web::Bytes;
+use lru::LruCache;
+use parking_lot::Mutex;
+use subspace_core_primitives::{Blake3Hash, PieceIndex};
+
+/// Key of the cached response.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub(crate) enum CacheKey {
+    /// Piece by its index
+    Piece(PieceIndex),
+    /// Object by its hash
+    Object(Blake3Hash),
+}
+
+#[derive(Debug)]
+struct Inner {
+    entries: LruCache<CacheKey, Bytes>,
+    size: usize,
+}
+
+/// LRU cache of response bodies limited by their total size in bytes.
+///
+/// Pieces and objects are immutable, hence cached contents never needs to be invalidated.
+#[derive(Debug)]
+pub(crate) struct ResponseCache {
+    inner: Mutex<Inner>,
+    capacity: usize,
+}
+
+impl ResponseCache {
+    /// Create new cache with capacity in bytes, zero capacity disables caching
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            inner: Mutex::new(Inner {
+                entries: LruCache::unbounded(),
+                size: 0,
+            }),
+            capacity,
+        }
+    }
+
+    pub(crate) fn get(&self, key: &CacheKey) -> Option<Bytes> {
+        self.inner.lock().entries.get(key).cloned()
+    }
+
+    /// Insert entry, evicting least recently used entries if necessary, entries larger than cache
+    /// capacity are not cached.
+    pub(crate) fn insert(&self, key: CacheKey, value: Bytes) {
+        if value.len() > self.capacity {
+            return;
+        }
+
+        let mut inner = self.inner.lock();
+        inner.size += value.len();
+        if let Some(old_value) = inner.entries.put(key, value) {
+            inner.size -= old_value.len();
+        }
+
+        while inner.size > self.capacity {
+            let Some((_key, evicted_value)) = inner.entries.pop_lru() else {
+                break;
+            };
+            inner.size -= evicted_value.len();
+        }
+    }
+}
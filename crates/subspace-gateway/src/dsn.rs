@@ -0,0 +1,56 @@
+//! DSN client configuration of the gateway.
+
+use subspace_networking::libp2p::identity::Keypair;
+use subspace_networking::libp2p::kad::Mode;
+use subspace_networking::libp2p::Multiaddr;
+use subspace_networking::{
+    construct, gossipsub_config, Config, CreationError, KademliaMode, Node, NodeRunner,
+    PieceByIndexRequestHandler, SegmentHeaderBySegmentIndexesRequestHandler,
+};
+
+/// DSN options of the gateway.
+#[derive(Debug, Clone)]
+pub(crate) struct DsnOptions {
+    /// Protocol version for libp2p stack, genesis hash of the blockchain
+    pub(crate) protocol_version: String,
+    /// Multiaddresses of bootstrap nodes
+    pub(crate) bootstrap_nodes: Vec<Multiaddr>,
+    /// Multiaddresses to listen on
+    pub(crate) listen_on: Vec<Multiaddr>,
+    /// Whether to allow non-global addresses in Kademlia DHT
+    pub(crate) allow_private_ips: bool,
+    /// Known external addresses
+    pub(crate) external_addresses: Vec<Multiaddr>,
+}
+
+/// Create DSN node that joins the network as a client.
+///
+/// Gateway doesn't store anything, but request handlers still need to be registered in order to be
+/// able to send corresponding requests.
+pub(crate) fn create_dsn_node(
+    keypair: Keypair,
+    DsnOptions {
+        protocol_version,
+        bootstrap_nodes,
+        listen_on,
+        allow_private_ips,
+        external_addresses,
+    }: DsnOptions,
+) -> Result<(Node, NodeRunner<()>), CreationError> {
+    let config = Config {
+        listen_on,
+        bootstrap_addresses: bootstrap_nodes,
+        allow_non_global_addresses_in_dht: allow_private_ips,
+        external_addresses,
+        kademlia_mode: KademliaMode::Static(Mode::Client),
+        request_response_protocols: vec![
+            PieceByIndexRequestHandler::create(|_, _| async { None }),
+            SegmentHeaderBySegmentIndexesRequestHandler::create(|_, _| async { None }),
+        ],
+        // Segment headers are received through announcements
+        gossipsub: Some(gossipsub_config()),
+        ..Config::new(protocol_version, keypair, (), None)
+    };
+
+    construct(config)
+}
@@ -0,0 +1,197 @@
+//! HTTP API of the gateway.
+
+#[cfg(test)]
+mod tests;
+
+use crate::cache::{CacheKey, ResponseCache};
+use crate::object_mappings::ObjectMappingSource;
+use crate::piece_getter::DsnPieceGetter;
+use crate::segment_headers::SegmentHeaders;
+use actix_web::http::header::{
+    self, ByteRangeSpec, ContentRange, ContentRangeSpec, HeaderValue, Range,
+};
+use actix_web::web::{Bytes, Data, Path};
+use actix_web::{get, App, HttpRequest, HttpResponse, HttpServer, Responder};
+use std::future::Future;
+use std::net::SocketAddr;
+use std::str::FromStr;
+use std::sync::Arc;
+use subspace_core_primitives::{Blake3Hash, PieceIndex, SegmentIndex};
+use subspace_networking::utils::object_fetcher::ObjectFetcher;
+use tracing::{debug, error, info};
+
+/// Pieces, segment headers and objects never change once archived.
+const IMMUTABLE_CACHE_CONTROL: &str = "public, max-age=31536000, immutable";
+
+/// Shared state of HTTP handlers.
+pub(crate) struct GatewayState {
+    pub(crate) piece_getter: Arc<DsnPieceGetter>,
+    pub(crate) segment_headers: SegmentHeaders,
+    pub(crate) object_fetcher: ObjectFetcher<Arc<DsnPieceGetter>>,
+    pub(crate) object_mapping_source: Option<Box<dyn ObjectMappingSource>>,
+    pub(crate) cache: ResponseCache,
+}
+
+#[get("/piece/{piece_index}")]
+async fn piece(
+    state: Data<GatewayState>,
+    piece_index: Path<u64>,
+    req: HttpRequest,
+) -> HttpResponse {
+    let piece_index = PieceIndex::from(piece_index.into_inner());
+    let cache_key = CacheKey::Piece(piece_index);
+
+    if let Some(bytes) = state.cache.get(&cache_key) {
+        return bytes_response(&req, bytes);
+    }
+
+    match state.piece_getter.get_piece(piece_index).await {
+        Some(piece) => {
+            let bytes = Bytes::copy_from_slice(piece.as_ref());
+            state.cache.insert(cache_key, bytes.clone());
+
+            bytes_response(&req, bytes)
+        }
+        None => {
+            debug!(%piece_index, "Piece not found");
+
+            HttpResponse::NotFound().body("Piece not found")
+        }
+    }
+}
+
+#[get("/segment-header/{segment_index}")]
+async fn segment_header(state: Data<GatewayState>, segment_index: Path<u64>) -> impl Responder {
+    let segment_index = SegmentIndex::from(segment_index.into_inner());
+
+    match state.segment_headers.get(segment_index) {
+        Some(segment_header) => HttpResponse::Ok()
+            .insert_header((header::CACHE_CONTROL, IMMUTABLE_CACHE_CONTROL))
+            .json(segment_header),
+        None => HttpResponse::NotFound().body("Segment header not found"),
+    }
+}
+
+#[get("/object/{object_hash}")]
+async fn object(
+    state: Data<GatewayState>,
+    object_hash: Path<String>,
+    req: HttpRequest,
+) -> HttpResponse {
+    let Some(object_hash) = hex::decode(object_hash.into_inner())
+        .ok()
+        .and_then(|object_hash| Blake3Hash::try_from(object_hash).ok())
+    else {
+        return HttpResponse::BadRequest().body("Object hash must be 32 hex-encoded bytes");
+    };
+    let cache_key = CacheKey::Object(object_hash);
+
+    if let Some(bytes) = state.cache.get(&cache_key) {
+        return bytes_response(&req, bytes);
+    }
+
+    let Some(object_mapping_source) = &state.object_mapping_source else {
+        return HttpResponse::NotFound().body("Object mappings are not available");
+    };
+
+    let global_object = match object_mapping_source.get_object_mapping(object_hash).await {
+        Ok(Some(global_object)) => global_object,
+        Ok(None) => {
+            return HttpResponse::NotFound().body("Object mapping not found");
+        }
+        Err(error) => {
+            error!(%error, object_hash = %hex::encode(object_hash), "Failed to get object mapping");
+
+            return HttpResponse::BadGateway().body("Failed to get object mapping");
+        }
+    };
+
+    match state
+        .object_fetcher
+        .fetch_object(global_object, object_hash)
+        .await
+    {
+        Ok(object) => {
+            let bytes = Bytes::from(object);
+            state.cache.insert(cache_key, bytes.clone());
+
+            bytes_response(&req, bytes)
+        }
+        Err(error) => {
+            debug!(
+                %error,
+                object_hash = %hex::encode(object_hash),
+                ?global_object,
+                "Failed to fetch object"
+            );
+
+            HttpResponse::BadGateway().body(format!("Failed to fetch object: {error}"))
+        }
+    }
+}
+
+/// Create response with immutable contents, supports single byte range requests.
+///
+/// Invalid and multiple ranges are ignored and the whole contents is returned.
+fn bytes_response(req: &HttpRequest, bytes: Bytes) -> HttpResponse {
+    let length = bytes.len() as u64;
+    let maybe_range = req
+        .headers()
+        .get(header::RANGE)
+        .and_then(|range| range.to_str().ok())
+        .and_then(|range| Range::from_str(range).ok());
+
+    let maybe_byte_range = match &maybe_range {
+        Some(Range::Bytes(ranges)) if ranges.len() == 1 => Some(&ranges[0]),
+        _ => None,
+    };
+
+    let Some(byte_range) = maybe_byte_range else {
+        return HttpResponse::Ok()
+            .insert_header((header::CACHE_CONTROL, IMMUTABLE_CACHE_CONTROL))
+            .insert_header((header::ACCEPT_RANGES, HeaderValue::from_static("bytes")))
+            .content_type("application/octet-stream")
+            .body(bytes);
+    };
+
+    match ByteRangeSpec::to_satisfiable_range(byte_range, length) {
+        Some((start, end)) => HttpResponse::PartialContent()
+            .insert_header((header::CACHE_CONTROL, IMMUTABLE_CACHE_CONTROL))
+            .insert_header(ContentRange(ContentRangeSpec::Bytes {
+                range: Some((start, end)),
+                instance_length: Some(length),
+            }))
+            .content_type("application/octet-stream")
+            .body(bytes.slice(start as usize..=end as usize)),
+        None => HttpResponse::RangeNotSatisfiable()
+            .insert_header(ContentRange(ContentRangeSpec::Bytes {
+                range: None,
+                instance_length: Some(length),
+            }))
+            .finish(),
+    }
+}
+
+/// Start HTTP server on the provided addresses.
+pub(crate) fn start_http_server(
+    endpoints: Vec<SocketAddr>,
+    state: GatewayState,
+) -> std::io::Result<impl Future<Output = std::io::Result<()>>> {
+    let data = Data::new(state);
+
+    let server = HttpServer::new(move || {
+        App::new()
+            .app_data(data.clone())
+            .service(piece)
+            .service(segment_header)
+            .service(object)
+    })
+    .bind(endpoints.as_slice())
+    .inspect_err(|error| {
+        error!(?error, "Failed to start HTTP server.");
+    })?;
+
+    info!(endpoints = ?server.addrs(), "HTTP server started.");
+
+    Ok(server.run())
+}
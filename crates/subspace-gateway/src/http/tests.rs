@@ -0,0 +1,318 @@
+use crate::cache::ResponseCache;
+use crate::dsn::{create_dsn_node, DsnOptions};
+use crate::http::{object, piece, segment_header, GatewayState};
+use crate::object_mappings::ObjectMappingSource;
+use crate::piece_getter::DsnPieceGetter;
+use crate::segment_headers::SegmentHeaders;
+use actix_web::http::{header, StatusCode};
+use actix_web::web::Data;
+use actix_web::{test, App};
+use async_trait::async_trait;
+use parity_scale_codec::Encode;
+use rand::{thread_rng, Rng};
+use std::collections::HashMap;
+use std::error::Error;
+use std::sync::Arc;
+use std::time::Duration;
+use subspace_archiving::archiver::{Archiver, NewArchivedSegment};
+use subspace_core_primitives::crypto::blake3_hash;
+use subspace_core_primitives::crypto::kzg::{embedded_kzg_settings, Kzg};
+use subspace_core_primitives::objects::{BlockObject, BlockObjectMapping, GlobalObject};
+use subspace_core_primitives::{
+    Blake3Hash, Piece, PieceIndex, RecordedHistorySegment, SegmentHeader,
+};
+use subspace_networking::libp2p::identity::Keypair;
+use subspace_networking::libp2p::kad::Mode;
+use subspace_networking::libp2p::multiaddr::Protocol;
+use subspace_networking::libp2p::Multiaddr;
+use subspace_networking::testing::TestPieceCache;
+use subspace_networking::utils::object_fetcher::ObjectFetcher;
+use subspace_networking::utils::piece_provider::PieceProvider;
+use subspace_networking::utils::piece_validator::SegmentCommitmentPieceValidator;
+use subspace_networking::{
+    construct, Config, KademliaMode, Node, PieceByIndexRequest, PieceByIndexRequestHandler,
+    PieceByIndexResponse, SegmentHeaderBySegmentIndexesRequestHandler, SegmentHeaderRequest,
+    SegmentHeaderResponse,
+};
+
+#[derive(Debug, Default)]
+struct InMemoryObjectMappingSource {
+    object_mappings: HashMap<Blake3Hash, GlobalObject>,
+}
+
+#[async_trait]
+impl ObjectMappingSource for InMemoryObjectMappingSource {
+    async fn get_object_mapping(
+        &self,
+        object_hash: Blake3Hash,
+    ) -> Result<Option<GlobalObject>, Box<dyn Error + Send + Sync + 'static>> {
+        Ok(self.object_mappings.get(&object_hash).copied())
+    }
+}
+
+/// Archive a block with a single object, returns archived segment, object and its mapping.
+fn archive_object(kzg: &Kzg) -> (NewArchivedSegment, Vec<u8>, Blake3Hash, GlobalObject) {
+    let mut archiver = Archiver::new(kzg.clone()).unwrap();
+
+    let mut object = vec![0u8; 1000];
+    thread_rng().fill(object.as_mut_slice());
+    let object_hash = blake3_hash(&object);
+    let offset = 100;
+
+    let mut block = vec![0u8; RecordedHistorySegment::SIZE];
+    thread_rng().fill(block.as_mut_slice());
+    let encoded_object = object.encode();
+    block[offset..][..encoded_object.len()].copy_from_slice(&encoded_object);
+    let block_object_mapping = BlockObjectMapping {
+        objects: vec![BlockObject::V0 {
+            hash: object_hash,
+            offset: offset as u32,
+        }],
+    };
+
+    let archived_segment = archiver
+        .add_block(block, block_object_mapping, true)
+        .into_iter()
+        .next()
+        .unwrap();
+
+    let segment_index = archived_segment.segment_header.segment_index();
+    let global_object = archived_segment
+        .object_mapping
+        .iter()
+        .enumerate()
+        .find_map(|(source_position, piece_object_mapping)| {
+            piece_object_mapping
+                .objects
+                .iter()
+                .find(|piece_object| piece_object.hash() == object_hash)
+                .map(|piece_object| GlobalObject::V0 {
+                    piece_index: segment_index.source_piece_index(source_position as u32),
+                    offset: piece_object.offset(),
+                })
+        })
+        .unwrap();
+
+    (archived_segment, object, object_hash, global_object)
+}
+
+/// Create node that serves provided pieces and segment headers, returns it with its address.
+async fn create_provider_node(
+    pieces: HashMap<PieceIndex, Piece>,
+    segment_headers: Vec<SegmentHeader>,
+) -> (Node, Multiaddr) {
+    let keypair = Keypair::generate_ed25519();
+    let piece_cache = TestPieceCache::new(keypair.public().to_peer_id());
+    piece_cache.insert_pieces(pieces);
+
+    let config = Config {
+        listen_on: vec!["/ip4/127.0.0.1/tcp/0".parse().unwrap()],
+        allow_non_global_addresses_in_dht: true,
+        kademlia_mode: KademliaMode::Static(Mode::Server),
+        request_response_protocols: vec![
+            PieceByIndexRequestHandler::create({
+                let piece_cache = piece_cache.clone();
+
+                move |_, &PieceByIndexRequest { piece_index }| {
+                    let piece = piece_cache.get_piece(piece_index);
+
+                    async move { Some(PieceByIndexResponse { piece }) }
+                }
+            }),
+            SegmentHeaderBySegmentIndexesRequestHandler::create(move |_, request| {
+                let segment_headers = match request {
+                    SegmentHeaderRequest::SegmentIndexes { segment_indexes } => segment_indexes
+                        .iter()
+                        .filter_map(|segment_index| {
+                            segment_headers.get(u64::from(*segment_index) as usize)
+                        })
+                        .copied()
+                        .collect(),
+                    SegmentHeaderRequest::LastSegmentHeaders {
+                        segment_header_number,
+                    } => segment_headers
+                        .iter()
+                        .rev()
+                        .take(*segment_header_number as usize)
+                        .rev()
+                        .copied()
+                        .collect(),
+                };
+
+                async move { Some(SegmentHeaderResponse { segment_headers }) }
+            }),
+        ],
+        ..Config::new("gateway-test".to_string(), keypair, piece_cache, None)
+    };
+
+    let (node, mut node_runner) = construct(config).unwrap();
+    tokio::spawn(async move {
+        node_runner.run().await;
+    });
+
+    let address = loop {
+        if let Some(address) = node.listeners().into_iter().next() {
+            break address.with(Protocol::P2p(node.id()));
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    };
+
+    (node, address)
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn serve_pieces_segment_headers_and_objects() {
+    let kzg = Kzg::new(embedded_kzg_settings());
+    let (archived_segment, object, object_hash, global_object) = archive_object(&kzg);
+    let segment_header = archived_segment.segment_header;
+
+    let first_piece_index = segment_header.segment_index().first_piece_index();
+    let pieces = archived_segment
+        .pieces
+        .iter()
+        .enumerate()
+        .map(|(position, piece)| {
+            (
+                first_piece_index + PieceIndex::from(position as u64),
+                Piece::from(piece),
+            )
+        })
+        .collect::<HashMap<_, _>>();
+    let expected_piece = pieces.get(&first_piece_index).unwrap().clone();
+
+    let (_provider_node, provider_address) =
+        create_provider_node(pieces, vec![segment_header]).await;
+
+    let (node, mut node_runner) = create_dsn_node(
+        Keypair::generate_ed25519(),
+        DsnOptions {
+            protocol_version: "gateway-test".to_string(),
+            bootstrap_nodes: vec![provider_address],
+            listen_on: vec!["/ip4/127.0.0.1/tcp/0".parse().unwrap()],
+            allow_private_ips: true,
+            external_addresses: Vec::new(),
+        },
+    )
+    .unwrap();
+    tokio::spawn(async move {
+        node_runner.run().await;
+    });
+    node.bootstrap().await.unwrap();
+
+    let segment_headers = SegmentHeaders::default();
+    segment_headers.add([segment_header]).unwrap();
+
+    let piece_getter = Arc::new(DsnPieceGetter::new(PieceProvider::new(
+        node.clone(),
+        Some(SegmentCommitmentPieceValidator::new(
+            node.clone(),
+            kzg,
+            segment_headers.clone(),
+        )),
+    )));
+    let state = GatewayState {
+        piece_getter: Arc::clone(&piece_getter),
+        segment_headers,
+        object_fetcher: ObjectFetcher::new(piece_getter, 2048),
+        object_mapping_source: Some(Box::new(InMemoryObjectMappingSource {
+            object_mappings: HashMap::from([(object_hash, global_object)]),
+        })),
+        cache: ResponseCache::new(1024 * 1024),
+    };
+
+    let app = test::init_service(
+        App::new()
+            .app_data(Data::new(state))
+            .service(piece)
+            .service(segment_header)
+            .service(object),
+    )
+    .await;
+
+    // Whole piece
+    let response = test::call_service(
+        &app,
+        test::TestRequest::get()
+            .uri(&format!("/piece/{first_piece_index}"))
+            .to_request(),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        test::read_body(response).await.as_ref(),
+        expected_piece.as_ref()
+    );
+
+    // Range of the piece, served from cache
+    let response = test::call_service(
+        &app,
+        test::TestRequest::get()
+            .uri(&format!("/piece/{first_piece_index}"))
+            .insert_header((header::RANGE, "bytes=10-19"))
+            .to_request(),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+    assert_eq!(
+        test::read_body(response).await.as_ref(),
+        &expected_piece.as_ref()[10..20]
+    );
+
+    let response = test::call_service(
+        &app,
+        test::TestRequest::get()
+            .uri(&format!("/piece/{first_piece_index}"))
+            .insert_header((header::RANGE, format!("bytes={}-", Piece::SIZE)))
+            .to_request(),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::RANGE_NOT_SATISFIABLE);
+
+    // Segment headers
+    let response = test::call_service(
+        &app,
+        test::TestRequest::get()
+            .uri("/segment-header/0")
+            .to_request(),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let received_segment_header: SegmentHeader = test::read_body_json(response).await;
+    assert_eq!(received_segment_header, segment_header);
+
+    let response = test::call_service(
+        &app,
+        test::TestRequest::get()
+            .uri("/segment-header/1")
+            .to_request(),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    // Objects
+    let response = test::call_service(
+        &app,
+        test::TestRequest::get()
+            .uri(&format!("/object/{}", hex::encode(object_hash)))
+            .to_request(),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(test::read_body(response).await.as_ref(), object.as_slice());
+
+    let response = test::call_service(
+        &app,
+        test::TestRequest::get()
+            .uri(&format!("/object/{}", hex::encode([1u8; 32])))
+            .to_request(),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let response = test::call_service(
+        &app,
+        test::TestRequest::get().uri("/object/not-hex").to_request(),
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
@@ -0,0 +1,170 @@
+//! HTTP gateway serving pieces, segment headers and objects from Subspace Network DSN
+
+mod cache;
+mod dsn;
+mod http;
+mod object_mappings;
+mod piece_getter;
+mod segment_headers;
+
+use crate::cache::ResponseCache;
+use crate::dsn::{create_dsn_node, DsnOptions};
+use crate::http::{start_http_server, GatewayState};
+use crate::object_mappings::{ObjectMappingSource, RpcObjectMappingSource};
+use crate::piece_getter::DsnPieceGetter;
+use crate::segment_headers::{sync_segment_headers, SegmentHeaders};
+use anyhow::anyhow;
+use clap::Parser;
+use futures::{select, FutureExt};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::sync::Arc;
+use subspace_core_primitives::crypto::kzg::{embedded_kzg_settings, Kzg};
+use subspace_networking::libp2p::identity::Keypair;
+use subspace_networking::libp2p::multiaddr::Protocol;
+use subspace_networking::libp2p::Multiaddr;
+use subspace_networking::utils::object_fetcher::{ObjectFetcher, MAX_OBJECT_SIZE};
+use subspace_networking::utils::piece_provider::PieceProvider;
+use subspace_networking::utils::piece_validator::SegmentCommitmentPieceValidator;
+use tracing::{info, Level};
+use tracing_subscriber::fmt::Subscriber;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::EnvFilter;
+
+/// Default size of in-memory response cache in bytes
+const DEFAULT_CACHE_SIZE: usize = 256 * 1024 * 1024;
+
+#[derive(Debug, Parser)]
+#[clap(about, version)]
+enum Command {
+    /// Start gateway
+    Run {
+        /// Multiaddresses of bootstrap nodes to connect to on startup, multiple are supported
+        #[arg(long, alias = "dsn-bootstrap-node", required = true)]
+        dsn_bootstrap_nodes: Vec<Multiaddr>,
+        /// Multiaddr to listen on for subspace networking, multiple are supported
+        #[arg(long, default_values_t = [
+            Multiaddr::from(IpAddr::V4(Ipv4Addr::UNSPECIFIED))
+                .with(Protocol::Tcp(0))
+        ])]
+        dsn_listen_on: Vec<Multiaddr>,
+        /// Determines whether we allow keeping non-global (private, shared, loopback..) addresses
+        /// in Kademlia DHT.
+        #[arg(long, default_value_t = false)]
+        dsn_allow_private_ips: bool,
+        /// Known external addresses
+        #[arg(long, alias = "dsn-external-address")]
+        dsn_external_addresses: Vec<Multiaddr>,
+        /// Protocol version for libp2p stack, should be set as genesis hash of the blockchain for
+        /// production use.
+        #[arg(long)]
+        protocol_version: String,
+        /// Endpoints for the HTTP server, multiple are supported. Format: 127.0.0.1:8080
+        #[arg(long, alias = "http-endpoint", default_values_t = [
+            SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 8080)
+        ])]
+        http_listen_on: Vec<SocketAddr>,
+        /// Size of in-memory cache of pieces and objects in bytes, 0 disables caching
+        #[arg(long, default_value_t = DEFAULT_CACHE_SIZE)]
+        cache_size: usize,
+        /// WebSocket RPC URL of the node with object mappings indexing enabled
+        /// (`--index-object-mappings`), objects are not served without it. Format:
+        /// ws://127.0.0.1:9944
+        #[arg(long)]
+        object_mappings_rpc: Option<String>,
+        /// Maximum size of the object in bytes, larger objects are not served
+        #[arg(long, default_value_t = MAX_OBJECT_SIZE)]
+        max_object_size: usize,
+    },
+}
+
+fn init_logging() {
+    // set default log to info if the RUST_LOG is not set.
+    let env_filter = EnvFilter::builder()
+        .with_default_directive(Level::INFO.into())
+        .from_env_lossy();
+
+    let builder = Subscriber::builder().with_env_filter(env_filter).finish();
+
+    builder.init()
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    init_logging();
+
+    let command: Command = Command::parse();
+
+    match command {
+        Command::Run {
+            dsn_bootstrap_nodes,
+            dsn_listen_on,
+            dsn_allow_private_ips,
+            dsn_external_addresses,
+            protocol_version,
+            http_listen_on,
+            cache_size,
+            object_mappings_rpc,
+            max_object_size,
+        } => {
+            let object_mapping_source = match object_mappings_rpc {
+                Some(url) => {
+                    let source = RpcObjectMappingSource::new(&url).await.map_err(|error| {
+                        anyhow!("Failed to connect to object mappings RPC {url}: {error}")
+                    })?;
+
+                    Some(Box::new(source) as Box<dyn ObjectMappingSource>)
+                }
+                None => {
+                    info!("Object mappings RPC is not specified, objects will not be served");
+
+                    None
+                }
+            };
+
+            let (node, mut node_runner) = create_dsn_node(
+                Keypair::generate_ed25519(),
+                DsnOptions {
+                    protocol_version,
+                    bootstrap_nodes: dsn_bootstrap_nodes,
+                    listen_on: dsn_listen_on,
+                    allow_private_ips: dsn_allow_private_ips,
+                    external_addresses: dsn_external_addresses,
+                },
+            )?;
+
+            let kzg = Kzg::new(embedded_kzg_settings());
+            let segment_headers = SegmentHeaders::default();
+            let piece_getter = Arc::new(DsnPieceGetter::new(PieceProvider::new(
+                node.clone(),
+                Some(SegmentCommitmentPieceValidator::new(
+                    node.clone(),
+                    kzg,
+                    segment_headers.clone(),
+                )),
+            )));
+
+            let state = GatewayState {
+                piece_getter: Arc::clone(&piece_getter),
+                segment_headers: segment_headers.clone(),
+                object_fetcher: ObjectFetcher::new(piece_getter, max_object_size),
+                object_mapping_source,
+                cache: ResponseCache::new(cache_size),
+            };
+            let http_server = start_http_server(http_listen_on, state)?;
+
+            info!("Subspace Gateway started");
+
+            select! {
+                _ = node_runner.run().fuse() => {
+                    info!("DSN node runner exited");
+                },
+                result = http_server.fuse() => {
+                    result?;
+                },
+                _ = sync_segment_headers(node, segment_headers).fuse() => {},
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,51 @@
+//! Sources of object mappings used to locate objects in archived history by their hash.
+
+use async_trait::async_trait;
+use jsonrpsee::core::client::ClientT;
+use jsonrpsee::rpc_params;
+use jsonrpsee::ws_client::{WsClient, WsClientBuilder};
+use std::error::Error;
+use std::fmt;
+use subspace_core_primitives::objects::GlobalObject;
+use subspace_core_primitives::Blake3Hash;
+
+/// Source of object mappings.
+#[async_trait]
+pub(crate) trait ObjectMappingSource: fmt::Debug + Send + Sync {
+    /// Find location of the object in archived history by its hash
+    async fn get_object_mapping(
+        &self,
+        object_hash: Blake3Hash,
+    ) -> Result<Option<GlobalObject>, Box<dyn Error + Send + Sync + 'static>>;
+}
+
+/// Object mappings from object mappings index of the node, retrieved over RPC.
+#[derive(Debug)]
+pub(crate) struct RpcObjectMappingSource {
+    client: WsClient,
+}
+
+impl RpcObjectMappingSource {
+    /// Connect to node RPC server, node must have object mappings indexing enabled
+    pub(crate) async fn new(url: &str) -> Result<Self, jsonrpsee::core::Error> {
+        let client = WsClientBuilder::default().build(url).await?;
+
+        Ok(Self { client })
+    }
+}
+
+#[async_trait]
+impl ObjectMappingSource for RpcObjectMappingSource {
+    async fn get_object_mapping(
+        &self,
+        object_hash: Blake3Hash,
+    ) -> Result<Option<GlobalObject>, Box<dyn Error + Send + Sync + 'static>> {
+        Ok(self
+            .client
+            .request(
+                "subspace_getObjectMapping",
+                rpc_params![format!("0x{}", hex::encode(object_hash))],
+            )
+            .await?)
+    }
+}
@@ -0,0 +1,56 @@
+//! Retrieval of pieces from DSN.
+
+use crate::segment_headers::SegmentHeaders;
+use async_trait::async_trait;
+use std::error::Error;
+use std::fmt;
+use subspace_core_primitives::{Piece, PieceIndex};
+use subspace_networking::utils::object_fetcher::ObjectPieceGetter;
+use subspace_networking::utils::piece_provider::PieceProvider;
+use subspace_networking::utils::piece_validator::SegmentCommitmentPieceValidator;
+use tracing::trace;
+
+/// Maximum number of random walking rounds when looking for piece in archival storage
+const MAX_RANDOM_WALK_ROUNDS: usize = 15;
+
+/// Retrieves validated pieces from DSN cache, falls back to archival storage.
+pub(crate) struct DsnPieceGetter {
+    piece_provider: PieceProvider<SegmentCommitmentPieceValidator<SegmentHeaders>>,
+}
+
+impl fmt::Debug for DsnPieceGetter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DsnPieceGetter").finish_non_exhaustive()
+    }
+}
+
+impl DsnPieceGetter {
+    pub(crate) fn new(
+        piece_provider: PieceProvider<SegmentCommitmentPieceValidator<SegmentHeaders>>,
+    ) -> Self {
+        Self { piece_provider }
+    }
+
+    /// Get piece by its index, `None` means piece was not found or was invalid
+    pub(crate) async fn get_piece(&self, piece_index: PieceIndex) -> Option<Piece> {
+        if let Some(piece) = self.piece_provider.get_piece_from_cache(piece_index).await {
+            trace!(%piece_index, "Got piece from DSN cache");
+
+            return Some(piece);
+        }
+
+        self.piece_provider
+            .get_piece_from_archival_storage(piece_index, MAX_RANDOM_WALK_ROUNDS)
+            .await
+    }
+}
+
+#[async_trait]
+impl ObjectPieceGetter for DsnPieceGetter {
+    async fn get_piece(
+        &self,
+        piece_index: PieceIndex,
+    ) -> Result<Option<Piece>, Box<dyn Error + Send + Sync + 'static>> {
+        Ok(DsnPieceGetter::get_piece(self, piece_index).await)
+    }
+}
@@ -0,0 +1,165 @@
+//! Segment headers known to the gateway, used for piece validation and served over HTTP.
+
+#[cfg(test)]
+mod tests;
+
+use async_trait::async_trait;
+use futures::StreamExt;
+use parking_lot::RwLock;
+use std::slice;
+use std::sync::Arc;
+use std::time::Duration;
+use subspace_core_primitives::{
+    verify_segment_headers_chain, verify_segment_headers_link, SegmentCommitment, SegmentHeader,
+    SegmentHeadersChainError, SegmentIndex,
+};
+use subspace_networking::utils::piece_validator::SegmentCommitmentSource;
+use subspace_networking::utils::segment_header_downloader::SegmentHeaderDownloader;
+use subspace_networking::{Node, SegmentHeaderAnnouncementError};
+use tracing::{debug, info, warn};
+
+/// Delay before retrying segment headers download after failure.
+const SEGMENT_HEADERS_SYNC_RETRY_DELAY: Duration = Duration::from_secs(10);
+
+/// In-memory collection of segment headers ordered from the first one to the last known.
+#[derive(Debug, Default, Clone)]
+pub(crate) struct SegmentHeaders {
+    segment_headers: Arc<RwLock<Vec<SegmentHeader>>>,
+}
+
+impl SegmentHeaders {
+    /// Get segment header by its index
+    pub(crate) fn get(&self, segment_index: SegmentIndex) -> Option<SegmentHeader> {
+        self.segment_headers
+            .read()
+            .get(u64::from(segment_index) as usize)
+            .copied()
+    }
+
+    /// Last known segment header
+    pub(crate) fn last(&self) -> Option<SegmentHeader> {
+        self.segment_headers.read().last().copied()
+    }
+
+    /// Add segment headers, those that are already known are ignored, the rest must form a chain
+    /// that extends the last known segment header (or starts with genesis segment header).
+    ///
+    /// Segment headers preceding the first one that fails verification are still added.
+    pub(crate) fn add(
+        &self,
+        segment_headers: impl IntoIterator<Item = SegmentHeader>,
+    ) -> Result<(), SegmentHeadersChainError> {
+        let mut known_segment_headers = self.segment_headers.write();
+
+        for segment_header in segment_headers {
+            if u64::from(segment_header.segment_index()) < known_segment_headers.len() as u64 {
+                continue;
+            }
+
+            match known_segment_headers.last() {
+                Some(last_segment_header) => {
+                    verify_segment_headers_link(last_segment_header, &segment_header)?;
+                }
+                None => {
+                    verify_segment_headers_chain(None, slice::from_ref(&segment_header))?;
+                }
+            }
+
+            known_segment_headers.push(segment_header);
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl SegmentCommitmentSource for SegmentHeaders {
+    async fn segment_commitment(&self, segment_index: SegmentIndex) -> Option<SegmentCommitment> {
+        self.get(segment_index)
+            .as_ref()
+            .map(SegmentHeader::segment_commitment)
+    }
+}
+
+/// Keep segment headers in sync with DSN.
+///
+/// Segment headers are downloaded initially and whenever announcements reveal that some segment
+/// headers are missing, newly archived segment headers are received through announcements.
+pub(crate) async fn sync_segment_headers(node: Node, segment_headers: SegmentHeaders) {
+    let segment_header_downloader = SegmentHeaderDownloader::new(&node);
+
+    loop {
+        let last_segment_index = segment_headers
+            .last()
+            .map(|segment_header| segment_header.segment_index())
+            .unwrap_or(SegmentIndex::ZERO);
+
+        match segment_header_downloader
+            .get_segment_headers(last_segment_index)
+            .await
+        {
+            Ok(new_segment_headers) => {
+                if let Err(error) = segment_headers.add(new_segment_headers) {
+                    warn!(
+                        ?error,
+                        "Downloaded segment headers failed verification, will retry"
+                    );
+
+                    tokio::time::sleep(SEGMENT_HEADERS_SYNC_RETRY_DELAY).await;
+                    continue;
+                }
+
+                if let Some(segment_header) = segment_headers.last() {
+                    info!(
+                        last_segment_index = %segment_header.segment_index(),
+                        "Segment headers synced"
+                    );
+                }
+            }
+            Err(error) => {
+                warn!(%error, "Failed to download segment headers, will retry");
+
+                tokio::time::sleep(SEGMENT_HEADERS_SYNC_RETRY_DELAY).await;
+                continue;
+            }
+        }
+
+        // Announcements are validated against the last downloaded segment header
+        let Some(last_segment_header) = segment_headers.last() else {
+            tokio::time::sleep(SEGMENT_HEADERS_SYNC_RETRY_DELAY).await;
+            continue;
+        };
+
+        let mut subscription = match node.subscribe_segment_headers(last_segment_header).await {
+            Ok(subscription) => subscription,
+            Err(error) => {
+                warn!(%error, "Failed to subscribe to segment header announcements");
+
+                tokio::time::sleep(SEGMENT_HEADERS_SYNC_RETRY_DELAY).await;
+                continue;
+            }
+        };
+
+        while let Some(result) = subscription.next().await {
+            match result {
+                Ok(segment_header) => {
+                    debug!(
+                        segment_index = %segment_header.segment_index(),
+                        "New segment header announced"
+                    );
+
+                    // Announcement was validated against the last known segment header already
+                    if let Err(error) = segment_headers.add([segment_header]) {
+                        warn!(?error, "Announced segment header failed verification");
+                        break;
+                    }
+                }
+                Err(SegmentHeaderAnnouncementError::MissingSegmentHeaders { expected, .. }) => {
+                    debug!(%expected, "Missing segment headers, downloading");
+
+                    break;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,74 @@
+use crate::segment_headers::SegmentHeaders;
+use subspace_core_primitives::{
+    ArchivedBlockProgress, Blake3Hash, LastArchivedBlock, SegmentCommitment, SegmentHeader,
+    SegmentHeadersChainError, SegmentIndex,
+};
+
+fn segment_header(segment_index: u64, prev_segment_header_hash: Blake3Hash) -> SegmentHeader {
+    SegmentHeader::V0 {
+        segment_index: SegmentIndex::from(segment_index),
+        segment_commitment: SegmentCommitment::default(),
+        prev_segment_header_hash,
+        last_archived_block: LastArchivedBlock {
+            number: segment_index as u32,
+            archived_progress: ArchivedBlockProgress::Complete,
+        },
+    }
+}
+
+#[test]
+fn add_segment_headers_chain() {
+    let segment_header_0 = segment_header(0, Blake3Hash::default());
+    let segment_header_1 = segment_header(1, segment_header_0.hash());
+    let segment_header_2 = segment_header(2, segment_header_1.hash());
+
+    let segment_headers = SegmentHeaders::default();
+    segment_headers
+        .add([segment_header_0, segment_header_1])
+        .unwrap();
+    assert_eq!(segment_headers.last(), Some(segment_header_1));
+
+    // Already known segment headers are ignored
+    segment_headers
+        .add([segment_header_0, segment_header_1, segment_header_2])
+        .unwrap();
+    assert_eq!(
+        segment_headers.get(SegmentIndex::ZERO),
+        Some(segment_header_0)
+    );
+    assert_eq!(
+        segment_headers.get(SegmentIndex::ONE),
+        Some(segment_header_1)
+    );
+    assert_eq!(segment_headers.last(), Some(segment_header_2));
+}
+
+#[test]
+fn reject_broken_segment_headers_chain() {
+    let segment_header_0 = segment_header(0, Blake3Hash::default());
+    let segment_header_1 = segment_header(1, segment_header_0.hash());
+
+    let segment_headers = SegmentHeaders::default();
+
+    // First segment header must be genesis segment header
+    assert!(segment_headers.add([segment_header_1]).is_err());
+    assert_eq!(segment_headers.last(), None);
+
+    // Segment header with correct index, but broken hash link
+    let forged_segment_header_1 = segment_header(1, Blake3Hash::default());
+    assert!(matches!(
+        segment_headers.add([segment_header_0, forged_segment_header_1, segment_header_1]),
+        Err(SegmentHeadersChainError::InvalidPrevSegmentHeaderHash { segment_index })
+            if segment_index == SegmentIndex::ONE
+    ));
+    // Valid segment headers preceding invalid one are still added
+    assert_eq!(segment_headers.last(), Some(segment_header_0));
+
+    // Gap in segment headers
+    let segment_header_2 = segment_header(2, segment_header_1.hash());
+    assert!(matches!(
+        segment_headers.add([segment_header_2]),
+        Err(SegmentHeadersChainError::UnexpectedSegmentIndex { .. })
+    ));
+    assert_eq!(segment_headers.last(), Some(segment_header_0));
+}
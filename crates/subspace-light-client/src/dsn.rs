@@ -0,0 +1,359 @@
+//! DSN client of the light client, used as a source of archived headers.
+
+#[cfg(test)]
+mod tests;
+
+use crate::source::{subspace_justification, HeaderSource, SourceHeader};
+use crate::storage::ParityDbStorage;
+use anyhow::anyhow;
+use async_trait::async_trait;
+use parity_scale_codec::Decode;
+use parking_lot::RwLock;
+use sp_lightclient::Storage;
+use sp_runtime::generic::SignedBlock;
+use sp_runtime::traits::Header as HeaderT;
+use std::slice;
+use std::sync::Arc;
+use std::time::Duration;
+use subspace_archiving::reconstructor::Reconstructor;
+use subspace_core_primitives::crypto::kzg::Kzg;
+use subspace_core_primitives::{
+    verify_segment_headers_chain, verify_segment_headers_link, Piece, PieceIndex,
+    SegmentCommitment, SegmentHeader, SegmentHeadersChainError, SegmentIndex,
+};
+use subspace_networking::libp2p::identity::Keypair;
+use subspace_networking::libp2p::kad::Mode;
+use subspace_networking::libp2p::Multiaddr;
+use subspace_networking::utils::piece_provider::{download_segment_pieces, PieceProvider};
+use subspace_networking::utils::piece_validator::{
+    SegmentCommitmentPieceValidator, SegmentCommitmentSource,
+};
+use subspace_networking::utils::segment_header_downloader::SegmentHeaderDownloader;
+use subspace_networking::{
+    construct, Config, CreationError, KademliaMode, Node, NodeRunner, PieceByIndexRequestHandler,
+    SegmentHeaderBySegmentIndexesRequestHandler,
+};
+use subspace_runtime_primitives::opaque::Block;
+use subspace_runtime_primitives::BlockNumber;
+use tracing::debug;
+
+/// Maximum number of random walking rounds when looking for piece in archival storage
+const MAX_RANDOM_WALK_ROUNDS: usize = 15;
+/// Interval between checks for newly archived segments once all known segments were processed
+const SEGMENT_HEADERS_POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+/// DSN options of the light client.
+#[derive(Debug, Clone)]
+pub(crate) struct DsnOptions {
+    /// Protocol version for libp2p stack, genesis hash of the blockchain
+    pub(crate) protocol_version: String,
+    /// Multiaddresses of bootstrap nodes
+    pub(crate) bootstrap_nodes: Vec<Multiaddr>,
+    /// Multiaddresses to listen on
+    pub(crate) listen_on: Vec<Multiaddr>,
+    /// Whether to allow non-global addresses in Kademlia DHT
+    pub(crate) allow_private_ips: bool,
+    /// Known external addresses
+    pub(crate) external_addresses: Vec<Multiaddr>,
+}
+
+/// Create DSN node that joins the network as a client.
+///
+/// Light client doesn't store anything, but request handlers still need to be registered in order
+/// to be able to send corresponding requests.
+pub(crate) fn create_dsn_node(
+    keypair: Keypair,
+    DsnOptions {
+        protocol_version,
+        bootstrap_nodes,
+        listen_on,
+        allow_private_ips,
+        external_addresses,
+    }: DsnOptions,
+) -> Result<(Node, NodeRunner<()>), CreationError> {
+    let config = Config {
+        listen_on,
+        bootstrap_addresses: bootstrap_nodes,
+        allow_non_global_addresses_in_dht: allow_private_ips,
+        external_addresses,
+        kademlia_mode: KademliaMode::Static(Mode::Client),
+        request_response_protocols: vec![
+            PieceByIndexRequestHandler::create(|_, _| async { None }),
+            SegmentHeaderBySegmentIndexesRequestHandler::create(|_, _| async { None }),
+        ],
+        ..Config::new(protocol_version, keypair, (), None)
+    };
+
+    construct(config)
+}
+
+/// In-memory collection of segment headers ordered from the first one to the last known.
+#[derive(Debug, Default, Clone)]
+struct SegmentHeaders {
+    segment_headers: Arc<RwLock<Vec<SegmentHeader>>>,
+}
+
+impl SegmentHeaders {
+    fn get(&self, segment_index: SegmentIndex) -> Option<SegmentHeader> {
+        self.segment_headers
+            .read()
+            .get(u64::from(segment_index) as usize)
+            .copied()
+    }
+
+    fn last(&self) -> Option<SegmentHeader> {
+        self.segment_headers.read().last().copied()
+    }
+
+    /// Index of the first segment that contains the beginning of the block with `block_number`
+    fn first_containing_block(&self, block_number: BlockNumber) -> Option<SegmentIndex> {
+        self.segment_headers
+            .read()
+            .iter()
+            .find(|segment_header| segment_header.last_archived_block().number >= block_number)
+            .map(SegmentHeader::segment_index)
+    }
+
+    /// Add segment headers, those that are already known are ignored, the rest must form a chain
+    /// that extends the last known segment header (or starts with genesis segment header).
+    ///
+    /// Segment headers preceding the first one that fails verification are still added. Returns
+    /// `true` if anything was added.
+    fn add(
+        &self,
+        segment_headers: impl IntoIterator<Item = SegmentHeader>,
+    ) -> Result<bool, SegmentHeadersChainError> {
+        let mut known_segment_headers = self.segment_headers.write();
+        let known_before = known_segment_headers.len();
+
+        for segment_header in segment_headers {
+            if u64::from(segment_header.segment_index()) < known_segment_headers.len() as u64 {
+                continue;
+            }
+
+            match known_segment_headers.last() {
+                Some(last_segment_header) => {
+                    verify_segment_headers_link(last_segment_header, &segment_header)?;
+                }
+                None => {
+                    verify_segment_headers_chain(None, slice::from_ref(&segment_header))?;
+                }
+            }
+
+            known_segment_headers.push(segment_header);
+        }
+
+        Ok(known_segment_headers.len() > known_before)
+    }
+}
+
+#[async_trait]
+impl SegmentCommitmentSource for SegmentHeaders {
+    async fn segment_commitment(&self, segment_index: SegmentIndex) -> Option<SegmentCommitment> {
+        self.get(segment_index)
+            .as_ref()
+            .map(SegmentHeader::segment_commitment)
+    }
+}
+
+/// Follows archived history in DSN.
+///
+/// Blocks are reconstructed from archived segments, hence only headers that are at least
+/// confirmation depth deep are available, and they trail the tip of the chain accordingly.
+pub(crate) struct DsnHeaderSource {
+    node: Node,
+    piece_provider: PieceProvider<SegmentCommitmentPieceValidator<SegmentHeaders>>,
+    segment_headers: SegmentHeaders,
+    reconstructor: Reconstructor,
+    maybe_next_segment_index: Option<SegmentIndex>,
+    /// Number of the last header returned, used to detect headers that failed to be imported
+    last_returned_number: BlockNumber,
+}
+
+impl DsnHeaderSource {
+    pub(crate) fn new(node: Node, kzg: Kzg) -> anyhow::Result<Self> {
+        let segment_headers = SegmentHeaders::default();
+        let piece_provider = PieceProvider::new(
+            node.clone(),
+            Some(SegmentCommitmentPieceValidator::new(
+                node.clone(),
+                kzg,
+                segment_headers.clone(),
+            )),
+        );
+
+        Ok(Self {
+            node,
+            piece_provider,
+            segment_headers,
+            reconstructor: Reconstructor::new().map_err(|error| anyhow!("{error}"))?,
+            maybe_next_segment_index: None,
+            last_returned_number: 0,
+        })
+    }
+
+    /// Download segment headers that are not known yet, waits for the next poll interval if there
+    /// are no new segment headers
+    async fn sync_segment_headers(&self) -> anyhow::Result<()> {
+        let last_segment_index = self
+            .segment_headers
+            .last()
+            .map(|segment_header| segment_header.segment_index())
+            .unwrap_or(SegmentIndex::ZERO);
+
+        let new_segment_headers = SegmentHeaderDownloader::new(&self.node)
+            .get_segment_headers(last_segment_index)
+            .await
+            .map_err(|error| anyhow!("Failed to download segment headers: {error}"))?;
+
+        let added = self
+            .segment_headers
+            .add(new_segment_headers)
+            .map_err(|error| {
+                anyhow!("Downloaded segment headers failed verification: {error:?}")
+            })?;
+
+        if added {
+            if let Some(segment_header) = self.segment_headers.last() {
+                debug!(
+                    last_segment_index = %segment_header.segment_index(),
+                    "Segment headers synced"
+                );
+            }
+        } else {
+            tokio::time::sleep(SEGMENT_HEADERS_POLL_INTERVAL).await;
+        }
+
+        Ok(())
+    }
+
+    async fn get_piece(&self, piece_index: PieceIndex) -> Option<Piece> {
+        if let Some(piece) = self.piece_provider.get_piece_from_cache(piece_index).await {
+            return Some(piece);
+        }
+
+        self.piece_provider
+            .get_piece_from_archival_storage(piece_index, MAX_RANDOM_WALK_ROUNDS)
+            .await
+    }
+
+    async fn reconstruct_segment(
+        &mut self,
+        segment_index: SegmentIndex,
+    ) -> anyhow::Result<Vec<(BlockNumber, Vec<u8>)>> {
+        let this = &*self;
+        let segment_pieces =
+            download_segment_pieces(segment_index, |piece_index| this.get_piece(piece_index)).await;
+
+        let reconstructed_contents = self
+            .reconstructor
+            .add_segment(&segment_pieces)
+            .map_err(|error| anyhow!("Failed to reconstruct segment {segment_index}: {error}"))?;
+
+        Ok(reconstructed_contents.blocks)
+    }
+}
+
+#[async_trait]
+impl HeaderSource for DsnHeaderSource {
+    async fn next_headers(
+        &mut self,
+        storage: &ParityDbStorage,
+    ) -> anyhow::Result<Vec<SourceHeader>> {
+        loop {
+            let best_number = *storage.best_header().header.number();
+
+            // Previously returned headers were not imported, start over from the best header
+            if best_number < self.last_returned_number {
+                self.maybe_next_segment_index.take();
+                self.last_returned_number = best_number;
+            }
+
+            let segment_index = match self.maybe_next_segment_index {
+                Some(segment_index) => segment_index,
+                None => match self.segment_headers.first_containing_block(best_number + 1) {
+                    Some(segment_index) => {
+                        // Blocks from previously reconstructed segments are not needed anymore
+                        self.reconstructor =
+                            Reconstructor::new().map_err(|error| anyhow!("{error}"))?;
+                        segment_index
+                    }
+                    None => {
+                        self.sync_segment_headers().await?;
+                        continue;
+                    }
+                },
+            };
+
+            if self.segment_headers.get(segment_index).is_none() {
+                self.sync_segment_headers().await?;
+                continue;
+            }
+
+            let blocks = match self.reconstruct_segment(segment_index).await {
+                Ok(blocks) => blocks,
+                Err(error) => {
+                    // Reconstruction will start from scratch next time
+                    self.maybe_next_segment_index.take();
+
+                    return Err(error);
+                }
+            };
+            self.maybe_next_segment_index
+                .replace(segment_index + SegmentIndex::ONE);
+
+            let mut headers = Vec::with_capacity(blocks.len());
+            for (block_number, block_bytes) in blocks {
+                if block_number <= best_number {
+                    continue;
+                }
+
+                let SignedBlock {
+                    block,
+                    justifications,
+                } = SignedBlock::<Block>::decode(&mut block_bytes.as_slice()).map_err(|error| {
+                    anyhow!("Failed to decode archived block #{block_number}: {error}")
+                })?;
+
+                let justification =
+                    subspace_justification(justifications.as_ref()).ok_or_else(|| {
+                        anyhow!(
+                            "Archived block #{block_number} doesn't have Subspace justification"
+                        )
+                    })??;
+
+                headers.push(SourceHeader {
+                    header: block.header,
+                    justification,
+                });
+            }
+
+            if let Some(last_header) = headers.last() {
+                self.last_returned_number = *last_header.header.number();
+
+                return Ok(headers);
+            }
+        }
+    }
+
+    async fn segment_headers(
+        &mut self,
+        segment_indices: Vec<SegmentIndex>,
+    ) -> anyhow::Result<Vec<SegmentHeader>> {
+        if segment_indices
+            .iter()
+            .any(|&segment_index| self.segment_headers.get(segment_index).is_none())
+        {
+            self.sync_segment_headers().await?;
+        }
+
+        segment_indices
+            .into_iter()
+            .map(|segment_index| {
+                self.segment_headers
+                    .get(segment_index)
+                    .ok_or_else(|| anyhow!("Segment header {segment_index} not found"))
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,132 @@
+use crate::dsn::SegmentHeaders;
+use subspace_core_primitives::{
+    ArchivedBlockProgress, Blake3Hash, LastArchivedBlock, SegmentCommitment, SegmentHeader,
+    SegmentHeadersChainError, SegmentIndex,
+};
+use subspace_networking::utils::piece_validator::SegmentCommitmentSource;
+
+fn segment_header(
+    segment_index: u64,
+    prev_segment_header_hash: Blake3Hash,
+    last_archived_block_number: u32,
+) -> SegmentHeader {
+    SegmentHeader::V0 {
+        segment_index: SegmentIndex::from(segment_index),
+        segment_commitment: SegmentCommitment::default(),
+        prev_segment_header_hash,
+        last_archived_block: LastArchivedBlock {
+            number: last_archived_block_number,
+            archived_progress: ArchivedBlockProgress::Complete,
+        },
+    }
+}
+
+#[test]
+fn add_segment_headers_chain() {
+    let segment_header_0 = segment_header(0, Blake3Hash::default(), 0);
+    let segment_header_1 = segment_header(1, segment_header_0.hash(), 10);
+    let segment_header_2 = segment_header(2, segment_header_1.hash(), 20);
+
+    let segment_headers = SegmentHeaders::default();
+    assert!(segment_headers
+        .add([segment_header_0, segment_header_1])
+        .unwrap());
+    assert_eq!(segment_headers.last(), Some(segment_header_1));
+
+    // Nothing new
+    assert!(!segment_headers
+        .add([segment_header_0, segment_header_1])
+        .unwrap());
+
+    // Already known segment headers are ignored
+    assert!(segment_headers
+        .add([segment_header_1, segment_header_2])
+        .unwrap());
+    assert_eq!(
+        segment_headers.get(SegmentIndex::ONE),
+        Some(segment_header_1)
+    );
+    assert_eq!(segment_headers.last(), Some(segment_header_2));
+}
+
+#[test]
+fn reject_broken_segment_headers_chain() {
+    let segment_header_0 = segment_header(0, Blake3Hash::default(), 0);
+    let segment_header_1 = segment_header(1, segment_header_0.hash(), 10);
+
+    let segment_headers = SegmentHeaders::default();
+
+    // First segment header must be genesis segment header
+    assert!(segment_headers.add([segment_header_1]).is_err());
+    assert_eq!(segment_headers.last(), None);
+
+    // Segment header with correct index, but broken hash link
+    let forged_segment_header_1 = segment_header(1, Blake3Hash::default(), 10);
+    assert!(matches!(
+        segment_headers.add([segment_header_0, forged_segment_header_1, segment_header_1]),
+        Err(SegmentHeadersChainError::InvalidPrevSegmentHeaderHash { segment_index })
+            if segment_index == SegmentIndex::ONE
+    ));
+    // Valid segment headers preceding invalid one are still added
+    assert_eq!(segment_headers.last(), Some(segment_header_0));
+
+    // Gap in segment headers
+    let segment_header_2 = segment_header(2, segment_header_1.hash(), 20);
+    assert!(matches!(
+        segment_headers.add([segment_header_2]),
+        Err(SegmentHeadersChainError::UnexpectedSegmentIndex { .. })
+    ));
+    assert_eq!(segment_headers.last(), Some(segment_header_0));
+}
+
+#[test]
+fn first_segment_containing_block() {
+    let segment_header_0 = segment_header(0, Blake3Hash::default(), 0);
+    let segment_header_1 = segment_header(1, segment_header_0.hash(), 10);
+    let segment_header_2 = segment_header(2, segment_header_1.hash(), 20);
+
+    let segment_headers = SegmentHeaders::default();
+    segment_headers
+        .add([segment_header_0, segment_header_1, segment_header_2])
+        .unwrap();
+
+    assert_eq!(
+        segment_headers.first_containing_block(0),
+        Some(SegmentIndex::ZERO)
+    );
+    assert_eq!(
+        segment_headers.first_containing_block(1),
+        Some(SegmentIndex::ONE)
+    );
+    assert_eq!(
+        segment_headers.first_containing_block(10),
+        Some(SegmentIndex::ONE)
+    );
+    assert_eq!(
+        segment_headers.first_containing_block(11),
+        Some(SegmentIndex::from(2))
+    );
+    // Not archived yet
+    assert_eq!(segment_headers.first_containing_block(21), None);
+}
+
+#[tokio::test]
+async fn segment_commitments_of_known_segment_headers() {
+    let segment_header_0 = segment_header(0, Blake3Hash::default(), 0);
+
+    let segment_headers = SegmentHeaders::default();
+    assert_eq!(
+        segment_headers.segment_commitment(SegmentIndex::ZERO).await,
+        None
+    );
+
+    segment_headers.add([segment_header_0]).unwrap();
+    assert_eq!(
+        segment_headers.segment_commitment(SegmentIndex::ZERO).await,
+        Some(segment_header_0.segment_commitment())
+    );
+    assert_eq!(
+        segment_headers.segment_commitment(SegmentIndex::ONE).await,
+        None
+    );
+}
@@ -0,0 +1,80 @@
+//! HTTP API of the light client.
+
+use crate::storage::ParityDbStorage;
+use actix_web::web::{Data, Path};
+use actix_web::{get, App, HttpResponse, HttpServer, Responder};
+use serde::Serialize;
+use sp_core::H256;
+use sp_lightclient::{HeaderExt, Storage};
+use sp_runtime::traits::Header as HeaderT;
+use std::future::Future;
+use std::net::SocketAddr;
+use std::str::FromStr;
+use subspace_runtime_primitives::opaque::Header;
+use subspace_runtime_primitives::BlockNumber;
+use tracing::{error, info};
+
+/// Verified header as returned by HTTP API.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct HeaderResponse {
+    hash: H256,
+    number: BlockNumber,
+    header: Header,
+}
+
+impl From<HeaderExt<Header>> for HeaderResponse {
+    fn from(header_ext: HeaderExt<Header>) -> Self {
+        Self {
+            hash: header_ext.header.hash(),
+            number: *header_ext.header.number(),
+            header: header_ext.header,
+        }
+    }
+}
+
+#[get("/best-header")]
+async fn best_header(storage: Data<ParityDbStorage>) -> impl Responder {
+    HttpResponse::Ok().json(HeaderResponse::from(storage.best_header()))
+}
+
+#[get("/finalized-header")]
+async fn finalized_header(storage: Data<ParityDbStorage>) -> impl Responder {
+    HttpResponse::Ok().json(HeaderResponse::from(storage.finalized_header()))
+}
+
+#[get("/header/{hash}")]
+async fn header(storage: Data<ParityDbStorage>, hash: Path<String>) -> impl Responder {
+    let Ok(hash) = H256::from_str(&hash.into_inner()) else {
+        return HttpResponse::BadRequest().body("Header hash must be 32 hex-encoded bytes");
+    };
+
+    match storage.header(hash) {
+        Some(header_ext) => HttpResponse::Ok().json(HeaderResponse::from(header_ext)),
+        None => HttpResponse::NotFound().body("Header not found"),
+    }
+}
+
+/// Start HTTP server on the provided addresses.
+pub(crate) fn start_http_server(
+    endpoints: Vec<SocketAddr>,
+    storage: ParityDbStorage,
+) -> std::io::Result<impl Future<Output = std::io::Result<()>>> {
+    let data = Data::new(storage);
+
+    let server = HttpServer::new(move || {
+        App::new()
+            .app_data(data.clone())
+            .service(best_header)
+            .service(finalized_header)
+            .service(header)
+    })
+    .bind(endpoints.as_slice())
+    .inspect_err(|error| {
+        error!(?error, "Failed to start HTTP server.");
+    })?;
+
+    info!(endpoints = ?server.addrs(), "HTTP server started.");
+
+    Ok(server.run())
+}
@@ -0,0 +1,220 @@
+//! Light client of Subspace Network.
+//!
+//! Follows consensus chain headers from the node RPC or archived history in DSN, verifies them
+//! with `sp-lightclient` and serves best and finalized headers over HTTP.
+
+mod dsn;
+mod http;
+mod rpc;
+mod source;
+mod storage;
+mod sync;
+
+use crate::dsn::{create_dsn_node, DsnHeaderSource, DsnOptions};
+use crate::http::start_http_server;
+use crate::rpc::{GenesisOptions, NodeRpcClient, RpcHeaderSource};
+use crate::storage::ParityDbStorage;
+use crate::sync::sync_headers;
+use anyhow::anyhow;
+use clap::Parser;
+use futures::{select, FutureExt};
+use sp_core::H256;
+use sp_lightclient::StorageBound;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::path::PathBuf;
+use subspace_core_primitives::crypto::kzg::{embedded_kzg_settings, Kzg};
+use subspace_networking::libp2p::identity::Keypair;
+use subspace_networking::libp2p::multiaddr::Protocol;
+use subspace_networking::libp2p::Multiaddr;
+use tracing::{info, Level};
+use tracing_subscriber::fmt::Subscriber;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::EnvFilter;
+
+#[derive(Debug, Parser)]
+#[clap(about, version)]
+enum Command {
+    /// Start light client
+    Run {
+        /// Directory where verified headers are stored
+        #[arg(long)]
+        base_path: PathBuf,
+        /// Hash of the genesis block of the chain to follow, node and existing storage that belong
+        /// to a different chain are rejected. Format: 0x1234...
+        #[arg(long)]
+        genesis_hash: H256,
+        /// WebSocket RPC URL of the node. It is used to initialize the light client on the first
+        /// start and to follow headers unless `--follow-dsn` is specified. Format:
+        /// ws://127.0.0.1:9944
+        #[arg(long)]
+        node_rpc_url: Option<String>,
+        /// Follow archived headers in DSN instead of node RPC. Archived headers trail the tip of
+        /// the chain by at least confirmation depth.
+        #[arg(long, default_value_t = false)]
+        follow_dsn: bool,
+        /// Multiaddresses of bootstrap nodes to connect to on startup, multiple are supported
+        #[arg(long, alias = "dsn-bootstrap-node")]
+        dsn_bootstrap_nodes: Vec<Multiaddr>,
+        /// Multiaddr to listen on for subspace networking, multiple are supported
+        #[arg(long, default_values_t = [
+            Multiaddr::from(IpAddr::V4(Ipv4Addr::UNSPECIFIED))
+                .with(Protocol::Tcp(0))
+        ])]
+        dsn_listen_on: Vec<Multiaddr>,
+        /// Determines whether we allow keeping non-global (private, shared, loopback..) addresses
+        /// in Kademlia DHT.
+        #[arg(long, default_value_t = false)]
+        dsn_allow_private_ips: bool,
+        /// Known external addresses
+        #[arg(long, alias = "dsn-external-address")]
+        dsn_external_addresses: Vec<Multiaddr>,
+        /// Protocol version for libp2p stack, defaults to genesis hash of the blockchain
+        #[arg(long)]
+        protocol_version: Option<String>,
+        /// Endpoints for the HTTP server, multiple are supported. Format: 127.0.0.1:8080
+        #[arg(long, alias = "http-endpoint", default_values_t = [
+            SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 8080)
+        ])]
+        http_listen_on: Vec<SocketAddr>,
+        /// External entropy used to derive genesis proof of time seed, only used on the first start
+        /// and ignored if chain spec of the node has explicit value
+        #[arg(long)]
+        pot_external_entropy: Option<String>,
+        /// Number of headers to keep beyond confirmation depth, all headers are kept by default,
+        /// only used on the first start
+        #[arg(long)]
+        headers_to_keep: Option<u32>,
+    },
+}
+
+fn init_logging() {
+    // set default log to info if the RUST_LOG is not set.
+    let env_filter = EnvFilter::builder()
+        .with_default_directive(Level::INFO.into())
+        .from_env_lossy();
+
+    let builder = Subscriber::builder().with_env_filter(env_filter).finish();
+
+    builder.init()
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    init_logging();
+
+    let command: Command = Command::parse();
+
+    match command {
+        Command::Run {
+            base_path,
+            genesis_hash,
+            node_rpc_url,
+            follow_dsn,
+            dsn_bootstrap_nodes,
+            dsn_listen_on,
+            dsn_allow_private_ips,
+            dsn_external_addresses,
+            protocol_version,
+            http_listen_on,
+            pot_external_entropy,
+            headers_to_keep,
+        } => {
+            let mut storage = ParityDbStorage::open(&base_path)
+                .map_err(|error| anyhow!("Failed to open storage at {base_path:?}: {error}"))?;
+
+            if let Some(stored_genesis_hash) = storage.genesis_hash() {
+                if stored_genesis_hash != genesis_hash {
+                    return Err(anyhow!(
+                        "Storage at {base_path:?} belongs to chain with genesis hash \
+                        {stored_genesis_hash:?}, expected {genesis_hash:?}"
+                    ));
+                }
+            }
+
+            let maybe_node_rpc_client =
+                match &node_rpc_url {
+                    Some(url) => Some(NodeRpcClient::new(url).await.map_err(|error| {
+                        anyhow!("Failed to connect to node RPC {url}: {error}")
+                    })?),
+                    None => None,
+                };
+
+            if let Some(node_rpc_client) = &maybe_node_rpc_client {
+                let node_genesis_hash = node_rpc_client.block_hash(0).await?;
+                if node_genesis_hash != Some(genesis_hash) {
+                    return Err(anyhow!(
+                        "Node belongs to chain with genesis hash {node_genesis_hash:?}, expected \
+                        {genesis_hash:?}"
+                    ));
+                }
+            }
+
+            if !storage.is_initialized() {
+                let node_rpc_client = maybe_node_rpc_client.as_ref().ok_or_else(|| {
+                    anyhow!("Node RPC URL is required to initialize the light client")
+                })?;
+
+                info!("Initializing light client from node RPC");
+
+                let genesis_state = node_rpc_client
+                    .genesis_state(GenesisOptions {
+                        genesis_hash,
+                        pot_external_entropy,
+                        storage_bound: match headers_to_keep {
+                            Some(headers_to_keep) => {
+                                StorageBound::NumberOfHeaderToKeepBeyondKDepth(headers_to_keep)
+                            }
+                            None => StorageBound::Unbounded,
+                        },
+                    })
+                    .await?;
+                storage.initialize(genesis_state)?;
+            }
+
+            let kzg = Kzg::new(embedded_kzg_settings());
+            let http_server = start_http_server(http_listen_on, storage.clone())?;
+
+            if follow_dsn {
+                let (node, mut node_runner) = create_dsn_node(
+                    Keypair::generate_ed25519(),
+                    DsnOptions {
+                        protocol_version: protocol_version
+                            .unwrap_or_else(|| hex::encode(genesis_hash)),
+                        bootstrap_nodes: dsn_bootstrap_nodes,
+                        listen_on: dsn_listen_on,
+                        allow_private_ips: dsn_allow_private_ips,
+                        external_addresses: dsn_external_addresses,
+                    },
+                )?;
+                let source = DsnHeaderSource::new(node, kzg.clone())?;
+
+                info!("Subspace light client started, following DSN");
+
+                select! {
+                    _ = node_runner.run().fuse() => {
+                        info!("DSN node runner exited");
+                    },
+                    result = http_server.fuse() => {
+                        result?;
+                    },
+                    _ = sync_headers(source, storage, kzg).fuse() => {},
+                }
+            } else {
+                let node_rpc_client = maybe_node_rpc_client
+                    .ok_or_else(|| anyhow!("Node RPC URL is required unless following DSN"))?;
+                let source = RpcHeaderSource::new(node_rpc_client);
+
+                info!("Subspace light client started, following node RPC");
+
+                select! {
+                    result = http_server.fuse() => {
+                        result?;
+                    },
+                    _ = sync_headers(source, storage, kzg).fuse() => {},
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,405 @@
+//! Node RPC client, used to initialize light client and as a source of headers.
+
+#[cfg(test)]
+mod tests;
+
+use crate::source::{subspace_justification, HeaderSource, SourceHeader};
+use crate::storage::{GenesisState, ParityDbStorage};
+use anyhow::anyhow;
+use async_trait::async_trait;
+use futures::StreamExt;
+use jsonrpsee::core::client::{ClientT, Subscription, SubscriptionClientT};
+use jsonrpsee::rpc_params;
+use jsonrpsee::ws_client::{WsClient, WsClientBuilder};
+use parity_scale_codec::Decode;
+use serde_json::{Map, Value};
+use sp_consensus_subspace::{
+    ChainConstants, FarmerPublicKey, PotEntropyInjectionParameters, PotParameters, SolutionRanges,
+};
+use sp_core::{Bytes, H256};
+use sp_lightclient::{HeaderExt, NextDigestItems, Storage, StorageBound};
+use sp_runtime::generic::SignedBlock;
+use sp_runtime::traits::Header as HeaderT;
+use std::collections::BTreeMap;
+use std::ops::RangeInclusive;
+use std::time::Duration;
+use subspace_core_primitives::{PotSeed, SegmentHeader, SegmentIndex};
+use subspace_runtime_primitives::opaque::{Block, Header};
+use subspace_runtime_primitives::BlockNumber;
+use tracing::{debug, warn};
+
+/// Maximum request/response size in bytes, large enough for any block
+const MAX_REQUEST_BODY_SIZE: u32 = 20 * 1024 * 1024;
+/// Headers further than this from the best header are downloaded by number along the canonical
+/// chain of the node rather than by walking back from the announced head
+const MAX_HEADERS_PER_BATCH: BlockNumber = 256;
+/// Delay before subscribing to new heads again after subscription failure
+const SUBSCRIPTION_RETRY_DELAY: Duration = Duration::from_secs(5);
+
+/// Light client parameters that are not available from the node and must be provided by the user.
+#[derive(Debug, Clone)]
+pub(crate) struct GenesisOptions {
+    /// Expected hash of the genesis block, node that belongs to a different chain is rejected
+    pub(crate) genesis_hash: H256,
+    /// External entropy used to derive genesis proof of time seed, ignored if chain spec of the
+    /// node has explicit value
+    pub(crate) pot_external_entropy: Option<String>,
+    /// Storage bound for the light client store
+    pub(crate) storage_bound: StorageBound<BlockNumber>,
+}
+
+/// Client of the node RPC server.
+#[derive(Debug)]
+pub(crate) struct NodeRpcClient {
+    client: WsClient,
+}
+
+impl NodeRpcClient {
+    /// Connect to node RPC server
+    pub(crate) async fn new(url: &str) -> Result<Self, jsonrpsee::core::Error> {
+        let client = WsClientBuilder::default()
+            .max_request_body_size(MAX_REQUEST_BODY_SIZE)
+            .build(url)
+            .await?;
+
+        Ok(Self { client })
+    }
+
+    /// Hash of the block at `number` in the canonical chain of the node
+    pub(crate) async fn block_hash(
+        &self,
+        number: BlockNumber,
+    ) -> Result<Option<H256>, jsonrpsee::core::Error> {
+        self.client
+            .request("chain_getBlockHash", rpc_params![number])
+            .await
+    }
+
+    /// Block with justifications by its hash
+    pub(crate) async fn block(
+        &self,
+        hash: H256,
+    ) -> Result<Option<SignedBlock<Block>>, jsonrpsee::core::Error> {
+        self.client
+            .request("chain_getBlock", rpc_params![hash])
+            .await
+    }
+
+    /// Subscribe to new best heads of the node
+    pub(crate) async fn subscribe_new_heads(
+        &self,
+    ) -> Result<Subscription<Header>, jsonrpsee::core::Error> {
+        self.client
+            .subscribe(
+                "chain_subscribeNewHeads",
+                rpc_params![],
+                "chain_unsubscribeNewHeads",
+            )
+            .await
+    }
+
+    /// Segment headers by their indices, `None` for segments that were not archived yet
+    pub(crate) async fn segment_headers(
+        &self,
+        segment_indices: Vec<SegmentIndex>,
+    ) -> Result<Vec<Option<SegmentHeader>>, jsonrpsee::core::Error> {
+        self.client
+            .request("subspace_segmentHeaders", rpc_params![segment_indices])
+            .await
+    }
+
+    /// Call runtime API method without arguments at block `at`
+    async fn runtime_call<T>(&self, method: &str, at: H256) -> anyhow::Result<T>
+    where
+        T: Decode,
+    {
+        let result = self
+            .client
+            .request::<Bytes, _>("state_call", rpc_params![method, Bytes(Vec::new()), at])
+            .await?;
+
+        T::decode(&mut result.as_ref())
+            .map_err(|error| anyhow!("Failed to decode result of {method}: {error}"))
+    }
+
+    /// Retrieve genesis state of the light client from the node.
+    ///
+    /// Genesis block must match expected genesis hash, after which the node is trusted to report
+    /// correct runtime constants at genesis, everything after genesis is verified by the light
+    /// client itself.
+    pub(crate) async fn genesis_state(
+        &self,
+        options: GenesisOptions,
+    ) -> anyhow::Result<GenesisState> {
+        let GenesisOptions {
+            genesis_hash,
+            pot_external_entropy,
+            storage_bound,
+        } = options;
+
+        let genesis_block = self
+            .block(genesis_hash)
+            .await?
+            .ok_or_else(|| anyhow!("Node doesn't have genesis block {genesis_hash:?}"))?;
+        // Hash is computed locally, node can't substitute genesis block of a different chain
+        if genesis_block.block.header.hash() != genesis_hash
+            || *genesis_block.block.header.number() != 0
+        {
+            return Err(anyhow!(
+                "Block returned by the node is not genesis block {genesis_hash:?}"
+            ));
+        }
+
+        let chain_constants = self
+            .runtime_call::<ChainConstants>("SubspaceApi_chain_constants", genesis_hash)
+            .await?;
+        let solution_ranges = self
+            .runtime_call::<SolutionRanges>("SubspaceApi_solution_ranges", genesis_hash)
+            .await?;
+        let pot_parameters = self
+            .runtime_call::<PotParameters>("SubspaceApi_pot_parameters", genesis_hash)
+            .await?;
+        let should_adjust_solution_range = self
+            .runtime_call::<bool>("SubspaceApi_should_adjust_solution_range", genesis_hash)
+            .await?;
+        let maybe_root_plot_public_key = self
+            .runtime_call::<Option<FarmerPublicKey>>(
+                "SubspaceApi_root_plot_public_key",
+                genesis_hash,
+            )
+            .await?;
+        let max_pieces_in_sector = self
+            .runtime_call::<u16>("SubspaceApi_max_pieces_in_sector", genesis_hash)
+            .await?;
+        let pot_entropy_injection_parameters = self
+            .runtime_call::<PotEntropyInjectionParameters<BlockNumber>>(
+                "SubspaceApi_pot_entropy_injection_parameters",
+                genesis_hash,
+            )
+            .await?;
+
+        let genesis_segment_header = self
+            .segment_headers(vec![SegmentIndex::ZERO])
+            .await?
+            .into_iter()
+            .next()
+            .flatten()
+            .ok_or_else(|| anyhow!("Node doesn't have genesis segment header"))?;
+
+        let pot_external_entropy = self
+            .client
+            .request::<Map<String, Value>, _>("system_properties", rpc_params![])
+            .await?
+            .get("potExternalEntropy")
+            .and_then(|value| value.as_str().map(str::to_string))
+            .or(pot_external_entropy)
+            .unwrap_or_default();
+
+        Ok(GenesisState {
+            chain_constants: sp_lightclient::ChainConstants {
+                k_depth: chain_constants.confirmation_depth_k(),
+                genesis_digest_items: NextDigestItems::new(
+                    solution_ranges.next.unwrap_or(solution_ranges.current),
+                ),
+                genesis_segment_commitments: BTreeMap::from([(
+                    SegmentIndex::ZERO,
+                    genesis_segment_header.segment_commitment(),
+                )]),
+                genesis_pot_seed: PotSeed::from_genesis(
+                    genesis_hash.as_ref(),
+                    pot_external_entropy.as_bytes(),
+                ),
+                genesis_pot_slot_iterations: pot_parameters.slot_iterations(),
+                block_authoring_delay: chain_constants.block_authoring_delay(),
+                pot_entropy_injection_interval: pot_entropy_injection_parameters.interval(),
+                pot_entropy_injection_lookback_depth: pot_entropy_injection_parameters
+                    .lookback_depth(),
+                pot_entropy_injection_delay: pot_entropy_injection_parameters.delay(),
+                era_duration: chain_constants.era_duration(),
+                slot_probability: chain_constants.slot_probability(),
+                storage_bound,
+                recent_segments: chain_constants.recent_segments(),
+                recent_history_fraction: chain_constants.recent_history_fraction(),
+                min_sector_lifetime: chain_constants.min_sector_lifetime(),
+            },
+            genesis_header: HeaderExt {
+                header: genesis_block.block.header,
+                total_weight: 0,
+                era_start_slot: Default::default(),
+                should_adjust_solution_range,
+                maybe_current_solution_range_override: None,
+                maybe_next_solution_range_override: None,
+                maybe_root_plot_public_key,
+                recent_proofs_of_time: Default::default(),
+                pot_entropy: Default::default(),
+            },
+            max_pieces_in_sector,
+        })
+    }
+}
+
+/// Follows best chain of the node over RPC.
+///
+/// Headers are imported as the node announces new heads, forks are followed by walking back from
+/// the announced head to the first known ancestor. When far behind, headers are downloaded by
+/// number along the canonical chain of the node instead.
+#[derive(Debug)]
+pub(crate) struct RpcHeaderSource {
+    client: NodeRpcClient,
+    maybe_new_heads: Option<Subscription<Header>>,
+    maybe_latest_head: Option<Header>,
+}
+
+impl RpcHeaderSource {
+    pub(crate) fn new(client: NodeRpcClient) -> Self {
+        Self {
+            client,
+            maybe_new_heads: None,
+            maybe_latest_head: None,
+        }
+    }
+
+    async fn source_header(&self, hash: H256) -> anyhow::Result<SourceHeader> {
+        let SignedBlock {
+            block,
+            justifications,
+        } = self
+            .client
+            .block(hash)
+            .await?
+            .ok_or_else(|| anyhow!("Block {hash} not found"))?;
+
+        let justification = subspace_justification(justifications.as_ref())
+            .ok_or_else(|| anyhow!("Block {hash} doesn't have Subspace justification"))??;
+
+        Ok(SourceHeader {
+            header: block.header,
+            justification,
+        })
+    }
+
+    async fn canonical_headers(
+        &self,
+        numbers: RangeInclusive<BlockNumber>,
+    ) -> anyhow::Result<Vec<SourceHeader>> {
+        let mut headers = Vec::with_capacity(numbers.clone().count());
+
+        for number in numbers {
+            let hash = self
+                .client
+                .block_hash(number)
+                .await?
+                .ok_or_else(|| anyhow!("Block #{number} not found"))?;
+
+            headers.push(self.source_header(hash).await?);
+        }
+
+        Ok(headers)
+    }
+
+    /// Headers from the first unknown ancestor of `head` up to `head` itself
+    async fn unknown_ancestry(
+        &self,
+        head: &Header,
+        storage: &ParityDbStorage,
+    ) -> anyhow::Result<Vec<SourceHeader>> {
+        let finalized_number = *storage.finalized_header().header.number();
+        let mut headers = Vec::new();
+        let mut hash = head.hash();
+        let mut number = *head.number();
+
+        // Headers at or below finalized number can't be imported anyway
+        while number > finalized_number && storage.header(hash).is_none() {
+            let source_header = self.source_header(hash).await?;
+            hash = *source_header.header.parent_hash();
+            number = number.saturating_sub(1);
+            headers.push(source_header);
+        }
+
+        headers.reverse();
+        Ok(headers)
+    }
+
+    async fn next_head(&mut self) -> Header {
+        loop {
+            if self.maybe_new_heads.is_none() {
+                match self.client.subscribe_new_heads().await {
+                    Ok(new_heads) => {
+                        self.maybe_new_heads.replace(new_heads);
+                    }
+                    Err(error) => {
+                        warn!(%error, "Failed to subscribe to new heads, will retry");
+
+                        tokio::time::sleep(SUBSCRIPTION_RETRY_DELAY).await;
+                        continue;
+                    }
+                }
+            }
+            let new_heads = self
+                .maybe_new_heads
+                .as_mut()
+                .expect("Subscribed above if there was no subscription; qed");
+
+            match new_heads.next().await {
+                Some(Ok(header)) => {
+                    return header;
+                }
+                Some(Err(error)) => {
+                    warn!(%error, "Failed to decode new head");
+                }
+                None => {
+                    debug!("New heads subscription ended, resubscribing");
+
+                    self.maybe_new_heads.take();
+                    tokio::time::sleep(SUBSCRIPTION_RETRY_DELAY).await;
+                }
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl HeaderSource for RpcHeaderSource {
+    async fn next_headers(
+        &mut self,
+        storage: &ParityDbStorage,
+    ) -> anyhow::Result<Vec<SourceHeader>> {
+        loop {
+            if let Some(head) = self.maybe_latest_head.take() {
+                let best_number = *storage.best_header().header.number();
+
+                if *head.number() > best_number.saturating_add(MAX_HEADERS_PER_BATCH) {
+                    let numbers = best_number + 1..=best_number + MAX_HEADERS_PER_BATCH;
+                    // Keep the head around until light client catches up with it
+                    self.maybe_latest_head.replace(head);
+
+                    return self.canonical_headers(numbers).await;
+                }
+
+                let headers = self.unknown_ancestry(&head, storage).await?;
+                if !headers.is_empty() {
+                    return Ok(headers);
+                }
+            }
+
+            let head = self.next_head().await;
+            self.maybe_latest_head.replace(head);
+        }
+    }
+
+    async fn segment_headers(
+        &mut self,
+        segment_indices: Vec<SegmentIndex>,
+    ) -> anyhow::Result<Vec<SegmentHeader>> {
+        self.client
+            .segment_headers(segment_indices.clone())
+            .await?
+            .into_iter()
+            .zip(segment_indices)
+            .map(|(maybe_segment_header, segment_index)| {
+                maybe_segment_header
+                    .ok_or_else(|| anyhow!("Segment header {segment_index} not found"))
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,368 @@
+use crate::rpc::{GenesisOptions, NodeRpcClient, RpcHeaderSource};
+use crate::source::HeaderSource;
+use crate::storage::ParityDbStorage;
+use jsonrpsee::server::{ServerBuilder, ServerHandle};
+use jsonrpsee::RpcModule;
+use parity_scale_codec::Encode;
+use serde_json::{Map, Value};
+use sp_consensus_slots::{Slot, SlotDuration};
+use sp_consensus_subspace::{
+    ChainConstants, FarmerPublicKey, PotEntropyInjectionParameters, PotParameters, SolutionRanges,
+    SubspaceJustification,
+};
+use sp_core::{Bytes, H256};
+use sp_lightclient::{NextDigestItems, StorageBound};
+use sp_runtime::generic::SignedBlock;
+use sp_runtime::traits::Header as HeaderT;
+use sp_runtime::{Digest, Justifications};
+use std::num::{NonZeroU32, NonZeroU64};
+use std::sync::Arc;
+use subspace_core_primitives::{
+    ArchivedBlockProgress, HistorySize, LastArchivedBlock, PotSeed, SegmentCommitment,
+    SegmentHeader, SegmentIndex,
+};
+use subspace_runtime_primitives::opaque::{Block, Header};
+use subspace_runtime_primitives::BlockNumber;
+use tempfile::TempDir;
+
+const CONFIRMATION_DEPTH_K: BlockNumber = 100;
+const MAX_PIECES_IN_SECTOR: u16 = 1000;
+const POT_ENTROPY_INJECTION_INTERVAL: BlockNumber = 50;
+const POT_ENTROPY_INJECTION_LOOKBACK_DEPTH: u8 = 2;
+const POT_ENTROPY_INJECTION_DELAY: u64 = 15;
+
+/// Canonical chain of the node, starting with genesis block
+fn blocks(length: BlockNumber) -> Vec<SignedBlock<Block>> {
+    let mut blocks = Vec::<SignedBlock<Block>>::new();
+    for number in 0..length {
+        let parent_hash = blocks
+            .last()
+            .map(|block| block.block.header.hash())
+            .unwrap_or_default();
+        let justifications = (number > 0).then(|| {
+            Justifications::from(
+                SubspaceJustification::PotCheckpoints {
+                    seed: PotSeed::default(),
+                    checkpoints: Vec::new(),
+                }
+                .into(),
+            )
+        });
+
+        blocks.push(SignedBlock {
+            block: Block {
+                header: Header::new(
+                    number,
+                    Default::default(),
+                    Default::default(),
+                    parent_hash,
+                    Digest::default(),
+                ),
+                extrinsics: Vec::new(),
+            },
+            justifications,
+        });
+    }
+
+    blocks
+}
+
+fn genesis_segment_header() -> SegmentHeader {
+    SegmentHeader::V0 {
+        segment_index: SegmentIndex::ZERO,
+        segment_commitment: SegmentCommitment::from([1; SegmentCommitment::SIZE]),
+        prev_segment_header_hash: Default::default(),
+        last_archived_block: LastArchivedBlock {
+            number: 0,
+            archived_progress: ArchivedBlockProgress::Complete,
+        },
+    }
+}
+
+/// Runtime API responses of the node, SCALE-encoded
+fn runtime_call(method: &str) -> Option<Vec<u8>> {
+    let result = match method {
+        "SubspaceApi_chain_constants" => ChainConstants::V0 {
+            confirmation_depth_k: CONFIRMATION_DEPTH_K,
+            block_authoring_delay: Slot::from(4),
+            era_duration: 2016,
+            slot_probability: (1, 6),
+            slot_duration: SlotDuration::from_millis(1000),
+            recent_segments: HistorySize::new(NonZeroU64::new(5).unwrap()),
+            recent_history_fraction: (
+                HistorySize::new(NonZeroU64::new(1).unwrap()),
+                HistorySize::new(NonZeroU64::new(10).unwrap()),
+            ),
+            min_sector_lifetime: HistorySize::new(NonZeroU64::new(4).unwrap()),
+        }
+        .encode(),
+        "SubspaceApi_solution_ranges" => SolutionRanges {
+            current: u64::MAX,
+            next: Some(u64::MAX / 2),
+            voting_current: u64::MAX,
+            voting_next: None,
+        }
+        .encode(),
+        "SubspaceApi_pot_parameters" => PotParameters::V0 {
+            slot_iterations: NonZeroU32::new(16).unwrap(),
+            next_change: None,
+        }
+        .encode(),
+        "SubspaceApi_should_adjust_solution_range" => false.encode(),
+        "SubspaceApi_root_plot_public_key" => None::<FarmerPublicKey>.encode(),
+        "SubspaceApi_max_pieces_in_sector" => MAX_PIECES_IN_SECTOR.encode(),
+        "SubspaceApi_pot_entropy_injection_parameters" => PotEntropyInjectionParameters::V0 {
+            interval: POT_ENTROPY_INJECTION_INTERVAL,
+            lookback_depth: POT_ENTROPY_INJECTION_LOOKBACK_DEPTH,
+            delay: Slot::from(POT_ENTROPY_INJECTION_DELAY),
+        }
+        .encode(),
+        _ => {
+            return None;
+        }
+    };
+
+    Some(result)
+}
+
+/// Start RPC server that mimics the node with canonical chain made of `blocks`, the last block is
+/// announced as the best block to new heads subscribers
+async fn start_mock_node(
+    blocks: Vec<SignedBlock<Block>>,
+    pot_external_entropy: Option<&'static str>,
+) -> (String, ServerHandle) {
+    let blocks = Arc::new(blocks);
+    let mut module = RpcModule::new(());
+
+    module
+        .register_method("chain_getBlockHash", {
+            let blocks = Arc::clone(&blocks);
+
+            move |params, _| {
+                let number = params.one::<BlockNumber>()?;
+
+                Ok(blocks
+                    .get(number as usize)
+                    .map(|block| block.block.header.hash()))
+            }
+        })
+        .unwrap();
+    module
+        .register_method("chain_getBlock", {
+            let blocks = Arc::clone(&blocks);
+
+            move |params, _| {
+                let hash = params.one::<H256>()?;
+
+                Ok(blocks
+                    .iter()
+                    .find(|block| block.block.header.hash() == hash)
+                    .cloned())
+            }
+        })
+        .unwrap();
+    module
+        .register_method("state_call", |params, _| {
+            let (method, _data, _at) = params.parse::<(String, Bytes, H256)>()?;
+
+            runtime_call(&method).map(Bytes).ok_or_else(|| {
+                jsonrpsee::core::Error::Custom(format!("Unknown runtime API method {method}"))
+            })
+        })
+        .unwrap();
+    module
+        .register_method("subspace_segmentHeaders", |params, _| {
+            let segment_indices = params.one::<Vec<SegmentIndex>>()?;
+
+            Ok(segment_indices
+                .into_iter()
+                .map(|segment_index| {
+                    (segment_index == SegmentIndex::ZERO).then(genesis_segment_header)
+                })
+                .collect::<Vec<_>>())
+        })
+        .unwrap();
+    module
+        .register_method("system_properties", move |_, _| {
+            let mut properties = Map::new();
+            if let Some(pot_external_entropy) = pot_external_entropy {
+                properties.insert(
+                    "potExternalEntropy".to_string(),
+                    Value::from(pot_external_entropy),
+                );
+            }
+
+            Ok(properties)
+        })
+        .unwrap();
+    module
+        .register_subscription(
+            "chain_subscribeNewHeads",
+            "chain_newHead",
+            "chain_unsubscribeNewHeads",
+            {
+                let best_header = blocks.last().unwrap().block.header.clone();
+
+                move |_, mut sink, _| {
+                    let best_header = best_header.clone();
+
+                    tokio::spawn(async move {
+                        sink.pipe_from_stream(futures::stream::iter([best_header]))
+                            .await;
+                    });
+
+                    Ok(())
+                }
+            },
+        )
+        .unwrap();
+
+    let server = ServerBuilder::default().build("127.0.0.1:0").await.unwrap();
+    let address = server.local_addr().unwrap();
+    let handle = server.start(module).unwrap();
+
+    (format!("ws://{address}"), handle)
+}
+
+fn genesis_options(genesis_hash: H256, pot_external_entropy: Option<&str>) -> GenesisOptions {
+    GenesisOptions {
+        genesis_hash,
+        pot_external_entropy: pot_external_entropy.map(str::to_string),
+        storage_bound: StorageBound::Unbounded,
+    }
+}
+
+#[tokio::test]
+async fn genesis_state_from_node() {
+    let blocks = blocks(1);
+    let genesis_header = blocks[0].block.header.clone();
+    let (url, _server) = start_mock_node(blocks, Some("node entropy")).await;
+
+    let client = NodeRpcClient::new(&url).await.unwrap();
+    let genesis_state = client
+        .genesis_state(genesis_options(genesis_header.hash(), Some("user entropy")))
+        .await
+        .unwrap();
+
+    assert_eq!(genesis_state.genesis_header.header, genesis_header);
+    assert_eq!(genesis_state.max_pieces_in_sector, MAX_PIECES_IN_SECTOR);
+
+    let chain_constants = &genesis_state.chain_constants;
+    assert_eq!(chain_constants.k_depth, CONFIRMATION_DEPTH_K);
+    assert_eq!(
+        chain_constants.genesis_digest_items.encode(),
+        NextDigestItems::new(u64::MAX / 2).encode()
+    );
+    assert_eq!(
+        chain_constants
+            .genesis_segment_commitments
+            .get(&SegmentIndex::ZERO),
+        Some(&genesis_segment_header().segment_commitment())
+    );
+    assert_eq!(
+        chain_constants.genesis_pot_slot_iterations,
+        NonZeroU32::new(16).unwrap()
+    );
+    // Explicit value in chain spec of the node takes precedence
+    assert_eq!(
+        chain_constants.genesis_pot_seed,
+        PotSeed::from_genesis(genesis_header.hash().as_ref(), b"node entropy")
+    );
+    assert_eq!(
+        chain_constants.pot_entropy_injection_interval,
+        POT_ENTROPY_INJECTION_INTERVAL
+    );
+    assert_eq!(
+        chain_constants.pot_entropy_injection_lookback_depth,
+        POT_ENTROPY_INJECTION_LOOKBACK_DEPTH
+    );
+    assert_eq!(
+        chain_constants.pot_entropy_injection_delay,
+        Slot::from(POT_ENTROPY_INJECTION_DELAY)
+    );
+}
+
+#[tokio::test]
+async fn genesis_state_of_different_chain_is_rejected() {
+    let blocks = blocks(2);
+    let other_block_hash = blocks[1].block.header.hash();
+    let (url, _server) = start_mock_node(blocks, None).await;
+
+    let client = NodeRpcClient::new(&url).await.unwrap();
+    // Node doesn't know such block
+    assert!(client
+        .genesis_state(genesis_options(H256::repeat_byte(1), None))
+        .await
+        .is_err());
+    // Block is known, but it is not genesis
+    assert!(client
+        .genesis_state(genesis_options(other_block_hash, None))
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn genesis_state_uses_provided_entropy() {
+    let blocks = blocks(1);
+    let genesis_hash = blocks[0].block.header.hash();
+    let (url, _server) = start_mock_node(blocks, None).await;
+
+    let client = NodeRpcClient::new(&url).await.unwrap();
+    let genesis_state = client
+        .genesis_state(genesis_options(genesis_hash, Some("user entropy")))
+        .await
+        .unwrap();
+
+    assert_eq!(
+        genesis_state.chain_constants.genesis_pot_seed,
+        PotSeed::from_genesis(genesis_hash.as_ref(), b"user entropy")
+    );
+}
+
+#[tokio::test]
+async fn headers_follow_best_chain_of_node() {
+    let blocks = blocks(3);
+    let genesis_hash = blocks[0].block.header.hash();
+    let expected_headers = blocks[1..]
+        .iter()
+        .map(|block| block.block.header.clone())
+        .collect::<Vec<_>>();
+    let (url, _server) = start_mock_node(blocks, None).await;
+
+    let directory = TempDir::new().unwrap();
+    let mut storage = ParityDbStorage::open(directory.path()).unwrap();
+    let client = NodeRpcClient::new(&url).await.unwrap();
+    storage
+        .initialize(
+            client
+                .genesis_state(genesis_options(genesis_hash, None))
+                .await
+                .unwrap(),
+        )
+        .unwrap();
+
+    let mut source = RpcHeaderSource::new(client);
+
+    // Everything between the genesis and announced best header is returned in order
+    let headers = source
+        .next_headers(&storage)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|source_header| source_header.header)
+        .collect::<Vec<_>>();
+    assert_eq!(headers, expected_headers);
+
+    assert_eq!(
+        source
+            .segment_headers(vec![SegmentIndex::ZERO])
+            .await
+            .unwrap(),
+        vec![genesis_segment_header()]
+    );
+    // Segments that were not archived yet are an error
+    assert!(source
+        .segment_headers(vec![SegmentIndex::ZERO, SegmentIndex::ONE])
+        .await
+        .is_err());
+}
@@ -0,0 +1,46 @@
+//! Sources of headers to be verified and imported by the light client.
+
+use crate::storage::ParityDbStorage;
+use async_trait::async_trait;
+use sp_consensus_subspace::SubspaceJustification;
+use sp_runtime::Justifications;
+use subspace_core_primitives::{SegmentHeader, SegmentIndex};
+use subspace_runtime_primitives::opaque::Header;
+
+/// Header along with justification that is necessary to import it.
+#[derive(Debug, Clone)]
+pub(crate) struct SourceHeader {
+    pub(crate) header: Header,
+    pub(crate) justification: SubspaceJustification,
+}
+
+/// Source of headers.
+///
+/// Nothing returned by the source is trusted, headers are verified on import and segment headers
+/// are checked to form a chain with already known segment commitments.
+#[async_trait]
+pub(crate) trait HeaderSource: Send {
+    /// Wait for and return the next headers that extend headers already known to `storage`, in the
+    /// order they are supposed to be imported
+    async fn next_headers(
+        &mut self,
+        storage: &ParityDbStorage,
+    ) -> anyhow::Result<Vec<SourceHeader>>;
+
+    /// Get segment headers by their indices, returned in the same order as requested
+    async fn segment_headers(
+        &mut self,
+        segment_indices: Vec<SegmentIndex>,
+    ) -> anyhow::Result<Vec<SegmentHeader>>;
+}
+
+/// Extract Subspace justification from block justifications.
+///
+/// `None` means there is no Subspace justification.
+pub(crate) fn subspace_justification(
+    justifications: Option<&Justifications>,
+) -> Option<Result<SubspaceJustification, parity_scale_codec::Error>> {
+    justifications?
+        .iter()
+        .find_map(SubspaceJustification::try_from_justification)
+}
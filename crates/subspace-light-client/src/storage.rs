@@ -0,0 +1,327 @@
+//! Persistent storage of verified headers on top of ParityDB.
+
+#[cfg(test)]
+pub(crate) mod tests;
+
+use parity_db::{ColId, Db, Options};
+use parity_scale_codec::{Decode, Encode};
+use sp_lightclient::{ChainConstants, HeaderExt, Storage};
+use sp_runtime::traits::Header as HeaderT;
+use std::collections::BTreeMap;
+use std::mem;
+use std::path::Path;
+use std::sync::Arc;
+use subspace_core_primitives::{SegmentCommitment, SegmentIndex};
+use subspace_runtime_primitives::opaque::Header;
+use subspace_runtime_primitives::BlockNumber;
+
+/// Extended headers by header hash
+const COLUMN_HEADERS: ColId = 0;
+/// Hashes of headers by header number
+const COLUMN_HASHES_BY_NUMBER: ColId = 1;
+/// Segment commitments by segment index
+const COLUMN_SEGMENT_COMMITMENTS: ColId = 2;
+/// Chain constants, best and finalized header hashes and other singletons
+const COLUMN_META: ColId = 3;
+const NUM_COLUMNS: u8 = 4;
+
+const KEY_CHAIN_CONSTANTS: &[u8] = b"chain_constants";
+const KEY_MAX_PIECES_IN_SECTOR: &[u8] = b"max_pieces_in_sector";
+const KEY_GENESIS_HASH: &[u8] = b"genesis_hash";
+const KEY_BEST_HEADER: &[u8] = b"best_header";
+const KEY_FINALIZED_HEADER: &[u8] = b"finalized_header";
+const KEY_NUMBER_OF_SEGMENTS: &[u8] = b"number_of_segments";
+
+type HeaderHash = <Header as HeaderT>::Hash;
+
+/// Everything storage needs to be initialized with before the first header can be imported.
+#[derive(Debug, Clone)]
+pub(crate) struct GenesisState {
+    /// Chain constants of the light client
+    pub(crate) chain_constants: ChainConstants<Header>,
+    /// Genesis header, it is trusted and considered both best and finalized
+    pub(crate) genesis_header: HeaderExt<Header>,
+    /// How many pieces one sector is supposed to contain (max)
+    pub(crate) max_pieces_in_sector: u16,
+}
+
+/// Light client storage backed by ParityDB.
+///
+/// Instances are cheap to clone and share the same database, which allows serving headers while
+/// they are being imported. [`Storage`] methods panic on database errors since they have no way to
+/// report them and light client can't continue without its storage anyway.
+///
+/// Changes are buffered in the instance that made them (and visible to it) until
+/// [`Self::commit()`] is called, such that all changes caused by import of a header are written
+/// atomically. Buffered changes are not shared with clones.
+pub(crate) struct ParityDbStorage {
+    db: Arc<Db>,
+    pending_changes: BTreeMap<(ColId, Vec<u8>), Option<Vec<u8>>>,
+}
+
+impl Clone for ParityDbStorage {
+    fn clone(&self) -> Self {
+        Self {
+            db: Arc::clone(&self.db),
+            pending_changes: BTreeMap::new(),
+        }
+    }
+}
+
+impl std::fmt::Debug for ParityDbStorage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ParityDbStorage").finish_non_exhaustive()
+    }
+}
+
+impl ParityDbStorage {
+    /// Open storage at `path`, creating it if it doesn't exist yet
+    pub(crate) fn open(path: &Path) -> Result<Self, parity_db::Error> {
+        let options = Options::with_columns(path, NUM_COLUMNS);
+        let db = Db::open_or_create(&options)?;
+
+        Ok(Self {
+            db: Arc::new(db),
+            pending_changes: BTreeMap::new(),
+        })
+    }
+
+    /// Whether storage was initialized with [`Self::initialize`] already
+    pub(crate) fn is_initialized(&self) -> bool {
+        self.get::<ChainConstants<Header>>(COLUMN_META, KEY_CHAIN_CONSTANTS)
+            .is_some()
+    }
+
+    /// Initialize empty storage with genesis state
+    pub(crate) fn initialize(
+        &mut self,
+        genesis_state: GenesisState,
+    ) -> Result<(), parity_db::Error> {
+        let GenesisState {
+            chain_constants,
+            genesis_header,
+            max_pieces_in_sector,
+        } = genesis_state;
+        let genesis_hash = genesis_header.header.hash();
+        self.store_header(genesis_header, true);
+        self.finalize_header(genesis_hash);
+        self.store_segment_commitments(chain_constants.genesis_segment_commitments.clone());
+        self.write(vec![
+            (
+                COLUMN_META,
+                KEY_GENESIS_HASH.to_vec(),
+                Some(genesis_hash.encode()),
+            ),
+            (
+                COLUMN_META,
+                KEY_MAX_PIECES_IN_SECTOR.to_vec(),
+                Some(max_pieces_in_sector.encode()),
+            ),
+            (
+                COLUMN_META,
+                KEY_CHAIN_CONSTANTS.to_vec(),
+                Some(chain_constants.encode()),
+            ),
+        ]);
+
+        // Everything is written at once, such that interrupted initialization is not mistaken for
+        // a complete one
+        self.try_commit()
+    }
+
+    /// Write changes buffered since the last commit to the database atomically
+    pub(crate) fn commit(&mut self) {
+        self.try_commit()
+            .unwrap_or_else(|error| panic!("Failed to write to light client database: {error}"));
+    }
+
+    /// Drop changes buffered since the last commit, for instance after failed header import
+    pub(crate) fn discard(&mut self) {
+        self.pending_changes.clear();
+    }
+
+    fn try_commit(&mut self) -> Result<(), parity_db::Error> {
+        let pending_changes = mem::take(&mut self.pending_changes);
+        if pending_changes.is_empty() {
+            return Ok(());
+        }
+
+        self.db.commit(
+            pending_changes
+                .into_iter()
+                .map(|((column, key), value)| (column, key, value)),
+        )
+    }
+
+    /// Hash of the genesis header, `None` if storage is not initialized yet
+    pub(crate) fn genesis_hash(&self) -> Option<HeaderHash> {
+        self.get(COLUMN_META, KEY_GENESIS_HASH)
+    }
+
+    fn get<T>(&self, column: ColId, key: &[u8]) -> Option<T>
+    where
+        T: Decode,
+    {
+        let maybe_bytes = match self.pending_changes.get(&(column, key.to_vec())) {
+            Some(maybe_bytes) => maybe_bytes.clone(),
+            None => self.db.get(column, key).unwrap_or_else(|error| {
+                panic!("Failed to read from light client database: {error}")
+            }),
+        };
+
+        maybe_bytes.map(|bytes| {
+            T::decode(&mut bytes.as_slice()).unwrap_or_else(|error| {
+                panic!("Light client database is corrupted: {error}");
+            })
+        })
+    }
+
+    fn write(&mut self, changes: Vec<(ColId, Vec<u8>, Option<Vec<u8>>)>) {
+        self.pending_changes.extend(
+            changes
+                .into_iter()
+                .map(|(column, key, value)| ((column, key), value)),
+        );
+    }
+
+    fn hashes_at_number(&self, number: BlockNumber) -> Vec<HeaderHash> {
+        self.get(COLUMN_HASHES_BY_NUMBER, &number.to_be_bytes())
+            .unwrap_or_default()
+    }
+
+    fn header_by_meta_key(&self, key: &[u8]) -> HeaderExt<Header> {
+        let hash = self
+            .get::<HeaderHash>(COLUMN_META, key)
+            .expect("Storage is initialized before use; qed");
+
+        self.header(hash)
+            .expect("Best and finalized headers are never pruned; qed")
+    }
+}
+
+impl Storage<Header> for ParityDbStorage {
+    fn chain_constants(&self) -> ChainConstants<Header> {
+        self.get(COLUMN_META, KEY_CHAIN_CONSTANTS)
+            .expect("Storage is initialized before use; qed")
+    }
+
+    fn header(&self, hash: HeaderHash) -> Option<HeaderExt<Header>> {
+        self.get(COLUMN_HEADERS, hash.as_ref())
+    }
+
+    fn store_header(&mut self, header_ext: HeaderExt<Header>, as_best_header: bool) {
+        let number = *header_ext.header.number();
+        let hash = header_ext.header.hash();
+
+        let mut changes = Vec::with_capacity(3);
+
+        let mut hashes = self.hashes_at_number(number);
+        if !hashes.contains(&hash) {
+            hashes.push(hash);
+            changes.push((
+                COLUMN_HASHES_BY_NUMBER,
+                number.to_be_bytes().to_vec(),
+                Some(hashes.encode()),
+            ));
+        }
+        changes.push((
+            COLUMN_HEADERS,
+            hash.as_ref().to_vec(),
+            Some(header_ext.encode()),
+        ));
+        if as_best_header {
+            changes.push((COLUMN_META, KEY_BEST_HEADER.to_vec(), Some(hash.encode())));
+        }
+
+        self.write(changes);
+    }
+
+    fn best_header(&self) -> HeaderExt<Header> {
+        self.header_by_meta_key(KEY_BEST_HEADER)
+    }
+
+    fn headers_at_number(&self, number: BlockNumber) -> Vec<HeaderExt<Header>> {
+        self.hashes_at_number(number)
+            .into_iter()
+            .filter_map(|hash| self.header(hash))
+            .collect()
+    }
+
+    fn prune_header(&mut self, hash: HeaderHash) {
+        let Some(header_ext) = self.header(hash) else {
+            return;
+        };
+        let number = *header_ext.header.number();
+
+        let mut hashes = self.hashes_at_number(number);
+        hashes.retain(|known_hash| known_hash != &hash);
+
+        self.write(vec![
+            (COLUMN_HEADERS, hash.as_ref().to_vec(), None),
+            (
+                COLUMN_HASHES_BY_NUMBER,
+                number.to_be_bytes().to_vec(),
+                (!hashes.is_empty()).then(|| hashes.encode()),
+            ),
+        ]);
+    }
+
+    fn finalize_header(&mut self, hash: HeaderHash) {
+        self.write(vec![(
+            COLUMN_META,
+            KEY_FINALIZED_HEADER.to_vec(),
+            Some(hash.encode()),
+        )]);
+    }
+
+    fn finalized_header(&self) -> HeaderExt<Header> {
+        self.header_by_meta_key(KEY_FINALIZED_HEADER)
+    }
+
+    fn store_segment_commitments(
+        &mut self,
+        segment_commitments: BTreeMap<SegmentIndex, SegmentCommitment>,
+    ) {
+        if segment_commitments.is_empty() {
+            return;
+        }
+
+        let mut number_of_segments = self.number_of_segments();
+        let mut changes = Vec::with_capacity(segment_commitments.len() + 1);
+
+        for (segment_index, segment_commitment) in segment_commitments {
+            if self.segment_commitment(segment_index).is_none() {
+                number_of_segments += 1;
+            }
+            changes.push((
+                COLUMN_SEGMENT_COMMITMENTS,
+                u64::from(segment_index).to_be_bytes().to_vec(),
+                Some(segment_commitment.encode()),
+            ));
+        }
+        changes.push((
+            COLUMN_META,
+            KEY_NUMBER_OF_SEGMENTS.to_vec(),
+            Some(number_of_segments.encode()),
+        ));
+
+        self.write(changes);
+    }
+
+    fn segment_commitment(&self, segment_index: SegmentIndex) -> Option<SegmentCommitment> {
+        self.get(
+            COLUMN_SEGMENT_COMMITMENTS,
+            &u64::from(segment_index).to_be_bytes(),
+        )
+    }
+
+    fn number_of_segments(&self) -> u64 {
+        self.get(COLUMN_META, KEY_NUMBER_OF_SEGMENTS)
+            .unwrap_or_default()
+    }
+
+    fn max_pieces_in_sector(&self) -> u16 {
+        self.get(COLUMN_META, KEY_MAX_PIECES_IN_SECTOR)
+            .expect("Storage is initialized before use; qed")
+    }
+}
@@ -0,0 +1,172 @@
+use crate::storage::{GenesisState, ParityDbStorage};
+use sp_lightclient::{ChainConstants, HeaderExt, NextDigestItems, Storage, StorageBound};
+use sp_runtime::traits::Header as HeaderT;
+use sp_runtime::Digest;
+use std::collections::BTreeMap;
+use std::num::{NonZeroU32, NonZeroU64};
+use subspace_core_primitives::{HistorySize, PotSeed, SegmentCommitment, SegmentIndex};
+use subspace_runtime_primitives::opaque::Header;
+use subspace_runtime_primitives::BlockNumber;
+use tempfile::TempDir;
+
+const MAX_PIECES_IN_SECTOR: u16 = 1000;
+
+pub(crate) fn genesis_state() -> GenesisState {
+    GenesisState {
+        chain_constants: ChainConstants {
+            k_depth: 7,
+            genesis_digest_items: NextDigestItems::new(u64::MAX),
+            genesis_segment_commitments: BTreeMap::from([(
+                SegmentIndex::ZERO,
+                SegmentCommitment::default(),
+            )]),
+            genesis_pot_seed: PotSeed::default(),
+            genesis_pot_slot_iterations: NonZeroU32::new(16).unwrap(),
+            block_authoring_delay: 4.into(),
+            pot_entropy_injection_interval: 50,
+            pot_entropy_injection_lookback_depth: 2,
+            pot_entropy_injection_delay: 15.into(),
+            era_duration: 2016,
+            slot_probability: (1, 6),
+            storage_bound: StorageBound::Unbounded,
+            recent_segments: HistorySize::new(NonZeroU64::new(5).unwrap()),
+            recent_history_fraction: (
+                HistorySize::new(NonZeroU64::new(1).unwrap()),
+                HistorySize::new(NonZeroU64::new(10).unwrap()),
+            ),
+            min_sector_lifetime: HistorySize::new(NonZeroU64::new(4).unwrap()),
+        },
+        genesis_header: header_ext(0, Default::default(), 0),
+        max_pieces_in_sector: MAX_PIECES_IN_SECTOR,
+    }
+}
+
+fn header_ext(
+    number: BlockNumber,
+    parent_hash: <Header as HeaderT>::Hash,
+    fork: u8,
+) -> HeaderExt<Header> {
+    HeaderExt {
+        header: Header::new(
+            number,
+            Default::default(),
+            [fork; 32].into(),
+            parent_hash,
+            Digest::default(),
+        ),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn initialization_is_persisted() {
+    let directory = TempDir::new().unwrap();
+
+    let genesis_hash = {
+        let mut storage = ParityDbStorage::open(directory.path()).unwrap();
+        assert!(!storage.is_initialized());
+        assert_eq!(storage.genesis_hash(), None);
+
+        let genesis_state = genesis_state();
+        let genesis_hash = genesis_state.genesis_header.header.hash();
+        storage.initialize(genesis_state).unwrap();
+
+        genesis_hash
+    };
+
+    let storage = ParityDbStorage::open(directory.path()).unwrap();
+    assert!(storage.is_initialized());
+    assert_eq!(storage.genesis_hash(), Some(genesis_hash));
+    assert_eq!(storage.best_header().header.hash(), genesis_hash);
+    assert_eq!(storage.finalized_header().header.hash(), genesis_hash);
+    assert_eq!(storage.chain_constants().k_depth, 7);
+    assert_eq!(storage.max_pieces_in_sector(), MAX_PIECES_IN_SECTOR);
+    assert_eq!(storage.number_of_segments(), 1);
+    assert_eq!(
+        storage.segment_commitment(SegmentIndex::ZERO),
+        Some(SegmentCommitment::default())
+    );
+}
+
+#[test]
+fn headers_are_stored_and_pruned() {
+    let directory = TempDir::new().unwrap();
+    let mut storage = ParityDbStorage::open(directory.path()).unwrap();
+    let genesis_state = genesis_state();
+    let genesis_hash = genesis_state.genesis_header.header.hash();
+    storage.initialize(genesis_state).unwrap();
+
+    let header_1 = header_ext(1, genesis_hash, 0);
+    let fork_header_1 = header_ext(1, genesis_hash, 1);
+    storage.store_header(header_1.clone(), true);
+    storage.store_header(fork_header_1.clone(), false);
+    // Storing the same header again doesn't duplicate it
+    storage.store_header(fork_header_1.clone(), false);
+
+    assert_eq!(storage.best_header(), header_1);
+    assert_eq!(storage.headers_at_number(1).len(), 2);
+
+    storage.finalize_header(header_1.header.hash());
+    storage.prune_header(fork_header_1.header.hash());
+
+    assert_eq!(storage.finalized_header(), header_1);
+    assert_eq!(storage.header(fork_header_1.header.hash()), None);
+    assert_eq!(storage.headers_at_number(1), vec![header_1.clone()]);
+
+    storage.prune_header(header_1.header.hash());
+    assert!(storage.headers_at_number(1).is_empty());
+}
+
+#[test]
+fn segment_commitments_are_counted_once() {
+    let directory = TempDir::new().unwrap();
+    let mut storage = ParityDbStorage::open(directory.path()).unwrap();
+    storage.initialize(genesis_state()).unwrap();
+
+    let segment_commitments = BTreeMap::from([
+        (SegmentIndex::ZERO, SegmentCommitment::default()),
+        (SegmentIndex::ONE, SegmentCommitment::default()),
+    ]);
+    storage.store_segment_commitments(segment_commitments.clone());
+    storage.store_segment_commitments(segment_commitments);
+
+    assert_eq!(storage.number_of_segments(), 2);
+    assert_eq!(
+        storage.segment_commitment(SegmentIndex::ONE),
+        Some(SegmentCommitment::default())
+    );
+    assert_eq!(storage.segment_commitment(SegmentIndex::from(2)), None);
+}
+
+#[test]
+fn changes_are_buffered_until_commit() {
+    let directory = TempDir::new().unwrap();
+    let mut storage = ParityDbStorage::open(directory.path()).unwrap();
+    let genesis_state = genesis_state();
+    let genesis_hash = genesis_state.genesis_header.header.hash();
+    storage.initialize(genesis_state).unwrap();
+    let other_storage = storage.clone();
+
+    let header_1 = header_ext(1, genesis_hash, 0);
+    storage.store_header(header_1.clone(), true);
+
+    // Buffered changes are only visible to the instance that made them
+    assert_eq!(storage.best_header(), header_1);
+    assert_eq!(other_storage.best_header().header.hash(), genesis_hash);
+    assert_eq!(other_storage.header(header_1.header.hash()), None);
+
+    storage.discard();
+    assert_eq!(storage.best_header().header.hash(), genesis_hash);
+    assert_eq!(storage.header(header_1.header.hash()), None);
+
+    storage.store_header(header_1.clone(), true);
+    storage.finalize_header(header_1.header.hash());
+    storage.commit();
+    assert_eq!(other_storage.best_header(), header_1);
+    assert_eq!(other_storage.finalized_header(), header_1);
+
+    // Committed changes are persisted
+    drop((storage, other_storage));
+    let storage = ParityDbStorage::open(directory.path()).unwrap();
+    assert_eq!(storage.best_header(), header_1);
+}
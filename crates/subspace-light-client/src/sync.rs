@@ -0,0 +1,200 @@
+//! Following headers from the source, verifying and importing them into storage.
+
+#[cfg(test)]
+mod tests;
+
+use crate::source::{HeaderSource, SourceHeader};
+use crate::storage::ParityDbStorage;
+use anyhow::anyhow;
+use sp_consensus_subspace::{KzgExtension, PosExtension};
+use sp_lightclient::{ChainNotification, HeaderImporter, ImportError, Storage};
+use sp_runtime::traits::Header as HeaderT;
+use sp_state_machine::BasicExternalities;
+use std::collections::BTreeMap;
+use std::time::Duration;
+use subspace_core_primitives::crypto::kzg::Kzg;
+use subspace_core_primitives::{verify_segment_headers_chain, SegmentIndex};
+use subspace_proof_of_space::chia::ChiaTable;
+use subspace_rpc_primitives::MAX_SEGMENT_HEADERS_PER_REQUEST;
+use subspace_runtime_primitives::opaque::Header;
+use tokio::task;
+use tracing::{debug, info, warn};
+
+type PosTable = ChiaTable;
+
+/// Delay before retrying after headers failed to be retrieved or imported
+const RETRY_DELAY: Duration = Duration::from_secs(10);
+
+/// Keep importing headers from the source into storage.
+///
+/// Headers that fail verification are skipped, light client will retry with whatever source
+/// provides next time.
+pub(crate) async fn sync_headers<HS>(mut source: HS, storage: ParityDbStorage, kzg: Kzg)
+where
+    HS: HeaderSource,
+{
+    let mut importer = HeaderImporter::new(storage.clone());
+    importer.add_notification_listener(|notification| {
+        if let ChainNotification::BestHeader {
+            hash,
+            number,
+            retracted,
+            ..
+        } = notification
+        {
+            if !retracted.is_empty() {
+                info!(
+                    %number,
+                    %hash,
+                    retracted = %retracted.len(),
+                    "Best chain reorganized"
+                );
+            }
+        }
+    });
+    // Solution verification is a runtime interface and needs corresponding extensions
+    let mut externalities = BasicExternalities::new_empty();
+    externalities.extensions().register(KzgExtension::new(kzg));
+    externalities
+        .extensions()
+        .register(PosExtension::new::<PosTable>());
+
+    loop {
+        let headers = match source.next_headers(&storage).await {
+            Ok(headers) => headers,
+            Err(error) => {
+                warn!(%error, "Failed to retrieve headers, will retry");
+
+                tokio::time::sleep(RETRY_DELAY).await;
+                continue;
+            }
+        };
+
+        for SourceHeader {
+            header,
+            justification,
+        } in headers
+        {
+            let number = *header.number();
+            let hash = header.hash();
+
+            let mut import = |importer: &mut HeaderImporter<Header, ParityDbStorage>| {
+                task::block_in_place(|| {
+                    externalities.execute_with(|| {
+                        importer.import_header(header.clone(), justification.clone())
+                    })
+                })
+            };
+
+            let mut result = import(&mut importer);
+            if let Err(ImportError::MissingSegmentCommitment(segment_index)) = result {
+                importer.store_mut().discard();
+
+                match fetch_segment_commitments(&mut source, storage.clone(), segment_index).await {
+                    Ok(()) => {
+                        result = import(&mut importer);
+                    }
+                    Err(error) => {
+                        warn!(%error, %segment_index, "Failed to fetch segment commitments");
+                    }
+                }
+            }
+
+            // All changes caused by header import are written at once, nothing is written if
+            // import failed half-way
+            if result.is_ok() {
+                importer.store_mut().commit();
+            } else {
+                importer.store_mut().discard();
+            }
+
+            match result {
+                Ok(()) => {
+                    debug!(%number, %hash, "Header imported");
+                }
+                Err(ImportError::HeaderAlreadyImported) => {
+                    debug!(%number, %hash, "Header was already imported");
+                }
+                Err(error) => {
+                    warn!(?error, %number, %hash, "Failed to import header, will retry");
+
+                    tokio::time::sleep(RETRY_DELAY).await;
+                    // Following headers depend on this one and can't be imported either
+                    break;
+                }
+            }
+        }
+
+        let best_header = storage.best_header();
+        let finalized_header = storage.finalized_header();
+        info!(
+            best_number = %best_header.header.number(),
+            best_hash = %best_header.header.hash(),
+            finalized_number = %finalized_header.header.number(),
+            "Headers imported"
+        );
+    }
+}
+
+/// Fetch segment commitments of segments that are not known yet up to `segment_index` (inclusive).
+///
+/// Segment commitments normally come from digests of finalized headers, but solutions can refer to
+/// segments archived in blocks that are not finalized from light client's point of view yet.
+/// Fetched segment headers must form a chain with the last known segment commitment.
+async fn fetch_segment_commitments<HS>(
+    source: &mut HS,
+    mut storage: ParityDbStorage,
+    segment_index: SegmentIndex,
+) -> anyhow::Result<()>
+where
+    HS: HeaderSource,
+{
+    let number_of_segments = storage.number_of_segments();
+    if u64::from(segment_index) < number_of_segments {
+        return Err(anyhow!(
+            "Segment commitment {segment_index} is missing, but {number_of_segments} segments are \
+            known"
+        ));
+    }
+    // Last known segment header is fetched too in order to anchor new segment headers to known
+    // segment commitment
+    let first_segment_index = SegmentIndex::from(number_of_segments.saturating_sub(1));
+
+    let segment_indices = (first_segment_index..=segment_index).collect::<Vec<_>>();
+    let mut segment_headers = Vec::with_capacity(segment_indices.len());
+    for segment_indices in segment_indices.chunks(MAX_SEGMENT_HEADERS_PER_REQUEST) {
+        segment_headers.extend(source.segment_headers(segment_indices.to_vec()).await?);
+    }
+
+    let (maybe_anchor, new_segment_headers) = if number_of_segments > 0 {
+        let (anchor, new_segment_headers) = segment_headers
+            .split_first()
+            .ok_or_else(|| anyhow!("Source returned no segment headers"))?;
+        if storage.segment_commitment(anchor.segment_index()) != Some(anchor.segment_commitment()) {
+            return Err(anyhow!(
+                "Segment header {} doesn't match known segment commitment",
+                anchor.segment_index()
+            ));
+        }
+
+        (Some(anchor), new_segment_headers)
+    } else {
+        (None, segment_headers.as_slice())
+    };
+    verify_segment_headers_chain(maybe_anchor, new_segment_headers)?;
+
+    storage.store_segment_commitments(
+        new_segment_headers
+            .iter()
+            .map(|segment_header| {
+                (
+                    segment_header.segment_index(),
+                    segment_header.segment_commitment(),
+                )
+            })
+            .collect::<BTreeMap<_, _>>(),
+    );
+    storage.commit();
+
+    Ok(())
+}
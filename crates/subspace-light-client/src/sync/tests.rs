@@ -0,0 +1,198 @@
+use crate::source::{HeaderSource, SourceHeader};
+use crate::storage::tests::genesis_state;
+use crate::storage::ParityDbStorage;
+use crate::sync::fetch_segment_commitments;
+use anyhow::anyhow;
+use async_trait::async_trait;
+use sp_lightclient::Storage;
+use std::collections::BTreeMap;
+use subspace_core_primitives::{
+    ArchivedBlockProgress, Blake3Hash, LastArchivedBlock, SegmentCommitment, SegmentHeader,
+    SegmentIndex,
+};
+use tempfile::TempDir;
+
+/// Source that only serves segment headers
+struct SegmentHeadersSource {
+    segment_headers: Vec<SegmentHeader>,
+    requests: Vec<Vec<SegmentIndex>>,
+}
+
+#[async_trait]
+impl HeaderSource for SegmentHeadersSource {
+    async fn next_headers(
+        &mut self,
+        _storage: &ParityDbStorage,
+    ) -> anyhow::Result<Vec<SourceHeader>> {
+        unimplemented!("Not used in tests")
+    }
+
+    async fn segment_headers(
+        &mut self,
+        segment_indices: Vec<SegmentIndex>,
+    ) -> anyhow::Result<Vec<SegmentHeader>> {
+        self.requests.push(segment_indices.clone());
+
+        segment_indices
+            .into_iter()
+            .map(|segment_index| {
+                self.segment_headers
+                    .get(u64::from(segment_index) as usize)
+                    .copied()
+                    .ok_or_else(|| anyhow!("Segment header {segment_index} not found"))
+            })
+            .collect()
+    }
+}
+
+fn segment_header(
+    segment_index: u64,
+    segment_commitment: SegmentCommitment,
+    prev_segment_header_hash: Blake3Hash,
+) -> SegmentHeader {
+    SegmentHeader::V0 {
+        segment_index: SegmentIndex::from(segment_index),
+        segment_commitment,
+        prev_segment_header_hash,
+        last_archived_block: LastArchivedBlock {
+            number: segment_index as u32,
+            archived_progress: ArchivedBlockProgress::Complete,
+        },
+    }
+}
+
+/// Chain of segment headers, the first one matches genesis segment commitment from
+/// [`genesis_state`]
+fn segment_headers_chain(length: u64) -> Vec<SegmentHeader> {
+    let mut segment_headers = Vec::<SegmentHeader>::new();
+    for segment_index in 0..length {
+        let segment_commitment = if segment_index == 0 {
+            SegmentCommitment::default()
+        } else {
+            SegmentCommitment::from([segment_index as u8; SegmentCommitment::SIZE])
+        };
+        let prev_segment_header_hash = segment_headers
+            .last()
+            .map(SegmentHeader::hash)
+            .unwrap_or_default();
+
+        segment_headers.push(segment_header(
+            segment_index,
+            segment_commitment,
+            prev_segment_header_hash,
+        ));
+    }
+
+    segment_headers
+}
+
+fn storage(directory: &TempDir) -> ParityDbStorage {
+    let mut storage = ParityDbStorage::open(directory.path()).unwrap();
+    storage.initialize(genesis_state()).unwrap();
+    storage
+}
+
+#[tokio::test]
+async fn segment_commitments_are_fetched() {
+    let directory = TempDir::new().unwrap();
+    let storage = storage(&directory);
+    let segment_headers = segment_headers_chain(4);
+    let mut source = SegmentHeadersSource {
+        segment_headers: segment_headers.clone(),
+        requests: Vec::new(),
+    };
+
+    fetch_segment_commitments(&mut source, storage.clone(), SegmentIndex::from(2))
+        .await
+        .unwrap();
+
+    // Last known segment header is requested as an anchor
+    assert_eq!(
+        source.requests,
+        vec![vec![
+            SegmentIndex::ZERO,
+            SegmentIndex::ONE,
+            SegmentIndex::from(2)
+        ]]
+    );
+    assert_eq!(storage.number_of_segments(), 3);
+    for segment_header in &segment_headers[..3] {
+        assert_eq!(
+            storage.segment_commitment(segment_header.segment_index()),
+            Some(segment_header.segment_commitment())
+        );
+    }
+
+    // Already known segment commitment can't be missing
+    assert!(
+        fetch_segment_commitments(&mut source, storage.clone(), SegmentIndex::ONE)
+            .await
+            .is_err()
+    );
+}
+
+#[tokio::test]
+async fn segment_commitments_must_match_anchor() {
+    let directory = TempDir::new().unwrap();
+    let storage = storage(&directory);
+    let mut segment_headers = segment_headers_chain(2);
+    // Genesis segment header doesn't match genesis segment commitment anymore
+    segment_headers[0] = segment_header(
+        0,
+        SegmentCommitment::from([1; SegmentCommitment::SIZE]),
+        Blake3Hash::default(),
+    );
+    segment_headers[1] = segment_header(1, SegmentCommitment::default(), segment_headers[0].hash());
+    let mut source = SegmentHeadersSource {
+        segment_headers,
+        requests: Vec::new(),
+    };
+
+    assert!(
+        fetch_segment_commitments(&mut source, storage.clone(), SegmentIndex::ONE)
+            .await
+            .is_err()
+    );
+    assert_eq!(storage.number_of_segments(), 1);
+}
+
+#[tokio::test]
+async fn segment_commitments_must_form_chain() {
+    let directory = TempDir::new().unwrap();
+    let mut storage = storage(&directory);
+    let mut segment_headers = segment_headers_chain(3);
+    // Broken link between the second and the third segment headers
+    segment_headers[2] = segment_header(
+        2,
+        segment_headers[2].segment_commitment(),
+        Blake3Hash::default(),
+    );
+    let mut source = SegmentHeadersSource {
+        segment_headers: segment_headers.clone(),
+        requests: Vec::new(),
+    };
+
+    assert!(
+        fetch_segment_commitments(&mut source, storage.clone(), SegmentIndex::from(2))
+            .await
+            .is_err()
+    );
+    assert_eq!(storage.number_of_segments(), 1);
+
+    // Starting from non-genesis anchor works the same way
+    storage.store_segment_commitments(BTreeMap::from([(
+        SegmentIndex::ONE,
+        segment_headers[1].segment_commitment(),
+    )]));
+    storage.commit();
+    assert!(
+        fetch_segment_commitments(&mut source, storage.clone(), SegmentIndex::from(2))
+            .await
+            .is_err()
+    );
+    assert_eq!(
+        source.requests.last(),
+        Some(&vec![SegmentIndex::ONE, SegmentIndex::from(2)])
+    );
+    assert_eq!(storage.number_of_segments(), 2);
+}
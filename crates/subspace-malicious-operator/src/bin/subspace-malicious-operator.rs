@@ -37,6 +37,7 @@ use subspace_proof_of_space::chia::ChiaTable;
 use subspace_runtime::{Block, RuntimeApi};
 use subspace_service::config::{SubspaceConfiguration, SubspaceNetworking};
 use subspace_service::dsn::DsnConfig;
+use subspace_service::NewPartialOptions;
 
 #[global_allocator]
 static GLOBAL: mimalloc::MiMalloc = mimalloc::MiMalloc;
@@ -200,6 +201,8 @@ fn main() -> Result<(), Error> {
                     max_pending_out_connections: 150,
                     external_addresses: vec![],
                     disable_bootstrap_on_start: false,
+                    lan_peer_discovery: false,
+                    segment_header_announcements: false,
                 }
             };
 
@@ -212,11 +215,13 @@ fn main() -> Result<(), Error> {
                 sync_from_dsn: true,
                 is_timekeeper: false,
                 timekeeper_cpu_cores: Default::default(),
+                object_mappings: None,
+                equivocation_reporting: false,
             };
 
             let partial_components = subspace_service::new_partial::<PosTable, RuntimeApi>(
                 &consensus_chain_config,
-                &pot_external_entropy,
+                NewPartialOptions::new(&pot_external_entropy),
             )
             .map_err(|error| {
                 sc_service::Error::Other(format!("Failed to build a full subspace node: {error:?}"))
@@ -21,6 +21,8 @@ use libp2p::gossipsub::{
 };
 use libp2p::identify::{Behaviour as Identify, Config as IdentifyConfig, Event as IdentifyEvent};
 use libp2p::kad::{Behaviour as Kademlia, Config as KademliaConfig, Event as KademliaEvent};
+use libp2p::mdns::tokio::Behaviour as Mdns;
+use libp2p::mdns::Event as MdnsEvent;
 use libp2p::ping::{Behaviour as Ping, Event as PingEvent};
 use libp2p::swarm::behaviour::toggle::Toggle;
 use libp2p::swarm::NetworkBehaviour;
@@ -48,6 +50,8 @@ pub(crate) struct BehaviorConfig<RecordStore> {
     pub(crate) reserved_peers: ReservedPeersConfig,
     /// Autonat configuration.
     pub(crate) autonat: AutonatWrapperConfig,
+    /// mDNS behaviour for LAN-local peer discovery, disabled if `None`.
+    pub(crate) mdns: Option<Mdns>,
 }
 
 // #[derive(Debug, Clone, Copy)]
@@ -76,6 +80,7 @@ pub(crate) struct Behavior<RecordStore> {
     // pub(crate) special_connected_peers:
     //     Toggle<ConnectedPeersBehaviour<SpecialConnectedPeersInstance>>,
     pub(crate) autonat: AutonatWrapper,
+    pub(crate) mdns: Toggle<Mdns>,
 }
 
 impl<RecordStore> Behavior<RecordStore>
@@ -120,6 +125,7 @@ where
             block_list: BlockListBehaviour::default(),
             reserved_peers: ReservedPeersBehaviour::new(config.reserved_peers),
             autonat: AutonatWrapper::new(config.autonat),
+            mdns: config.mdns.into(),
         }
     }
 }
@@ -135,4 +141,5 @@ pub(crate) enum Event {
     VoidEventStub(VoidEvent),
     ReservedPeers(ReservedPeersEvent),
     Autonat(AutonatEvent),
+    Mdns(MdnsEvent),
 }
@@ -0,0 +1,174 @@
+//! Admin HTTP API of the bootstrap node, exposes introspection of the networking stack and a few
+//! management operations.
+
+use actix_web::web::{Data, Json, Path};
+use actix_web::{get, post, App, HttpResponse, HttpServer, Responder};
+use libp2p::{Multiaddr, PeerId};
+use serde::Serialize;
+use std::future::Future;
+use std::net::SocketAddr;
+use subspace_networking::Node;
+use tracing::{debug, error, info};
+
+/// Connected peer details.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ConnectedPeer {
+    peer_id: PeerId,
+    addresses: Vec<Multiaddr>,
+    agent_version: Option<String>,
+}
+
+/// Kademlia routing table entry.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RoutingTableEntry {
+    peer_id: PeerId,
+    addresses: Vec<Multiaddr>,
+}
+
+/// Addresses of the node itself.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct NodeAddresses {
+    peer_id: PeerId,
+    listeners: Vec<Multiaddr>,
+    external_addresses: Vec<Multiaddr>,
+}
+
+#[get("/peers/connected")]
+async fn connected_peers(node: Data<Node>) -> impl Responder {
+    match node.connected_peers_info().await {
+        Ok(connected_peers_info) => HttpResponse::Ok().json(
+            connected_peers_info
+                .into_iter()
+                .map(|peer_info| ConnectedPeer {
+                    peer_id: peer_info.peer_id,
+                    addresses: peer_info.addresses,
+                    agent_version: peer_info.agent_version,
+                })
+                .collect::<Vec<_>>(),
+        ),
+        Err(error) => {
+            error!(%error, "Failed to get connected peers");
+
+            HttpResponse::InternalServerError().body(error.to_string())
+        }
+    }
+}
+
+#[get("/peers/banned")]
+async fn banned_peers(node: Data<Node>) -> impl Responder {
+    match node.banned_peers().await {
+        Ok(banned_peers) => HttpResponse::Ok().json(banned_peers),
+        Err(error) => {
+            error!(%error, "Failed to get banned peers");
+
+            HttpResponse::InternalServerError().body(error.to_string())
+        }
+    }
+}
+
+#[post("/peers/{peer_id}/ban")]
+async fn ban_peer(node: Data<Node>, peer_id: Path<PeerId>) -> impl Responder {
+    let peer_id = peer_id.into_inner();
+
+    debug!(%peer_id, "Banning peer via admin API");
+
+    match node.ban_peer(peer_id).await {
+        Ok(()) => HttpResponse::Ok().finish(),
+        Err(error) => {
+            error!(%error, %peer_id, "Failed to ban peer");
+
+            HttpResponse::InternalServerError().body(error.to_string())
+        }
+    }
+}
+
+#[post("/peers/{peer_id}/unban")]
+async fn unban_peer(node: Data<Node>, peer_id: Path<PeerId>) -> impl Responder {
+    let peer_id = peer_id.into_inner();
+
+    debug!(%peer_id, "Unbanning peer via admin API");
+
+    match node.unban_peer(peer_id).await {
+        Ok(()) => HttpResponse::Ok().finish(),
+        Err(error) => {
+            error!(%error, %peer_id, "Failed to unban peer");
+
+            HttpResponse::InternalServerError().body(error.to_string())
+        }
+    }
+}
+
+#[get("/routing-table")]
+async fn routing_table(node: Data<Node>) -> impl Responder {
+    match node.routing_table().await {
+        Ok(routing_table) => HttpResponse::Ok().json(
+            routing_table
+                .into_iter()
+                .map(|entry| RoutingTableEntry {
+                    peer_id: entry.peer_id,
+                    addresses: entry.addresses,
+                })
+                .collect::<Vec<_>>(),
+        ),
+        Err(error) => {
+            error!(%error, "Failed to get routing table");
+
+            HttpResponse::InternalServerError().body(error.to_string())
+        }
+    }
+}
+
+#[get("/addresses")]
+async fn addresses(node: Data<Node>) -> Json<NodeAddresses> {
+    Json(NodeAddresses {
+        peer_id: node.id(),
+        listeners: node.listeners(),
+        external_addresses: node.external_addresses(),
+    })
+}
+
+#[post("/bootstrap")]
+async fn bootstrap(node: Data<Node>) -> impl Responder {
+    debug!("Kademlia bootstrap requested via admin API");
+
+    match node.bootstrap().await {
+        Ok(()) => HttpResponse::Ok().finish(),
+        Err(error) => {
+            error!(%error, "Failed to bootstrap");
+
+            HttpResponse::InternalServerError().body(error.to_string())
+        }
+    }
+}
+
+/// Start admin API server on the provided addresses.
+pub(crate) fn start_admin_api_server(
+    endpoints: Vec<SocketAddr>,
+    node: Node,
+) -> std::io::Result<impl Future<Output = std::io::Result<()>>> {
+    let data = Data::new(node);
+
+    let server = HttpServer::new(move || {
+        App::new()
+            .app_data(data.clone())
+            .service(connected_peers)
+            .service(banned_peers)
+            .service(ban_peer)
+            .service(unban_peer)
+            .service(routing_table)
+            .service(addresses)
+            .service(bootstrap)
+    })
+    .workers(1)
+    .bind(endpoints.as_slice())
+    .inspect_err(|error| {
+        error!(?error, "Failed to start admin API server.");
+    })?;
+
+    info!(endpoints = ?server.addrs(), "Admin API server started.");
+
+    Ok(server.run())
+}
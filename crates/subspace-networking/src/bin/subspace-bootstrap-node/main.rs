@@ -2,7 +2,11 @@
 
 #![feature(const_option, type_changing_struct_update)]
 
+mod admin_api;
+
+use crate::admin_api::start_admin_api_server;
 use clap::Parser;
+use futures::future::pending;
 use futures::{select, FutureExt};
 use libp2p::identity::ed25519::Keypair;
 use libp2p::kad::Mode;
@@ -12,6 +16,7 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 use std::error::Error;
 use std::fmt::{Display, Formatter};
+use std::future::Future;
 use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
 use std::num::NonZeroUsize;
 use std::sync::Arc;
@@ -44,7 +49,9 @@ enum Command {
         /// Keypair for node identity, can be obtained with `generate-keypair` command
         #[clap(long)]
         keypair: String,
-        /// Multiaddr to listen on for subspace networking, multiple are supported
+        /// Multiaddr to listen on for subspace networking, multiple are supported. WebSocket
+        /// addresses (like `/ip4/0.0.0.0/tcp/30534/ws`) can be added to make it accessible from
+        /// browsers.
         #[arg(long, default_values_t = [
             Multiaddr::from(IpAddr::V4(Ipv4Addr::UNSPECIFIED))
                 .with(Protocol::Udp(0))
@@ -87,6 +94,11 @@ enum Command {
         /// one specified endpoint. Format: 127.0.0.1:8080
         #[arg(long, aliases = ["metrics-endpoint", "metrics-endpoints"])]
         prometheus_listen_on: Vec<SocketAddr>,
+        /// Defines endpoints for the admin HTTP API (connected peers, routing table, bans,
+        /// bootstrapping, etc.). It doesn't start without at least one specified endpoint. Must
+        /// not be exposed publicly. Format: 127.0.0.1:8081
+        #[arg(long, alias = "admin-api-endpoint")]
+        admin_api_listen_on: Vec<SocketAddr>,
     },
     /// Generate a new keypair
     GenerateKeypair {
@@ -150,6 +162,7 @@ async fn main() -> Result<(), Box<dyn Error>> {
             protocol_version,
             external_addresses,
             prometheus_listen_on,
+            admin_api_listen_on,
         } => {
             debug!(
                 "Libp2p protocol stack instantiated with version: {} ",
@@ -225,13 +238,14 @@ async fn main() -> Result<(), Box<dyn Error>> {
                     )
                 })
                 .transpose()?;
-            if let Some(prometheus_task) = prometheus_task {
-                select! {
-                   _ = node_runner.run().fuse() => {},
-                   _ = prometheus_task.fuse() => {},
-                }
-            } else {
-                node_runner.run().await
+            let admin_api_task = (!admin_api_listen_on.is_empty())
+                .then(|| start_admin_api_server(admin_api_listen_on, node.clone()))
+                .transpose()?;
+
+            select! {
+               _ = node_runner.run().fuse() => {},
+               _ = run_optional(prometheus_task).fuse() => {},
+               _ = run_optional(admin_api_task).fuse() => {},
             }
         }
         Command::GenerateKeypair { json } => {
@@ -253,3 +267,14 @@ async fn main() -> Result<(), Box<dyn Error>> {
 fn peer_id_from_keypair(keypair: Keypair) -> PeerId {
     peer_id(&libp2p::identity::Keypair::from(keypair))
 }
+
+/// Runs the task if present, never resolves otherwise.
+async fn run_optional<Fut>(task: Option<Fut>) -> Fut::Output
+where
+    Fut: Future,
+{
+    match task {
+        Some(task) => task.await,
+        None => pending().await,
+    }
+}
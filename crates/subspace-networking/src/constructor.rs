@@ -10,13 +10,15 @@ use crate::node_runner::{NodeRunner, NodeRunnerConfig};
 use crate::protocols::autonat_wrapper::Config as AutonatWrapperConfig;
 use crate::protocols::request_response::request_response_factory::RequestHandler;
 use crate::protocols::reserved_peers::Config as ReservedPeersConfig;
-use crate::shared::Shared;
+use crate::shared::{LanPeers, Shared};
 use crate::utils::rate_limiter::RateLimiter;
 use crate::utils::{strip_peer_id, SubspaceMetrics};
 use backoff::{ExponentialBackoff, SystemClock};
 use futures::channel::mpsc;
 use libp2p::autonat::Config as AutonatConfig;
 use libp2p::connection_limits::ConnectionLimits;
+use libp2p::core::muxing::StreamMuxerBox;
+use libp2p::core::transport::Boxed;
 use libp2p::gossipsub::{
     Config as GossipsubConfig, ConfigBuilder as GossipsubConfigBuilder,
     Message as GossipsubMessage, MessageId, ValidationMode,
@@ -27,6 +29,8 @@ use libp2p::kad::{
     store, BucketInserts, Config as KademliaConfig, Mode, ProviderRecord, Record, RecordKey,
     StoreInserts,
 };
+use libp2p::mdns::tokio::Behaviour as Mdns;
+use libp2p::mdns::Config as MdnsConfig;
 use libp2p::metrics::Metrics;
 use libp2p::multiaddr::Protocol;
 use libp2p::yamux::Config as YamuxConfig;
@@ -62,9 +66,6 @@ const SWARM_MAX_PENDING_INCOMING_CONNECTIONS: u32 = 80;
 const SWARM_MAX_PENDING_OUTGOING_CONNECTIONS: u32 = 80;
 const KADEMLIA_QUERY_TIMEOUT: Duration = Duration::from_secs(40);
 const SWARM_MAX_ESTABLISHED_CONNECTIONS_PER_PEER: Option<u32> = Some(3);
-// TODO: Consider moving this constant to configuration or removing `Toggle` wrapper when we find a
-//  use-case for gossipsub protocol.
-const ENABLE_GOSSIP_PROTOCOL: bool = false;
 
 const TEMPORARY_BANS_CACHE_SIZE: NonZeroUsize = NonZeroUsize::new(10_000).expect("Not zero; qed");
 const TEMPORARY_BANS_DEFAULT_BACKOFF_INITIAL_INTERVAL: Duration = Duration::from_secs(5);
@@ -191,6 +192,12 @@ pub struct Config<LocalRecordProvider> {
     /// Identity keypair of a node used for authenticated connections.
     pub keypair: identity::Keypair,
     /// List of [`Multiaddr`] on which to listen for incoming connections.
+    ///
+    /// Besides TCP and QUIC, WebSocket addresses like `/ip4/0.0.0.0/tcp/30533/ws` are supported,
+    /// which makes the node reachable by browser clients. Secure WebSocket (`/wss`) is expected to
+    /// be terminated by a reverse proxy, in which case public `/wss` address should be added to
+    /// [`Config::external_addresses`] to be advertised in Kademlia. WebTransport is not supported
+    /// since libp2p doesn't implement it outside of browsers yet.
     pub listen_on: Vec<Multiaddr>,
     /// Fallback to random port if specified (or default) port is already occupied.
     pub listen_on_fallback_to_random_port: bool,
@@ -201,7 +208,8 @@ pub struct Config<LocalRecordProvider> {
     pub identify: IdentifyConfig,
     /// The configuration for the Kademlia behaviour.
     pub kademlia: KademliaConfig,
-    /// The configuration for the Gossip behaviour.
+    /// The configuration for the Gossip behaviour. None disables gossipsub, see
+    /// [`gossipsub_config()`] for configuration used for segment header announcements.
     pub gossipsub: Option<GossipsubConfig>,
     /// Externally provided implementation of the local records provider
     pub local_records_provider: LocalRecordProvider,
@@ -246,6 +254,12 @@ pub struct Config<LocalRecordProvider> {
     pub external_addresses: Vec<Multiaddr>,
     /// Defines whether we should run blocking Kademlia bootstrap() operation before other requests.
     pub disable_bootstrap_on_start: bool,
+    /// mDNS configuration for discovery of peers in local network. None disables discovery.
+    ///
+    /// Discovered peers are connected to regardless of [`Config::allow_non_global_addresses_in_dht`]
+    /// and are kept connected similarly to reserved peers, which allows fetching pieces from
+    /// other farmers and nodes in the same LAN without going through the Internet.
+    pub mdns: Option<MdnsConfig>,
 }
 
 impl<LocalRecordProvider> fmt::Debug for Config<LocalRecordProvider> {
@@ -312,20 +326,6 @@ where
             .set_receive_window_size(YAMUX_RECEIVING_WINDOW as u32)
             .set_max_buffer_size(YAMUX_BUFFER_SIZE);
 
-        let gossipsub = ENABLE_GOSSIP_PROTOCOL.then(|| {
-            GossipsubConfigBuilder::default()
-                .protocol_id_prefix(GOSSIPSUB_PROTOCOL_PREFIX)
-                // TODO: Do we want message signing?
-                .validation_mode(ValidationMode::None)
-                // To content-address message, we can take the hash of message and use it as an ID.
-                .message_id_fn(|message: &GossipsubMessage| {
-                    MessageId::from(crypto::blake3_hash(&message.data))
-                })
-                .max_transmit_size(2 * 1024 * 1024) // 2MB
-                .build()
-                .expect("Default config for gossipsub is always correct; qed")
-        });
-
         let protocol_version = format!("/subspace/2/{}", protocol_version);
         let identify = IdentifyConfig::new(protocol_version.clone(), keypair.public());
 
@@ -347,7 +347,7 @@ where
             timeout: Duration::from_secs(10),
             identify,
             kademlia,
-            gossipsub,
+            gossipsub: None,
             local_records_provider,
             allow_non_global_addresses_in_dht: false,
             initial_random_query_interval: Duration::from_secs(1),
@@ -368,10 +368,29 @@ where
             kademlia_mode: KademliaMode::Static(Mode::Client),
             external_addresses: Vec::new(),
             disable_bootstrap_on_start: false,
+            mdns: None,
         }
     }
 }
 
+/// Gossipsub configuration used for announcements of newly archived segment headers.
+///
+/// Messages are not relayed to other peers until subscriber validates them.
+pub fn gossipsub_config() -> GossipsubConfig {
+    GossipsubConfigBuilder::default()
+        .protocol_id_prefix(GOSSIPSUB_PROTOCOL_PREFIX)
+        // TODO: Do we want message signing?
+        .validation_mode(ValidationMode::None)
+        .validate_messages()
+        // To content-address message, we can take the hash of message and use it as an ID.
+        .message_id_fn(|message: &GossipsubMessage| {
+            MessageId::from(crypto::blake3_hash(&message.data))
+        })
+        .max_transmit_size(2 * 1024 * 1024) // 2MB
+        .build()
+        .expect("Default config for gossipsub is always correct; qed")
+}
+
 /// Errors that might happen during network creation.
 #[derive(Debug, Error)]
 pub enum CreationError {
@@ -403,6 +422,28 @@ pub fn construct<LocalRecordProvider>(
 ) -> Result<(Node, NodeRunner<LocalRecordProvider>), CreationError>
 where
     LocalRecordProvider: self::LocalRecordProvider + Send + Sync + 'static,
+{
+    construct_with_transport(config, build_transport)
+}
+
+/// Create a new network node and node runner instances with custom transport stack.
+///
+/// `transport_builder` receives the same arguments as default transport builder does, which
+/// allows replacing default transport stack in tests.
+pub(crate) fn construct_with_transport<LocalRecordProvider, TransportBuilder>(
+    config: Config<LocalRecordProvider>,
+    transport_builder: TransportBuilder,
+) -> Result<(Node, NodeRunner<LocalRecordProvider>), CreationError>
+where
+    LocalRecordProvider: self::LocalRecordProvider + Send + Sync + 'static,
+    TransportBuilder: FnOnce(
+        bool,
+        &identity::Keypair,
+        Arc<Mutex<TemporaryBans>>,
+        LanPeers,
+        Duration,
+        YamuxConfig,
+    ) -> io::Result<Boxed<(PeerId, StreamMuxerBox)>>,
 {
     let Config {
         keypair,
@@ -432,6 +473,7 @@ where
         kademlia_mode,
         external_addresses,
         disable_bootstrap_on_start,
+        mdns,
     } = config;
     let local_peer_id = peer_id(&keypair);
 
@@ -464,6 +506,10 @@ where
         "Autonat boot delay set."
     );
 
+    let mdns = mdns
+        .map(|mdns_config| Mdns::new(mdns_config, local_peer_id))
+        .transpose()?;
+
     let mut behaviour = Behavior::new(BehaviorConfig {
         peer_id: local_peer_id,
         identify,
@@ -487,6 +533,7 @@ where
             local_peer_id,
             servers: bootstrap_addresses.clone(),
         },
+        mdns,
     });
 
     match (kademlia_mode, external_addresses.is_empty()) {
@@ -506,13 +553,16 @@ where
         temporary_ban_backoff,
     )));
 
+    let lan_peers = LanPeers::default();
+
     let mut swarm = SwarmBuilder::with_existing_identity(keypair)
         .with_tokio()
         .with_other_transport(|keypair| {
-            Ok(build_transport(
+            Ok(transport_builder(
                 allow_non_global_addresses_in_dht,
                 keypair,
                 Arc::clone(&temporary_bans),
+                Arc::clone(&lan_peers),
                 timeout,
                 yamux_config,
             )?)
@@ -537,6 +587,15 @@ where
             }
 
             let addr_string = addr.to_string();
+            // WebSocket protocol goes after TCP port, strip it temporarily
+            let websocket_protocol = match addr.pop() {
+                Some(protocol @ (Protocol::Ws(_) | Protocol::Wss(_))) => Some(protocol),
+                Some(protocol) => {
+                    addr.push(protocol);
+                    None
+                }
+                None => None,
+            };
             // Listen on random port if specified is already occupied
             match addr.pop() {
                 Some(Protocol::Tcp(_port)) => {
@@ -544,6 +603,9 @@ where
                         "Failed to listen on {addr_string} ({error}), falling back to random port"
                     );
                     addr.push(Protocol::Tcp(0));
+                    if let Some(websocket_protocol) = websocket_protocol {
+                        addr.push(websocket_protocol);
+                    }
                     swarm.listen_on(addr)?;
                 }
                 Some(Protocol::Udp(_port)) => {
@@ -574,7 +636,12 @@ where
         max_pending_outgoing_connections,
     );
 
-    let shared = Arc::new(Shared::new(local_peer_id, command_sender, rate_limiter));
+    let shared = Arc::new(Shared::new(
+        local_peer_id,
+        command_sender,
+        rate_limiter,
+        lan_peers,
+    ));
     let shared_weak = Arc::downgrade(&shared);
 
     let node = Node::new(shared);
@@ -1,4 +1,8 @@
+#[cfg(test)]
+mod tests;
+
 use crate::constructor::temporary_bans::TemporaryBans;
+use crate::shared::LanPeers;
 use futures::future::Either;
 use libp2p::core::multiaddr::{Multiaddr, Protocol};
 use libp2p::core::muxing::StreamMuxerBox;
@@ -9,6 +13,7 @@ use libp2p::quic::tokio::Transport as QuicTransport;
 use libp2p::quic::Config as QuicConfig;
 use libp2p::tcp::tokio::Transport as TokioTcpTransport;
 use libp2p::tcp::Config as GenTcpConfig;
+use libp2p::websocket::WsConfig;
 use libp2p::yamux::Config as YamuxConfig;
 use libp2p::{core, identity, noise, PeerId};
 use parking_lot::Mutex;
@@ -24,9 +29,13 @@ pub(super) fn build_transport(
     allow_non_global_addresses_in_dht: bool,
     keypair: &identity::Keypair,
     temporary_bans: Arc<Mutex<TemporaryBans>>,
+    lan_peers: LanPeers,
     timeout: Duration,
     yamux_config: YamuxConfig,
 ) -> io::Result<Boxed<(PeerId, StreamMuxerBox)>> {
+    let noise =
+        noise::Config::new(keypair).expect("Signing libp2p-noise static DH keypair failed.");
+
     let wrapped_tcp = {
         let tcp_config = GenTcpConfig::default().nodelay(true);
 
@@ -34,14 +43,31 @@ pub(super) fn build_transport(
             TokioTcpTransport::new(tcp_config.clone()),
             allow_non_global_addresses_in_dht,
             temporary_bans.clone(),
+            lan_peers.clone(),
         )
     };
 
-    let tcp_upgraded = {
-        let noise =
-            noise::Config::new(keypair).expect("Signing libp2p-noise static DH keypair failed.");
+    let tcp_upgraded = wrapped_tcp
+        .upgrade(core::upgrade::Version::V1Lazy)
+        .authenticate(noise.clone())
+        .multiplex(yamux_config.clone())
+        .timeout(timeout)
+        .boxed();
+
+    // WebSocket transport allows browser clients to connect to the node, it is only used for
+    // `/ws` and `/wss` addresses. DNS resolution happens underneath WebSocket transport such that
+    // domain name is preserved for TLS server name verification of `/wss` addresses.
+    let ws_upgraded = {
+        let tcp_config = GenTcpConfig::default().nodelay(true);
+
+        let wrapped_tcp = CustomTransportWrapper::new(
+            TokioTcpTransport::new(tcp_config),
+            allow_non_global_addresses_in_dht,
+            temporary_bans.clone(),
+            lan_peers.clone(),
+        );
 
-        wrapped_tcp
+        WsConfig::new(TokioTransport::system(wrapped_tcp)?)
             .upgrade(core::upgrade::Version::V1Lazy)
             .authenticate(noise)
             .multiplex(yamux_config)
@@ -57,8 +83,12 @@ pub(super) fn build_transport(
     let quic = QuicTransport::new(quic_config)
         .map(|(peer_id, muxer), _| (peer_id, StreamMuxerBox::new(muxer)));
 
-    let wrapped_quic =
-        CustomTransportWrapper::new(quic, allow_non_global_addresses_in_dht, temporary_bans);
+    let wrapped_quic = CustomTransportWrapper::new(
+        quic,
+        allow_non_global_addresses_in_dht,
+        temporary_bans,
+        lan_peers,
+    );
 
     let quic_tcp = wrapped_quic
         .or_transport(tcp_upgraded)
@@ -67,7 +97,15 @@ pub(super) fn build_transport(
             Either::Right((peer_id, muxer)) => (peer_id, muxer),
         });
 
-    Ok(TokioTransport::system(quic_tcp)?.boxed())
+    // WebSocket transport must go first, otherwise DNS transport would resolve `/ws` and `/wss`
+    // addresses and try to dial them with TCP transport
+    Ok(ws_upgraded
+        .or_transport(TokioTransport::system(quic_tcp)?)
+        .map(|either, _| match either {
+            Either::Left((peer_id, muxer)) => (peer_id, muxer),
+            Either::Right((peer_id, muxer)) => (peer_id, muxer),
+        })
+        .boxed())
 }
 
 #[derive(Debug, Clone)]
@@ -75,6 +113,7 @@ struct CustomTransportWrapper<T> {
     base_transport: T,
     allow_non_global_addresses: bool,
     temporary_bans: Arc<Mutex<TemporaryBans>>,
+    lan_peers: LanPeers,
 }
 
 impl<T> CustomTransportWrapper<T> {
@@ -82,13 +121,25 @@ impl<T> CustomTransportWrapper<T> {
         base_transport: T,
         allow_non_global_addresses: bool,
         temporary_bans: Arc<Mutex<TemporaryBans>>,
+        lan_peers: LanPeers,
     ) -> Self {
         CustomTransportWrapper {
             base_transport,
             allow_non_global_addresses,
             temporary_bans,
+            lan_peers,
         }
     }
+
+    /// Whether address belongs to a peer discovered in local network, such peers are allowed to
+    /// be dialed on non-global addresses.
+    fn is_lan_peer_address(&self, addr: &Multiaddr) -> bool {
+        let Some(Protocol::P2p(peer_id)) = addr.iter().last() else {
+            return false;
+        };
+
+        self.lan_peers.lock().contains_key(&peer_id)
+    }
 }
 
 impl<T> Transport for CustomTransportWrapper<T>
@@ -118,13 +169,19 @@ where
 
         match addr_iter.next() {
             Some(Protocol::Ip4(a)) => {
-                if !(self.allow_non_global_addresses || a.is_global()) {
+                if !(self.allow_non_global_addresses
+                    || a.is_global()
+                    || self.is_lan_peer_address(&addr))
+                {
                     debug!(?a, "Not dialing non global IP address.",);
                     return Err(TransportError::MultiaddrNotSupported(addr));
                 }
             }
             Some(Protocol::Ip6(a)) => {
-                if !(self.allow_non_global_addresses || a.is_global()) {
+                if !(self.allow_non_global_addresses
+                    || a.is_global()
+                    || self.is_lan_peer_address(&addr))
+                {
                     debug!(?a, "Not dialing non global IP address.");
                     return Err(TransportError::MultiaddrNotSupported(addr));
                 }
@@ -0,0 +1,69 @@
+use crate::{Config, PieceByIndexRequest, PieceByIndexRequestHandler, PieceByIndexResponse};
+use futures::channel::oneshot;
+use libp2p::multiaddr::Protocol;
+use parking_lot::Mutex;
+use std::sync::Arc;
+use subspace_core_primitives::{Piece, PieceIndex};
+
+#[tokio::test]
+async fn test_piece_request_over_websocket() {
+    let config_1 = Config {
+        listen_on: vec!["/ip4/127.0.0.1/tcp/0/ws".parse().unwrap()],
+        allow_non_global_addresses_in_dht: true,
+        request_response_protocols: vec![PieceByIndexRequestHandler::create(
+            |_, &PieceByIndexRequest { piece_index }| async move {
+                Some(PieceByIndexResponse {
+                    piece: (piece_index == PieceIndex::ONE).then(Piece::default),
+                })
+            },
+        )],
+        ..Config::default()
+    };
+    let (node_1, mut node_runner_1) = crate::construct(config_1).unwrap();
+
+    let (node_1_address_sender, node_1_address_receiver) = oneshot::channel();
+    let on_new_listener_handler = node_1.on_new_listener(Arc::new({
+        let node_1_address_sender = Mutex::new(Some(node_1_address_sender));
+
+        move |address| {
+            if let Some(node_1_address_sender) = node_1_address_sender.lock().take() {
+                node_1_address_sender.send(address.clone()).unwrap();
+            }
+        }
+    }));
+
+    tokio::spawn(async move {
+        node_runner_1.run().await;
+    });
+
+    // Wait for first node to know its address
+    let node_1_addr = node_1_address_receiver.await.unwrap();
+    drop(on_new_listener_handler);
+    assert!(matches!(node_1_addr.iter().last(), Some(Protocol::Ws(_))));
+
+    let bootstrap_addresses = vec![node_1_addr.with(Protocol::P2p(node_1.id()))];
+    let config_2 = Config {
+        allow_non_global_addresses_in_dht: true,
+        request_response_protocols: vec![PieceByIndexRequestHandler::create(|_, _| async { None })],
+        bootstrap_addresses,
+        ..Config::default()
+    };
+
+    let (node_2, mut node_runner_2) = crate::construct(config_2).unwrap();
+
+    tokio::spawn(async move {
+        node_runner_2.run().await;
+    });
+
+    let response = node_2
+        .send_generic_request(
+            node_1.id(),
+            PieceByIndexRequest {
+                piece_index: PieceIndex::ONE,
+            },
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.piece, Some(Piece::default()));
+}
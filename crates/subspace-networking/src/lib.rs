@@ -25,6 +25,10 @@ mod node_runner;
 mod protocols;
 
 mod shared;
+#[cfg(test)]
+mod simulation;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
 pub mod utils;
 
 pub use crate::behavior::persistent_parameters::{
@@ -32,11 +36,13 @@ pub use crate::behavior::persistent_parameters::{
     KnownPeersRegistry, PeerAddressRemovedEvent,
 };
 pub use crate::node::{
-    GetClosestPeersError, Node, SendRequestError, SubscribeError, TopicSubscription,
+    BannedPeersError, BootstrapError, ConnectedPeersError, GetClosestPeersError, Node,
+    PublishError, RoutingTableError, SegmentHeaderAnnouncementError, SegmentHeaderSubscription,
+    SendRequestError, SubscribeError, TopicSubscription,
 };
 pub use crate::node_runner::NodeRunner;
 pub use constructor::{
-    construct, peer_id, Config, CreationError, KademliaMode, LocalRecordProvider,
+    construct, gossipsub_config, peer_id, Config, CreationError, KademliaMode, LocalRecordProvider,
 };
 pub use libp2p;
 pub use protocols::request_response::handlers::generic_request_handler::{
@@ -48,7 +54,7 @@ pub use protocols::request_response::handlers::piece_by_index::{
 pub use protocols::request_response::handlers::segment_header::{
     SegmentHeaderBySegmentIndexesRequestHandler, SegmentHeaderRequest, SegmentHeaderResponse,
 };
-pub use shared::PeerDiscovered;
+pub use shared::{ConnectedPeerInfo, PeerDiscovered, RoutingTableEntry};
 pub use utils::multihash::Multihash;
 pub use utils::unique_record_binary_heap::{KeyWrapper, UniqueRecordBinaryHeap};
 pub use utils::PeerAddress;
@@ -1,6 +1,12 @@
+#[cfg(test)]
+mod tests;
+
 use crate::protocols::request_response::handlers::generic_request_handler::GenericRequest;
 use crate::protocols::request_response::request_response_factory;
-use crate::shared::{Command, CreatedSubscription, PeerDiscovered, Shared};
+use crate::shared::{
+    Command, ConnectedPeerInfo, CreatedSubscription, PeerDiscovered, RoutingTableEntry, Shared,
+    TopicMessage,
+};
 use crate::utils::multihash::Multihash;
 use crate::utils::HandlerFn;
 use bytes::Bytes;
@@ -8,17 +14,21 @@ use event_listener_primitives::HandlerId;
 use futures::channel::mpsc::SendError;
 use futures::channel::{mpsc, oneshot};
 use futures::{SinkExt, Stream, StreamExt};
-use libp2p::gossipsub::{Sha256Topic, SubscriptionError};
+use libp2p::gossipsub::{MessageAcceptance, Sha256Topic, SubscriptionError};
 use libp2p::kad::PeerRecord;
 use libp2p::{Multiaddr, PeerId};
-use parity_scale_codec::Decode;
+use parity_scale_codec::{Decode, Encode};
 use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context, Poll};
+use subspace_core_primitives::{SegmentHeader, SegmentIndex};
 use thiserror::Error;
 use tokio::sync::OwnedSemaphorePermit;
 use tracing::{debug, error, trace};
 
+/// Gossipsub topic on which newly archived segment headers are announced.
+const SEGMENT_HEADERS_TOPIC: &str = "/subspace/segment-headers/1";
+
 /// Topic subscription, will unsubscribe when last instance is dropped for a particular topic.
 #[derive(Debug)]
 #[pin_project::pin_project(PinnedDrop)]
@@ -27,14 +37,17 @@ pub struct TopicSubscription {
     subscription_id: usize,
     command_sender: Option<mpsc::Sender<Command>>,
     #[pin]
-    receiver: mpsc::UnboundedReceiver<Bytes>,
+    receiver: mpsc::UnboundedReceiver<TopicMessage>,
     _permit: OwnedSemaphorePermit,
 }
 
 impl Stream for TopicSubscription {
     type Item = Bytes;
     fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-        self.project().receiver.poll_next(cx)
+        self.project()
+            .receiver
+            .poll_next(cx)
+            .map(|maybe_message| maybe_message.map(|message| message.data))
     }
     fn size_hint(&self) -> (usize, Option<usize>) {
         self.receiver.size_hint()
@@ -66,6 +79,161 @@ impl PinnedDrop for TopicSubscription {
     }
 }
 
+/// Defines errors of segment header subscription.
+#[derive(Debug, Error)]
+pub enum SegmentHeaderAnnouncementError {
+    /// Some segment headers were missed (for instance, node was offline or announcements were
+    /// lost), they need to be retrieved from peers with segment header requests, after which
+    /// [`SegmentHeaderSubscription::set_last_segment_header()`] must be called to resume
+    /// validation.
+    #[error(
+        "Missing segment headers: expected segment index {expected}, received {}",
+        received.segment_index()
+    )]
+    MissingSegmentHeaders {
+        /// Segment index that was expected next
+        expected: SegmentIndex,
+        /// Segment header that was received instead, it wasn't validated
+        received: SegmentHeader,
+    },
+}
+
+/// Subscription to segment headers announced on the DSN.
+///
+/// Every announced segment header is validated against hash of the previous segment header, headers
+/// that do not extend the last known segment header are rejected and not relayed to other peers.
+/// Announcements that were received, but not validated before subscription is dropped are ignored.
+#[derive(Debug)]
+#[pin_project::pin_project(PinnedDrop)]
+pub struct SegmentHeaderSubscription {
+    #[pin]
+    subscription: TopicSubscription,
+    command_sender: mpsc::Sender<Command>,
+    last_segment_header: SegmentHeader,
+}
+
+impl Stream for SegmentHeaderSubscription {
+    type Item = Result<SegmentHeader, SegmentHeaderAnnouncementError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+
+        loop {
+            let Some(message) =
+                futures::ready!(this.subscription.as_mut().project().receiver.poll_next(cx))
+            else {
+                return Poll::Ready(None);
+            };
+
+            let (acceptance, maybe_result) =
+                validate_segment_header_announcement(this.last_segment_header, &message.data);
+
+            // Each clone of the sender has a guaranteed slot in the channel, hence this can't fail
+            // due to channel being full
+            let _ = this
+                .command_sender
+                .clone()
+                .try_send(Command::ReportMessageValidationResult {
+                    message_id: message.message_id,
+                    propagation_source: message.propagation_source,
+                    acceptance,
+                });
+
+            if let Some(result) = maybe_result {
+                if let Ok(segment_header) = &result {
+                    *this.last_segment_header = *segment_header;
+                }
+
+                return Poll::Ready(Some(result));
+            }
+        }
+    }
+}
+
+#[pin_project::pinned_drop]
+impl PinnedDrop for SegmentHeaderSubscription {
+    fn drop(self: Pin<&mut Self>) {
+        let this = self.project();
+        let mut receiver = this.subscription.project().receiver;
+
+        // Messages that were not validated yet must not be left waiting for validation result
+        receiver.close();
+        while let Ok(Some(message)) = receiver.try_next() {
+            let _ = this
+                .command_sender
+                .clone()
+                .try_send(Command::ReportMessageValidationResult {
+                    message_id: message.message_id,
+                    propagation_source: message.propagation_source,
+                    acceptance: MessageAcceptance::Ignore,
+                });
+        }
+    }
+}
+
+impl SegmentHeaderSubscription {
+    /// Last segment header that was successfully validated or set explicitly.
+    pub fn last_segment_header(&self) -> &SegmentHeader {
+        &self.last_segment_header
+    }
+
+    /// Set last known segment header, next announced segment header will be validated against it.
+    ///
+    /// Must be called after segment headers missed by subscription were retrieved and verified by
+    /// other means.
+    pub fn set_last_segment_header(&mut self, segment_header: SegmentHeader) {
+        self.last_segment_header = segment_header;
+    }
+}
+
+/// Validate announced segment header against the last known segment header, returns whether
+/// announcement should be relayed to other peers and result to be yielded by subscription (if
+/// any).
+fn validate_segment_header_announcement(
+    last_segment_header: &SegmentHeader,
+    mut message: &[u8],
+) -> (
+    MessageAcceptance,
+    Option<Result<SegmentHeader, SegmentHeaderAnnouncementError>>,
+) {
+    let segment_header = match SegmentHeader::decode(&mut message) {
+        Ok(segment_header) => segment_header,
+        Err(error) => {
+            debug!(%error, "Failed to decode segment header announcement");
+            return (MessageAcceptance::Reject, None);
+        }
+    };
+
+    let expected = last_segment_header.segment_index() + SegmentIndex::ONE;
+    let segment_index = segment_header.segment_index();
+
+    if segment_index < expected {
+        trace!(%segment_index, "Ignoring announcement of already known segment header");
+        return (MessageAcceptance::Ignore, None);
+    }
+
+    if segment_index > expected {
+        // Can't be validated until missing segment headers are retrieved, hence not relayed
+        return (
+            MessageAcceptance::Ignore,
+            Some(Err(SegmentHeaderAnnouncementError::MissingSegmentHeaders {
+                expected,
+                received: segment_header,
+            })),
+        );
+    }
+
+    if segment_header.prev_segment_header_hash() != last_segment_header.hash() {
+        debug!(
+            %segment_index,
+            "Rejecting announced segment header that doesn't extend last known segment header"
+        );
+        return (MessageAcceptance::Reject, None);
+    }
+
+    (MessageAcceptance::Accept, Some(Ok(segment_header)))
+}
+
 #[derive(Debug, Error)]
 pub enum GetValueError {
     /// Failed to send command to the node runner
@@ -139,6 +307,7 @@ impl From<oneshot::Canceled> for SubscribeError {
     }
 }
 
+/// Defines errors for `publish` operation.
 #[derive(Debug, Error)]
 pub enum PublishError {
     /// Failed to send command to the node runner
@@ -203,6 +372,7 @@ impl From<oneshot::Canceled> for SendRequestError {
     }
 }
 
+/// Defines errors for `connected-peers` operation.
 #[derive(Debug, Error)]
 pub enum ConnectedPeersError {
     /// Failed to send command to the node runner
@@ -223,6 +393,43 @@ impl From<oneshot::Canceled> for ConnectedPeersError {
     }
 }
 
+/// Defines errors for `banned-peers` operation.
+#[derive(Debug, Error)]
+pub enum BannedPeersError {
+    /// Failed to send command to the node runner
+    #[error("Failed to send command to the node runner: {0}")]
+    SendCommand(#[from] SendError),
+    /// Node runner was dropped
+    #[error("Node runner was dropped")]
+    NodeRunnerDropped,
+}
+
+impl From<oneshot::Canceled> for BannedPeersError {
+    #[inline]
+    fn from(oneshot::Canceled: oneshot::Canceled) -> Self {
+        Self::NodeRunnerDropped
+    }
+}
+
+/// Defines errors for `routing-table` operation.
+#[derive(Debug, Error)]
+pub enum RoutingTableError {
+    /// Failed to send command to the node runner
+    #[error("Failed to send command to the node runner: {0}")]
+    SendCommand(#[from] SendError),
+    /// Node runner was dropped
+    #[error("Node runner was dropped")]
+    NodeRunnerDropped,
+}
+
+impl From<oneshot::Canceled> for RoutingTableError {
+    #[inline]
+    fn from(oneshot::Canceled: oneshot::Canceled) -> Self {
+        Self::NodeRunnerDropped
+    }
+}
+
+/// Defines errors for `bootstrap` operation.
 #[derive(Debug, Error)]
 pub enum BootstrapError {
     /// Failed to send command to the node runner
@@ -308,6 +515,14 @@ impl Node {
 
     /// Subcribe to some topic on the DSN.
     pub async fn subscribe(&self, topic: Sha256Topic) -> Result<TopicSubscription, SubscribeError> {
+        self.subscribe_internal(topic, false).await
+    }
+
+    async fn subscribe_internal(
+        &self,
+        topic: Sha256Topic,
+        validate_messages: bool,
+    ) -> Result<TopicSubscription, SubscribeError> {
         let permit = self.shared.rate_limiter.acquire_permit().await;
         let (result_sender, result_receiver) = oneshot::channel();
 
@@ -316,6 +531,7 @@ impl Node {
             .clone()
             .send(Command::Subscribe {
                 topic: topic.clone(),
+                validate_messages,
                 result_sender,
             })
             .await?;
@@ -352,6 +568,43 @@ impl Node {
         result_receiver.await?.map_err(PublishError::Publish)
     }
 
+    /// Subscribe to segment headers announced on the DSN.
+    ///
+    /// `last_segment_header` is the last segment header known to the caller, it must come from a
+    /// trusted source (like local segment headers store or segment headers verified against each
+    /// other), announced segment headers are validated against it. Announcements are only relayed
+    /// to other peers after successful validation.
+    ///
+    /// Requires gossipsub to be enabled in [`Config::gossipsub`](crate::Config::gossipsub).
+    pub async fn subscribe_segment_headers(
+        &self,
+        last_segment_header: SegmentHeader,
+    ) -> Result<SegmentHeaderSubscription, SubscribeError> {
+        let subscription = self
+            .subscribe_internal(Sha256Topic::new(SEGMENT_HEADERS_TOPIC), true)
+            .await?;
+
+        Ok(SegmentHeaderSubscription {
+            subscription,
+            command_sender: self.shared.command_sender.clone(),
+            last_segment_header,
+        })
+    }
+
+    /// Announce newly archived segment header on the DSN.
+    ///
+    /// Requires gossipsub to be enabled in [`Config::gossipsub`](crate::Config::gossipsub).
+    pub async fn publish_segment_header(
+        &self,
+        segment_header: &SegmentHeader,
+    ) -> Result<(), PublishError> {
+        self.publish(
+            Sha256Topic::new(SEGMENT_HEADERS_TOPIC),
+            segment_header.encode(),
+        )
+        .await
+    }
+
     async fn send_generic_request_internal<Request>(
         &self,
         peer_id: PeerId,
@@ -479,6 +732,30 @@ impl Node {
             .await
     }
 
+    /// Remove ban of the peer with specified peer ID.
+    pub async fn unban_peer(&self, peer_id: PeerId) -> Result<(), SendError> {
+        self.shared
+            .command_sender
+            .clone()
+            .send(Command::UnbanPeer { peer_id })
+            .await
+    }
+
+    /// Returns a collection of peers banned on network level.
+    pub async fn banned_peers(&self) -> Result<Vec<PeerId>, BannedPeersError> {
+        let (result_sender, result_receiver) = oneshot::channel();
+
+        trace!("Starting 'banned_peers' request.");
+
+        self.shared
+            .command_sender
+            .clone()
+            .send(Command::BannedPeers { result_sender })
+            .await?;
+
+        result_receiver.await.map_err(Into::into)
+    }
+
     /// Dial multiaddress.
     /// It could be used to test libp2p transports bypassing protocol checks for bootstrap
     /// or listen-on addresses.
@@ -501,6 +778,11 @@ impl Node {
         self.shared.external_addresses.lock().clone()
     }
 
+    /// Peers discovered in local network, they are preferred for piece retrieval.
+    pub fn lan_peers(&self) -> Vec<PeerId> {
+        self.shared.lan_peers.lock().keys().copied().collect()
+    }
+
     /// Callback is called when node starts listening on new address.
     pub fn on_new_listener(&self, callback: HandlerFn<Multiaddr>) -> HandlerId {
         self.shared.handlers.new_listener.add(callback)
@@ -534,6 +816,41 @@ impl Node {
             .map_err(|_| ConnectedPeersError::ConnectedPeers)
     }
 
+    /// Returns a collection of currently connected peers with connection addresses and agent
+    /// versions.
+    pub async fn connected_peers_info(
+        &self,
+    ) -> Result<Vec<ConnectedPeerInfo>, ConnectedPeersError> {
+        let (result_sender, result_receiver) = oneshot::channel();
+
+        trace!("Starting 'connected_peers_info' request.");
+
+        self.shared
+            .command_sender
+            .clone()
+            .send(Command::ConnectedPeersInfo { result_sender })
+            .await?;
+
+        result_receiver
+            .await
+            .map_err(|_| ConnectedPeersError::ConnectedPeers)
+    }
+
+    /// Returns a snapshot of Kademlia routing table.
+    pub async fn routing_table(&self) -> Result<Vec<RoutingTableEntry>, RoutingTableError> {
+        let (result_sender, result_receiver) = oneshot::channel();
+
+        trace!("Starting 'routing_table' request.");
+
+        self.shared
+            .command_sender
+            .clone()
+            .send(Command::RoutingTable { result_sender })
+            .await?;
+
+        result_receiver.await.map_err(Into::into)
+    }
+
     /// Bootstraps Kademlia network
     pub async fn bootstrap(&self) -> Result<(), BootstrapError> {
         let (result_sender, mut result_receiver) = mpsc::unbounded();
@@ -0,0 +1,318 @@
+use super::{validate_segment_header_announcement, TopicSubscription, SEGMENT_HEADERS_TOPIC};
+use crate::shared::{Command, TopicMessage};
+use crate::simulation::{LinkConditions, SimulatedNetwork};
+use crate::{Config, SegmentHeaderAnnouncementError, SegmentHeaderSubscription};
+use bytes::Bytes;
+use futures::channel::{mpsc, oneshot};
+use futures::StreamExt;
+use libp2p::gossipsub::{MessageAcceptance, MessageId, Sha256Topic};
+use libp2p::multiaddr::Protocol;
+use libp2p::PeerId;
+use parity_scale_codec::Encode;
+use parking_lot::Mutex;
+use std::sync::Arc;
+use std::time::Duration;
+use subspace_core_primitives::{
+    ArchivedBlockProgress, Blake3Hash, LastArchivedBlock, SegmentCommitment, SegmentHeader,
+    SegmentIndex,
+};
+use tokio::sync::Semaphore;
+use tokio::time::{sleep, timeout};
+
+fn segment_header(segment_index: u64, prev_segment_header_hash: Blake3Hash) -> SegmentHeader {
+    SegmentHeader::V0 {
+        segment_index: SegmentIndex::from(segment_index),
+        segment_commitment: SegmentCommitment::default(),
+        prev_segment_header_hash,
+        last_archived_block: LastArchivedBlock {
+            number: 0,
+            archived_progress: ArchivedBlockProgress::Complete,
+        },
+    }
+}
+
+#[test]
+fn segment_header_announcement_validation() {
+    let segment_header_0 = segment_header(0, Blake3Hash::default());
+    let segment_header_1 = segment_header(1, segment_header_0.hash());
+
+    let (acceptance, result) =
+        validate_segment_header_announcement(&segment_header_0, &segment_header_1.encode());
+    assert!(matches!(acceptance, MessageAcceptance::Accept));
+    assert_eq!(result.unwrap().unwrap(), segment_header_1);
+
+    // Forged segment header that doesn't extend the last known one
+    let (acceptance, result) = validate_segment_header_announcement(
+        &segment_header_0,
+        &segment_header(1, Blake3Hash::default()).encode(),
+    );
+    assert!(matches!(acceptance, MessageAcceptance::Reject));
+    assert!(result.is_none());
+
+    // Garbage
+    let (acceptance, result) = validate_segment_header_announcement(&segment_header_0, &[1, 2, 3]);
+    assert!(matches!(acceptance, MessageAcceptance::Reject));
+    assert!(result.is_none());
+
+    // Already known segment header
+    let (acceptance, result) =
+        validate_segment_header_announcement(&segment_header_1, &segment_header_0.encode());
+    assert!(matches!(acceptance, MessageAcceptance::Ignore));
+    assert!(result.is_none());
+
+    // Segment header after a gap can't be validated and must not be relayed
+    let segment_header_3 = segment_header(3, Blake3Hash::default());
+    let (acceptance, result) =
+        validate_segment_header_announcement(&segment_header_1, &segment_header_3.encode());
+    assert!(matches!(acceptance, MessageAcceptance::Ignore));
+    match result {
+        Some(Err(SegmentHeaderAnnouncementError::MissingSegmentHeaders { expected, received })) => {
+            assert_eq!(expected, SegmentIndex::from(2));
+            assert_eq!(received, segment_header_3);
+        }
+        result => {
+            panic!("Expected missing segment headers error, got {result:?}");
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_segment_header_announcements() {
+    let network = SimulatedNetwork::new(2, LinkConditions::default(), 0);
+    network.bootstrap().await;
+
+    let segment_header_0 = segment_header(0, Blake3Hash::default());
+    let segment_header_1 = segment_header(1, segment_header_0.hash());
+    let segment_header_2 = segment_header(2, segment_header_1.hash());
+
+    let mut subscription = network
+        .node(1)
+        .subscribe_segment_headers(segment_header_0)
+        .await
+        .unwrap();
+
+    // Forged segment header is announced first, publishing fails until subscription of the other
+    // node is known
+    while network
+        .node(0)
+        .publish_segment_header(&segment_header(1, Blake3Hash::default()))
+        .await
+        .is_err()
+    {
+        sleep(Duration::from_millis(100)).await;
+    }
+    // Forged segment header must be rejected rather than become the new trust anchor
+    network
+        .node(0)
+        .publish_segment_header(&segment_header_1)
+        .await
+        .unwrap();
+    assert_eq!(
+        subscription.next().await.unwrap().unwrap(),
+        segment_header_1
+    );
+
+    network
+        .node(0)
+        .publish_segment_header(&segment_header_2)
+        .await
+        .unwrap();
+    assert_eq!(
+        subscription.next().await.unwrap().unwrap(),
+        segment_header_2
+    );
+    assert_eq!(subscription.last_segment_header(), &segment_header_2);
+
+    // Gap in segment headers must be reported
+    let segment_header_4 = segment_header(4, Blake3Hash::default());
+    network
+        .node(0)
+        .publish_segment_header(&segment_header_4)
+        .await
+        .unwrap();
+    match subscription.next().await.unwrap() {
+        Err(SegmentHeaderAnnouncementError::MissingSegmentHeaders { expected, received }) => {
+            assert_eq!(expected, SegmentIndex::from(3));
+            assert_eq!(received, segment_header_4);
+        }
+        result => {
+            panic!("Expected missing segment headers error, got {result:?}");
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_dropped_segment_header_subscription_ignores_pending_announcements() {
+    let (command_sender, mut command_receiver) = mpsc::channel(10);
+    let (message_sender, receiver) = mpsc::unbounded();
+    let segment_header_0 = segment_header(0, Blake3Hash::default());
+    let subscription = SegmentHeaderSubscription {
+        subscription: TopicSubscription {
+            topic: Some(Sha256Topic::new(SEGMENT_HEADERS_TOPIC)),
+            subscription_id: 0,
+            command_sender: Some(command_sender.clone()),
+            receiver,
+            _permit: Arc::new(Semaphore::new(1)).try_acquire_owned().unwrap(),
+        },
+        command_sender,
+        last_segment_header: segment_header_0,
+    };
+
+    // Announcements were received, but subscriber didn't get to validating them
+    let message_ids = (0..3_u8)
+        .map(|index| MessageId::new(&[index]))
+        .collect::<Vec<_>>();
+    for message_id in &message_ids {
+        message_sender
+            .unbounded_send(TopicMessage {
+                message_id: message_id.clone(),
+                propagation_source: PeerId::random(),
+                data: Bytes::from(segment_header(1, segment_header_0.hash()).encode()),
+            })
+            .unwrap();
+    }
+
+    drop(subscription);
+
+    // Every pending announcement gets validation result, so it doesn't stay in gossipsub's cache
+    // waiting for validation that will never happen
+    for expected_message_id in &message_ids {
+        match command_receiver.next().await {
+            Some(Command::ReportMessageValidationResult {
+                message_id,
+                acceptance,
+                ..
+            }) => {
+                assert_eq!(&message_id, expected_message_id);
+                assert!(matches!(acceptance, MessageAcceptance::Ignore));
+            }
+            _ => {
+                panic!("Expected message validation result");
+            }
+        }
+    }
+    assert!(matches!(
+        command_receiver.next().await,
+        Some(Command::Unsubscribe { .. })
+    ));
+    // Messages that arrive after subscription was dropped can't be delivered anymore
+    assert!(message_sender.is_closed());
+}
+
+#[tokio::test]
+async fn test_non_validated_topic_propagation() {
+    let network = SimulatedNetwork::new(3, LinkConditions::default(), 0);
+    // Messages published by node 1 can only reach node 2 through node 0
+    network
+        .node(1)
+        .ban_peer(network.node(2).id())
+        .await
+        .unwrap();
+    network
+        .node(2)
+        .ban_peer(network.node(1).id())
+        .await
+        .unwrap();
+    network.bootstrap().await;
+
+    let topic = Sha256Topic::new("/subspace/test/1");
+    // Relaying node validates segment header announcements, which must not prevent relaying of
+    // messages on other topics
+    let _segment_header_subscription = network
+        .node(0)
+        .subscribe_segment_headers(segment_header(0, Blake3Hash::default()))
+        .await
+        .unwrap();
+    let _relay_subscription = network.node(0).subscribe(topic.clone()).await.unwrap();
+    let mut subscription = network.node(2).subscribe(topic.clone()).await.unwrap();
+
+    // Publishing fails until subscription of the relaying node is known and messages published
+    // before gossipsub mesh is formed might not be relayed, hence distinct messages are published
+    // until one of them arrives
+    let received = timeout(Duration::from_secs(30), async {
+        let mut attempt = 0_u32;
+        loop {
+            let _ = network
+                .node(1)
+                .publish(topic.clone(), attempt.encode())
+                .await;
+            attempt += 1;
+
+            if let Ok(Some(message)) =
+                timeout(Duration::from_millis(500), subscription.next()).await
+            {
+                break message;
+            }
+        }
+    })
+    .await
+    .expect("Message must be relayed by node that also validates other topic");
+    assert_eq!(received.len(), 4);
+}
+
+#[tokio::test]
+async fn test_node_introspection_and_bans() {
+    let config_1 = Config {
+        listen_on: vec!["/ip4/0.0.0.0/tcp/0".parse().unwrap()],
+        allow_non_global_addresses_in_dht: true,
+        ..Config::default()
+    };
+    let (node_1, mut node_runner_1) = crate::construct(config_1).unwrap();
+
+    let (node_1_address_sender, node_1_address_receiver) = oneshot::channel();
+    let on_new_listener_handler = node_1.on_new_listener(Arc::new({
+        let node_1_address_sender = Mutex::new(Some(node_1_address_sender));
+
+        move |address| {
+            if matches!(address.iter().next(), Some(Protocol::Ip4(_))) {
+                if let Some(node_1_address_sender) = node_1_address_sender.lock().take() {
+                    node_1_address_sender.send(address.clone()).unwrap();
+                }
+            }
+        }
+    }));
+
+    tokio::spawn(async move {
+        node_runner_1.run().await;
+    });
+
+    // Wait for first node to know its address
+    let node_1_addr = node_1_address_receiver.await.unwrap();
+    drop(on_new_listener_handler);
+
+    let bootstrap_addresses = vec![node_1_addr.with(Protocol::P2p(node_1.id()))];
+    let config_2 = Config {
+        listen_on: vec!["/ip4/0.0.0.0/tcp/0".parse().unwrap()],
+        allow_non_global_addresses_in_dht: true,
+        bootstrap_addresses,
+        ..Config::default()
+    };
+
+    let (node_2, mut node_runner_2) = crate::construct(config_2).unwrap();
+
+    tokio::spawn(async move {
+        node_runner_2.run().await;
+    });
+
+    node_2.bootstrap().await.unwrap();
+
+    let routing_table = node_2.routing_table().await.unwrap();
+    assert!(routing_table
+        .iter()
+        .any(|entry| entry.peer_id == node_1.id() && !entry.addresses.is_empty()));
+
+    let connected_peers_info = node_2.connected_peers_info().await.unwrap();
+    let node_1_info = connected_peers_info
+        .iter()
+        .find(|peer_info| peer_info.peer_id == node_1.id())
+        .expect("Bootstrap node must be connected");
+    assert!(!node_1_info.addresses.is_empty());
+
+    assert!(node_2.banned_peers().await.unwrap().is_empty());
+
+    node_2.ban_peer(node_1.id()).await.unwrap();
+    assert_eq!(node_2.banned_peers().await.unwrap(), vec![node_1.id()]);
+
+    node_2.unban_peer(node_1.id()).await.unwrap();
+    assert!(node_2.banned_peers().await.unwrap().is_empty());
+}
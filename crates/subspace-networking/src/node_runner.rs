@@ -8,7 +8,10 @@ use crate::constructor::LocalOnlyRecordStore;
 use crate::protocols::request_response::request_response_factory::{
     Event as RequestResponseEvent, IfDisconnected,
 };
-use crate::shared::{Command, CreatedSubscription, PeerDiscovered, Shared};
+use crate::shared::{
+    Command, ConnectedPeerInfo, CreatedSubscription, PeerDiscovered, RoutingTableEntry, Shared,
+    TopicMessage,
+};
 use crate::utils::{is_global_address_or_dns, strip_peer_id, SubspaceMetrics};
 use async_mutex::Mutex as AsyncMutex;
 use bytes::Bytes;
@@ -18,13 +21,14 @@ use futures::future::Fuse;
 use futures::{FutureExt, StreamExt};
 use libp2p::autonat::{Event as AutonatEvent, NatStatus, OutboundProbeEvent};
 use libp2p::core::ConnectedPoint;
-use libp2p::gossipsub::{Event as GossipsubEvent, TopicHash};
+use libp2p::gossipsub::{Event as GossipsubEvent, MessageAcceptance, MessageId, TopicHash};
 use libp2p::identify::Event as IdentifyEvent;
 use libp2p::kad::{
     Behaviour as Kademlia, BootstrapOk, Event as KademliaEvent, GetClosestPeersError,
     GetClosestPeersOk, GetProvidersError, GetProvidersOk, GetRecordError, GetRecordOk,
     InboundRequest, PeerRecord, ProgressStep, PutRecordOk, QueryId, QueryResult, Quorum, Record,
 };
+use libp2p::mdns::Event as MdnsEvent;
 use libp2p::metrics::{Metrics, Recorder};
 use libp2p::multiaddr::Protocol;
 use libp2p::swarm::{DialError, SwarmEvent};
@@ -100,7 +104,11 @@ where
     next_subscription_id: usize,
     /// Topic subscription senders for logical subscriptions (multiple logical subscriptions can be
     /// present for the same physical subscription).
-    topic_subscription_senders: HashMap<TopicHash, IntMap<usize, mpsc::UnboundedSender<Bytes>>>,
+    topic_subscription_senders:
+        HashMap<TopicHash, IntMap<usize, mpsc::UnboundedSender<TopicMessage>>>,
+    /// Topics whose messages are validated by subscribers, messages of other topics are accepted
+    /// on receipt.
+    validated_topics: HashSet<TopicHash>,
     random_query_timeout: Pin<Box<Fuse<Sleep>>>,
     /// Defines an interval between periodical tasks.
     periodical_tasks_interval: Pin<Box<Fuse<Sleep>>>,
@@ -116,6 +124,10 @@ where
     metrics: Option<SubspaceMetrics>,
     /// Mapping from specific peer to ip addresses
     peer_ip_addresses: HashMap<PeerId, HashSet<IpAddr>>,
+    /// Mapping from specific peer to remote addresses of established connections
+    peer_connection_addresses: HashMap<PeerId, Vec<Multiaddr>>,
+    /// Mapping from specific peer to agent version received via identify protocol
+    peer_agent_versions: HashMap<PeerId, String>,
     /// Defines protocol version for the network peers. Affects network partition.
     protocol_version: String,
     /// Addresses to bootstrap Kademlia network
@@ -207,6 +219,7 @@ where
             query_id_receivers: HashMap::default(),
             next_subscription_id: 0,
             topic_subscription_senders: HashMap::default(),
+            validated_topics: HashSet::default(),
             // We'll make the first query right away and continue at the interval.
             random_query_timeout: Box::pin(tokio::time::sleep(Duration::from_secs(0)).fuse()),
             // We'll make the first dial right away and continue at the interval.
@@ -217,6 +230,8 @@ where
             libp2p_metrics,
             metrics,
             peer_ip_addresses: HashMap::new(),
+            peer_connection_addresses: HashMap::new(),
+            peer_agent_versions: HashMap::new(),
             protocol_version,
             bootstrap_addresses,
             bootstrap_command_state: Arc::new(AsyncMutex::new(BootstrapCommandState::default())),
@@ -433,6 +448,9 @@ where
             SwarmEvent::Behaviour(Event::Autonat(event)) => {
                 self.handle_autonat_event(event).await;
             }
+            SwarmEvent::Behaviour(Event::Mdns(event)) => {
+                self.handle_mdns_event(event);
+            }
             SwarmEvent::NewListenAddr { address, .. } => {
                 let shared = match self.shared_weak.upgrade() {
                     Some(shared) => shared,
@@ -491,6 +509,10 @@ where
                         })
                         .or_insert(HashSet::from([ip]));
                 }
+                self.peer_connection_addresses
+                    .entry(peer_id)
+                    .or_default()
+                    .push(endpoint.get_remote_address().clone());
 
                 let num_established_peer_connections = shared
                     .num_established_peer_connections
@@ -513,6 +535,7 @@ where
             }
             SwarmEvent::ConnectionClosed {
                 peer_id,
+                endpoint,
                 num_established,
                 cause,
                 ..
@@ -530,6 +553,16 @@ where
 
                 if num_established == 0 {
                     self.peer_ip_addresses.remove(&peer_id);
+                    self.peer_connection_addresses.remove(&peer_id);
+                    self.peer_agent_versions.remove(&peer_id);
+                } else if let Some(addresses) = self.peer_connection_addresses.get_mut(&peer_id) {
+                    let remote_address = endpoint.get_remote_address();
+                    if let Some(position) = addresses
+                        .iter()
+                        .position(|address| address == remote_address)
+                    {
+                        addresses.swap_remove(position);
+                    }
                 }
                 let num_established_peer_connections = shared
                     .num_established_peer_connections
@@ -701,6 +734,9 @@ where
             // Remove temporary ban if there was any
             self.temporary_bans.lock().remove(&peer_id);
 
+            self.peer_agent_versions
+                .insert(peer_id, info.agent_version.clone());
+
             if info.listen_addrs.len() > 30 {
                 debug!(
                     %local_peer_id,
@@ -1108,15 +1144,59 @@ where
     }
 
     async fn handle_gossipsub_event(&mut self, event: GossipsubEvent) {
-        if let GossipsubEvent::Message { message, .. } = event {
+        if let GossipsubEvent::Message {
+            propagation_source,
+            message_id,
+            message,
+        } = event
+        {
+            let mut delivered = false;
             if let Some(senders) = self.topic_subscription_senders.get(&message.topic) {
-                let bytes = Bytes::from(message.data);
+                let topic_message = TopicMessage {
+                    message_id: message_id.clone(),
+                    propagation_source,
+                    data: Bytes::from(message.data),
+                };
 
                 for sender in senders.values() {
-                    // Doesn't matter if receiver is still listening for messages or not.
-                    let _ = sender.unbounded_send(bytes.clone());
+                    // Receiver might have been dropped already, unsubscription will follow shortly
+                    delivered |= sender.unbounded_send(topic_message.clone()).is_ok();
                 }
             }
+
+            // Validation is enabled for all topics in gossipsub config, but only some subscribers
+            // validate messages, the rest are accepted right away. Messages that no validating
+            // subscriber received are ignored, otherwise they'd stay in gossipsub's cache until
+            // they expire without ever being relayed.
+            if !self.validated_topics.contains(&message.topic) {
+                self.report_message_validation_result(
+                    &message_id,
+                    &propagation_source,
+                    MessageAcceptance::Accept,
+                );
+            } else if !delivered {
+                self.report_message_validation_result(
+                    &message_id,
+                    &propagation_source,
+                    MessageAcceptance::Ignore,
+                );
+            }
+        }
+    }
+
+    fn report_message_validation_result(
+        &mut self,
+        message_id: &MessageId,
+        propagation_source: &PeerId,
+        acceptance: MessageAcceptance,
+    ) {
+        if let Some(gossipsub) = self.swarm.behaviour_mut().gossipsub.as_mut() {
+            // Message might have been removed from cache already, nothing to do in that case.
+            let _ = gossipsub.report_message_validation_result(
+                message_id,
+                propagation_source,
+                acceptance,
+            );
         }
     }
 
@@ -1195,6 +1275,66 @@ where
         }
     }
 
+    fn handle_mdns_event(&mut self, event: MdnsEvent) {
+        let shared = match self.shared_weak.upgrade() {
+            Some(shared) => shared,
+            None => {
+                return;
+            }
+        };
+
+        match event {
+            MdnsEvent::Discovered(peers) => {
+                let local_peer_id = *self.swarm.local_peer_id();
+                let mut lan_peers = shared.lan_peers.lock();
+
+                for (peer_id, address) in peers {
+                    if peer_id == local_peer_id {
+                        continue;
+                    }
+
+                    debug!(%peer_id, %address, "Peer discovered in local network.");
+
+                    let addresses = lan_peers.entry(peer_id).or_default();
+                    let newly_discovered = addresses.is_empty();
+                    addresses.insert(address.clone());
+
+                    // Statically configured reserved peers are managed independently
+                    if newly_discovered && !self.reserved_peers.contains_key(&peer_id) {
+                        self.swarm
+                            .behaviour_mut()
+                            .reserved_peers
+                            .add_reserved_peer(peer_id, address);
+                    }
+                }
+            }
+            MdnsEvent::Expired(peers) => {
+                let mut lan_peers = shared.lan_peers.lock();
+
+                for (peer_id, address) in peers {
+                    let Entry::Occupied(mut entry) = lan_peers.entry(peer_id) else {
+                        continue;
+                    };
+
+                    entry.get_mut().remove(&address);
+
+                    if entry.get().is_empty() {
+                        debug!(%peer_id, "Peer in local network expired.");
+
+                        entry.remove();
+
+                        if !self.reserved_peers.contains_key(&peer_id) {
+                            self.swarm
+                                .behaviour_mut()
+                                .reserved_peers
+                                .remove_reserved_peer(&peer_id);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
     fn handle_command(&mut self, command: Command) {
         match command {
             Command::GetValue {
@@ -1253,6 +1393,7 @@ where
             }
             Command::Subscribe {
                 topic,
+                validate_messages,
                 result_sender,
             } => {
                 if !self.swarm.behaviour().gossipsub.is_enabled() {
@@ -1271,11 +1412,15 @@ where
                     receiver,
                 };
 
-                match self.topic_subscription_senders.entry(topic_hash) {
+                match self.topic_subscription_senders.entry(topic_hash.clone()) {
                     Entry::Occupied(mut entry) => {
                         // In case subscription already exists, just add one more sender to it.
                         if result_sender.send(Ok(created_subscription)).is_ok() {
                             entry.get_mut().insert(subscription_id, sender);
+
+                            if validate_messages {
+                                self.validated_topics.insert(topic_hash);
+                            }
                         }
                     }
                     Entry::Vacant(entry) => {
@@ -1287,6 +1432,10 @@ where
                                     if result_sender.send(Ok(created_subscription)).is_ok() {
                                         entry
                                             .insert(IntMap::from_iter([(subscription_id, sender)]));
+
+                                        if validate_messages {
+                                            self.validated_topics.insert(topic_hash);
+                                        }
                                     }
                                 }
                                 Ok(false) => {
@@ -1316,6 +1465,7 @@ where
                     // If last sender was removed - unsubscribe.
                     if entry.get().is_empty() {
                         entry.remove_entry();
+                        self.validated_topics.remove(&topic.hash());
 
                         if let Some(gossipsub) = self.swarm.behaviour_mut().gossipsub.as_mut() {
                             if let Err(error) = gossipsub.unsubscribe(&topic) {
@@ -1345,6 +1495,13 @@ where
                         result_sender.send(gossipsub.publish(topic, message).map(|_message_id| ()));
                 }
             }
+            Command::ReportMessageValidationResult {
+                message_id,
+                propagation_source,
+                acceptance,
+            } => {
+                self.report_message_validation_result(&message_id, &propagation_source, acceptance);
+            }
             Command::GetClosestPeers {
                 key,
                 result_sender,
@@ -1396,6 +1553,23 @@ where
             Command::BanPeer { peer_id } => {
                 self.ban_peer(peer_id);
             }
+            Command::UnbanPeer { peer_id } => {
+                debug!(?peer_id, "Unbanning peer on network level");
+
+                self.swarm.behaviour_mut().block_list.unblock_peer(peer_id);
+            }
+            Command::BannedPeers { result_sender } => {
+                let banned_peers = self
+                    .swarm
+                    .behaviour()
+                    .block_list
+                    .blocked_peers()
+                    .iter()
+                    .copied()
+                    .collect();
+
+                let _ = result_sender.send(banned_peers);
+            }
             Command::Dial { address } => {
                 let _ = self.swarm.dial(address);
             }
@@ -1404,6 +1578,36 @@ where
 
                 let _ = result_sender.send(connected_peers);
             }
+            Command::ConnectedPeersInfo { result_sender } => {
+                let connected_peers_info = self
+                    .swarm
+                    .connected_peers()
+                    .map(|peer_id| ConnectedPeerInfo {
+                        peer_id: *peer_id,
+                        addresses: self
+                            .peer_connection_addresses
+                            .get(peer_id)
+                            .cloned()
+                            .unwrap_or_default(),
+                        agent_version: self.peer_agent_versions.get(peer_id).cloned(),
+                    })
+                    .collect();
+
+                let _ = result_sender.send(connected_peers_info);
+            }
+            Command::RoutingTable { result_sender } => {
+                let mut routing_table = Vec::new();
+                for kbucket in self.swarm.behaviour_mut().kademlia.kbuckets() {
+                    for entry in kbucket.iter() {
+                        routing_table.push(RoutingTableEntry {
+                            peer_id: *entry.node.key.preimage(),
+                            addresses: entry.node.value.iter().cloned().collect(),
+                        });
+                    }
+                }
+
+                let _ = result_sender.send(routing_table);
+            }
             Command::Bootstrap { result_sender } => {
                 let kademlia = &mut self.swarm.behaviour_mut().kademlia;
 
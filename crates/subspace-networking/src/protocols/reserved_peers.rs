@@ -130,6 +130,36 @@ impl Behaviour {
         }
     }
 
+    /// Add reserved peer at runtime, does nothing if peer is already reserved.
+    pub fn add_reserved_peer(&mut self, peer_id: PeerId, address: Multiaddr) {
+        if self.reserved_peers_state.contains_key(&peer_id) {
+            return;
+        }
+
+        debug!(%peer_id, %address, "Adding reserved peer.");
+
+        self.reserved_peers_state.insert(
+            peer_id,
+            ReservedPeerState {
+                peer_id,
+                address,
+                connection_status: ConnectionStatus::NotConnected,
+            },
+        );
+        self.wake();
+    }
+
+    /// Remove reserved peer at runtime.
+    ///
+    /// Already established connections keep their keep-alive policy until closed.
+    pub fn remove_reserved_peer(&mut self, peer_id: &PeerId) {
+        if self.reserved_peers_state.remove(peer_id).is_some() {
+            debug!(%peer_id, "Removed reserved peer.");
+
+            self.wake();
+        }
+    }
+
     /// Create a connection handler for the reserved peers protocol.
     fn new_reserved_peers_handler(&self, peer_id: &PeerId) -> Handler {
         Handler::new(self.reserved_peers_state.contains_key(peer_id))
@@ -105,6 +105,61 @@ async fn test_connection_reservation() {
     assert!(peer2.is_connected(peer1.local_peer_id()));
 }
 
+#[tokio::test()]
+async fn test_runtime_connection_reservation() {
+    let connection_timeout = Duration::from_millis(300);
+    let long_delay = Duration::from_millis(1000);
+
+    let identity1 = Keypair::generate_ed25519();
+    let identity2 = Keypair::generate_ed25519();
+
+    let peer1_id = identity1.public().to_peer_id();
+    let peer2_id = identity2.public().to_peer_id();
+
+    let mut peer1 = new_ephemeral(
+        identity1,
+        connection_timeout,
+        Behaviour::new(Config {
+            reserved_peers: Vec::new(),
+            dialing_interval: DIALING_INTERVAL_IN_SECS,
+        }),
+    );
+
+    let mut peer2 = new_ephemeral(
+        identity2,
+        connection_timeout,
+        Behaviour::new(Config {
+            reserved_peers: Vec::new(),
+            dialing_interval: DIALING_INTERVAL_IN_SECS,
+        }),
+    );
+
+    peer1
+        .behaviour_mut()
+        .add_reserved_peer(peer2_id, "/memory/0".parse().unwrap());
+    peer2
+        .behaviour_mut()
+        .add_reserved_peer(peer1_id, "/memory/0".parse().unwrap());
+
+    peer1.listen().with_memory_addr_external().await;
+    peer2.listen().with_memory_addr_external().await;
+    peer1.connect(&mut peer2).await;
+
+    loop {
+        select! {
+            _ = peer1.next_swarm_event().fuse() => {},
+            _ = peer2.next_swarm_event().fuse() => {},
+            _ = sleep(long_delay).fuse() => {
+                break;
+            }
+        }
+    }
+
+    // Connections should be maintained with reservation added at runtime.
+    assert!(peer1.is_connected(&peer2_id));
+    assert!(peer2.is_connected(&peer1_id));
+}
+
 #[tokio::test()]
 async fn test_connection_reservation_symmetry() {
     let connection_timeout = Duration::from_millis(300);
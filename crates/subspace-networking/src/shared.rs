@@ -7,10 +7,13 @@ use crate::utils::rate_limiter::RateLimiter;
 use crate::utils::Handler;
 use bytes::Bytes;
 use futures::channel::{mpsc, oneshot};
-use libp2p::gossipsub::{PublishError, Sha256Topic, SubscriptionError};
+use libp2p::gossipsub::{
+    MessageAcceptance, MessageId, PublishError, Sha256Topic, SubscriptionError,
+};
 use libp2p::kad::PeerRecord;
 use libp2p::{Multiaddr, PeerId};
 use parking_lot::Mutex;
+use std::collections::{HashMap, HashSet};
 use std::sync::atomic::AtomicUsize;
 use std::sync::Arc;
 use tokio::sync::OwnedSemaphorePermit;
@@ -33,6 +36,26 @@ pub enum PeerDiscovered {
     },
 }
 
+/// Information about a currently connected peer.
+#[derive(Clone, Debug)]
+pub struct ConnectedPeerInfo {
+    /// Peer ID
+    pub peer_id: PeerId,
+    /// Remote addresses of established connections with this peer
+    pub addresses: Vec<Multiaddr>,
+    /// Agent version reported by the peer via identify protocol (if received already)
+    pub agent_version: Option<String>,
+}
+
+/// Entry of the Kademlia routing table.
+#[derive(Clone, Debug)]
+pub struct RoutingTableEntry {
+    /// Peer ID
+    pub peer_id: PeerId,
+    /// Known addresses of the peer
+    pub addresses: Vec<Multiaddr>,
+}
+
 impl PeerDiscovered {
     /// Extracts peer ID from event.
     pub fn peer_id(&self) -> PeerId {
@@ -43,12 +66,26 @@ impl PeerDiscovered {
     }
 }
 
+/// Peers discovered in local network with their local addresses.
+pub(crate) type LanPeers = Arc<Mutex<HashMap<PeerId, HashSet<Multiaddr>>>>;
+
+/// Message received on subscribed topic.
+#[derive(Debug, Clone)]
+pub(crate) struct TopicMessage {
+    /// Message ID, used for reporting of validation result.
+    pub(crate) message_id: MessageId,
+    /// Peer message was received from.
+    pub(crate) propagation_source: PeerId,
+    /// Message contents.
+    pub(crate) data: Bytes,
+}
+
 #[derive(Debug)]
 pub(crate) struct CreatedSubscription {
     /// Subscription ID to be used for unsubscribing.
     pub(crate) subscription_id: usize,
     /// Receiver side of the channel with new messages.
-    pub(crate) receiver: mpsc::UnboundedReceiver<Bytes>,
+    pub(crate) receiver: mpsc::UnboundedReceiver<TopicMessage>,
 }
 
 #[derive(Debug)]
@@ -66,6 +103,8 @@ pub(crate) enum Command {
     },
     Subscribe {
         topic: Sha256Topic,
+        /// Messages are validated by subscriber instead of being accepted on receipt.
+        validate_messages: bool,
         result_sender: oneshot::Sender<Result<CreatedSubscription, SubscriptionError>>,
     },
     Unsubscribe {
@@ -77,6 +116,11 @@ pub(crate) enum Command {
         message: Vec<u8>,
         result_sender: oneshot::Sender<Result<(), PublishError>>,
     },
+    ReportMessageValidationResult {
+        message_id: MessageId,
+        propagation_source: PeerId,
+        acceptance: MessageAcceptance,
+    },
     GetClosestPeers {
         key: Multihash,
         result_sender: mpsc::UnboundedSender<PeerId>,
@@ -96,12 +140,24 @@ pub(crate) enum Command {
     BanPeer {
         peer_id: PeerId,
     },
+    UnbanPeer {
+        peer_id: PeerId,
+    },
+    BannedPeers {
+        result_sender: oneshot::Sender<Vec<PeerId>>,
+    },
     Dial {
         address: Multiaddr,
     },
     ConnectedPeers {
         result_sender: oneshot::Sender<Vec<PeerId>>,
     },
+    ConnectedPeersInfo {
+        result_sender: oneshot::Sender<Vec<ConnectedPeerInfo>>,
+    },
+    RoutingTable {
+        result_sender: oneshot::Sender<Vec<RoutingTableEntry>>,
+    },
     Bootstrap {
         result_sender: mpsc::UnboundedSender<()>,
     },
@@ -123,6 +179,8 @@ pub(crate) struct Shared {
     /// Addresses on which node is listening for incoming requests.
     pub(crate) listeners: Mutex<Vec<Multiaddr>>,
     pub(crate) external_addresses: Mutex<Vec<Multiaddr>>,
+    /// Peers discovered in local network.
+    pub(crate) lan_peers: LanPeers,
     pub(crate) num_established_peer_connections: Arc<AtomicUsize>,
     /// Sender end of the channel for sending commands to the swarm.
     pub(crate) command_sender: mpsc::Sender<Command>,
@@ -134,12 +192,14 @@ impl Shared {
         id: PeerId,
         command_sender: mpsc::Sender<Command>,
         rate_limiter: RateLimiter,
+        lan_peers: LanPeers,
     ) -> Self {
         Self {
             handlers: Handlers::default(),
             id,
             listeners: Mutex::default(),
             external_addresses: Mutex::default(),
+            lan_peers,
             num_established_peer_connections: Arc::new(AtomicUsize::new(0)),
             command_sender,
             rate_limiter,
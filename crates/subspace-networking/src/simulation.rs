@@ -0,0 +1,235 @@
+//! In-process DSN simulation for tests.
+//!
+//! Builds a number of [`Node`]/[`NodeRunner`](crate::NodeRunner) pairs connected over in-memory
+//! transport with controllable latency, packet loss, bandwidth and network partitions (see
+//! [`NetworkConditions`]). Every node has a simple piece cache that is announced in Kademlia and
+//! served via piece request handler, which allows checking piece retrieval under different
+//! network conditions.
+//!
+//! Packet losses are derived from a seed, but libp2p itself is not deterministic, so assertions
+//! should be done on success rates rather than on individual requests.
+
+#[cfg(test)]
+mod tests;
+mod transport;
+
+use crate::constructor::{construct_with_transport, gossipsub_config, Config};
+use crate::simulation::transport::SimulatedTransport;
+pub(crate) use crate::simulation::transport::{LinkConditions, NetworkConditions};
+use crate::testing::TestPieceCache;
+use crate::utils::piece_provider::{NoPieceValidator, PieceProvider};
+use crate::{
+    KademliaMode, Node, PieceByIndexRequest, PieceByIndexRequestHandler, PieceByIndexResponse,
+};
+use libp2p::core::muxing::StreamMuxerBox;
+use libp2p::core::upgrade;
+use libp2p::kad::Mode;
+use libp2p::multiaddr::Protocol;
+use libp2p::{identity, noise, Multiaddr, Transport};
+use subspace_core_primitives::{Piece, PieceIndex};
+use tokio::task::JoinHandle;
+
+/// Single node of the simulated network.
+#[derive(Debug)]
+pub(crate) struct SimulatedNode {
+    node: Node,
+    address: Multiaddr,
+    piece_cache: TestPieceCache,
+    node_runner_handle: JoinHandle<()>,
+}
+
+impl SimulatedNode {
+    /// Networking node.
+    pub(crate) fn node(&self) -> &Node {
+        &self.node
+    }
+
+    /// Address node is listening on (including `/p2p/` suffix).
+    pub(crate) fn address(&self) -> &Multiaddr {
+        &self.address
+    }
+}
+
+/// Network of in-process nodes with fault injection.
+#[derive(Debug)]
+pub(crate) struct SimulatedNetwork {
+    conditions: NetworkConditions,
+    nodes: Vec<SimulatedNode>,
+}
+
+impl Drop for SimulatedNetwork {
+    fn drop(&mut self) {
+        for node in &self.nodes {
+            node.node_runner_handle.abort();
+        }
+    }
+}
+
+impl SimulatedNetwork {
+    /// Create network of `num_nodes` nodes, all nodes bootstrap from the first one.
+    ///
+    /// `seed` determines packet losses of the network with specified `default_link` conditions.
+    pub(crate) fn new(num_nodes: usize, default_link: LinkConditions, seed: u64) -> Self {
+        let conditions = NetworkConditions::new(default_link, seed);
+        let mut nodes = Vec::<SimulatedNode>::with_capacity(num_nodes);
+
+        for index in 0..num_nodes {
+            let bootstrap_addresses = nodes
+                .first()
+                .map(|node| vec![node.address.clone()])
+                .unwrap_or_default();
+            let node = Self::create_node(&conditions, index, bootstrap_addresses);
+            nodes.push(node);
+        }
+
+        Self { conditions, nodes }
+    }
+
+    fn create_node(
+        conditions: &NetworkConditions,
+        index: usize,
+        bootstrap_addresses: Vec<Multiaddr>,
+    ) -> SimulatedNode {
+        let keypair = identity::Keypair::generate_ed25519();
+        let peer_id = keypair.public().to_peer_id();
+        // Memory transport ports are process-global, random port avoids collisions between
+        // concurrently running tests
+        let port = rand::random::<u64>().max(1);
+        let listen_on = Multiaddr::empty().with(Protocol::Memory(port));
+
+        let piece_cache = TestPieceCache::new(peer_id);
+
+        let config = Config {
+            listen_on: vec![listen_on.clone()],
+            listen_on_fallback_to_random_port: false,
+            allow_non_global_addresses_in_dht: true,
+            kademlia_mode: KademliaMode::Static(Mode::Server),
+            request_response_protocols: vec![PieceByIndexRequestHandler::create({
+                let piece_cache = piece_cache.clone();
+
+                move |_, &PieceByIndexRequest { piece_index }| {
+                    let piece = piece_cache.get_piece(piece_index);
+
+                    async move { Some(PieceByIndexResponse { piece }) }
+                }
+            })],
+            bootstrap_addresses,
+            gossipsub: Some(gossipsub_config()),
+            ..Config::new("simulation".to_string(), keypair, piece_cache.clone(), None)
+        };
+
+        let (node, mut node_runner) = construct_with_transport(
+            config,
+            |_allow_non_global_addresses_in_dht,
+             keypair,
+             _temporary_bans,
+             _lan_peers,
+             timeout,
+             yamux_config| {
+                let noise = noise::Config::new(keypair)
+                    .expect("Signing libp2p-noise static DH keypair failed.");
+
+                Ok(SimulatedTransport::new(conditions.clone(), index)
+                    .upgrade(upgrade::Version::V1Lazy)
+                    .authenticate(noise)
+                    .multiplex(yamux_config)
+                    .timeout(timeout)
+                    .map(|(peer_id, muxer), _| (peer_id, StreamMuxerBox::new(muxer)))
+                    .boxed())
+            },
+        )
+        .expect("Simulated node creation must not fail");
+
+        let node_runner_handle = tokio::spawn(async move {
+            node_runner.run().await;
+        });
+
+        SimulatedNode {
+            address: listen_on.with(Protocol::P2p(node.id())),
+            node,
+            piece_cache,
+            node_runner_handle,
+        }
+    }
+
+    /// Network conditions, can be modified while simulation is running.
+    pub(crate) fn conditions(&self) -> &NetworkConditions {
+        &self.conditions
+    }
+
+    /// All nodes of the network.
+    pub(crate) fn nodes(&self) -> &[SimulatedNode] {
+        &self.nodes
+    }
+
+    /// Node with specified index.
+    pub(crate) fn node(&self, index: usize) -> &Node {
+        &self.nodes[index].node
+    }
+
+    /// Bootstrap Kademlia of every node in the network.
+    pub(crate) async fn bootstrap(&self) {
+        for node in &self.nodes {
+            node.node
+                .bootstrap()
+                .await
+                .expect("Node runner is running; qed");
+        }
+    }
+
+    /// Put pieces into piece cache of the specified node.
+    pub(crate) fn seed_pieces<I>(&self, index: usize, pieces: I)
+    where
+        I: IntoIterator<Item = (PieceIndex, Piece)>,
+    {
+        self.nodes[index].piece_cache.insert_pieces(pieces);
+    }
+
+    /// Try to retrieve pieces from piece caches of other nodes using node with specified index,
+    /// returns share of successfully retrieved pieces.
+    pub(crate) async fn retrieval_success_rate<I>(&self, index: usize, piece_indices: I) -> f64
+    where
+        I: IntoIterator<Item = PieceIndex>,
+    {
+        let piece_provider =
+            PieceProvider::<NoPieceValidator>::new(self.nodes[index].node.clone(), None);
+
+        let mut total = 0_usize;
+        let mut retrieved = 0_usize;
+        for piece_index in piece_indices {
+            total += 1;
+            if piece_provider
+                .get_piece_from_cache(piece_index)
+                .await
+                .is_some()
+            {
+                retrieved += 1;
+            }
+        }
+
+        if total == 0 {
+            return 1.0;
+        }
+
+        retrieved as f64 / total as f64
+    }
+
+    /// Assert that at least `min_success_rate` of pieces can be retrieved by node with specified
+    /// index.
+    pub(crate) async fn assert_retrieval_success_rate<I>(
+        &self,
+        index: usize,
+        piece_indices: I,
+        min_success_rate: f64,
+    ) where
+        I: IntoIterator<Item = PieceIndex>,
+    {
+        let success_rate = self.retrieval_success_rate(index, piece_indices).await;
+
+        assert!(
+            success_rate >= min_success_rate,
+            "Retrieval success rate of node {index} is {success_rate}, expected at least \
+            {min_success_rate}"
+        );
+    }
+}
@@ -0,0 +1,46 @@
+use crate::simulation::{LinkConditions, SimulatedNetwork};
+use std::time::Duration;
+use subspace_core_primitives::{Piece, PieceIndex};
+
+#[tokio::test]
+async fn test_simulated_network_partition() {
+    let network = SimulatedNetwork::new(
+        5,
+        LinkConditions {
+            latency: Duration::from_millis(5),
+            ..LinkConditions::default()
+        },
+        0,
+    );
+    let piece_indices = (0_u64..4).map(PieceIndex::from).collect::<Vec<_>>();
+    network.seed_pieces(
+        4,
+        piece_indices
+            .iter()
+            .map(|&piece_index| (piece_index, Piece::default())),
+    );
+    network.bootstrap().await;
+
+    for simulated_node in network.nodes() {
+        assert!(!simulated_node.address().is_empty());
+    }
+    assert!(!network.node(4).connected_peers().await.unwrap().is_empty());
+
+    network.conditions().partition(&[&[0, 1], &[2, 3, 4]]);
+    assert!(!network.conditions().is_reachable(1, 4));
+    assert_eq!(
+        network
+            .retrieval_success_rate(0, piece_indices.iter().copied())
+            .await,
+        0.0
+    );
+    network
+        .assert_retrieval_success_rate(3, piece_indices.iter().copied(), 1.0)
+        .await;
+
+    network.conditions().heal_partitions();
+    network.bootstrap().await;
+    network
+        .assert_retrieval_success_rate(0, piece_indices.iter().copied(), 1.0)
+        .await;
+}
@@ -0,0 +1,460 @@
+//! In-memory transport with controllable network conditions.
+//!
+//! Built on top of libp2p's [`MemoryTransport`]. Dialer sends its node index right after
+//! connection is established, such that both sides know which link the connection belongs to.
+//! Each side then delays incoming data according to [`LinkConditions`] of that link and resets the
+//! connection as soon as nodes are partitioned from each other.
+
+#[cfg(test)]
+mod tests;
+
+use futures::future::BoxFuture;
+use futures::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, FutureExt};
+use libp2p::core::multiaddr::{Multiaddr, Protocol};
+use libp2p::core::transport::memory::Channel;
+use libp2p::core::transport::{ListenerId, MemoryTransport, TransportError, TransportEvent};
+use libp2p::core::Transport;
+use parking_lot::Mutex;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+use std::io;
+use std::num::NonZeroU64;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{ready, Context, Poll};
+use std::time::Duration;
+use tokio::time::{Instant, Sleep};
+
+/// Size of the buffer used for reading from underlying connection at once.
+const READ_CHUNK_SIZE: usize = 64 * 1024;
+/// Minimal delay caused by retransmission of the lost packet.
+const MIN_RETRANSMISSION_DELAY: Duration = Duration::from_millis(200);
+
+/// Conditions of the link between two nodes.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct LinkConditions {
+    /// One-way latency.
+    pub(crate) latency: Duration,
+    /// Probability of the packet loss in range `0.0..=1.0`.
+    ///
+    /// Transport is reliable, so lost packet is retransmitted, which results in additional delay
+    /// of the corresponding chunk of data.
+    pub(crate) packet_loss: f64,
+    /// Bandwidth limit in bytes per second, unlimited if `None`.
+    pub(crate) bandwidth: Option<NonZeroU64>,
+}
+
+impl LinkConditions {
+    /// Time it takes to push `bytes` through the link, zero if bandwidth is unlimited.
+    fn serialization_delay(&self, bytes: usize) -> Duration {
+        match self.bandwidth {
+            Some(bandwidth) => Duration::from_secs_f64(bytes as f64 / bandwidth.get() as f64),
+            None => Duration::ZERO,
+        }
+    }
+
+    /// Time it takes for data to reach the other side once it was pushed through the link.
+    fn propagation_delay<R>(&self, rng: &mut R) -> Duration
+    where
+        R: Rng,
+    {
+        let mut delay = self.latency;
+
+        if self.packet_loss > 0.0 && rng.gen_bool(self.packet_loss.min(1.0)) {
+            delay += (self.latency * 3).max(MIN_RETRANSMISSION_DELAY);
+        }
+
+        delay
+    }
+}
+
+#[derive(Debug)]
+struct NetworkConditionsInner {
+    default_link: LinkConditions,
+    links: HashMap<(usize, usize), LinkConditions>,
+    /// Partition group of each node, nodes are only reachable within the same group.
+    partitions: Option<HashMap<usize, usize>>,
+    /// Mapping from memory transport port to node index.
+    ports: HashMap<u64, usize>,
+    rng: StdRng,
+}
+
+/// Conditions of the whole simulated network, shared between transports of all nodes.
+#[derive(Debug, Clone)]
+pub(crate) struct NetworkConditions {
+    inner: Arc<Mutex<NetworkConditionsInner>>,
+}
+
+impl NetworkConditions {
+    /// Create network conditions with the same conditions for every link, `seed` determines
+    /// packet losses.
+    pub(crate) fn new(default_link: LinkConditions, seed: u64) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(NetworkConditionsInner {
+                default_link,
+                links: HashMap::new(),
+                partitions: None,
+                ports: HashMap::new(),
+                rng: StdRng::seed_from_u64(seed),
+            })),
+        }
+    }
+
+    /// Set conditions of the link between two nodes (in both directions).
+    pub(crate) fn set_link(&self, a: usize, b: usize, conditions: LinkConditions) {
+        self.inner.lock().links.insert(link_key(a, b), conditions);
+    }
+
+    /// Set conditions for all links that don't have explicitly specified conditions.
+    pub(crate) fn set_default_link(&self, conditions: LinkConditions) {
+        self.inner.lock().default_link = conditions;
+    }
+
+    /// Split the network into groups of nodes, nodes that are not mentioned in any group will be
+    /// isolated from everyone else.
+    pub(crate) fn partition(&self, groups: &[&[usize]]) {
+        let partitions = groups
+            .iter()
+            .enumerate()
+            .flat_map(|(group, nodes)| nodes.iter().map(move |&node| (node, group)))
+            .collect();
+
+        self.inner.lock().partitions.replace(partitions);
+    }
+
+    /// Remove all network partitions.
+    pub(crate) fn heal_partitions(&self) {
+        self.inner.lock().partitions.take();
+    }
+
+    /// Whether two nodes can reach each other.
+    pub(crate) fn is_reachable(&self, a: usize, b: usize) -> bool {
+        match &self.inner.lock().partitions {
+            Some(partitions) => match (partitions.get(&a), partitions.get(&b)) {
+                (Some(group_a), Some(group_b)) => group_a == group_b,
+                _ => false,
+            },
+            None => true,
+        }
+    }
+
+    pub(super) fn register_port(&self, port: u64, node: usize) {
+        self.inner.lock().ports.insert(port, node);
+    }
+
+    fn node_by_port(&self, port: u64) -> Option<usize> {
+        self.inner.lock().ports.get(&port).copied()
+    }
+
+    /// Serialization and propagation delays of `bytes` sent from one node to another.
+    fn transmission_delays(&self, from: usize, to: usize, bytes: usize) -> (Duration, Duration) {
+        let inner = &mut *self.inner.lock();
+        let conditions = inner
+            .links
+            .get(&link_key(from, to))
+            .copied()
+            .unwrap_or(inner.default_link);
+
+        (
+            conditions.serialization_delay(bytes),
+            conditions.propagation_delay(&mut inner.rng),
+        )
+    }
+}
+
+fn link_key(a: usize, b: usize) -> (usize, usize) {
+    (a.min(b), a.max(b))
+}
+
+fn connection_reset() -> io::Error {
+    io::Error::new(io::ErrorKind::ConnectionReset, "Nodes are partitioned")
+}
+
+/// Stream that applies network conditions to incoming data.
+pub(crate) struct SimulatedStream {
+    inner: Channel<Vec<u8>>,
+    network: NetworkConditions,
+    local_node: usize,
+    remote_node: usize,
+    /// Data received from underlying channel along with time when it is delivered.
+    incoming: VecDeque<(Instant, Vec<u8>)>,
+    /// Offset in the front chunk of incoming data that was already read.
+    incoming_offset: usize,
+    /// Time until which the link is busy transmitting previously received data, used to enforce
+    /// bandwidth limit.
+    link_busy_until: Instant,
+    /// Time when last chunk of data is delivered, used to keep chunks ordered.
+    last_delivery: Instant,
+    delivery_timer: Option<Pin<Box<Sleep>>>,
+    inner_finished: bool,
+}
+
+impl SimulatedStream {
+    fn new(
+        inner: Channel<Vec<u8>>,
+        network: NetworkConditions,
+        local_node: usize,
+        remote_node: usize,
+    ) -> Self {
+        Self {
+            inner,
+            network,
+            local_node,
+            remote_node,
+            incoming: VecDeque::new(),
+            incoming_offset: 0,
+            link_busy_until: Instant::now(),
+            last_delivery: Instant::now(),
+            delivery_timer: None,
+            inner_finished: false,
+        }
+    }
+
+    fn check_reachable(&self) -> io::Result<()> {
+        if self.network.is_reachable(self.local_node, self.remote_node) {
+            Ok(())
+        } else {
+            Err(connection_reset())
+        }
+    }
+
+    /// Read everything available from underlying channel into incoming queue.
+    fn poll_fill_incoming(&mut self, cx: &mut Context<'_>) -> io::Result<()> {
+        while !self.inner_finished {
+            let mut buffer = vec![0; READ_CHUNK_SIZE];
+            match Pin::new(&mut self.inner).poll_read(cx, &mut buffer) {
+                Poll::Ready(Ok(0)) => {
+                    self.inner_finished = true;
+                }
+                Poll::Ready(Ok(read)) => {
+                    buffer.truncate(read);
+                    let (serialization_delay, propagation_delay) = self
+                        .network
+                        .transmission_delays(self.remote_node, self.local_node, read);
+                    // Chunk can only start being transmitted once previous chunks are through
+                    let transmission_start = Instant::now().max(self.link_busy_until);
+                    self.link_busy_until = transmission_start + serialization_delay;
+                    let delivery =
+                        (self.link_busy_until + propagation_delay).max(self.last_delivery);
+                    self.last_delivery = delivery;
+                    self.incoming.push_back((delivery, buffer));
+                }
+                Poll::Ready(Err(error)) => {
+                    return Err(error);
+                }
+                Poll::Pending => {
+                    break;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl AsyncRead for SimulatedStream {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        self.check_reachable()?;
+        self.poll_fill_incoming(cx)?;
+
+        let Some(&(delivery, _)) = self.incoming.front() else {
+            return if self.inner_finished {
+                Poll::Ready(Ok(0))
+            } else {
+                Poll::Pending
+            };
+        };
+
+        if delivery > Instant::now() {
+            let delivery_timer = self
+                .delivery_timer
+                .get_or_insert_with(|| Box::pin(tokio::time::sleep_until(delivery)));
+            delivery_timer.as_mut().reset(delivery);
+            ready!(delivery_timer.as_mut().poll(cx));
+        }
+        self.delivery_timer.take();
+
+        let incoming_offset = self.incoming_offset;
+        let (_, chunk) = self
+            .incoming
+            .front()
+            .expect("Checked above that queue is not empty; qed");
+        let read = buf.len().min(chunk.len() - incoming_offset);
+        buf[..read].copy_from_slice(&chunk[incoming_offset..][..read]);
+
+        if incoming_offset + read == chunk.len() {
+            self.incoming.pop_front();
+            self.incoming_offset = 0;
+        } else {
+            self.incoming_offset += read;
+        }
+
+        Poll::Ready(Ok(read))
+    }
+}
+
+impl AsyncWrite for SimulatedStream {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        self.check_reachable()?;
+
+        Pin::new(&mut self.inner).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.check_reachable()?;
+
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_close(cx)
+    }
+}
+
+/// Memory transport of a single node that applies network conditions to its connections.
+pub(crate) struct SimulatedTransport {
+    inner: MemoryTransport,
+    network: NetworkConditions,
+    local_node: usize,
+}
+
+impl SimulatedTransport {
+    pub(crate) fn new(network: NetworkConditions, local_node: usize) -> Self {
+        Self {
+            inner: MemoryTransport::default(),
+            network,
+            local_node,
+        }
+    }
+}
+
+fn memory_port(address: &Multiaddr) -> Option<u64> {
+    match address.iter().next() {
+        Some(Protocol::Memory(port)) => Some(port),
+        _ => None,
+    }
+}
+
+impl Transport for SimulatedTransport {
+    type Output = SimulatedStream;
+    type Error = io::Error;
+    type ListenerUpgrade = BoxFuture<'static, Result<Self::Output, Self::Error>>;
+    type Dial = BoxFuture<'static, Result<Self::Output, Self::Error>>;
+
+    fn listen_on(
+        &mut self,
+        id: ListenerId,
+        addr: Multiaddr,
+    ) -> Result<(), TransportError<Self::Error>> {
+        if let Some(port) = memory_port(&addr) {
+            self.network.register_port(port, self.local_node);
+        }
+
+        self.inner
+            .listen_on(id, addr)
+            .map_err(|error| error.map(|error| io::Error::new(io::ErrorKind::Other, error)))
+    }
+
+    fn remove_listener(&mut self, id: ListenerId) -> bool {
+        self.inner.remove_listener(id)
+    }
+
+    fn dial(&mut self, addr: Multiaddr) -> Result<Self::Dial, TransportError<Self::Error>> {
+        let Some(remote_node) = memory_port(&addr).and_then(|port| self.network.node_by_port(port))
+        else {
+            return Err(TransportError::MultiaddrNotSupported(addr));
+        };
+
+        if !self.network.is_reachable(self.local_node, remote_node) {
+            return Err(TransportError::Other(io::Error::new(
+                io::ErrorKind::ConnectionRefused,
+                "Nodes are partitioned",
+            )));
+        }
+
+        let dial = self
+            .inner
+            .dial(addr)
+            .map_err(|error| error.map(|error| io::Error::new(io::ErrorKind::Other, error)))?;
+        let network = self.network.clone();
+        let local_node = self.local_node;
+
+        Ok(async move {
+            let mut channel = dial
+                .await
+                .map_err(|error| io::Error::new(io::ErrorKind::Other, error))?;
+
+            // Let the other side know who is connecting
+            channel
+                .write_all(&(local_node as u64).to_le_bytes())
+                .await?;
+            channel.flush().await?;
+
+            Ok(SimulatedStream::new(
+                channel,
+                network,
+                local_node,
+                remote_node,
+            ))
+        }
+        .boxed())
+    }
+
+    fn dial_as_listener(
+        &mut self,
+        addr: Multiaddr,
+    ) -> Result<Self::Dial, TransportError<Self::Error>> {
+        self.dial(addr)
+    }
+
+    fn address_translation(&self, _listen: &Multiaddr, _observed: &Multiaddr) -> Option<Multiaddr> {
+        None
+    }
+
+    fn poll(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<TransportEvent<Self::ListenerUpgrade, Self::Error>> {
+        let event = ready!(Pin::new(&mut self.inner).poll(cx));
+        let network = self.network.clone();
+        let local_node = self.local_node;
+
+        Poll::Ready(
+            event
+                .map_upgrade(move |upgrade| {
+                    async move {
+                        let mut channel = upgrade
+                            .await
+                            .map_err(|error| io::Error::new(io::ErrorKind::Other, error))?;
+
+                        let mut remote_node = [0; 8];
+                        channel.read_exact(&mut remote_node).await?;
+                        let remote_node = u64::from_le_bytes(remote_node) as usize;
+
+                        if !network.is_reachable(local_node, remote_node) {
+                            return Err(connection_reset());
+                        }
+
+                        Ok(SimulatedStream::new(
+                            channel,
+                            network,
+                            local_node,
+                            remote_node,
+                        ))
+                    }
+                    .boxed()
+                })
+                .map_err(|error| io::Error::new(io::ErrorKind::Other, error)),
+        )
+    }
+}
@@ -0,0 +1,71 @@
+use crate::simulation::transport::{LinkConditions, NetworkConditions, SimulatedTransport};
+use futures::{AsyncReadExt, AsyncWriteExt};
+use libp2p::core::multiaddr::{Multiaddr, Protocol};
+use libp2p::core::transport::{ListenerId, TransportEvent};
+use libp2p::core::Transport;
+use std::num::NonZeroU64;
+use std::pin::Pin;
+use std::time::Duration;
+use tokio::time::Instant;
+
+#[tokio::test]
+async fn test_bandwidth_limit() {
+    const BANDWIDTH: u64 = 1024 * 1024;
+    const TRANSFER_SIZE: usize = 512 * 1024;
+    const LATENCY: Duration = Duration::from_millis(10);
+
+    let network = NetworkConditions::new(
+        LinkConditions {
+            latency: LATENCY,
+            packet_loss: 0.0,
+            bandwidth: NonZeroU64::new(BANDWIDTH),
+        },
+        0,
+    );
+    let mut listener = SimulatedTransport::new(network.clone(), 0);
+    let mut dialer = SimulatedTransport::new(network, 1);
+
+    let port = rand::random::<u64>().max(1);
+    let address = Multiaddr::empty().with(Protocol::Memory(port));
+    listener
+        .listen_on(ListenerId::next(), address.clone())
+        .unwrap();
+
+    let dial = dialer.dial(address).unwrap();
+    let accept = async {
+        loop {
+            let event = futures::future::poll_fn(|cx| Pin::new(&mut listener).poll(cx)).await;
+            if let TransportEvent::Incoming { upgrade, .. } = event {
+                break upgrade.await;
+            }
+        }
+    };
+    let (outgoing, incoming) = tokio::join!(dial, accept);
+    let mut outgoing = outgoing.unwrap();
+    let mut incoming = incoming.unwrap();
+
+    let start = Instant::now();
+    let send = async {
+        outgoing.write_all(&vec![1; TRANSFER_SIZE]).await.unwrap();
+        outgoing.flush().await.unwrap();
+    };
+    let receive = async {
+        let mut received = vec![0; TRANSFER_SIZE];
+        incoming.read_exact(&mut received).await.unwrap();
+        received
+    };
+    let ((), received) = tokio::join!(send, receive);
+    let elapsed = start.elapsed();
+
+    assert!(received.iter().all(|&byte| byte == 1));
+    // Whole transfer has to go through the link, not just individual chunks
+    let expected = Duration::from_secs_f64(TRANSFER_SIZE as f64 / BANDWIDTH as f64) + LATENCY;
+    assert!(
+        elapsed >= expected.mul_f64(0.9),
+        "Transfer took {elapsed:?}, expected about {expected:?}"
+    );
+    assert!(
+        elapsed <= expected * 2,
+        "Transfer took {elapsed:?}, expected about {expected:?}"
+    );
+}
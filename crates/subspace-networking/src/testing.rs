@@ -0,0 +1,61 @@
+//! Utilities for testing of networking and crates that build on top of it.
+
+use crate::utils::multihash::ToMultihash;
+use crate::LocalRecordProvider;
+use libp2p::kad::{ProviderRecord, RecordKey};
+use libp2p::PeerId;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::sync::Arc;
+use subspace_core_primitives::{Piece, PieceIndex};
+
+/// In-memory piece cache, pieces are announced as provider records of the local peer.
+#[derive(Debug, Clone)]
+pub struct TestPieceCache {
+    peer_id: PeerId,
+    pieces: Arc<Mutex<HashMap<RecordKey, Piece>>>,
+}
+
+impl LocalRecordProvider for TestPieceCache {
+    fn record(&self, key: &RecordKey) -> Option<ProviderRecord> {
+        self.pieces
+            .lock()
+            .contains_key(key)
+            .then(|| ProviderRecord {
+                key: key.clone(),
+                provider: self.peer_id,
+                expires: None,
+                addresses: Vec::new(),
+            })
+    }
+}
+
+impl TestPieceCache {
+    /// Create empty piece cache of the peer with specified ID.
+    pub fn new(peer_id: PeerId) -> Self {
+        Self {
+            peer_id,
+            pieces: Arc::default(),
+        }
+    }
+
+    /// Put pieces into the cache.
+    pub fn insert_pieces<I>(&self, pieces: I)
+    where
+        I: IntoIterator<Item = (PieceIndex, Piece)>,
+    {
+        self.pieces.lock().extend(
+            pieces
+                .into_iter()
+                .map(|(piece_index, piece)| (piece_index.to_multihash().into(), piece)),
+        );
+    }
+
+    /// Get piece from the cache.
+    pub fn get_piece(&self, piece_index: PieceIndex) -> Option<Piece> {
+        self.pieces
+            .lock()
+            .get(&piece_index.to_multihash().into())
+            .cloned()
+    }
+}
@@ -1,8 +1,11 @@
 //! Miscellaneous utilities for networking.
 
 pub mod multihash;
+pub mod object_fetcher;
 pub mod piece_provider;
+pub mod piece_validator;
 pub(crate) mod rate_limiter;
+pub mod segment_header_downloader;
 #[cfg(test)]
 mod tests;
 pub(crate) mod unique_record_binary_heap;
@@ -0,0 +1,288 @@
+//! Retrieval of objects stored in archived history of the blockchain.
+//!
+//! Objects are located using [`GlobalObject`] mapping (piece index and offset within raw record of
+//! that piece), stored in SCALE-encoded form (compact length prefix followed by object bytes) and
+//! might span multiple pieces and even segments. Retrieved object is verified against expected
+//! Blake3 hash.
+
+#[cfg(test)]
+mod tests;
+
+use crate::utils::piece_provider::{PieceProvider, PieceValidator};
+use async_trait::async_trait;
+use parity_scale_codec::{Compact, CompactLen, Decode};
+use std::error::Error;
+use std::fmt;
+use std::sync::Arc;
+use subspace_core_primitives::crypto::{blake3_hash, Scalar};
+use subspace_core_primitives::objects::GlobalObject;
+use subspace_core_primitives::{Blake3Hash, Piece, PieceIndex, RawRecord, SegmentHeader};
+use thiserror::Error;
+use tracing::{debug, trace};
+
+/// Maximum size of the object, objects are stored in blocks, hence can't be larger than a block.
+pub const MAX_OBJECT_SIZE: usize = 5 * 1024 * 1024;
+/// Maximum length of SCALE-encoded compact `u64`.
+const MAX_COMPACT_LENGTH: usize = 9;
+/// Encoding of `Segment::V0` variant from `subspace-archiving`.
+const SEGMENT_V0_VARIANT: u8 = 0;
+/// Encoding of `SegmentItem::BlockContinuation` variant from `subspace-archiving`.
+const SEGMENT_ITEM_BLOCK_CONTINUATION_VARIANT: u8 = 3;
+/// Encoding of `SegmentItem::ParentSegmentHeader` variant from `subspace-archiving`.
+const SEGMENT_ITEM_PARENT_SEGMENT_HEADER_VARIANT: u8 = 4;
+
+/// Object fetching errors.
+#[derive(Debug, Error)]
+pub enum ObjectFetchingError {
+    /// Objects can only start in source pieces.
+    #[error("Piece index {piece_index} is not a source piece")]
+    NotSourcePiece {
+        /// Piece index from the object mapping
+        piece_index: PieceIndex,
+    },
+    /// Offset is outside of raw record.
+    #[error("Offset {offset} is outside of raw record")]
+    InvalidOffset {
+        /// Offset from the object mapping
+        offset: u32,
+    },
+    /// Failed to get piece.
+    #[error("Failed to get piece {piece_index}: {error}")]
+    PieceGetter {
+        /// Piece index
+        piece_index: PieceIndex,
+        /// Low-level error
+        error: Box<dyn Error + Send + Sync + 'static>,
+    },
+    /// Piece was not found.
+    #[error("Piece {piece_index} was not found")]
+    PieceNotFound {
+        /// Piece index
+        piece_index: PieceIndex,
+    },
+    /// Beginning of the segment has unexpected contents.
+    #[error("Unexpected segment beginning in piece {piece_index}: {error}")]
+    InvalidSegmentBeginning {
+        /// Piece index
+        piece_index: PieceIndex,
+        /// Low-level error
+        error: parity_scale_codec::Error,
+    },
+    /// Failed to decode object length.
+    #[error("Failed to decode object length: {0}")]
+    InvalidObjectLength(parity_scale_codec::Error),
+    /// Object is larger than allowed.
+    #[error("Object size {size} exceeds limit of {max_object_size} bytes")]
+    ObjectTooLarge {
+        /// Object size
+        size: u64,
+        /// Maximum allowed object size
+        max_object_size: usize,
+    },
+    /// Hash of retrieved object doesn't match expected hash.
+    #[error(
+        "Object hash mismatch: expected {}, actual {}",
+        hex::encode(expected),
+        hex::encode(actual)
+    )]
+    HashMismatch {
+        /// Expected object hash
+        expected: Blake3Hash,
+        /// Hash of retrieved bytes
+        actual: Blake3Hash,
+    },
+}
+
+/// Source of pieces for [`ObjectFetcher`].
+#[async_trait]
+pub trait ObjectPieceGetter: fmt::Debug {
+    /// Get piece by its index.
+    async fn get_piece(
+        &self,
+        piece_index: PieceIndex,
+    ) -> Result<Option<Piece>, Box<dyn Error + Send + Sync + 'static>>;
+}
+
+#[async_trait]
+impl<T> ObjectPieceGetter for Arc<T>
+where
+    T: ObjectPieceGetter + Send + Sync + ?Sized,
+{
+    async fn get_piece(
+        &self,
+        piece_index: PieceIndex,
+    ) -> Result<Option<Piece>, Box<dyn Error + Send + Sync + 'static>> {
+        self.as_ref().get_piece(piece_index).await
+    }
+}
+
+#[async_trait]
+impl<PV> ObjectPieceGetter for PieceProvider<PV>
+where
+    PV: PieceValidator,
+{
+    async fn get_piece(
+        &self,
+        piece_index: PieceIndex,
+    ) -> Result<Option<Piece>, Box<dyn Error + Send + Sync + 'static>> {
+        Ok(self.get_piece_from_cache(piece_index).await)
+    }
+}
+
+/// Fetches objects from archived history using provided piece getter.
+#[derive(Debug)]
+pub struct ObjectFetcher<PG> {
+    piece_getter: PG,
+    max_object_size: usize,
+}
+
+impl<PG> ObjectFetcher<PG>
+where
+    PG: ObjectPieceGetter,
+{
+    /// Create new instance, objects larger than `max_object_size` are rejected without retrieving
+    /// all of their pieces.
+    pub fn new(piece_getter: PG, max_object_size: usize) -> Self {
+        Self {
+            piece_getter,
+            max_object_size,
+        }
+    }
+
+    /// Fetch object by its mapping and verify it against expected hash.
+    ///
+    /// NOTE: Objects spanning segment boundary are expected to continue right after the beginning
+    /// of the next segment, which is how archiver splits blocks between segments.
+    pub async fn fetch_object(
+        &self,
+        global_object: GlobalObject,
+        object_hash: Blake3Hash,
+    ) -> Result<Vec<u8>, ObjectFetchingError> {
+        let mut piece_index = global_object.piece_index();
+        let offset = global_object.offset();
+
+        // Source pieces are interleaved with parity pieces
+        if piece_index.position() % 2 != 0 {
+            return Err(ObjectFetchingError::NotSourcePiece { piece_index });
+        }
+        if offset as usize >= RawRecord::SIZE {
+            return Err(ObjectFetchingError::InvalidOffset { offset });
+        }
+
+        trace!(%piece_index, %offset, "Fetching object");
+
+        let mut data = self.read_raw_record(piece_index).await?;
+        data.drain(..offset as usize);
+
+        // Length prefix might be split between pieces too
+        let (object_size, prefix_size) = loop {
+            match Compact::<u64>::decode(&mut data.as_slice()) {
+                Ok(Compact(object_size)) => {
+                    break (object_size, Compact::<u64>::compact_len(&object_size));
+                }
+                Err(_error) if data.len() < MAX_COMPACT_LENGTH => {
+                    piece_index = next_source_piece_index(piece_index);
+                    data.extend(self.read_continuation(piece_index).await?);
+                }
+                Err(error) => {
+                    return Err(ObjectFetchingError::InvalidObjectLength(error));
+                }
+            }
+        };
+
+        let object_size = usize::try_from(object_size)
+            .ok()
+            .filter(|&object_size| object_size <= self.max_object_size)
+            .ok_or(ObjectFetchingError::ObjectTooLarge {
+                size: object_size,
+                max_object_size: self.max_object_size,
+            })?;
+
+        while data.len() < prefix_size + object_size {
+            piece_index = next_source_piece_index(piece_index);
+            data.extend(self.read_continuation(piece_index).await?);
+        }
+
+        data.truncate(prefix_size + object_size);
+        data.drain(..prefix_size);
+
+        let actual = blake3_hash(&data);
+        if actual != object_hash {
+            debug!(
+                object_hash = %hex::encode(object_hash),
+                actual = %hex::encode(actual),
+                "Retrieved object doesn't match expected hash"
+            );
+
+            return Err(ObjectFetchingError::HashMismatch {
+                expected: object_hash,
+                actual,
+            });
+        }
+
+        Ok(data)
+    }
+
+    /// Read bytes of the next source piece that continue data of the previous source piece.
+    async fn read_continuation(
+        &self,
+        piece_index: PieceIndex,
+    ) -> Result<Vec<u8>, ObjectFetchingError> {
+        let mut data = self.read_raw_record(piece_index).await?;
+
+        if piece_index.position() == 0 {
+            let prefix_size = segment_prefix_size(&data).map_err(|error| {
+                ObjectFetchingError::InvalidSegmentBeginning { piece_index, error }
+            })?;
+            data.drain(..prefix_size);
+        }
+
+        Ok(data)
+    }
+
+    async fn read_raw_record(
+        &self,
+        piece_index: PieceIndex,
+    ) -> Result<Vec<u8>, ObjectFetchingError> {
+        let piece = self
+            .piece_getter
+            .get_piece(piece_index)
+            .await
+            .map_err(|error| ObjectFetchingError::PieceGetter { piece_index, error })?
+            .ok_or(ObjectFetchingError::PieceNotFound { piece_index })?;
+
+        // Only the first `Scalar::SAFE_BYTES` of each chunk of source record contain data
+        Ok(piece
+            .record()
+            .iter()
+            .flat_map(|chunk| &chunk[..Scalar::SAFE_BYTES])
+            .copied()
+            .collect())
+    }
+}
+
+/// Source pieces are located at even positions of the segment.
+fn next_source_piece_index(piece_index: PieceIndex) -> PieceIndex {
+    piece_index + PieceIndex::from(2)
+}
+
+/// Size of the data at the beginning of the segment that precedes continuation of the block from
+/// the previous segment: segment variant, parent segment header item and block continuation
+/// variant with its length.
+fn segment_prefix_size(data: &[u8]) -> Result<usize, parity_scale_codec::Error> {
+    let mut input = data;
+
+    if u8::decode(&mut input)? != SEGMENT_V0_VARIANT {
+        return Err("Unexpected segment variant".into());
+    }
+    if u8::decode(&mut input)? != SEGMENT_ITEM_PARENT_SEGMENT_HEADER_VARIANT {
+        return Err("Segment doesn't start with parent segment header".into());
+    }
+    SegmentHeader::decode(&mut input)?;
+    if u8::decode(&mut input)? != SEGMENT_ITEM_BLOCK_CONTINUATION_VARIANT {
+        return Err("Parent segment header is not followed by block continuation".into());
+    }
+    Compact::<u32>::decode(&mut input)?;
+
+    Ok(data.len() - input.len())
+}
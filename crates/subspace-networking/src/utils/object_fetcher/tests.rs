@@ -0,0 +1,141 @@
+use crate::utils::object_fetcher::{ObjectFetcher, ObjectFetchingError, ObjectPieceGetter};
+use async_trait::async_trait;
+use parity_scale_codec::{Compact, Encode};
+use rand::{thread_rng, Rng};
+use std::collections::HashMap;
+use std::error::Error;
+use subspace_archiving::archiver::Archiver;
+use subspace_core_primitives::crypto::blake3_hash;
+use subspace_core_primitives::crypto::kzg::{embedded_kzg_settings, Kzg};
+use subspace_core_primitives::objects::{BlockObject, BlockObjectMapping, GlobalObject};
+use subspace_core_primitives::{Blake3Hash, Piece, PieceIndex, RawRecord, RecordedHistorySegment};
+
+#[derive(Debug)]
+struct TestPieceGetter {
+    pieces: HashMap<PieceIndex, Piece>,
+}
+
+#[async_trait]
+impl ObjectPieceGetter for TestPieceGetter {
+    async fn get_piece(
+        &self,
+        piece_index: PieceIndex,
+    ) -> Result<Option<Piece>, Box<dyn Error + Send + Sync + 'static>> {
+        Ok(self.pieces.get(&piece_index).cloned())
+    }
+}
+
+/// Write SCALE-encoded object into the block at specified offset.
+fn write_object(block: &mut [u8], offset: usize, object: &[u8]) -> BlockObject {
+    let encoded_object = object.encode();
+    block[offset..][..encoded_object.len()].copy_from_slice(&encoded_object);
+
+    BlockObject::V0 {
+        hash: blake3_hash(object),
+        offset: offset as u32,
+    }
+}
+
+#[tokio::test]
+async fn test_fetch_objects_spanning_pieces_and_segments() {
+    let kzg = Kzg::new(embedded_kzg_settings());
+    let mut archiver = Archiver::new(kzg).unwrap();
+
+    let mut objects = HashMap::<Blake3Hash, Vec<u8>>::new();
+    // Block doesn't fit into the first segment and continues in the second one
+    let mut block_0 = vec![0u8; RecordedHistorySegment::SIZE + 10_000];
+    thread_rng().fill(block_0.as_mut_slice());
+    let block_0_object_mapping = BlockObjectMapping {
+        objects: [
+            // Object data spans two pieces
+            (RawRecord::SIZE * 2 - 500, 1000),
+            // Length prefix spans two pieces
+            (RawRecord::SIZE * 3 - 1, 100),
+            // Object spans two segments
+            (RecordedHistorySegment::SIZE - 500, 1000),
+        ]
+        .into_iter()
+        .map(|(offset, size)| {
+            let mut object = vec![0u8; size];
+            thread_rng().fill(object.as_mut_slice());
+            let block_object = write_object(&mut block_0, offset, &object);
+            objects.insert(block_object.hash(), object);
+            block_object
+        })
+        .collect(),
+    };
+
+    let mut block_1 = vec![0u8; RecordedHistorySegment::SIZE];
+    thread_rng().fill(block_1.as_mut_slice());
+
+    let archived_segments = archiver
+        .add_block(block_0, block_0_object_mapping, true)
+        .into_iter()
+        .chain(archiver.add_block(block_1, BlockObjectMapping::default(), true))
+        .collect::<Vec<_>>();
+    assert!(archived_segments.len() >= 2);
+
+    let mut pieces = HashMap::new();
+    let mut global_objects = Vec::new();
+    for archived_segment in &archived_segments {
+        let segment_index = archived_segment.segment_header.segment_index();
+
+        for (source_position, (piece, piece_object_mapping)) in archived_segment
+            .pieces
+            .source()
+            .zip(&archived_segment.object_mapping)
+            .enumerate()
+        {
+            let piece_index = segment_index.source_piece_index(source_position as u32);
+            pieces.insert(piece_index, Piece::from(piece));
+
+            for piece_object in &piece_object_mapping.objects {
+                global_objects.push((
+                    GlobalObject::V0 {
+                        piece_index,
+                        offset: piece_object.offset(),
+                    },
+                    piece_object.hash(),
+                ));
+            }
+        }
+    }
+    assert_eq!(global_objects.len(), objects.len());
+
+    let object_fetcher = ObjectFetcher::new(TestPieceGetter { pieces }, 2048);
+
+    for &(global_object, object_hash) in &global_objects {
+        let object = object_fetcher
+            .fetch_object(global_object, object_hash)
+            .await
+            .unwrap();
+        assert_eq!(&object, objects.get(&object_hash).unwrap());
+    }
+
+    let (global_object, _object_hash) = global_objects[0];
+    assert!(matches!(
+        object_fetcher
+            .fetch_object(global_object, Blake3Hash::default())
+            .await,
+        Err(ObjectFetchingError::HashMismatch { .. })
+    ));
+
+    let parity_global_object = GlobalObject::V0 {
+        piece_index: global_object.piece_index() + PieceIndex::ONE,
+        offset: global_object.offset(),
+    };
+    assert!(matches!(
+        object_fetcher
+            .fetch_object(parity_global_object, Blake3Hash::default())
+            .await,
+        Err(ObjectFetchingError::NotSourcePiece { .. })
+    ));
+
+    let small_object_fetcher = ObjectFetcher::new(object_fetcher.piece_getter, 10);
+    assert!(matches!(
+        small_object_fetcher
+            .fetch_object(global_object, Blake3Hash::default())
+            .await,
+        Err(ObjectFetchingError::ObjectTooLarge { .. })
+    ));
+}
@@ -1,15 +1,29 @@
 //! Provides methods to retrieve pieces from DSN.
 
+#[cfg(test)]
+mod tests;
+
 use crate::utils::multihash::ToMultihash;
 use crate::{Node, PieceByIndexRequest, PieceByIndexResponse};
 use async_trait::async_trait;
+use futures::stream::FuturesUnordered;
 use futures::StreamExt;
 use libp2p::PeerId;
 use std::collections::HashSet;
 use std::fmt;
-use subspace_core_primitives::{Piece, PieceIndex};
+use std::future::Future;
+use std::time::Duration;
+use subspace_core_primitives::{
+    ArchivedHistorySegment, Piece, PieceIndex, RecordedHistorySegment, SegmentIndex,
+};
+use tokio::sync::Semaphore;
+use tokio::time::timeout;
 use tracing::{debug, trace, warn};
 
+/// Timeout for piece requests to peers in local network, they are expected to respond quickly and
+/// unresponsive peer shouldn't delay fallback to DHT providers
+pub(super) const LAN_PEERS_REQUEST_TIMEOUT: Duration = Duration::from_secs(3);
+
 /// Validates piece against using its commitment.
 #[async_trait]
 pub trait PieceValidator: Sync + Send {
@@ -57,7 +71,22 @@ where
     }
 
     /// Returns piece by its index from farmer's piece cache (L2)
+    ///
+    /// Peers discovered in local network are queried concurrently first before looking for
+    /// providers in DHT.
     pub async fn get_piece_from_cache(&self, piece_index: PieceIndex) -> Option<Piece> {
+        if let Some((peer_id, piece)) = get_piece_from_lan_peers(
+            self.node.lan_peers(),
+            LAN_PEERS_REQUEST_TIMEOUT,
+            |peer_id| self.get_piece_from_peer(peer_id, piece_index),
+        )
+        .await
+        {
+            trace!(%peer_id, %piece_index, "Piece retrieved from local network peer.");
+
+            return Some(piece);
+        }
+
         let key = piece_index.to_multihash();
 
         let mut request_batch = self.node.get_requests_batch_handle().await;
@@ -263,3 +292,115 @@ where
         None
     }
 }
+
+/// Requests piece from all `lan_peers` concurrently using `get_piece_from_peer`, returns the first
+/// piece received alongside the peer it was received from.
+///
+/// Each request is limited by `request_timeout`, remaining requests are cancelled once piece is
+/// received.
+async fn get_piece_from_lan_peers<GP, Fut>(
+    lan_peers: Vec<PeerId>,
+    request_timeout: Duration,
+    get_piece_from_peer: GP,
+) -> Option<(PeerId, Piece)>
+where
+    GP: Fn(PeerId) -> Fut,
+    Fut: Future<Output = Option<Piece>>,
+{
+    let get_piece_from_peer = &get_piece_from_peer;
+
+    let mut piece_requests = lan_peers
+        .into_iter()
+        .map(|peer_id| async move {
+            match timeout(request_timeout, get_piece_from_peer(peer_id)).await {
+                Ok(maybe_piece) => maybe_piece.map(|piece| (peer_id, piece)),
+                Err(_elapsed) => {
+                    debug!(%peer_id, "Piece request to local network peer timed out");
+                    None
+                }
+            }
+        })
+        .collect::<FuturesUnordered<_>>();
+
+    while let Some(maybe_result) = piece_requests.next().await {
+        if maybe_result.is_some() {
+            return maybe_result;
+        }
+    }
+
+    None
+}
+
+/// Retrieves enough pieces of the segment for its reconstruction using `get_piece`.
+///
+/// Source pieces are requested first, parity pieces are only requested in place of pieces that
+/// failed to be retrieved. Returned vector is indexed by piece position in the segment, pieces
+/// that were not retrieved are `None`. Retrieval stops once
+/// [`RecordedHistorySegment::NUM_RAW_RECORDS`] pieces were received, if fewer pieces are returned
+/// then segment can't be reconstructed.
+pub async fn download_segment_pieces<GP, Fut>(
+    segment_index: SegmentIndex,
+    get_piece: GP,
+) -> Vec<Option<Piece>>
+where
+    GP: Fn(PieceIndex) -> Fut,
+    Fut: Future<Output = Option<Piece>>,
+{
+    debug!(%segment_index, "Retrieving pieces of the segment");
+
+    let semaphore = &Semaphore::new(RecordedHistorySegment::NUM_RAW_RECORDS);
+    let get_piece = &get_piece;
+
+    let mut received_segment_pieces = segment_index
+        .segment_piece_indexes_source_first()
+        .into_iter()
+        .map(|piece_index| {
+            // Source pieces will acquire permit here right away
+            let maybe_permit = semaphore.try_acquire().ok();
+
+            async move {
+                let permit = match maybe_permit {
+                    Some(permit) => permit,
+                    // Other pieces will acquire permit here instead
+                    None => semaphore.acquire().await.ok()?,
+                };
+                let maybe_piece = get_piece(piece_index).await;
+
+                trace!(
+                    %piece_index,
+                    piece_found = maybe_piece.is_some(),
+                    "Piece request finished"
+                );
+
+                maybe_piece.map(|piece| {
+                    // Piece was received successfully, "remove" this slot from semaphore
+                    permit.forget();
+                    (piece_index, piece)
+                })
+            }
+        })
+        .collect::<FuturesUnordered<_>>();
+
+    let mut segment_pieces = vec![None::<Piece>; ArchivedHistorySegment::NUM_PIECES];
+    let mut pieces_received = 0;
+
+    while let Some(maybe_result) = received_segment_pieces.next().await {
+        let Some((piece_index, piece)) = maybe_result else {
+            continue;
+        };
+
+        segment_pieces
+            .get_mut(piece_index.position() as usize)
+            .expect("Piece position is by definition within segment; qed")
+            .replace(piece);
+
+        pieces_received += 1;
+
+        if pieces_received >= RecordedHistorySegment::NUM_RAW_RECORDS {
+            trace!(%segment_index, "Received half of the segment.");
+            break;
+        }
+    }
+
+    segment_pieces
+}
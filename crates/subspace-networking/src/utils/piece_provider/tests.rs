@@ -0,0 +1,131 @@
+use super::{download_segment_pieces, get_piece_from_lan_peers, LAN_PEERS_REQUEST_TIMEOUT};
+use futures::future;
+use libp2p::PeerId;
+use parking_lot::Mutex;
+use std::collections::HashSet;
+use std::time::{Duration, Instant};
+use subspace_core_primitives::{
+    ArchivedHistorySegment, Piece, PieceIndex, RecordedHistorySegment, SegmentIndex,
+};
+use tokio::time::sleep;
+
+#[tokio::test]
+async fn segment_pieces_download_source_first() {
+    let requested = Mutex::new(HashSet::<PieceIndex>::new());
+
+    let segment_pieces = download_segment_pieces(SegmentIndex::ONE, |piece_index| {
+        requested.lock().insert(piece_index);
+
+        async move { Some(Piece::default()) }
+    })
+    .await;
+
+    assert_eq!(segment_pieces.len(), ArchivedHistorySegment::NUM_PIECES);
+    assert_eq!(
+        segment_pieces.iter().flatten().count(),
+        RecordedHistorySegment::NUM_RAW_RECORDS
+    );
+    // Only source pieces are requested when all of them are available
+    let requested = requested.into_inner();
+    assert_eq!(requested.len(), RecordedHistorySegment::NUM_RAW_RECORDS);
+    assert!(requested.iter().all(
+        |piece_index| piece_index.segment_index() == SegmentIndex::ONE
+            && piece_index.position() % 2 == 0
+    ));
+}
+
+#[tokio::test]
+async fn segment_pieces_download_replaces_missing_pieces() {
+    // Every 4th piece is missing, which includes half of the source pieces
+    let segment_pieces = download_segment_pieces(SegmentIndex::ZERO, |piece_index| async move {
+        (piece_index.position() % 4 != 0).then(Piece::default)
+    })
+    .await;
+
+    assert_eq!(
+        segment_pieces.iter().flatten().count(),
+        RecordedHistorySegment::NUM_RAW_RECORDS
+    );
+    for (position, maybe_piece) in segment_pieces.iter().enumerate() {
+        if position % 4 == 0 {
+            assert!(maybe_piece.is_none(), "Piece at {position} is missing");
+        }
+    }
+}
+
+#[tokio::test]
+async fn segment_pieces_download_not_enough_pieces() {
+    let segment_pieces = download_segment_pieces(SegmentIndex::ZERO, |piece_index| async move {
+        (piece_index.position() % 3 == 0).then(Piece::default)
+    })
+    .await;
+
+    assert!(segment_pieces.iter().flatten().count() < RecordedHistorySegment::NUM_RAW_RECORDS);
+}
+
+#[tokio::test]
+async fn lan_peers_are_queried_concurrently() {
+    let unresponsive_peer_id = PeerId::random();
+    let empty_peer_id = PeerId::random();
+    let slow_peer_id = PeerId::random();
+    let requested = Mutex::new(HashSet::<PeerId>::new());
+
+    let started = Instant::now();
+    let result = get_piece_from_lan_peers(
+        vec![unresponsive_peer_id, empty_peer_id, slow_peer_id],
+        LAN_PEERS_REQUEST_TIMEOUT,
+        |peer_id| {
+            requested.lock().insert(peer_id);
+
+            async move {
+                if peer_id == unresponsive_peer_id {
+                    future::pending::<()>().await;
+                } else if peer_id == slow_peer_id {
+                    sleep(Duration::from_millis(100)).await;
+
+                    return Some(Piece::default());
+                }
+
+                None
+            }
+        },
+    )
+    .await;
+
+    // Unresponsive peer that was queried first doesn't block other peers
+    assert_eq!(result, Some((slow_peer_id, Piece::default())));
+    assert!(started.elapsed() < LAN_PEERS_REQUEST_TIMEOUT);
+    assert_eq!(requested.into_inner().len(), 3);
+}
+
+#[tokio::test]
+async fn lan_peers_requests_time_out() {
+    let request_timeout = Duration::from_millis(100);
+
+    let started = Instant::now();
+    let result = get_piece_from_lan_peers(
+        vec![PeerId::random(), PeerId::random()],
+        request_timeout,
+        |_peer_id| async move {
+            future::pending::<()>().await;
+
+            Some(Piece::default())
+        },
+    )
+    .await;
+
+    assert_eq!(result, None);
+    assert!(started.elapsed() >= request_timeout);
+    assert!(started.elapsed() < LAN_PEERS_REQUEST_TIMEOUT);
+
+    // Nothing to do without peers
+    assert_eq!(
+        get_piece_from_lan_peers(
+            Vec::new(),
+            request_timeout,
+            |_peer_id| -> future::Ready<Option<Piece>> { unreachable!("No peers to query") },
+        )
+        .await,
+        None
+    );
+}
@@ -0,0 +1,89 @@
+//! Validation of pieces retrieved from DSN against segment commitments.
+
+use crate::utils::piece_provider::PieceValidator;
+use crate::Node;
+use async_trait::async_trait;
+use libp2p::PeerId;
+use subspace_archiving::archiver::is_piece_valid;
+use subspace_core_primitives::crypto::kzg::Kzg;
+use subspace_core_primitives::{Piece, PieceIndex, SegmentCommitment, SegmentIndex};
+use tracing::{debug, warn};
+
+/// Source of segment commitments that pieces are validated against.
+#[async_trait]
+pub trait SegmentCommitmentSource: Send + Sync {
+    /// Segment commitment of the segment with `segment_index`, `None` if segment is not known.
+    async fn segment_commitment(&self, segment_index: SegmentIndex) -> Option<SegmentCommitment>;
+}
+
+/// Validates pieces against segment commitments, bans peers that sent invalid pieces.
+#[derive(Clone)]
+pub struct SegmentCommitmentPieceValidator<SCS> {
+    dsn_node: Node,
+    kzg: Kzg,
+    segment_commitment_source: SCS,
+}
+
+impl<SCS> SegmentCommitmentPieceValidator<SCS> {
+    /// Create new instance
+    pub fn new(dsn_node: Node, kzg: Kzg, segment_commitment_source: SCS) -> Self {
+        Self {
+            dsn_node,
+            kzg,
+            segment_commitment_source,
+        }
+    }
+}
+
+#[async_trait]
+impl<SCS> PieceValidator for SegmentCommitmentPieceValidator<SCS>
+where
+    SCS: SegmentCommitmentSource,
+{
+    async fn validate_piece(
+        &self,
+        source_peer_id: PeerId,
+        piece_index: PieceIndex,
+        piece: Piece,
+    ) -> Option<Piece> {
+        if source_peer_id == self.dsn_node.id() {
+            return Some(piece);
+        }
+
+        let segment_index = piece_index.segment_index();
+
+        let Some(segment_commitment) = self
+            .segment_commitment_source
+            .segment_commitment(segment_index)
+            .await
+        else {
+            debug!(%segment_index, "Segment commitment is not known, can't validate piece");
+
+            return None;
+        };
+
+        let is_valid_fut = tokio::task::spawn_blocking({
+            let kzg = self.kzg.clone();
+
+            move || {
+                is_piece_valid(&kzg, &piece, &segment_commitment, piece_index.position())
+                    .then_some(piece)
+            }
+        });
+
+        match is_valid_fut.await.unwrap_or_default() {
+            Some(piece) => Some(piece),
+            None => {
+                warn!(
+                    %piece_index,
+                    %source_peer_id,
+                    "Received invalid piece from peer"
+                );
+
+                // We don't care about result here
+                let _ = self.dsn_node.ban_peer(source_peer_id).await;
+                None
+            }
+        }
+    }
+}
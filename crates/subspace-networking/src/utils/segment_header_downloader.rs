@@ -1,11 +1,13 @@
+//! Downloading of segment headers from DSN.
+
+use crate::{Node, SegmentHeaderRequest, SegmentHeaderResponse};
 use futures::stream::FuturesUnordered;
 use futures::StreamExt;
+use libp2p::PeerId;
 use std::collections::{BTreeSet, HashMap};
 use std::error::Error;
 use std::pin::pin;
-use subspace_core_primitives::{SegmentHeader, SegmentIndex};
-use subspace_networking::libp2p::PeerId;
-use subspace_networking::{Node, SegmentHeaderRequest, SegmentHeaderResponse};
+use subspace_core_primitives::{verify_segment_headers_link, SegmentHeader, SegmentIndex};
 use tracing::{debug, error, trace, warn};
 
 const SEGMENT_HEADER_NUMBER_PER_REQUEST: u64 = 1000;
@@ -18,6 +20,7 @@ pub struct SegmentHeaderDownloader<'a> {
 }
 
 impl<'a> SegmentHeaderDownloader<'a> {
+    /// Create new instance
     pub fn new(dsn_node: &'a Node) -> Self {
         Self { dsn_node }
     }
@@ -66,19 +69,17 @@ impl<'a> SegmentHeaderDownloader<'a> {
                 .await?;
 
             for segment_header in segment_headers {
-                if segment_header.hash() != last_segment_header.prev_segment_header_hash() {
+                if let Err(error) =
+                    verify_segment_headers_link(&segment_header, &last_segment_header)
+                {
                     error!(
                         %peer_id,
                         segment_index=%last_segment_header.segment_index() - SegmentIndex::ONE,
-                        actual_hash=?segment_header.hash(),
-                        expected_hash=?last_segment_header.prev_segment_header_hash(),
-                        "Segment header hash doesn't match expected hash from the last block."
+                        %error,
+                        "Segment header doesn't form a chain with the last segment header."
                     );
 
-                    return Err(
-                        "Segment header hash doesn't match expected hash from the last block."
-                            .into(),
-                    );
+                    return Err(error.into());
                 }
 
                 last_segment_header = segment_header;
@@ -1,5 +1,8 @@
 use super::CollectionBatcher;
-use std::num::NonZeroUsize;
+use crate::simulation::{LinkConditions, SimulatedNetwork};
+use std::num::{NonZeroU64, NonZeroUsize};
+use std::time::Duration;
+use subspace_core_primitives::{Piece, PieceIndex};
 
 #[test]
 fn test_empty_collection() {
@@ -60,3 +63,49 @@ fn test_batching() {
     assert_eq!(batcher.next_batch(collection.clone()), vec![3, 4, 5, 6]);
     assert_eq!(batcher.next_batch(collection), vec![7, 1, 2, 3]);
 }
+
+#[tokio::test]
+async fn test_piece_retrieval_on_lossy_network() {
+    let network = SimulatedNetwork::new(
+        4,
+        LinkConditions {
+            latency: Duration::from_millis(10),
+            packet_loss: 0.05,
+            bandwidth: NonZeroU64::new(10 * 1024 * 1024),
+        },
+        42,
+    );
+    // Slow link between requesting node and one of the holders
+    network.conditions().set_link(
+        0,
+        3,
+        LinkConditions {
+            latency: Duration::from_millis(100),
+            packet_loss: 0.2,
+            bandwidth: NonZeroU64::new(1024 * 1024),
+        },
+    );
+
+    for (node_index, piece_index) in (1..4).zip(0_u64..) {
+        network.seed_pieces(
+            node_index,
+            [(PieceIndex::from(piece_index), Piece::default())],
+        );
+    }
+    network.bootstrap().await;
+
+    network
+        .assert_retrieval_success_rate(0, (0_u64..3).map(PieceIndex::from), 1.0)
+        .await;
+
+    // Missing pieces are never retrieved
+    network
+        .conditions()
+        .set_default_link(LinkConditions::default());
+    assert_eq!(
+        network
+            .retrieval_success_rate(0, (3_u64..5).map(PieceIndex::from))
+            .await,
+        0.0
+    );
+}
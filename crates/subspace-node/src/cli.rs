@@ -15,7 +15,7 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 use crate::chain_spec;
-use crate::commands::{RunOptions, WipeOptions};
+use crate::commands::{AuditPotCmd, ExportSegmentsCmd, ImportSegmentsCmd, RunOptions, WipeOptions};
 use clap::Parser;
 use sc_chain_spec::GenericChainSpec;
 use sc_cli::SubstrateCli;
@@ -44,6 +44,15 @@ pub enum Cli {
     /// Import blocks.
     ImportBlocks(sc_cli::ImportBlocksCmd),
 
+    /// Export archived segments (pieces and segment headers) into files.
+    ExportSegments(ExportSegmentsCmd),
+
+    /// Import blocks from exported archived segments without network access.
+    ImportSegments(ImportSegmentsCmd),
+
+    /// Verify proof of time chain of blocks in the database.
+    AuditPot(AuditPotCmd),
+
     /// Remove all node's data
     Wipe(WipeOptions),
 
@@ -1,8 +1,12 @@
+mod archived_segments;
+mod audit_pot;
 mod domain_key;
 mod run;
 mod shared;
 mod wipe;
 
+pub use archived_segments::{ExportSegmentsCmd, ImportSegmentsCmd};
+pub use audit_pot::{AuditPotCmd, AuditPotError};
 pub use domain_key::{
     create_domain_key, insert_domain_key, CreateDomainKeyOptions, InsertDomainKeyOptions,
 };
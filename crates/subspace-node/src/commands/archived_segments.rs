@@ -0,0 +1,148 @@
+use clap::Parser;
+use sc_cli::{CliConfiguration, DatabaseParams, ImportParams, PruningParams, SharedParams};
+use sc_client_api::{AuxStore, BlockBackend, HeaderBackend};
+use sc_consensus::import_queue::ImportQueueService;
+use sc_consensus_subspace::archiver::SegmentHeadersStore;
+use sp_api::ProvideRuntimeApi;
+use sp_objects::ObjectsApi;
+use sp_runtime::traits::Block as BlockT;
+use std::path::PathBuf;
+use subspace_core_primitives::crypto::kzg::Kzg;
+use subspace_core_primitives::SegmentIndex;
+use subspace_service::archived_segments_files::{
+    export_archived_segments, import_archived_segments,
+};
+
+/// Export archived segments (pieces and segment headers) into files.
+#[derive(Debug, Parser)]
+pub struct ExportSegmentsCmd {
+    /// Directory where segment files will be written.
+    #[arg(long)]
+    pub output: PathBuf,
+
+    /// First segment to export.
+    #[arg(long, default_value_t = 0)]
+    pub from: u64,
+
+    /// Last segment to export, defaults to the last archived segment.
+    #[arg(long)]
+    pub to: Option<u64>,
+
+    /// The base struct of the command.
+    #[clap(flatten)]
+    pub shared_params: SharedParams,
+
+    /// Pruning parameters of the database.
+    #[clap(flatten)]
+    pub pruning_params: PruningParams,
+
+    /// Database parameters.
+    #[clap(flatten)]
+    pub database_params: DatabaseParams,
+}
+
+impl CliConfiguration for ExportSegmentsCmd {
+    fn shared_params(&self) -> &SharedParams {
+        &self.shared_params
+    }
+
+    fn pruning_params(&self) -> Option<&PruningParams> {
+        Some(&self.pruning_params)
+    }
+
+    fn database_params(&self) -> Option<&DatabaseParams> {
+        Some(&self.database_params)
+    }
+}
+
+impl ExportSegmentsCmd {
+    /// Run the export-segments command
+    pub async fn run<Block, Client, AS>(
+        &self,
+        client: &Client,
+        segment_headers_store: &SegmentHeadersStore<AS>,
+        kzg: Kzg,
+    ) -> sc_cli::Result<()>
+    where
+        Block: BlockT,
+        Client: BlockBackend<Block> + HeaderBackend<Block>,
+        AS: AuxStore,
+    {
+        let to_segment_index = match self.to {
+            Some(to) => SegmentIndex::from(to),
+            None => segment_headers_store
+                .max_segment_index()
+                .ok_or_else(|| sc_cli::Error::Input("No segments were archived yet".to_string()))?,
+        };
+
+        export_archived_segments(
+            client,
+            segment_headers_store,
+            kzg,
+            SegmentIndex::from(self.from)..=to_segment_index,
+            &self.output,
+        )?;
+
+        Ok(())
+    }
+}
+
+/// Import blocks from archived segments previously exported with `export-segments`.
+#[derive(Debug, Parser)]
+pub struct ImportSegmentsCmd {
+    /// Directory with segment files.
+    #[arg(long)]
+    pub input: PathBuf,
+
+    /// The base struct of the command.
+    #[clap(flatten)]
+    pub shared_params: SharedParams,
+
+    /// Import parameters, including pruning parameters of the database.
+    #[clap(flatten)]
+    pub import_params: ImportParams,
+}
+
+impl CliConfiguration for ImportSegmentsCmd {
+    fn shared_params(&self) -> &SharedParams {
+        &self.shared_params
+    }
+
+    fn import_params(&self) -> Option<&ImportParams> {
+        Some(&self.import_params)
+    }
+}
+
+impl ImportSegmentsCmd {
+    /// Run the import-segments command
+    pub async fn run<Block, Client, AS, IQS>(
+        &self,
+        client: &Client,
+        segment_headers_store: &SegmentHeadersStore<AS>,
+        kzg: Kzg,
+        import_queue_service: &mut IQS,
+    ) -> sc_cli::Result<()>
+    where
+        Block: BlockT,
+        Client: ProvideRuntimeApi<Block>
+            + BlockBackend<Block>
+            + HeaderBackend<Block>
+            + Send
+            + Sync
+            + 'static,
+        Client::Api: ObjectsApi<Block>,
+        AS: AuxStore + Send + Sync + 'static,
+        IQS: ImportQueueService<Block> + ?Sized,
+    {
+        import_archived_segments(
+            client,
+            segment_headers_store,
+            kzg,
+            import_queue_service,
+            &self.input,
+        )
+        .await?;
+
+        Ok(())
+    }
+}
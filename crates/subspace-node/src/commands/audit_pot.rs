@@ -0,0 +1,581 @@
+#[cfg(test)]
+mod tests;
+
+use clap::Parser;
+use sc_cli::{CliConfiguration, DatabaseParams, PruningParams, SharedParams};
+use sc_client_api::{BlockBackend, HeaderBackend};
+use sp_api::ProvideRuntimeApi;
+use sp_consensus_slots::Slot;
+use sp_consensus_subspace::digests::{extract_subspace_digest_items, SubspaceDigestItems};
+use sp_consensus_subspace::{
+    collect_pot_entropy, FarmerPublicKey, FarmerSignature, PotEntropyInjectionParameters,
+    PotEntropyValue, PotNextSlotInput, PotParametersChange, SubspaceApi, SubspaceJustification,
+};
+use sp_runtime::traits::{Block as BlockT, Header as HeaderT, NumberFor};
+use sp_runtime::SaturatedConversion;
+use std::collections::BTreeMap;
+use std::num::NonZeroU32;
+use subspace_core_primitives::{BlockNumber, PotCheckpoints, PotOutput, PotSeed};
+use subspace_proof_of_time::{verify_batch, PotVerificationInput};
+use tracing::{error, info};
+
+/// Number of blocks whose checkpoints are verified together in parallel
+const VERIFICATION_BATCH_SIZE: usize = 1_000;
+
+/// Verify proof of time chain of blocks stored in the database.
+#[derive(Debug, Parser)]
+pub struct AuditPotCmd {
+    /// First block to audit.
+    #[arg(long, default_value_t = 1)]
+    pub from: BlockNumber,
+
+    /// Last block to audit, defaults to the best block.
+    #[arg(long)]
+    pub to: Option<BlockNumber>,
+
+    /// External entropy, used initially when PoT chain starts to derive the first seed, must
+    /// match the value the node was started with.
+    #[arg(long)]
+    pub pot_external_entropy: Option<String>,
+
+    /// The base struct of the command.
+    #[clap(flatten)]
+    pub shared_params: SharedParams,
+
+    /// Pruning parameters of the database.
+    #[clap(flatten)]
+    pub pruning_params: PruningParams,
+
+    /// Database parameters.
+    #[clap(flatten)]
+    pub database_params: DatabaseParams,
+}
+
+impl CliConfiguration for AuditPotCmd {
+    fn shared_params(&self) -> &SharedParams {
+        &self.shared_params
+    }
+
+    fn pruning_params(&self) -> Option<&PruningParams> {
+        Some(&self.pruning_params)
+    }
+
+    fn database_params(&self) -> Option<&DatabaseParams> {
+        Some(&self.database_params)
+    }
+}
+
+/// Error of audit-pot command
+#[derive(Debug, thiserror::Error)]
+pub enum AuditPotError {
+    /// Audit couldn't be performed
+    #[error(transparent)]
+    Cli(#[from] sc_cli::Error),
+    /// Audit found inconsistencies in proof of time chain
+    #[error("Found {inconsistencies} proof of time inconsistencies in blocks {from}..={to}")]
+    InconsistentChain {
+        /// Number of inconsistencies found
+        inconsistencies: usize,
+        /// First audited block
+        from: BlockNumber,
+        /// Last audited block
+        to: BlockNumber,
+    },
+}
+
+/// Inconsistency found in proof of time chain
+#[derive(Debug, thiserror::Error)]
+enum PotInconsistency {
+    /// Subspace digest items can't be extracted
+    #[error("Subspace digest items can't be extracted: {0}")]
+    InvalidDigest(String),
+    /// Subspace justification is missing
+    #[error("Subspace justification is missing")]
+    MissingJustification,
+    /// Subspace justification can't be decoded
+    #[error("Subspace justification can't be decoded: {0}")]
+    InvalidJustification(parity_scale_codec::Error),
+    /// Seed of checkpoints doesn't follow from parent block
+    #[error("Seed mismatch: expected {expected}, found {actual}")]
+    SeedMismatch { expected: PotSeed, actual: PotSeed },
+    /// Number of checkpoints doesn't match number of slots since parent block
+    #[error("Expected {expected} checkpoints, found {actual}")]
+    CheckpointsCountMismatch { expected: u64, actual: u64 },
+    /// Future proof of time doesn't match the last checkpoints
+    #[error("Future proof of time doesn't match the last checkpoints")]
+    FutureProofOfTimeMismatch,
+    /// Proof of time doesn't match checkpoints of block's slot
+    #[error("Proof of time doesn't match checkpoints of slot {slot}")]
+    ProofOfTimeMismatch { slot: Slot },
+    /// Checkpoints of block's slot were not included in this or any of the recent blocks
+    #[error("Proof of time of slot {slot} is not found in checkpoints")]
+    MissingProofOfTime { slot: Slot },
+    /// Parameters change doesn't match entropy collected on chain
+    #[error("Parameters change mismatch: expected {expected:?}, found {actual:?}")]
+    ParametersChangeMismatch {
+        expected: Option<PotParametersChange>,
+        actual: Option<PotParametersChange>,
+    },
+    /// Checkpoints are invalid
+    #[error("Invalid checkpoints for slot {slot}")]
+    InvalidCheckpoints { slot: Slot },
+}
+
+/// Checkpoints of one slot that need to be verified
+#[derive(Debug)]
+struct CheckpointsToVerify<Hash> {
+    block_number: BlockNumber,
+    block_hash: Hash,
+    input: PotNextSlotInput,
+    checkpoints: PotCheckpoints,
+}
+
+struct PotChainAuditor<Hash> {
+    genesis_seed: PotSeed,
+    block_authoring_delay: Slot,
+    /// Future slot and future proof of time of the parent block, `None` if unknown
+    parent_future_proof_of_time: Option<(Slot, PotOutput)>,
+    recent_proofs_of_time: BTreeMap<Slot, PotOutput>,
+    /// The first slot for which checkpoints were collected, proofs of time of earlier slots are
+    /// unknown
+    first_checkpoint_slot: Option<Slot>,
+    pot_entropy: BTreeMap<BlockNumber, PotEntropyValue>,
+    checkpoints_to_verify: Vec<CheckpointsToVerify<Hash>>,
+    inconsistencies: usize,
+}
+
+impl<Hash> PotChainAuditor<Hash>
+where
+    Hash: Copy + std::fmt::Display,
+{
+    fn report(&mut self, block_number: BlockNumber, block_hash: Hash, error: PotInconsistency) {
+        self.inconsistencies += 1;
+        error!(%block_number, %block_hash, %error, "Proof of time inconsistency");
+    }
+
+    /// Track entropy collected on chain the same way runtime does and return parameters change
+    /// that is expected in the block
+    fn expected_parameters_change(
+        &mut self,
+        injection_parameters: &PotEntropyInjectionParameters<BlockNumber>,
+        block_number: BlockNumber,
+        subspace_digest_items: &SubspaceDigestItems<
+            FarmerPublicKey,
+            FarmerPublicKey,
+            FarmerSignature,
+        >,
+    ) -> Option<PotParametersChange> {
+        collect_pot_entropy(
+            injection_parameters,
+            &mut self.pot_entropy,
+            block_number,
+            &subspace_digest_items.pre_digest,
+            subspace_digest_items.pot_slot_iterations,
+        )
+    }
+
+    /// Check block's proof of time against its parent and collect checkpoints for verification
+    fn audit_block(
+        &mut self,
+        injection_parameters: &PotEntropyInjectionParameters<BlockNumber>,
+        block_number: BlockNumber,
+        block_hash: Hash,
+        subspace_digest_items: &SubspaceDigestItems<
+            FarmerPublicKey,
+            FarmerPublicKey,
+            FarmerSignature,
+        >,
+        maybe_subspace_justification: Option<
+            Result<SubspaceJustification, parity_scale_codec::Error>,
+        >,
+    ) {
+        let pre_digest = &subspace_digest_items.pre_digest;
+        let slot = pre_digest.slot();
+        let future_slot = slot + self.block_authoring_delay;
+        let future_proof_of_time = pre_digest.pot_info().future_proof_of_time();
+        let maybe_parent_future_proof_of_time = self
+            .parent_future_proof_of_time
+            .replace((future_slot, future_proof_of_time));
+
+        let expected_parameters_change = self.expected_parameters_change(
+            injection_parameters,
+            block_number,
+            subspace_digest_items,
+        );
+        if subspace_digest_items.pot_parameters_change != expected_parameters_change {
+            self.report(
+                block_number,
+                block_hash,
+                PotInconsistency::ParametersChangeMismatch {
+                    expected: expected_parameters_change,
+                    actual: subspace_digest_items.pot_parameters_change,
+                },
+            );
+        }
+
+        let (seed, checkpoints) = match maybe_subspace_justification {
+            Some(Ok(SubspaceJustification::PotCheckpoints { seed, checkpoints })) => {
+                (seed, checkpoints)
+            }
+            Some(Err(error)) => {
+                self.report(
+                    block_number,
+                    block_hash,
+                    PotInconsistency::InvalidJustification(error),
+                );
+                return;
+            }
+            None => {
+                self.report(
+                    block_number,
+                    block_hash,
+                    PotInconsistency::MissingJustification,
+                );
+                return;
+            }
+        };
+
+        // Seed and number of checkpoints must follow from parent block
+        let maybe_expected_seed = if block_number == 1 {
+            // Checkpoints of the first block start right after genesis
+            Some((Slot::from(0), self.genesis_seed))
+        } else {
+            maybe_parent_future_proof_of_time.map(
+                |(parent_future_slot, parent_future_proof_of_time)| {
+                    let expected_input = PotNextSlotInput::derive(
+                        subspace_digest_items.pot_slot_iterations,
+                        parent_future_slot,
+                        parent_future_proof_of_time,
+                        &subspace_digest_items.pot_parameters_change,
+                    );
+                    (parent_future_slot, expected_input.seed)
+                },
+            )
+        };
+        if let Some((parent_future_slot, expected_seed)) = maybe_expected_seed {
+            if seed != expected_seed {
+                self.report(
+                    block_number,
+                    block_hash,
+                    PotInconsistency::SeedMismatch {
+                        expected: expected_seed,
+                        actual: seed,
+                    },
+                );
+            }
+
+            let expected_checkpoints = (*future_slot).saturating_sub(*parent_future_slot);
+            if checkpoints.len() as u64 != expected_checkpoints {
+                self.report(
+                    block_number,
+                    block_hash,
+                    PotInconsistency::CheckpointsCountMismatch {
+                        expected: expected_checkpoints,
+                        actual: checkpoints.len() as u64,
+                    },
+                );
+            }
+        }
+
+        // Last checkpoints must correspond to future proof of time
+        if checkpoints.last().map(|checkpoints| checkpoints.output()) != Some(future_proof_of_time)
+        {
+            self.report(
+                block_number,
+                block_hash,
+                PotInconsistency::FutureProofOfTimeMismatch,
+            );
+            return;
+        }
+
+        let first_slot = Slot::from((*future_slot + 1).saturating_sub(checkpoints.len() as u64));
+        self.first_checkpoint_slot.get_or_insert(first_slot);
+        let mut input = PotNextSlotInput {
+            slot: first_slot,
+            slot_iterations: slot_iterations_at(subspace_digest_items, first_slot),
+            seed,
+        };
+        for checkpoints in checkpoints {
+            self.recent_proofs_of_time
+                .insert(input.slot, checkpoints.output());
+            self.checkpoints_to_verify.push(CheckpointsToVerify {
+                block_number,
+                block_hash,
+                input,
+                checkpoints,
+            });
+
+            input = PotNextSlotInput::derive(
+                input.slot_iterations,
+                input.slot,
+                checkpoints.output(),
+                &subspace_digest_items.pot_parameters_change,
+            );
+        }
+
+        // Proof of time of the block's slot was included in checkpoints of this or one of the
+        // recent blocks
+        match self.recent_proofs_of_time.get(&slot) {
+            Some(&proof_of_time) => {
+                if proof_of_time != pre_digest.pot_info().proof_of_time() {
+                    self.report(
+                        block_number,
+                        block_hash,
+                        PotInconsistency::ProofOfTimeMismatch { slot },
+                    );
+                }
+            }
+            None => {
+                // Checkpoints of slots before the first audited block are not known
+                if self
+                    .first_checkpoint_slot
+                    .is_some_and(|first_checkpoint_slot| first_checkpoint_slot <= slot)
+                {
+                    self.report(
+                        block_number,
+                        block_hash,
+                        PotInconsistency::MissingProofOfTime { slot },
+                    );
+                }
+            }
+        }
+        // Descendants can only have higher slots, older proofs are not needed anymore
+        self.recent_proofs_of_time
+            .retain(|&recent_slot, _| recent_slot > slot);
+    }
+
+    /// Verify collected checkpoints in parallel
+    fn verify_checkpoints(&mut self) {
+        let inputs = self
+            .checkpoints_to_verify
+            .iter()
+            .map(|checkpoints_to_verify| PotVerificationInput {
+                seed: checkpoints_to_verify.input.seed,
+                iterations: checkpoints_to_verify.input.slot_iterations,
+                checkpoints: checkpoints_to_verify.checkpoints.as_slice(),
+            })
+            .collect::<Vec<_>>();
+        let invalid_checkpoints = verify_batch(&inputs)
+            .into_iter()
+            .zip(&self.checkpoints_to_verify)
+            .filter(|(result, _)| !matches!(result, Ok(true)))
+            .map(|(_, checkpoints_to_verify)| {
+                (
+                    checkpoints_to_verify.block_number,
+                    checkpoints_to_verify.block_hash,
+                    checkpoints_to_verify.input.slot,
+                )
+            })
+            .collect::<Vec<_>>();
+        self.checkpoints_to_verify.clear();
+
+        for (block_number, block_hash, slot) in invalid_checkpoints {
+            self.report(
+                block_number,
+                block_hash,
+                PotInconsistency::InvalidCheckpoints { slot },
+            );
+        }
+    }
+}
+
+/// Number of iterations for slot, accounting for parameters change in the block
+fn slot_iterations_at(
+    subspace_digest_items: &SubspaceDigestItems<FarmerPublicKey, FarmerPublicKey, FarmerSignature>,
+    slot: Slot,
+) -> NonZeroU32 {
+    subspace_digest_items
+        .pot_parameters_change
+        .as_ref()
+        .and_then(|parameters_change| {
+            (parameters_change.slot == slot).then_some(parameters_change.slot_iterations)
+        })
+        .unwrap_or(subspace_digest_items.pot_slot_iterations)
+}
+
+/// Hash of the block with specified number in the canonical chain
+fn block_hash_by_number<Block, Client>(
+    client: &Client,
+    block_number: BlockNumber,
+) -> sc_cli::Result<Block::Hash>
+where
+    Block: BlockT,
+    Client: HeaderBackend<Block>,
+{
+    client
+        .hash(NumberFor::<Block>::from(block_number))?
+        .ok_or_else(|| sc_cli::Error::Input(format!("Block {block_number} not found in database")))
+}
+
+/// Proof of time entropy injection parameters of the runtime at block `at`
+fn pot_entropy_injection_parameters<Block, Client>(
+    client: &Client,
+    at: Block::Hash,
+) -> sc_cli::Result<PotEntropyInjectionParameters<BlockNumber>>
+where
+    Block: BlockT,
+    Client: ProvideRuntimeApi<Block>,
+    Client::Api: SubspaceApi<Block, FarmerPublicKey>,
+{
+    let injection_parameters = client
+        .runtime_api()
+        .pot_entropy_injection_parameters(at)
+        .map_err(|error| sc_cli::Error::Application(Box::new(error)))?;
+
+    Ok(PotEntropyInjectionParameters::V0 {
+        interval: injection_parameters.interval().saturated_into(),
+        lookback_depth: injection_parameters.lookback_depth(),
+        delay: injection_parameters.delay(),
+    })
+}
+
+impl AuditPotCmd {
+    /// Run the audit-pot command
+    pub fn run<Block, Client>(
+        &self,
+        client: &Client,
+        pot_external_entropy: &[u8],
+    ) -> Result<(), AuditPotError>
+    where
+        Block: BlockT,
+        Client: ProvideRuntimeApi<Block> + BlockBackend<Block> + HeaderBackend<Block>,
+        Client::Api: SubspaceApi<Block, FarmerPublicKey>,
+    {
+        let (from, to, inconsistencies) = self.audit(client, pot_external_entropy)?;
+
+        if inconsistencies > 0 {
+            return Err(AuditPotError::InconsistentChain {
+                inconsistencies,
+                from,
+                to,
+            });
+        }
+
+        info!(%from, %to, "Proof of time chain is consistent");
+
+        Ok(())
+    }
+
+    /// Audit blocks, returns audited range of blocks and number of inconsistencies found
+    fn audit<Block, Client>(
+        &self,
+        client: &Client,
+        pot_external_entropy: &[u8],
+    ) -> sc_cli::Result<(BlockNumber, BlockNumber, usize)>
+    where
+        Block: BlockT,
+        Client: ProvideRuntimeApi<Block> + BlockBackend<Block> + HeaderBackend<Block>,
+        Client::Api: SubspaceApi<Block, FarmerPublicKey>,
+    {
+        let info = client.info();
+        let best_number = info.best_number.saturated_into::<BlockNumber>();
+        let from = self.from.max(1);
+        let to = self.to.unwrap_or(best_number).min(best_number);
+        if from > to {
+            return Err(sc_cli::Error::Input(format!(
+                "Nothing to audit in blocks {from}..={to}, best block is {best_number}"
+            )));
+        }
+
+        let chain_constants = client
+            .runtime_api()
+            .chain_constants(info.best_hash)
+            .map_err(|error| sc_cli::Error::Application(Box::new(error)))?;
+
+        let mut auditor = PotChainAuditor {
+            genesis_seed: PotSeed::from_genesis(info.genesis_hash.as_ref(), pot_external_entropy),
+            block_authoring_delay: chain_constants.block_authoring_delay(),
+            parent_future_proof_of_time: None,
+            recent_proofs_of_time: BTreeMap::new(),
+            first_checkpoint_slot: None,
+            pot_entropy: BTreeMap::new(),
+            checkpoints_to_verify: Vec::new(),
+            inconsistencies: 0,
+        };
+
+        // Entropy of blocks before the first audited block needs to be collected to check
+        // parameters change
+        let injection_parameters = pot_entropy_injection_parameters(
+            client,
+            block_hash_by_number::<Block, _>(client, from - 1)?,
+        )?;
+        let warm_up_from = from
+            .saturating_sub(
+                injection_parameters.interval()
+                    * (BlockNumber::from(injection_parameters.lookback_depth()) + 1),
+            )
+            .max(1);
+
+        info!(%from, %to, "Auditing proof of time chain");
+
+        for block_number in warm_up_from..=to {
+            let block_hash = block_hash_by_number::<Block, _>(client, block_number)?;
+            let header = client.header(block_hash)?.ok_or_else(|| {
+                sc_cli::Error::Input(format!("Header of block {block_number} not found"))
+            })?;
+            // Parameters of the runtime that has produced the block
+            let injection_parameters =
+                pot_entropy_injection_parameters(client, *header.parent_hash())?;
+
+            let subspace_digest_items = match extract_subspace_digest_items::<
+                _,
+                FarmerPublicKey,
+                FarmerPublicKey,
+                FarmerSignature,
+            >(&header)
+            {
+                Ok(subspace_digest_items) => subspace_digest_items,
+                Err(error) => {
+                    auditor.parent_future_proof_of_time.take();
+                    if block_number >= from {
+                        auditor.report(
+                            block_number,
+                            block_hash,
+                            PotInconsistency::InvalidDigest(error.to_string()),
+                        );
+                    }
+                    continue;
+                }
+            };
+
+            if block_number < from {
+                auditor.expected_parameters_change(
+                    &injection_parameters,
+                    block_number,
+                    &subspace_digest_items,
+                );
+                let pre_digest = &subspace_digest_items.pre_digest;
+                auditor.parent_future_proof_of_time.replace((
+                    pre_digest.slot() + auditor.block_authoring_delay,
+                    pre_digest.pot_info().future_proof_of_time(),
+                ));
+                continue;
+            }
+
+            let maybe_subspace_justification =
+                client
+                    .justifications(block_hash)?
+                    .and_then(|justifications| {
+                        justifications
+                            .iter()
+                            .find_map(SubspaceJustification::try_from_justification)
+                    });
+
+            auditor.audit_block(
+                &injection_parameters,
+                block_number,
+                block_hash,
+                &subspace_digest_items,
+                maybe_subspace_justification,
+            );
+
+            if auditor.checkpoints_to_verify.len() >= VERIFICATION_BATCH_SIZE {
+                auditor.verify_checkpoints();
+                info!(%block_number, inconsistencies = %auditor.inconsistencies, "Audit progress");
+            }
+        }
+        auditor.verify_checkpoints();
+
+        Ok((from, to, auditor.inconsistencies))
+    }
+}
@@ -0,0 +1,357 @@
+use crate::commands::audit_pot::{slot_iterations_at, PotChainAuditor};
+use sp_consensus_slots::Slot;
+use sp_consensus_subspace::digests::{PreDigest, PreDigestPotInfo, SubspaceDigestItems};
+use sp_consensus_subspace::{
+    FarmerPublicKey, FarmerSignature, PotEntropyInjectionParameters, PotParametersChange,
+    SubspaceJustification,
+};
+use sp_core::crypto::UncheckedFrom;
+use std::collections::BTreeMap;
+use std::num::NonZeroU32;
+use subspace_core_primitives::{BlockNumber, PotCheckpoints, PotOutput, PotSeed, Solution};
+use subspace_verification::derive_pot_entropy;
+
+const BLOCK_AUTHORING_DELAY: u64 = 4;
+const POT_ENTROPY_INJECTION_INTERVAL: BlockNumber = 50;
+const POT_ENTROPY_INJECTION_LOOKBACK_DEPTH: u8 = 2;
+const POT_ENTROPY_INJECTION_DELAY: u64 = 15;
+
+fn slot_iterations() -> NonZeroU32 {
+    NonZeroU32::new(16).expect("Not zero; qed")
+}
+
+fn injection_parameters() -> PotEntropyInjectionParameters<BlockNumber> {
+    PotEntropyInjectionParameters::V0 {
+        interval: POT_ENTROPY_INJECTION_INTERVAL,
+        lookback_depth: POT_ENTROPY_INJECTION_LOOKBACK_DEPTH,
+        delay: Slot::from(POT_ENTROPY_INJECTION_DELAY),
+    }
+}
+
+type DigestItems = SubspaceDigestItems<FarmerPublicKey, FarmerPublicKey, FarmerSignature>;
+
+fn digest_items(
+    slot: u64,
+    proof_of_time: PotOutput,
+    future_proof_of_time: PotOutput,
+    pot_parameters_change: Option<PotParametersChange>,
+) -> DigestItems {
+    let public_key = FarmerPublicKey::unchecked_from([0; 32]);
+
+    SubspaceDigestItems {
+        pre_digest: PreDigest::V0 {
+            slot: Slot::from(slot),
+            solution: Solution::genesis_solution(public_key.clone(), public_key),
+            pot_info: PreDigestPotInfo::V0 {
+                proof_of_time,
+                future_proof_of_time,
+            },
+        },
+        signature: None,
+        pot_slot_iterations: slot_iterations(),
+        solution_range: 0,
+        pot_parameters_change,
+        next_solution_range: None,
+        segment_commitments: BTreeMap::new(),
+        enable_solution_range_adjustment_and_override: None,
+        root_plot_public_key_update: None,
+    }
+}
+
+fn auditor(genesis_seed: PotSeed) -> PotChainAuditor<BlockNumber> {
+    PotChainAuditor {
+        genesis_seed,
+        block_authoring_delay: Slot::from(BLOCK_AUTHORING_DELAY),
+        parent_future_proof_of_time: None,
+        recent_proofs_of_time: BTreeMap::new(),
+        first_checkpoint_slot: None,
+        pot_entropy: BTreeMap::new(),
+        checkpoints_to_verify: Vec::new(),
+        inconsistencies: 0,
+    }
+}
+
+/// Proof of time chain without parameters changes, checkpoints at index `n` correspond to slot `n`
+/// (there are no checkpoints for slot 0)
+struct PotChain {
+    genesis_seed: PotSeed,
+    checkpoints: Vec<PotCheckpoints>,
+}
+
+impl PotChain {
+    fn new(slots: u64) -> Self {
+        let genesis_seed = PotSeed::from_genesis(&[1; 32], b"");
+        let mut checkpoints = vec![PotCheckpoints::default()];
+        let mut seed = genesis_seed;
+        for _ in 1..=slots {
+            let slot_checkpoints = subspace_proof_of_time::prove(seed, slot_iterations()).unwrap();
+            seed = slot_checkpoints.output().seed();
+            checkpoints.push(slot_checkpoints);
+        }
+
+        Self {
+            genesis_seed,
+            checkpoints,
+        }
+    }
+
+    fn output(&self, slot: u64) -> PotOutput {
+        self.checkpoints[slot as usize].output()
+    }
+
+    /// Digest items and justification of block at `slot` whose parent was at `parent_slot`
+    fn block(&self, parent_slot: Option<u64>, slot: u64) -> (DigestItems, SubspaceJustification) {
+        let future_slot = slot + BLOCK_AUTHORING_DELAY;
+        let first_slot =
+            parent_slot.map_or(1, |parent_slot| parent_slot + BLOCK_AUTHORING_DELAY + 1);
+        let seed = if first_slot == 1 {
+            self.genesis_seed
+        } else {
+            self.output(first_slot - 1).seed()
+        };
+
+        (
+            digest_items(slot, self.output(slot), self.output(future_slot), None),
+            SubspaceJustification::PotCheckpoints {
+                seed,
+                checkpoints: self.checkpoints[first_slot as usize..=future_slot as usize].to_vec(),
+            },
+        )
+    }
+}
+
+#[test]
+fn slot_iterations_with_parameters_change() {
+    let slot_iterations = NonZeroU32::new(32).unwrap();
+    let mut subspace_digest_items =
+        digest_items(10, PotOutput::default(), PotOutput::default(), None);
+
+    assert_eq!(
+        slot_iterations_at(&subspace_digest_items, Slot::from(10)),
+        slot_iterations()
+    );
+
+    subspace_digest_items.pot_parameters_change = Some(PotParametersChange {
+        slot: Slot::from(12),
+        slot_iterations,
+        entropy: Default::default(),
+    });
+    assert_eq!(
+        slot_iterations_at(&subspace_digest_items, Slot::from(11)),
+        slot_iterations()
+    );
+    assert_eq!(
+        slot_iterations_at(&subspace_digest_items, Slot::from(12)),
+        slot_iterations
+    );
+}
+
+#[test]
+fn expected_parameters_change_follows_entropy_injection() {
+    let injection_interval = POT_ENTROPY_INJECTION_INTERVAL;
+    let lookback_depth = POT_ENTROPY_INJECTION_LOOKBACK_DEPTH;
+    let injection_delay = POT_ENTROPY_INJECTION_DELAY;
+    // The first injection uses entropy of the first block at injection interval
+    let first_injection_block = injection_interval * (BlockNumber::from(lookback_depth) + 1);
+    let second_injection_block = first_injection_block + injection_interval;
+    let mut auditor = auditor(PotSeed::default());
+
+    // Slot is twice the block number and proof of time is unique for every block
+    let block_digest_items = |block_number: BlockNumber| {
+        let proof_of_time = PotOutput::from([block_number as u8; PotOutput::SIZE]);
+        digest_items(
+            u64::from(block_number) * 2,
+            proof_of_time,
+            PotOutput::default(),
+            None,
+        )
+    };
+    let block_entropy = |block_number: BlockNumber| {
+        let subspace_digest_items = block_digest_items(block_number);
+        derive_pot_entropy(
+            subspace_digest_items.pre_digest.solution().chunk,
+            subspace_digest_items.pre_digest.pot_info().proof_of_time(),
+        )
+    };
+
+    for block_number in 1..=second_injection_block {
+        let expected_parameters_change = auditor.expected_parameters_change(
+            &injection_parameters(),
+            block_number,
+            &block_digest_items(block_number),
+        );
+
+        // Parameters change is expected until injection slot is reached
+        let expected_entropy_source =
+            if block_number == first_injection_block || block_number == first_injection_block + 1 {
+                Some((injection_interval, first_injection_block))
+            } else if block_number == second_injection_block {
+                Some((injection_interval * 2, second_injection_block))
+            } else {
+                None
+            };
+        match expected_entropy_source {
+            Some((entropy_source_block_number, injection_block_number)) => {
+                assert_eq!(
+                    expected_parameters_change,
+                    Some(PotParametersChange {
+                        slot: Slot::from(u64::from(injection_block_number) * 2 + injection_delay),
+                        slot_iterations: slot_iterations(),
+                        entropy: block_entropy(entropy_source_block_number),
+                    }),
+                    "Block {block_number}"
+                );
+            }
+            None => {
+                // Entropy was already injected before the block preceding the second injection
+                if block_number < first_injection_block
+                    || block_number == second_injection_block - 1
+                {
+                    assert_eq!(expected_parameters_change, None, "Block {block_number}");
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn consistent_chain() {
+    let pot_chain = PotChain::new(20);
+    let mut auditor = auditor(pot_chain.genesis_seed);
+
+    let mut parent_slot = None;
+    for (block_number, slot) in [(1, 1), (2, 3), (3, 4), (4, 9)] {
+        let (subspace_digest_items, justification) = pot_chain.block(parent_slot, slot);
+        auditor.audit_block(
+            &injection_parameters(),
+            block_number,
+            block_number,
+            &subspace_digest_items,
+            Some(Ok(justification)),
+        );
+        parent_slot.replace(slot);
+    }
+    auditor.verify_checkpoints();
+
+    assert_eq!(auditor.inconsistencies, 0);
+}
+
+#[test]
+fn inconsistent_chain() {
+    let pot_chain = PotChain::new(20);
+
+    // Proof of time of block's slot doesn't match checkpoints
+    {
+        let mut auditor = auditor(pot_chain.genesis_seed);
+        let (mut subspace_digest_items, justification) = pot_chain.block(None, 1);
+        subspace_digest_items.pre_digest = PreDigest::V0 {
+            slot: Slot::from(1),
+            solution: subspace_digest_items.pre_digest.solution().clone(),
+            pot_info: PreDigestPotInfo::V0 {
+                proof_of_time: pot_chain.output(2),
+                future_proof_of_time: pot_chain.output(5),
+            },
+        };
+        auditor.audit_block(
+            &injection_parameters(),
+            1,
+            1,
+            &subspace_digest_items,
+            Some(Ok(justification)),
+        );
+        auditor.verify_checkpoints();
+
+        assert_eq!(auditor.inconsistencies, 1);
+    }
+
+    // Checkpoints are tampered with, which is only detected during verification
+    {
+        let mut auditor = auditor(pot_chain.genesis_seed);
+        let (subspace_digest_items, mut justification) = pot_chain.block(None, 1);
+        let SubspaceJustification::PotCheckpoints { checkpoints, .. } = &mut justification;
+        checkpoints[2][0] = PotOutput::default();
+        auditor.audit_block(
+            &injection_parameters(),
+            1,
+            1,
+            &subspace_digest_items,
+            Some(Ok(justification)),
+        );
+        assert_eq!(auditor.inconsistencies, 0);
+
+        auditor.verify_checkpoints();
+        assert_eq!(auditor.inconsistencies, 1);
+    }
+
+    // Checkpoints don't follow from parent block
+    {
+        let mut auditor = auditor(pot_chain.genesis_seed);
+        let (subspace_digest_items, justification) = pot_chain.block(None, 1);
+        auditor.audit_block(
+            &injection_parameters(),
+            1,
+            1,
+            &subspace_digest_items,
+            Some(Ok(justification)),
+        );
+        // Skips checkpoints for slot 6
+        let (subspace_digest_items, justification) = pot_chain.block(Some(2), 3);
+        auditor.audit_block(
+            &injection_parameters(),
+            2,
+            2,
+            &subspace_digest_items,
+            Some(Ok(justification)),
+        );
+        auditor.verify_checkpoints();
+
+        // Both seed and number of checkpoints are wrong
+        assert_eq!(auditor.inconsistencies, 2);
+    }
+
+    // Block without checkpoints results in missing proof of time for its descendant
+    {
+        let mut auditor = auditor(pot_chain.genesis_seed);
+        let (subspace_digest_items, justification) = pot_chain.block(None, 1);
+        auditor.audit_block(
+            &injection_parameters(),
+            1,
+            1,
+            &subspace_digest_items,
+            Some(Ok(justification)),
+        );
+        let (subspace_digest_items, _justification) = pot_chain.block(Some(1), 7);
+        auditor.audit_block(&injection_parameters(), 2, 2, &subspace_digest_items, None);
+        let (subspace_digest_items, justification) = pot_chain.block(Some(7), 9);
+        auditor.audit_block(
+            &injection_parameters(),
+            3,
+            3,
+            &subspace_digest_items,
+            Some(Ok(justification)),
+        );
+        auditor.verify_checkpoints();
+
+        // Missing justification and missing proof of time of slot 9
+        assert_eq!(auditor.inconsistencies, 2);
+    }
+
+    // Proofs of time before the first audited block are not known and not reported
+    {
+        let mut auditor = auditor(pot_chain.genesis_seed);
+        let (subspace_digest_items, justification) = pot_chain.block(Some(3), 5);
+        auditor
+            .parent_future_proof_of_time
+            .replace((Slot::from(3 + BLOCK_AUTHORING_DELAY), pot_chain.output(7)));
+        auditor.audit_block(
+            &injection_parameters(),
+            2,
+            2,
+            &subspace_digest_items,
+            Some(Ok(justification)),
+        );
+        auditor.verify_checkpoints();
+
+        assert_eq!(auditor.inconsistencies, 0);
+    }
+}
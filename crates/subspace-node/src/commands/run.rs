@@ -25,6 +25,7 @@ use sp_messenger::messages::ChainId;
 use std::env;
 use subspace_metrics::{start_prometheus_metrics_server, RegistryAdapter};
 use subspace_runtime::{Block, RuntimeApi};
+use subspace_service::NewPartialOptions;
 use tracing::{debug, error, info, info_span, warn};
 
 /// Options for running a node
@@ -86,6 +87,7 @@ pub async fn run(run_options: RunOptions) -> Result<(), Error> {
         subspace_configuration,
         dev,
         pot_external_entropy,
+        kzg_fixed_base_tables_path,
         storage_monitor,
         mut prometheus_configuration,
     } = create_consensus_chain_configuration(consensus, enable_color, domain_options.is_some())?;
@@ -121,7 +123,10 @@ pub async fn run(run_options: RunOptions) -> Result<(), Error> {
 
             let partial_components = subspace_service::new_partial::<PosTable, RuntimeApi>(
                 &subspace_configuration,
-                &pot_external_entropy,
+                NewPartialOptions {
+                    pot_external_entropy: &pot_external_entropy,
+                    kzg_fixed_base_tables_path: kzg_fixed_base_tables_path.as_deref(),
+                },
             )
             .map_err(|error| {
                 sc_service::Error::Other(format!(
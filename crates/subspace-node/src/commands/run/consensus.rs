@@ -17,10 +17,11 @@ use std::fmt;
 use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
 use std::path::PathBuf;
 use std::str::FromStr;
+use subspace_core_primitives::SegmentIndex;
 use subspace_networking::libp2p::multiaddr::Protocol;
 use subspace_networking::libp2p::Multiaddr;
 use subspace_service::config::{
-    SubspaceConfiguration, SubspaceNetworking, SubstrateConfiguration,
+    ObjectMappingsConfig, SubspaceConfiguration, SubspaceNetworking, SubstrateConfiguration,
     SubstrateNetworkConfiguration, SubstrateRpcConfiguration,
 };
 use subspace_service::dsn::DsnConfig;
@@ -116,6 +117,9 @@ struct SubstrateNetworkOptions {
 #[derive(Debug, Parser)]
 struct DsnOptions {
     /// Where local DSN node will listen for incoming connections.
+    ///
+    /// WebSocket addresses (like `/ip4/0.0.0.0/tcp/30434/ws`) can be added to make DSN accessible
+    /// from browsers.
     // TODO: Add more DSN-related parameters
     #[arg(long, default_values_t = [
         Multiaddr::from(IpAddr::V4(Ipv4Addr::UNSPECIFIED))
@@ -159,6 +163,15 @@ struct DsnOptions {
     #[arg(long, default_value_t = false)]
     dsn_disable_bootstrap_on_start: bool,
 
+    /// Discover peers in local network using mDNS, useful when farmers and nodes share the same LAN.
+    #[arg(long, default_value_t = false)]
+    dsn_lan_peer_discovery: bool,
+
+    /// Announce newly archived segment headers to other peers over gossipsub, used by gateways to
+    /// learn about new segments without polling.
+    #[arg(long, default_value_t = false)]
+    dsn_segment_header_announcements: bool,
+
     /// Known external addresses
     #[arg(long, alias = "dsn-external-address")]
     dsn_external_addresses: Vec<Multiaddr>,
@@ -302,6 +315,22 @@ struct TimekeeperOptions {
     timekeeper_cpu_cores: HashSet<usize>,
 }
 
+/// Options for object mappings index
+#[derive(Debug, Parser)]
+struct ObjectMappingsOptions {
+    /// Index object mappings of archived segments, such that objects can be located in archived
+    /// history by their hash using `subspace_getObjectMapping` RPC method.
+    #[arg(long)]
+    index_object_mappings: bool,
+
+    /// Re-create and index already archived segments starting with this segment index.
+    ///
+    /// Requires blocks and state of corresponding blocks to be present in the database, hence
+    /// typically used together with `--state-pruning archive`.
+    #[arg(long, requires = "index_object_mappings")]
+    object_mappings_backfill_from: Option<u64>,
+}
+
 /// Options for running a node
 #[derive(Debug, Parser)]
 pub(super) struct ConsensusChainOptions {
@@ -407,6 +436,22 @@ pub(super) struct ConsensusChainOptions {
 
     #[clap(flatten)]
     timekeeper_options: TimekeeperOptions,
+
+    #[clap(flatten)]
+    object_mappings_options: ObjectMappingsOptions,
+
+    /// Detect equivocations in imported and announced blocks and report them to the runtime.
+    ///
+    /// Collected evidence can be queried using `subspace_equivocationEvidence` RPC method.
+    #[arg(long)]
+    report_equivocations: bool,
+
+    /// Use precomputed fixed-base tables for KZG commitments, speeds up archiving at the cost of
+    /// memory usage.
+    ///
+    /// Tables are built on first start and cached in the base path afterwards.
+    #[arg(long)]
+    kzg_fixed_base_tables: bool,
 }
 
 pub(super) struct PrometheusConfiguration {
@@ -421,6 +466,8 @@ pub(super) struct ConsensusChainConfiguration {
     pub(super) dev: bool,
     /// External entropy, used initially when PoT chain starts to derive the first seed
     pub(super) pot_external_entropy: Vec<u8>,
+    /// Location of cached KZG fixed-base tables, `None` if tables are not used
+    pub(super) kzg_fixed_base_tables_path: Option<PathBuf>,
     pub(super) storage_monitor: StorageMonitorParams,
     pub(super) prometheus_configuration: Option<PrometheusConfiguration>,
 }
@@ -450,6 +497,9 @@ pub(super) fn create_consensus_chain_configuration(
         sync_from_dsn,
         storage_monitor,
         mut timekeeper_options,
+        object_mappings_options,
+        report_equivocations,
+        kzg_fixed_base_tables,
     } = consensus_node_options;
 
     let transaction_pool;
@@ -520,6 +570,8 @@ pub(super) fn create_consensus_chain_configuration(
         }
     };
     let net_config_path = base_path.join("network");
+    let kzg_fixed_base_tables_path =
+        kzg_fixed_base_tables.then(|| base_path.join("kzg_fixed_base_tables.bin"));
 
     let node_name = name.unwrap_or_else(generate_node_name);
 
@@ -645,6 +697,8 @@ pub(super) fn create_consensus_chain_configuration(
             max_pending_out_connections: dsn_options.dsn_pending_out_connections,
             external_addresses: dsn_options.dsn_external_addresses,
             disable_bootstrap_on_start: dsn_options.dsn_disable_bootstrap_on_start,
+            lan_peer_discovery: dsn_options.dsn_lan_peer_discovery,
+            segment_header_announcements: dsn_options.dsn_segment_header_announcements,
         }
     };
 
@@ -660,9 +714,18 @@ pub(super) fn create_consensus_chain_configuration(
             sync_from_dsn,
             is_timekeeper: timekeeper_options.timekeeper,
             timekeeper_cpu_cores: timekeeper_options.timekeeper_cpu_cores,
+            object_mappings: object_mappings_options.index_object_mappings.then(|| {
+                ObjectMappingsConfig {
+                    backfill_from: object_mappings_options
+                        .object_mappings_backfill_from
+                        .map(SegmentIndex::from),
+                }
+            }),
+            equivocation_reporting: report_equivocations,
         },
         dev,
         pot_external_entropy,
+        kzg_fixed_base_tables_path,
         storage_monitor,
         prometheus_configuration: prometheus_listen_on.zip(substrate_registry).map(
             |(listen_on, substrate_registry)| PrometheusConfiguration {
@@ -24,6 +24,7 @@ mod cli;
 mod domain;
 
 use crate::cli::{Cli, SubspaceCliPlaceholder};
+use crate::commands::AuditPotError;
 use crate::domain::cli::DomainKey;
 use crate::domain::{DomainCli, DomainSubcommand};
 use clap::Parser;
@@ -37,7 +38,7 @@ use serde_json::Value;
 use sp_core::crypto::Ss58AddressFormat;
 use subspace_proof_of_space::chia::ChiaTable;
 use subspace_runtime::{Block, RuntimeApi};
-use subspace_service::HostFunctions;
+use subspace_service::{HostFunctions, NewPartialOptions};
 use tracing::warn;
 
 #[global_allocator]
@@ -56,6 +57,10 @@ pub enum Error {
     #[error(transparent)]
     SubstrateCli(#[from] sc_cli::Error),
 
+    /// Proof of time audit error.
+    #[error(transparent)]
+    AuditPot(#[from] AuditPotError),
+
     /// Substrate service error.
     #[error(transparent)]
     SubstrateService(#[from] sc_service::Error),
@@ -145,7 +150,7 @@ fn main() -> Result<(), Error> {
                     ..
                 } = subspace_service::new_partial::<PosTable, RuntimeApi>(
                     &config,
-                    &derive_pot_external_entropy(&config, None)?,
+                    NewPartialOptions::new(&derive_pot_external_entropy(&config, None)?),
                 )?;
                 Ok((
                     cmd.run(client, import_queue).map_err(Error::SubstrateCli),
@@ -163,7 +168,7 @@ fn main() -> Result<(), Error> {
                     ..
                 } = subspace_service::new_partial::<PosTable, RuntimeApi>(
                     &config,
-                    &derive_pot_external_entropy(&config, None)?,
+                    NewPartialOptions::new(&derive_pot_external_entropy(&config, None)?),
                 )?;
                 Ok((
                     cmd.run(client, config.database)
@@ -182,7 +187,7 @@ fn main() -> Result<(), Error> {
                     ..
                 } = subspace_service::new_partial::<PosTable, RuntimeApi>(
                     &config,
-                    &derive_pot_external_entropy(&config, None)?,
+                    NewPartialOptions::new(&derive_pot_external_entropy(&config, None)?),
                 )?;
                 Ok((
                     cmd.run(client, config.chain_spec)
@@ -202,7 +207,7 @@ fn main() -> Result<(), Error> {
                     ..
                 } = subspace_service::new_partial::<PosTable, RuntimeApi>(
                     &config,
-                    &derive_pot_external_entropy(&config, None)?,
+                    NewPartialOptions::new(&derive_pot_external_entropy(&config, None)?),
                 )?;
                 Ok((
                     cmd.run(client, import_queue).map_err(Error::SubstrateCli),
@@ -210,6 +215,86 @@ fn main() -> Result<(), Error> {
                 ))
             })?;
         }
+        Cli::ExportSegments(cmd) => {
+            let runner = SubspaceCliPlaceholder.create_runner(&cmd)?;
+            set_default_ss58_version(runner.config().chain_spec.as_ref());
+            runner.async_run(|config| {
+                let PartialComponents {
+                    client,
+                    task_manager,
+                    other,
+                    ..
+                } = subspace_service::new_partial::<PosTable, RuntimeApi>(
+                    &config,
+                    NewPartialOptions::new(&derive_pot_external_entropy(&config, None)?),
+                )?;
+                Ok((
+                    async move {
+                        cmd.run(
+                            client.as_ref(),
+                            &other.segment_headers_store,
+                            other.subspace_link.kzg().clone(),
+                        )
+                        .await
+                        .map_err(Error::SubstrateCli)
+                    },
+                    task_manager,
+                ))
+            })?;
+        }
+        Cli::ImportSegments(cmd) => {
+            let runner = SubspaceCliPlaceholder.create_runner(&cmd)?;
+            set_default_ss58_version(runner.config().chain_spec.as_ref());
+            runner.async_run(|config| {
+                let PartialComponents {
+                    client,
+                    import_queue,
+                    task_manager,
+                    other,
+                    ..
+                } = subspace_service::new_partial::<PosTable, RuntimeApi>(
+                    &config,
+                    NewPartialOptions::new(&derive_pot_external_entropy(&config, None)?),
+                )?;
+                Ok((
+                    async move {
+                        let mut import_queue_service = import_queue.service();
+                        cmd.run(
+                            client.as_ref(),
+                            &other.segment_headers_store,
+                            other.subspace_link.kzg().clone(),
+                            import_queue_service.as_mut(),
+                        )
+                        .await
+                        .map_err(Error::SubstrateCli)
+                    },
+                    task_manager,
+                ))
+            })?;
+        }
+        Cli::AuditPot(cmd) => {
+            let runner = SubspaceCliPlaceholder.create_runner(&cmd)?;
+            set_default_ss58_version(runner.config().chain_spec.as_ref());
+            runner.async_run(|config| {
+                let pot_external_entropy =
+                    derive_pot_external_entropy(&config, cmd.pot_external_entropy.clone())?;
+                let PartialComponents {
+                    client,
+                    task_manager,
+                    ..
+                } = subspace_service::new_partial::<PosTable, RuntimeApi>(
+                    &config,
+                    NewPartialOptions::new(&pot_external_entropy),
+                )?;
+                Ok((
+                    async move {
+                        cmd.run(client.as_ref(), &pot_external_entropy)
+                            .map_err(Error::AuditPot)
+                    },
+                    task_manager,
+                ))
+            })?;
+        }
         Cli::Wipe(wipe_options) => {
             commands::wipe(wipe_options).map_err(|error| Error::Other(error.to_string()))?;
         }
@@ -224,7 +309,7 @@ fn main() -> Result<(), Error> {
                     ..
                 } = subspace_service::new_partial::<PosTable, RuntimeApi>(
                     &config,
-                    &derive_pot_external_entropy(&config, None)?,
+                    NewPartialOptions::new(&derive_pot_external_entropy(&config, None)?),
                 )?;
                 Ok((
                     cmd.run(client, backend, None).map_err(Error::SubstrateCli),
@@ -255,11 +340,13 @@ fn main() -> Result<(), Error> {
                         cmd.run::<Block, HostFunctions>(config)
                     }
                     BenchmarkCmd::Block(cmd) => {
-                        let PartialComponents { client, .. } =
-                            subspace_service::new_partial::<PosTable, RuntimeApi>(
-                                &config,
-                                &derive_pot_external_entropy(&config, None)?,
-                            )?;
+                        let PartialComponents { client, .. } = subspace_service::new_partial::<
+                            PosTable,
+                            RuntimeApi,
+                        >(
+                            &config,
+                            NewPartialOptions::new(&derive_pot_external_entropy(&config, None)?),
+                        )?;
 
                         cmd.run(client)
                     }
@@ -274,7 +361,7 @@ fn main() -> Result<(), Error> {
                             client, backend, ..
                         } = subspace_service::new_partial::<PosTable, RuntimeApi>(
                             &config,
-                            &derive_pot_external_entropy(&config, None)?,
+                            NewPartialOptions::new(&derive_pot_external_entropy(&config, None)?),
                         )?;
                         let db = backend.expose_db();
                         let storage = backend.expose_storage();
@@ -0,0 +1,59 @@
+use core::num::NonZeroU32;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use rand::{thread_rng, Rng};
+use subspace_core_primitives::PotSeed;
+use subspace_proof_of_time::{prove, verify, verify_batch, PotVerificationInput};
+
+/// Number of consecutive slots verified at once, similar to what syncing node does
+const SLOTS: usize = 16;
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let mut seed = PotSeed::default();
+    thread_rng().fill(seed.as_mut());
+    // About 1s on 6.0 GHz Raptor Lake CPU (14900K)
+    let pot_iterations = NonZeroU32::new(200_032_000).expect("Not zero; qed");
+
+    let mut proofs = Vec::with_capacity(SLOTS);
+    for _ in 0..SLOTS {
+        let checkpoints = prove(seed, pot_iterations).unwrap();
+        proofs.push((seed, checkpoints));
+        seed = checkpoints.output().seed();
+    }
+    let inputs = proofs
+        .iter()
+        .map(|(seed, checkpoints)| PotVerificationInput {
+            seed: *seed,
+            iterations: pot_iterations,
+            checkpoints: checkpoints.as_slice(),
+        })
+        .collect::<Vec<_>>();
+
+    let mut group = c.benchmark_group("verify-sync");
+    group.sample_size(10);
+
+    group.bench_function(format!("sequential/{SLOTS}-slots"), |b| {
+        b.iter(|| {
+            for input in &inputs {
+                black_box(verify(
+                    black_box(input.seed),
+                    black_box(input.iterations),
+                    black_box(input.checkpoints),
+                ))
+                .unwrap();
+            }
+        })
+    });
+
+    group.bench_function(format!("batch/{SLOTS}-slots"), |b| {
+        b.iter(|| {
+            for result in black_box(verify_batch(black_box(&inputs))) {
+                result.unwrap();
+            }
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
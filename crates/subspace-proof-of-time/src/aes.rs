@@ -75,6 +75,76 @@ pub(crate) fn verify_sequential(
     inputs == outputs
 }
 
+/// Number of lanes that [`verify_lanes`] interleaves in AES pipeline at once
+#[cfg(target_arch = "x86_64")]
+pub(crate) const VERIFY_LANES: usize = x86_64::NUM_LANES;
+/// Number of lanes that [`verify_lanes`] interleaves in AES pipeline at once
+#[cfg(not(target_arch = "x86_64"))]
+pub(crate) const VERIFY_LANES: usize = 1;
+
+/// Pair of consecutive checkpoints (or seed and the first checkpoint) of a slot.
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct VerificationLane {
+    /// Key of the slot
+    pub(crate) key: PotKey,
+    /// Seed or previous checkpoint
+    pub(crate) input: [u8; 16],
+    /// Checkpoint that must be produced from input
+    pub(crate) output: [u8; 16],
+}
+
+/// Verifies independent checkpoint pairs that may belong to different slots (and use different
+/// keys), interleaving them in AES pipeline.
+///
+/// Results are in the same order as lanes. Panics if `checkpoint_iterations` is not a multiple of
+/// `2`.
+pub(crate) fn verify_lanes(lanes: &[VerificationLane], checkpoint_iterations: u32) -> Vec<bool> {
+    assert_eq!(checkpoint_iterations % 2, 0);
+
+    #[cfg(target_arch = "x86_64")]
+    {
+        let mut results = Vec::with_capacity(lanes.len());
+        for chunk in lanes.chunks(VERIFY_LANES) {
+            // Incomplete chunk is padded with copies of its first lane, their results are ignored
+            let mut keys = [*chunk[0].key; VERIFY_LANES];
+            let mut inputs = [chunk[0].input; VERIFY_LANES];
+            let mut outputs = [chunk[0].output; VERIFY_LANES];
+            for (index, lane) in chunk.iter().enumerate() {
+                keys[index] = *lane.key;
+                inputs[index] = lane.input;
+                outputs[index] = lane.output;
+            }
+
+            let chunk_results =
+                unsafe { x86_64::verify_lanes(&keys, &inputs, &outputs, checkpoint_iterations) };
+            results.extend_from_slice(&chunk_results[..chunk.len()]);
+        }
+        results
+    }
+    #[cfg(not(target_arch = "x86_64"))]
+    verify_lanes_generic(lanes, checkpoint_iterations)
+}
+
+#[cfg(any(not(target_arch = "x86_64"), test))]
+fn verify_lanes_generic(lanes: &[VerificationLane], checkpoint_iterations: u32) -> Vec<bool> {
+    lanes
+        .iter()
+        .map(|lane| {
+            let key = GenericArray::from(*lane.key);
+            let cipher = Aes128::new(&key);
+            let mut input = GenericArray::from(lane.input);
+            let mut output = GenericArray::from(lane.output);
+
+            for _ in 0..checkpoint_iterations / 2 {
+                cipher.encrypt_block(&mut input);
+                cipher.decrypt_block(&mut output);
+            }
+
+            input == output
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -158,4 +228,60 @@ mod tests {
             checkpoint_iterations,
         ));
     }
+
+    #[test]
+    fn test_verify_lanes() {
+        let checkpoint_iterations = 100;
+        let slots = [(SEED, KEY), (SEED_1, KEY_1)].map(|(seed, key)| {
+            (
+                seed,
+                key,
+                create(
+                    PotSeed::from(seed),
+                    PotKey::from(key),
+                    checkpoint_iterations,
+                ),
+            )
+        });
+
+        // Checkpoint pairs of different slots with different keys are mixed together, there are
+        // more of them than lanes to also cover padding
+        let mut lanes = Vec::new();
+        for checkpoint_index in 0..3 {
+            for &(seed, key, checkpoints) in &slots {
+                lanes.push(VerificationLane {
+                    key: PotKey::from(key),
+                    input: if checkpoint_index == 0 {
+                        seed
+                    } else {
+                        *checkpoints[checkpoint_index - 1]
+                    },
+                    output: *checkpoints[checkpoint_index],
+                });
+            }
+        }
+        // Invalid checkpoint
+        lanes.push(VerificationLane {
+            output: BAD_CIPHER,
+            ..lanes[0]
+        });
+        // Key of another slot
+        lanes.push(VerificationLane {
+            key: PotKey::from(KEY_1),
+            ..lanes[0]
+        });
+
+        let mut expected = vec![true; lanes.len()];
+        expected[lanes.len() - 2..].fill(false);
+        assert_eq!(verify_lanes(&lanes, checkpoint_iterations), expected);
+        assert_eq!(
+            verify_lanes_generic(&lanes, checkpoint_iterations),
+            expected
+        );
+
+        // Wrong number of iterations
+        assert!(verify_lanes(&lanes, checkpoint_iterations + 2)
+            .into_iter()
+            .all(|valid| !valid));
+    }
 }
@@ -40,6 +40,83 @@ pub(super) unsafe fn create(
     checkpoints
 }
 
+/// Number of independent checkpoint pairs verified by [`verify_lanes`] at once, each pair occupies
+/// two blocks in AES pipeline (one is encrypted and another is decrypted)
+pub(super) const NUM_LANES: usize = 4;
+
+/// State of a single lane of [`verify_lanes`]
+struct Lane {
+    input: __m128i,
+    output: __m128i,
+    encrypt_keys: RoundKeys,
+    decrypt_keys: RoundKeys,
+}
+
+/// Verify [`NUM_LANES`] independent checkpoint pairs, each with its own key, interleaving them in
+/// AES pipeline.
+///
+/// Each input is encrypted and corresponding output is decrypted half of `checkpoint_iterations`
+/// times, lane is valid if they meet in the middle.
+#[target_feature(enable = "aes")]
+#[inline]
+pub(super) unsafe fn verify_lanes(
+    keys: &[[u8; 16]; NUM_LANES],
+    inputs: &[[u8; 16]; NUM_LANES],
+    outputs: &[[u8; 16]; NUM_LANES],
+    checkpoint_iterations: u32,
+) -> [bool; NUM_LANES] {
+    // SAFETY: `Lane` only contains `__m128i` values which can be initialized with all zeroes.
+    let mut lanes: [Lane; NUM_LANES] = mem::zeroed();
+    for (lane, ((key, input), output)) in lanes.iter_mut().zip(keys.iter().zip(inputs).zip(outputs))
+    {
+        lane.encrypt_keys = expand_key(key);
+        lane.decrypt_keys = inverse_keys(&lane.encrypt_keys);
+        lane.input = _mm_loadu_si128(input.as_ptr() as *const __m128i);
+        lane.output = _mm_loadu_si128(output.as_ptr() as *const __m128i);
+    }
+
+    for _ in 0..checkpoint_iterations / 2 {
+        for lane in &mut lanes {
+            lane.input = _mm_xor_si128(lane.input, lane.encrypt_keys[0]);
+            lane.output = _mm_xor_si128(lane.output, lane.decrypt_keys[0]);
+        }
+        for round in 1..10 {
+            for lane in &mut lanes {
+                lane.input = _mm_aesenc_si128(lane.input, lane.encrypt_keys[round]);
+                lane.output = _mm_aesdec_si128(lane.output, lane.decrypt_keys[round]);
+            }
+        }
+        for lane in &mut lanes {
+            lane.input = _mm_aesenclast_si128(lane.input, lane.encrypt_keys[10]);
+            lane.output = _mm_aesdeclast_si128(lane.output, lane.decrypt_keys[10]);
+        }
+    }
+
+    let mut results = [false; NUM_LANES];
+    for (result, lane) in results.iter_mut().zip(&lanes) {
+        *result = _mm_movemask_epi8(_mm_cmpeq_epi8(lane.input, lane.output)) == 0xffff;
+    }
+
+    results
+}
+
+/// Round keys for decryption with `aesdec` from round keys for encryption
+#[target_feature(enable = "aes")]
+#[inline]
+unsafe fn inverse_keys(keys: &RoundKeys) -> RoundKeys {
+    // SAFETY: `RoundKeys` is a `[__m128i; 11]` which can be initialized
+    // with all zeroes.
+    let mut inverse_keys: RoundKeys = mem::zeroed();
+
+    inverse_keys[0] = keys[10];
+    for (inverse_key, key) in inverse_keys[1..10].iter_mut().zip(keys[1..10].iter().rev()) {
+        *inverse_key = _mm_aesimc_si128(*key);
+    }
+    inverse_keys[10] = keys[0];
+
+    inverse_keys
+}
+
 // Below code copied with minor changes from following place under MIT/Apache-2.0 license by Artyom
 // Pavlov:
 // https://github.com/RustCrypto/block-ciphers/blob/9413fcadd28d53854954498c0589b747d8e4ade2/aes/src/ni/aes128.rs
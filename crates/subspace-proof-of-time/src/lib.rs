@@ -2,8 +2,14 @@
 
 #![cfg_attr(not(feature = "std"), no_std)]
 mod aes;
+#[cfg(all(test, feature = "parallel"))]
+mod tests;
 
 use core::num::NonZeroU32;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+#[cfg(feature = "parallel")]
+use std::collections::BTreeMap;
 use subspace_core_primitives::{PotCheckpoints, PotOutput, PotSeed};
 
 /// Proof of time error
@@ -67,3 +73,73 @@ pub fn verify(
         iterations.get() / num_checkpoints,
     ))
 }
+
+/// Checkpoints of a single slot to be verified as a part of a batch.
+#[derive(Debug, Copy, Clone)]
+pub struct PotVerificationInput<'a> {
+    /// Seed used for proving
+    pub seed: PotSeed,
+    /// Slot iterations used for proving
+    pub iterations: NonZeroU32,
+    /// Checkpoints to verify
+    pub checkpoints: &'a [PotOutput],
+}
+
+/// Verify checkpoints of many independent slots (for example, during sync) at once.
+///
+/// Results are in the same order as inputs and are identical to calling [`verify`] on each input.
+///
+/// Every pair of consecutive checkpoints is verified independently regardless of the slot it
+/// belongs to, such that pairs of different slots (each with its own key) are interleaved in AES
+/// pipeline and distributed across threads.
+#[cfg(feature = "parallel")]
+pub fn verify_batch(inputs: &[PotVerificationInput<'_>]) -> Vec<Result<bool, PotError>> {
+    let mut results = Vec::with_capacity(inputs.len());
+    // Lanes along with index of the input they belong to, grouped by iterations per checkpoint
+    let mut lanes_by_iterations = BTreeMap::<u32, Vec<(usize, aes::VerificationLane)>>::new();
+
+    for (index, input) in inputs.iter().enumerate() {
+        let num_checkpoints = input.checkpoints.len() as u32;
+        if input.iterations.get() % (num_checkpoints * 2) != 0 {
+            results.push(Err(PotError::NotMultipleOfCheckpoints {
+                iterations: input.iterations,
+                num_checkpoints,
+            }));
+            continue;
+        }
+        results.push(Ok(true));
+
+        let key = input.seed.key();
+        let lanes = lanes_by_iterations
+            .entry(input.iterations.get() / num_checkpoints)
+            .or_default();
+        let mut previous = *input.seed;
+        for checkpoint in input.checkpoints {
+            lanes.push((
+                index,
+                aes::VerificationLane {
+                    key,
+                    input: previous,
+                    output: **checkpoint,
+                },
+            ));
+            previous = **checkpoint;
+        }
+    }
+
+    for (checkpoint_iterations, lanes) in lanes_by_iterations {
+        let (indices, lanes) = lanes.into_iter().unzip::<_, _, Vec<_>, Vec<_>>();
+        let lane_results = lanes
+            .par_chunks(aes::VERIFY_LANES)
+            .map(|lanes| aes::verify_lanes(lanes, checkpoint_iterations))
+            .collect::<Vec<_>>();
+
+        for (index, valid) in indices.into_iter().zip(lane_results.into_iter().flatten()) {
+            if !valid {
+                results[index] = Ok(false);
+            }
+        }
+    }
+
+    results
+}
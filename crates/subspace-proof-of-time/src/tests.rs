@@ -0,0 +1,70 @@
+use crate::{prove, verify, verify_batch, PotVerificationInput};
+use core::num::NonZeroU32;
+use subspace_core_primitives::{PotOutput, PotSeed};
+
+#[test]
+fn verify_batch_matches_verify() {
+    let iterations = NonZeroU32::new(1_600).unwrap();
+    let proofs = (0..8_u8)
+        .map(|index| {
+            let seed = PotSeed::from([index; 16]);
+            (seed, prove(seed, iterations).unwrap())
+        })
+        .collect::<Vec<_>>();
+
+    let mut bad_checkpoints = proofs[1].1;
+    bad_checkpoints[5] = PotOutput::from([22; 16]);
+    let partial_checkpoints = &proofs[2].1[..4];
+    let not_multiple_iterations = NonZeroU32::new(1_602).unwrap();
+
+    let mut inputs = proofs
+        .iter()
+        .map(|(seed, checkpoints)| PotVerificationInput {
+            seed: *seed,
+            iterations,
+            checkpoints: checkpoints.as_slice(),
+        })
+        .collect::<Vec<_>>();
+    inputs.extend([
+        PotVerificationInput {
+            seed: proofs[1].0,
+            iterations,
+            checkpoints: bad_checkpoints.as_slice(),
+        },
+        PotVerificationInput {
+            seed: proofs[0].0,
+            iterations,
+            checkpoints: proofs[3].1.as_slice(),
+        },
+        PotVerificationInput {
+            seed: proofs[2].0,
+            iterations: NonZeroU32::new(800).unwrap(),
+            checkpoints: partial_checkpoints,
+        },
+        PotVerificationInput {
+            seed: proofs[4].0,
+            iterations: not_multiple_iterations,
+            checkpoints: proofs[4].1.as_slice(),
+        },
+    ]);
+
+    let results = verify_batch(&inputs);
+    assert_eq!(results.len(), inputs.len());
+
+    for (input, result) in inputs.iter().zip(results) {
+        let expected = verify(input.seed, input.iterations, input.checkpoints);
+        match (result, expected) {
+            (Ok(result), Ok(expected)) => assert_eq!(result, expected),
+            (Err(_), Err(_)) => {}
+            (result, expected) => panic!("Batch result {result:?} differs from {expected:?}"),
+        }
+    }
+
+    assert_eq!(
+        verify_batch(&inputs[..proofs.len() + 2])
+            .into_iter()
+            .map(Result::unwrap)
+            .collect::<Vec<_>>(),
+        [true, true, true, true, true, true, true, true, false, false]
+    );
+}
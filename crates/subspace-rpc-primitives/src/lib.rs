@@ -17,8 +17,10 @@
 
 use serde::{Deserialize, Serialize};
 use std::time::Duration;
+use subspace_core_primitives::objects::GlobalObject;
 use subspace_core_primitives::{
-    Blake3Hash, PublicKey, RewardSignature, SlotNumber, Solution, SolutionRange,
+    Blake3Hash, BlockHash, BlockNumber, HistorySize, PieceOffset, PublicKey, RewardSignature,
+    SectorIndex, SegmentIndex, SlotNumber, Solution, SolutionRange,
 };
 use subspace_farmer_components::FarmerProtocolInfo;
 use subspace_networking::libp2p::Multiaddr;
@@ -92,3 +94,170 @@ pub struct RewardSignatureResponse {
     /// Pre-header or vote hash signature.
     pub signature: Option<RewardSignature>,
 }
+
+/// Location of the object in archived history.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ObjectMappingItem {
+    /// Object hash.
+    #[serde(with = "hex::serde")]
+    pub hash: Blake3Hash,
+    /// Object location.
+    pub global_object: GlobalObject,
+}
+
+/// Object mappings of newly indexed segment.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ObjectMappingsInfo {
+    /// Segment index.
+    pub segment_index: SegmentIndex,
+    /// Object mappings contained in the segment.
+    pub object_mappings: Vec<ObjectMappingItem>,
+}
+
+/// Reason why solution submitted by farmer didn't result in block or vote.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", rename_all_fields = "camelCase")]
+pub enum SolutionRejectionReason {
+    /// Only solutions of root plot public key are accepted
+    NotRootPlotPublicKey,
+    /// Farmer is in block list
+    InBlockList,
+    /// Segment commitment of the segment solution refers to is not known to the node
+    SegmentCommitmentNotFound {
+        /// Segment index
+        segment_index: SegmentIndex,
+    },
+    /// Sector expiration check segment can't be derived from solution's history size
+    InvalidSectorExpirationCheck,
+    /// Solution failed verification
+    InvalidSolution {
+        /// Verification error
+        error: String,
+    },
+    /// Solution has quality sufficient for block, but block was already claimed with another
+    /// solution at this slot
+    BlockAlreadyClaimed,
+    /// Votes are not created on top of genesis block
+    VoteOnGenesisBlock,
+    /// Solution arrived for slot that node didn't try to claim
+    SlotNotClaimed,
+    /// Solution arrived after slot was already claimed
+    TooLate,
+    /// Solution was submitted for slot node is not expecting solutions for, either because it was
+    /// never announced or because it is too old
+    UnknownSlot,
+    /// Too many solutions were submitted for the same slot
+    TooManySolutions,
+}
+
+/// Outcome of the solution submitted by farmer.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", rename_all_fields = "camelCase")]
+pub enum SolutionOutcome {
+    /// Solution was used to claim block
+    ClaimedBlock,
+    /// Solution was used to claim vote
+    ClaimedVote,
+    /// Solution was not used
+    Rejected {
+        /// Reason why solution was not used
+        reason: SolutionRejectionReason,
+    },
+}
+
+/// Outcome of the solution submitted by farmer, used for diagnostics.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SolutionOutcomeInfo {
+    /// Slot number for which solution was submitted.
+    pub slot_number: SlotNumber,
+    /// Public key of the farmer.
+    #[serde(with = "hex::serde")]
+    pub public_key: [u8; 32],
+    /// Index of the sector where solution was found.
+    pub sector_index: SectorIndex,
+    /// Piece offset within sector.
+    pub piece_offset: PieceOffset,
+    /// Outcome of the solution.
+    pub outcome: SolutionOutcome,
+}
+
+/// Estimated total space pledged to the network, in bytes.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkSpaceEstimate {
+    /// Point estimate.
+    pub estimate: u128,
+    /// Lower bound of ~95% confidence interval.
+    pub lower_bound: u128,
+    /// Upper bound of ~95% confidence interval.
+    pub upper_bound: u128,
+}
+
+/// Solution ranges that were set at the start of an era.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EraSolutionRangesInfo {
+    /// First block of the era.
+    pub start_block: BlockNumber,
+    /// Slot at which solution ranges for the era were derived.
+    pub start_slot: SlotNumber,
+    /// Solution range used for block authoring during the era.
+    pub solution_range: SolutionRange,
+    /// Solution range used for voting during the era.
+    pub voting_solution_range: SolutionRange,
+    /// Size of the blockchain history at the start of the era.
+    pub history_size: HistorySize,
+    /// Network space estimated from solution range of the era.
+    pub network_space: NetworkSpaceEstimate,
+}
+
+/// History of solution ranges and network space estimated from them.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SolutionRangeHistoryInfo {
+    /// Solution ranges of the most recent eras in ascending order.
+    pub eras: Vec<EraSolutionRangesInfo>,
+    /// Current solution range for block authoring.
+    pub solution_range: SolutionRange,
+    /// Current solution range for voting.
+    pub voting_solution_range: SolutionRange,
+    /// Whether solution range adjustment is enabled, network space can't be estimated from
+    /// solution range otherwise.
+    pub solution_range_adjustment_enabled: bool,
+    /// Network space estimated from current solution range.
+    pub network_space: NetworkSpaceEstimate,
+    /// Current size of the blockchain history.
+    pub history_size: HistorySize,
+}
+
+/// Equivocation detected by the node, used for monitoring.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EquivocationEvidenceInfo {
+    /// Slot at which equivocation happened.
+    pub slot_number: SlotNumber,
+    /// Public key of the equivocating farmer.
+    #[serde(with = "hex::serde")]
+    pub offender: [u8; 32],
+    /// Number of the first block.
+    pub first_block_number: BlockNumber,
+    /// Hash of the first block.
+    #[serde(with = "hex::serde")]
+    pub first_block_hash: BlockHash,
+    /// SCALE-encoded header of the first block.
+    #[serde(with = "hex::serde")]
+    pub first_header: Vec<u8>,
+    /// Number of the second block.
+    pub second_block_number: BlockNumber,
+    /// Hash of the second block.
+    #[serde(with = "hex::serde")]
+    pub second_block_hash: BlockHash,
+    /// SCALE-encoded header of the second block.
+    #[serde(with = "hex::serde")]
+    pub second_header: Vec<u8>,
+    /// Whether equivocation report was submitted by the node.
+    pub reported: bool,
+}
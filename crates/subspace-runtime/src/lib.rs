@@ -53,8 +53,8 @@ use scale_info::TypeInfo;
 use sp_api::impl_runtime_apis;
 use sp_consensus_slots::{Slot, SlotDuration};
 use sp_consensus_subspace::{
-    ChainConstants, EquivocationProof, FarmerPublicKey, PotParameters, SignedVote, SolutionRanges,
-    Vote,
+    ChainConstants, EquivocationProof, FarmerPublicKey, PotEntropyInjectionParameters,
+    PotParameters, SignedVote, SolutionRangeHistory, SolutionRanges, Vote,
 };
 use sp_core::crypto::{ByteArray, KeyTypeId};
 use sp_core::{OpaqueMetadata, H256};
@@ -82,8 +82,8 @@ use sp_version::RuntimeVersion;
 use static_assertions::const_assert;
 use subspace_core_primitives::objects::BlockObjectMapping;
 use subspace_core_primitives::{
-    HistorySize, Piece, Randomness, Record, SegmentCommitment, SegmentHeader, SegmentIndex,
-    SlotNumber, SolutionRange, U256,
+    sectors_to_solution_range, solution_range_to_sectors, HistorySize, Piece, Randomness,
+    SegmentCommitment, SegmentHeader, SegmentIndex, SlotNumber, SolutionRange, U256,
 };
 use subspace_runtime_primitives::{
     AccountId, Balance, BlockNumber, FindBlockRewardAddress, Hash, Moment, Nonce, Signature,
@@ -110,7 +110,7 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
     spec_name: create_runtime_str!("subspace"),
     impl_name: create_runtime_str!("subspace"),
     authoring_version: 0,
-    spec_version: 2,
+    spec_version: 3,
     impl_version: 0,
     apis: RUNTIME_API_VERSIONS,
     transaction_version: 0,
@@ -176,13 +176,17 @@ const INITIAL_DOMAIN_TX_RANGE: u64 = 3;
 const TX_RANGE_ADJUSTMENT_INTERVAL_BLOCKS: u64 = 100;
 
 // We assume initial plot size starts with the a single sector.
-const INITIAL_SOLUTION_RANGE: SolutionRange = sectors_to_solution_range(1);
+const INITIAL_SOLUTION_RANGE: SolutionRange =
+    sectors_to_solution_range(1, SLOT_PROBABILITY, MAX_PIECES_IN_SECTOR);
 
 /// Number of votes expected per block.
 ///
 /// This impacts solution range for votes in consensus.
 const EXPECTED_VOTES_PER_BLOCK: u32 = 9;
 
+/// Number of most recent eras for which solution ranges are kept in history.
+const SOLUTION_RANGE_HISTORY_LENGTH: u32 = 256;
+
 /// Number of latest archived segments that are considered "recent history".
 const RECENT_SEGMENTS: HistorySize = HistorySize::new(NonZeroU64::new(5).expect("Not zero; qed"));
 /// Fraction of pieces from the "recent history" (`recent_segments`) in each sector.
@@ -204,40 +208,28 @@ const NORMAL_DISPATCH_RATIO: Perbill = Perbill::from_percent(75);
 /// Maximum block length for non-`Normal` extrinsic is 5 MiB.
 const MAX_BLOCK_LENGTH: u32 = 5 * 1024 * 1024;
 
-/// Computes the following:
-/// ```
-/// MAX * slot_probability / (pieces_in_sector * chunks / s_buckets) / sectors
-/// ```
-const fn sectors_to_solution_range(sectors: u64) -> SolutionRange {
-    let solution_range = SolutionRange::MAX
-        // Account for slot probability
-        / SLOT_PROBABILITY.1 * SLOT_PROBABILITY.0
-        // Now take sector size and probability of hitting occupied s-bucket in sector into account
-        / (MAX_PIECES_IN_SECTOR as u64 * Record::NUM_CHUNKS as u64 / Record::NUM_S_BUCKETS as u64);
-
-    // Take number of sectors into account
-    solution_range / sectors
-}
-
-/// Computes the following:
-/// ```
-/// MAX * slot_probability / (pieces_in_sector * chunks / s_buckets) / solution_range
-/// ```
-const fn solution_range_to_sectors(solution_range: SolutionRange) -> u64 {
-    let sectors = SolutionRange::MAX
-        // Account for slot probability
-        / SLOT_PROBABILITY.1 * SLOT_PROBABILITY.0
-        // Now take sector size and probability of hitting occupied s-bucket in sector into account
-        / (MAX_PIECES_IN_SECTOR as u64 * Record::NUM_CHUNKS as u64 / Record::NUM_S_BUCKETS as u64);
-
-    // Take solution range into account
-    sectors / solution_range
-}
-
-// Quick test to ensure functions above are the inverse of each other
-const_assert!(solution_range_to_sectors(sectors_to_solution_range(1)) == 1);
-const_assert!(solution_range_to_sectors(sectors_to_solution_range(3)) == 3);
-const_assert!(solution_range_to_sectors(sectors_to_solution_range(5)) == 5);
+// Quick test to ensure solution range conversion functions are the inverse of each other
+const_assert!(
+    solution_range_to_sectors(
+        sectors_to_solution_range(1, SLOT_PROBABILITY, MAX_PIECES_IN_SECTOR),
+        SLOT_PROBABILITY,
+        MAX_PIECES_IN_SECTOR
+    ) == 1
+);
+const_assert!(
+    solution_range_to_sectors(
+        sectors_to_solution_range(3, SLOT_PROBABILITY, MAX_PIECES_IN_SECTOR),
+        SLOT_PROBABILITY,
+        MAX_PIECES_IN_SECTOR
+    ) == 3
+);
+const_assert!(
+    solution_range_to_sectors(
+        sectors_to_solution_range(5, SLOT_PROBABILITY, MAX_PIECES_IN_SECTOR),
+        SLOT_PROBABILITY,
+        MAX_PIECES_IN_SECTOR
+    ) == 5
+);
 
 parameter_types! {
     pub const Version: RuntimeVersion = VERSION;
@@ -347,6 +339,7 @@ impl pallet_subspace::Config for Runtime {
     type ExpectedVotesPerBlock = ExpectedVotesPerBlock;
     type MaxPiecesInSector = ConstU16<{ MAX_PIECES_IN_SECTOR }>;
     type ShouldAdjustSolutionRange = ShouldAdjustSolutionRange;
+    type SolutionRangeHistoryLength = ConstU32<SOLUTION_RANGE_HISTORY_LENGTH>;
     type EraChangeTrigger = pallet_subspace::NormalEraChange;
 
     type HandleEquivocation = pallet_subspace::equivocation::EquivocationHandler<
@@ -424,7 +417,11 @@ impl pallet_balances::Config for Runtime {
 parameter_types! {
     pub CreditSupply: Balance = Balances::total_issuance();
     pub TotalSpacePledged: u128 = {
-        let sectors = solution_range_to_sectors(Subspace::solution_ranges().current);
+        let sectors = solution_range_to_sectors(
+            Subspace::solution_ranges().current,
+            SLOT_PROBABILITY,
+            MAX_PIECES_IN_SECTOR,
+        );
         sectors as u128 * MAX_PIECES_IN_SECTOR as u128 * Piece::SIZE as u128
     };
     pub BlockchainHistorySize: u128 = u128::from(Subspace::archived_history_size());
@@ -1006,6 +1003,18 @@ impl_runtime_apis! {
                 min_sector_lifetime: MinSectorLifetime::get(),
             }
         }
+
+        fn solution_range_history() -> SolutionRangeHistory<BlockNumber> {
+            Subspace::solution_range_history()
+        }
+
+        fn pot_entropy_injection_parameters() -> PotEntropyInjectionParameters<BlockNumber> {
+            PotEntropyInjectionParameters::V0 {
+                interval: PotEntropyInjectionInterval::get(),
+                lookback_depth: PotEntropyInjectionLookbackDepth::get(),
+                delay: Slot::from(PotEntropyInjectionDelay::get()),
+            }
+        }
     }
 
     impl sp_domains::DomainsApi<Block, DomainHeader> for Runtime {
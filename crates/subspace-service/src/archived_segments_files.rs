@@ -0,0 +1,701 @@
+//! Export of archived segments into files and offline import of blocks from them.
+//!
+//! Each segment is stored in a separate file named `segment-{segment_index}.bin` that contains
+//! SCALE-encoded [`ExportedSegment`]. Such files can be copied to another machine and used to
+//! reconstruct the chain there without any network access, all segment headers and pieces are
+//! verified against genesis segment and segment commitments before blocks are imported.
+
+#[cfg(test)]
+mod tests;
+
+use crate::sync_from_dsn::{import_blocks_from_dsn, DsnSyncPieceGetter, SegmentHeaderSource};
+use async_trait::async_trait;
+use parity_scale_codec::{Decode, Encode, IoReader};
+use sc_client_api::{AuxStore, BlockBackend, HeaderBackend};
+use sc_consensus::import_queue::ImportQueueService;
+use sc_consensus_subspace::archiver::{
+    decode_block, encode_block, recreate_genesis_segment, SegmentHeadersStore,
+};
+use sp_api::ProvideRuntimeApi;
+use sp_consensus_subspace::digests::CompatibleDigestItem;
+use sp_objects::ObjectsApi;
+use sp_runtime::traits::{Block as BlockT, Header as HeaderT, NumberFor, One};
+use std::collections::BTreeSet;
+use std::error::Error;
+use std::fs::File;
+use std::io::BufReader;
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use std::{fmt, fs, slice};
+use subspace_archiving::archiver::{is_piece_valid, Archiver, ExportedSegment};
+use subspace_archiving::reconstructor::Reconstructor;
+use subspace_core_primitives::crypto::kzg::Kzg;
+use subspace_core_primitives::objects::BlockObjectMapping;
+use subspace_core_primitives::{Piece, PieceIndex, SegmentHeader, SegmentIndex};
+use tokio::sync::Mutex;
+use tracing::{debug, info, warn};
+
+/// Interval at which import progress is checked after blocks were added to the import queue
+const CHECK_IMPORT_PROGRESS_INTERVAL: Duration = Duration::from_secs(1);
+/// How long to wait for import queue to make progress before giving up
+const IMPORT_STALL_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Path of the file with exported segment in the directory
+pub fn segment_file_path(directory: &Path, segment_index: SegmentIndex) -> PathBuf {
+    directory.join(format!("segment-{segment_index}.bin"))
+}
+
+fn write_exported_segment(
+    directory: &Path,
+    exported_segment: &ExportedSegment,
+) -> Result<(), sc_service::Error> {
+    let segment_index = exported_segment.segment_header.segment_index();
+    fs::write(
+        segment_file_path(directory, segment_index),
+        exported_segment.encode(),
+    )?;
+
+    Ok(())
+}
+
+fn read_exported_segment(
+    directory: &Path,
+    segment_index: SegmentIndex,
+) -> Result<ExportedSegment, sc_service::Error> {
+    let file = File::open(segment_file_path(directory, segment_index))?;
+    let exported_segment =
+        ExportedSegment::decode(&mut IoReader(BufReader::new(file))).map_err(|error| {
+            sc_service::Error::Other(format!("Failed to decode segment {segment_index}: {error}"))
+        })?;
+
+    if exported_segment.segment_header.segment_index() != segment_index {
+        return Err(sc_service::Error::Other(format!(
+            "File of segment {segment_index} contains segment {}",
+            exported_segment.segment_header.segment_index()
+        )));
+    }
+
+    Ok(exported_segment)
+}
+
+/// Re-archive blocks of the chain and export archived segments in the range into `directory`.
+///
+/// Segments are produced by the same archiver that node uses, produced segment headers are checked
+/// against segment headers store to make sure blocks of the canonical chain were archived.
+pub fn export_archived_segments<Block, Client, AS>(
+    client: &Client,
+    segment_headers_store: &SegmentHeadersStore<AS>,
+    kzg: Kzg,
+    segment_indices: RangeInclusive<SegmentIndex>,
+    directory: &Path,
+) -> Result<(), sc_service::Error>
+where
+    Block: BlockT,
+    Client: BlockBackend<Block> + HeaderBackend<Block>,
+    AS: AuxStore,
+{
+    let (from_segment_index, to_segment_index) = segment_indices.into_inner();
+    if from_segment_index > to_segment_index {
+        return Err(sc_service::Error::Other(format!(
+            "Invalid range of segments {from_segment_index}..={to_segment_index}"
+        )));
+    }
+    match segment_headers_store.max_segment_index() {
+        Some(max_segment_index) if max_segment_index >= to_segment_index => {}
+        maybe_max_segment_index => {
+            return Err(sc_service::Error::Other(format!(
+                "Segment {to_segment_index} was not archived yet, last archived segment is \
+                {maybe_max_segment_index:?}"
+            )));
+        }
+    }
+
+    fs::create_dir_all(directory)?;
+
+    info!(
+        %from_segment_index,
+        %to_segment_index,
+        directory = %directory.display(),
+        "Exporting archived segments"
+    );
+
+    // Object mappings are not a part of archived segments, hence not needed for export
+    let mut archiver = if from_segment_index == SegmentIndex::ZERO {
+        Archiver::new(kzg)
+    } else {
+        let previous_segment_index = from_segment_index - SegmentIndex::ONE;
+        let previous_segment_header = segment_headers_store
+            .get_segment_header(previous_segment_index)
+            .expect("Checked that segment headers up to the last one exist above; qed");
+
+        let last_archived_block_number = previous_segment_header.last_archived_block().number;
+        let last_archived_block = block_by_number(client, last_archived_block_number)?;
+
+        Archiver::with_initial_state(
+            kzg,
+            previous_segment_header,
+            &encode_block(last_archived_block),
+            BlockObjectMapping::default(),
+        )
+    }
+    .map_err(|error| {
+        sc_service::Error::Other(format!(
+            "Failed to instantiate archiver for export: {error}"
+        ))
+    })?;
+
+    let mut block_number = archiver
+        .last_archived_block_number()
+        .map(|block_number| block_number + 1)
+        .unwrap_or_default();
+
+    loop {
+        let block = block_by_number(client, block_number)?;
+
+        for archived_segment in
+            archiver.add_block(encode_block(block), BlockObjectMapping::default(), false)
+        {
+            let segment_header = archived_segment.segment_header;
+            let segment_index = segment_header.segment_index();
+
+            if segment_index < from_segment_index {
+                continue;
+            }
+
+            if segment_headers_store.get_segment_header(segment_index) != Some(segment_header) {
+                return Err(sc_service::Error::Other(format!(
+                    "Archived segment {segment_index} doesn't match segment header of the chain"
+                )));
+            }
+
+            write_exported_segment(
+                directory,
+                &ExportedSegment {
+                    segment_header,
+                    pieces: archived_segment.pieces,
+                },
+            )?;
+
+            info!(%segment_index, "Exported archived segment");
+
+            if segment_index >= to_segment_index {
+                return Ok(());
+            }
+        }
+
+        block_number += 1;
+    }
+}
+
+/// Import blocks from archived segments previously exported into `directory` with
+/// [`export_archived_segments`].
+///
+/// Segments in the directory must be consecutive and must either start with genesis segment or
+/// continue segment that is already known to the node. Segment headers are verified to form a chain
+/// that starts at genesis segment of this chain and to match segment commitments included in
+/// headers of exported blocks, pieces are verified against segment commitments. Each segment header
+/// is only stored after all of these checks succeed and blocks of the corresponding segment are
+/// imported.
+///
+/// Segment commitment is included in a block that is archived in one of the following segments,
+/// hence the last few exported segments might not be confirmed by exported blocks yet, such
+/// segments are not imported.
+///
+/// Returns number of imported blocks.
+pub async fn import_archived_segments<Block, Client, AS, IQS>(
+    client: &Client,
+    segment_headers_store: &SegmentHeadersStore<AS>,
+    kzg: Kzg,
+    import_queue_service: &mut IQS,
+    directory: &Path,
+) -> Result<u64, sc_service::Error>
+where
+    Block: BlockT,
+    Client: ProvideRuntimeApi<Block>
+        + BlockBackend<Block>
+        + HeaderBackend<Block>
+        + Send
+        + Sync
+        + 'static,
+    Client::Api: ObjectsApi<Block>,
+    AS: AuxStore + Send + Sync + 'static,
+    IQS: ImportQueueService<Block> + ?Sized,
+{
+    let mut segment_headers = read_segment_headers(directory)?;
+    let Some(first_segment_header) = segment_headers.first() else {
+        return Err(sc_service::Error::Other(format!(
+            "No exported segments found in {}",
+            directory.display()
+        )));
+    };
+    let first_segment_index = first_segment_header.segment_index();
+
+    verify_segment_headers(client, segment_headers_store, kzg.clone(), &segment_headers)?;
+
+    let confirmed_segment_headers = confirm_segment_headers_with_blocks::<Block, _>(
+        directory,
+        segment_headers_store,
+        &segment_headers,
+    )?;
+    if let Some(first_unconfirmed_segment_header) = segment_headers.get(confirmed_segment_headers) {
+        warn!(
+            first_unconfirmed_segment_index = %first_unconfirmed_segment_header.segment_index(),
+            "Segments are not confirmed by exported blocks yet, they will not be imported"
+        );
+        segment_headers.truncate(confirmed_segment_headers);
+    }
+    let Some(last_segment_header) = segment_headers.last() else {
+        return Err(sc_service::Error::Other(
+            "None of exported segments are confirmed by exported blocks, export more segments"
+                .to_string(),
+        ));
+    };
+    let last_segment_index = last_segment_header.segment_index();
+
+    info!(
+        %first_segment_index,
+        %last_segment_index,
+        directory = %directory.display(),
+        "Importing archived segments"
+    );
+
+    // Genesis segment corresponds to contents that every node has, hence it is considered processed
+    // right away, the same way as during sync from DSN
+    let mut last_processed_segment_index =
+        first_segment_index.max(SegmentIndex::ONE) - SegmentIndex::ONE;
+    let info = client.info();
+    if last_processed_segment_index > SegmentIndex::ZERO {
+        let previous_segment_header = segment_headers_store
+            .get_segment_header(last_processed_segment_index)
+            .expect("Segment headers were verified to continue known segment headers; qed");
+        let last_archived_block_number = previous_segment_header.last_archived_block().number;
+
+        if NumberFor::<Block>::from(last_archived_block_number) > info.best_number {
+            return Err(sc_service::Error::Other(format!(
+                "Segment {first_segment_index} continues block {last_archived_block_number}, but \
+                best block is {}, import previous segments first",
+                info.best_number
+            )));
+        }
+    }
+    // Finalized block is canonical, hence it is safe to skip segments with blocks before it
+    let mut last_processed_block_number = info.finalized_number;
+
+    let piece_getter = SegmentFilesPieceGetter {
+        directory: directory.to_path_buf(),
+        segment_headers: segment_headers.clone(),
+        kzg,
+        cached_segment: Mutex::default(),
+    };
+
+    let mut imported_blocks = 0;
+    let mut reconstructor = Reconstructor::new().map_err(|error| error.to_string())?;
+    for segment_header in &segment_headers {
+        let segment_index = segment_header.segment_index();
+
+        if segment_index > last_processed_segment_index {
+            imported_blocks += import_blocks_from_dsn(
+                segment_headers.as_slice(),
+                segment_index,
+                client,
+                &piece_getter,
+                import_queue_service,
+                &mut reconstructor,
+                &mut last_processed_segment_index,
+                &mut last_processed_block_number,
+            )
+            .await?;
+
+            // Last block might be archived partially, in which case it can't be imported yet
+            let last_archived_block = segment_header.last_archived_block();
+            let target_block_number = if last_archived_block.partial_archived().is_some() {
+                NumberFor::<Block>::from(last_archived_block.number) - One::one()
+            } else {
+                NumberFor::<Block>::from(last_archived_block.number)
+            };
+
+            wait_for_import(client, target_block_number).await?;
+        }
+
+        // Segment header is stored only once blocks of the segment are imported, such that
+        // interrupted import doesn't leave known segment headers without corresponding blocks
+        segment_headers_store.add_segment_headers(slice::from_ref(segment_header))?;
+    }
+
+    info!(
+        %imported_blocks,
+        best_block_number = %client.info().best_number,
+        "Finished importing archived segments"
+    );
+
+    Ok(imported_blocks)
+}
+
+/// Read segment headers of all exported segments in the directory, segments must be consecutive
+fn read_segment_headers(directory: &Path) -> Result<Vec<SegmentHeader>, sc_service::Error> {
+    let mut segment_indices = Vec::new();
+    for entry in fs::read_dir(directory)? {
+        let file_name = entry?.file_name();
+        let maybe_segment_index = file_name
+            .to_str()
+            .and_then(|file_name| file_name.strip_prefix("segment-"))
+            .and_then(|file_name| file_name.strip_suffix(".bin"))
+            .and_then(|segment_index| segment_index.parse::<u64>().ok());
+
+        if let Some(segment_index) = maybe_segment_index {
+            segment_indices.push(SegmentIndex::from(segment_index));
+        }
+    }
+    segment_indices.sort_unstable();
+
+    let mut segment_headers = Vec::<SegmentHeader>::with_capacity(segment_indices.len());
+    for segment_index in segment_indices {
+        if let Some(previous_segment_header) = segment_headers.last()
+            && previous_segment_header.segment_index() + SegmentIndex::ONE != segment_index
+        {
+            return Err(sc_service::Error::Other(format!(
+                "Exported segments are not consecutive, segment {} is followed by {segment_index}",
+                previous_segment_header.segment_index()
+            )));
+        }
+
+        let file = File::open(segment_file_path(directory, segment_index))?;
+        // Segment header is stored first, no need to read pieces here
+        let segment_header =
+            SegmentHeader::decode(&mut IoReader(BufReader::new(file))).map_err(|error| {
+                sc_service::Error::Other(format!(
+                    "Failed to decode segment header of segment {segment_index}: {error}"
+                ))
+            })?;
+
+        if segment_header.segment_index() != segment_index {
+            return Err(sc_service::Error::Other(format!(
+                "File of segment {segment_index} contains segment header of segment {}",
+                segment_header.segment_index()
+            )));
+        }
+
+        segment_headers.push(segment_header);
+    }
+
+    Ok(segment_headers)
+}
+
+/// Verify that segment headers form a chain that starts at genesis segment of this chain and
+/// doesn't contradict already known segment headers
+fn verify_segment_headers<Block, Client, AS>(
+    client: &Client,
+    segment_headers_store: &SegmentHeadersStore<AS>,
+    kzg: Kzg,
+    segment_headers: &[SegmentHeader],
+) -> Result<(), sc_service::Error>
+where
+    Block: BlockT,
+    Client: ProvideRuntimeApi<Block> + BlockBackend<Block> + HeaderBackend<Block>,
+    Client::Api: ObjectsApi<Block>,
+    AS: AuxStore,
+{
+    let first_segment_header = segment_headers
+        .first()
+        .expect("Only called with non-empty list of segment headers; qed");
+    let first_segment_index = first_segment_header.segment_index();
+
+    if first_segment_index == SegmentIndex::ZERO {
+        let genesis_segment = recreate_genesis_segment(client, kzg)
+            .map_err(|error| {
+                sc_service::Error::Other(format!("Failed to recreate genesis segment: {error}"))
+            })?
+            .ok_or_else(|| {
+                sc_service::Error::Other(
+                    "Genesis block is pruned, can't verify genesis segment".to_string(),
+                )
+            })?;
+
+        if genesis_segment.segment_header != *first_segment_header {
+            return Err(sc_service::Error::Other(
+                "Genesis segment doesn't belong to this chain".to_string(),
+            ));
+        }
+    }
+
+    verify_segment_headers_chain(segment_headers_store, segment_headers)
+}
+
+/// Verify that segment headers form a chain that starts with genesis segment header or continues
+/// the last known segment header and doesn't contradict already known segment headers
+fn verify_segment_headers_chain<AS>(
+    segment_headers_store: &SegmentHeadersStore<AS>,
+    segment_headers: &[SegmentHeader],
+) -> Result<(), sc_service::Error>
+where
+    AS: AuxStore,
+{
+    let first_segment_header = segment_headers
+        .first()
+        .expect("Only called with non-empty list of segment headers; qed");
+    let first_segment_index = first_segment_header.segment_index();
+
+    match segment_headers_store.max_segment_index() {
+        Some(last_segment_index) => {
+            if first_segment_index > last_segment_index + SegmentIndex::ONE {
+                return Err(sc_service::Error::Other(format!(
+                    "Segment {first_segment_index} doesn't continue last known segment \
+                    {last_segment_index}, import previous segments first"
+                )));
+            }
+
+            let last_segment_header = segment_headers_store
+                .get_segment_header(last_segment_index)
+                .expect("Last segment index is known, hence segment header exists; qed");
+            if let Some(next_segment_header) =
+                segment_headers.get_segment_header(last_segment_index + SegmentIndex::ONE)
+                && next_segment_header.prev_segment_header_hash() != last_segment_header.hash()
+            {
+                return Err(sc_service::Error::Other(format!(
+                    "Segment {} doesn't continue last known segment {last_segment_index}",
+                    next_segment_header.segment_index()
+                )));
+            }
+        }
+        None => {
+            if first_segment_index > SegmentIndex::ZERO {
+                return Err(sc_service::Error::Other(format!(
+                    "No segments are known, segment {first_segment_index} can't be imported \
+                    before genesis segment"
+                )));
+            }
+        }
+    }
+
+    for (previous_segment_header, segment_header) in
+        segment_headers.iter().zip(segment_headers.iter().skip(1))
+    {
+        if segment_header.prev_segment_header_hash() != previous_segment_header.hash() {
+            return Err(sc_service::Error::Other(format!(
+                "Segment {} doesn't continue segment {}",
+                segment_header.segment_index(),
+                previous_segment_header.segment_index()
+            )));
+        }
+    }
+
+    for segment_header in segment_headers {
+        let segment_index = segment_header.segment_index();
+        if let Some(known_segment_header) = segment_headers_store.get_segment_header(segment_index)
+            && known_segment_header != *segment_header
+        {
+            return Err(sc_service::Error::Other(format!(
+                "Segment {segment_index} doesn't match already known segment header"
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+/// Check segment headers against segment commitments included in headers of blocks reconstructed
+/// from exported segments.
+///
+/// Returns number of leading segment headers that are confirmed by exported blocks, the rest was
+/// not included in any exported block yet.
+fn confirm_segment_headers_with_blocks<Block, AS>(
+    directory: &Path,
+    segment_headers_store: &SegmentHeadersStore<AS>,
+    segment_headers: &[SegmentHeader],
+) -> Result<usize, sc_service::Error>
+where
+    Block: BlockT,
+    AS: AuxStore,
+{
+    let first_segment_index = segment_headers
+        .first()
+        .expect("Only called with non-empty list of segment headers; qed")
+        .segment_index();
+    let mut confirmed_segment_indices = BTreeSet::new();
+    let mut reconstructor = Reconstructor::new().map_err(|error| error.to_string())?;
+
+    for segment_header in segment_headers {
+        let segment_index = segment_header.segment_index();
+        let exported_segment = read_exported_segment(directory, segment_index)?;
+        if exported_segment.segment_header != *segment_header {
+            return Err(sc_service::Error::Other(format!(
+                "File of segment {segment_index} contains unexpected segment header"
+            )));
+        }
+
+        // Source pieces are sufficient for reconstruction, it doesn't need parity pieces
+        let segment_pieces = exported_segment
+            .pieces
+            .iter()
+            .enumerate()
+            .map(|(position, piece)| (position % 2 == 0).then(|| Piece::from(piece)))
+            .collect::<Vec<_>>();
+        let reconstructed_contents = reconstructor
+            .add_segment(&segment_pieces)
+            .map_err(|error| error.to_string())?;
+
+        for (block_number, block_bytes) in reconstructed_contents.blocks {
+            let signed_block = decode_block::<Block>(&block_bytes).map_err(|error| {
+                sc_service::Error::Other(format!(
+                    "Failed to decode block {block_number} from segment {segment_index}: {error}"
+                ))
+            })?;
+
+            for log in signed_block.block.header().digest().logs() {
+                let Some((included_segment_index, segment_commitment)) =
+                    log.as_segment_commitment()
+                else {
+                    continue;
+                };
+
+                let expected_segment_commitment = if included_segment_index >= first_segment_index {
+                    segment_headers
+                        .get(u64::from(included_segment_index - first_segment_index) as usize)
+                        .map(SegmentHeader::segment_commitment)
+                } else {
+                    segment_headers_store
+                        .get_segment_header(included_segment_index)
+                        .map(|segment_header| segment_header.segment_commitment())
+                };
+
+                if let Some(expected_segment_commitment) = expected_segment_commitment {
+                    if expected_segment_commitment != segment_commitment {
+                        return Err(sc_service::Error::Other(format!(
+                            "Segment {included_segment_index} doesn't match segment commitment \
+                            included in block {block_number}"
+                        )));
+                    }
+
+                    confirmed_segment_indices.insert(included_segment_index);
+                }
+            }
+        }
+    }
+
+    Ok(segment_headers
+        .iter()
+        .take_while(|segment_header| {
+            confirmed_segment_indices.contains(&segment_header.segment_index())
+        })
+        .count())
+}
+
+/// Wait for import queue to import blocks up to target block number
+async fn wait_for_import<Block, Client>(
+    client: &Client,
+    target_block_number: NumberFor<Block>,
+) -> Result<(), sc_service::Error>
+where
+    Block: BlockT,
+    Client: HeaderBackend<Block>,
+{
+    let mut best_block_number = client.info().best_number;
+    let mut stalled_for = Duration::ZERO;
+
+    while best_block_number < target_block_number {
+        tokio::time::sleep(CHECK_IMPORT_PROGRESS_INTERVAL).await;
+
+        let new_best_block_number = client.info().best_number;
+        if new_best_block_number > best_block_number {
+            best_block_number = new_best_block_number;
+            stalled_for = Duration::ZERO;
+        } else {
+            stalled_for += CHECK_IMPORT_PROGRESS_INTERVAL;
+            if stalled_for >= IMPORT_STALL_TIMEOUT {
+                return Err(sc_service::Error::Other(format!(
+                    "Block import stalled at block {best_block_number}, expected to reach block \
+                    {target_block_number}"
+                )));
+            }
+        }
+
+        debug!(%best_block_number, %target_block_number, "Waiting for blocks to be imported");
+    }
+
+    Ok(())
+}
+
+fn block_by_number<Block, Client>(
+    client: &Client,
+    block_number: u32,
+) -> Result<sp_runtime::generic::SignedBlock<Block>, sc_service::Error>
+where
+    Block: BlockT,
+    Client: BlockBackend<Block> + HeaderBackend<Block>,
+{
+    client
+        .hash(block_number.into())?
+        .map(|block_hash| client.block(block_hash))
+        .transpose()?
+        .flatten()
+        .ok_or_else(|| sc_service::Error::Other(format!("Block {block_number} is not available")))
+}
+
+/// Piece getter that reads pieces from exported segment files, the whole segment is kept in memory
+/// while its pieces are requested
+struct SegmentFilesPieceGetter {
+    directory: PathBuf,
+    /// Segment headers of imported segments, sorted by segment index
+    segment_headers: Vec<SegmentHeader>,
+    kzg: Kzg,
+    cached_segment: Mutex<Option<ExportedSegment>>,
+}
+
+impl fmt::Debug for SegmentFilesPieceGetter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SegmentFilesPieceGetter")
+            .field("directory", &self.directory)
+            .finish_non_exhaustive()
+    }
+}
+
+#[async_trait]
+impl DsnSyncPieceGetter for SegmentFilesPieceGetter {
+    async fn get_piece(
+        &self,
+        piece_index: PieceIndex,
+    ) -> Result<Option<Piece>, Box<dyn Error + Send + Sync + 'static>> {
+        let segment_index = piece_index.segment_index();
+        let Some(segment_header) = self.segment_headers.get_segment_header(segment_index) else {
+            return Ok(None);
+        };
+
+        let piece = {
+            let mut cached_segment = self.cached_segment.lock().await;
+            if cached_segment.as_ref().map_or(true, |exported_segment| {
+                exported_segment.segment_header != segment_header
+            }) {
+                let exported_segment = read_exported_segment(&self.directory, segment_index)?;
+
+                if exported_segment.segment_header != segment_header {
+                    return Err(format!(
+                        "File of segment {segment_index} contains unexpected segment header"
+                    )
+                    .into());
+                }
+
+                cached_segment.replace(exported_segment);
+            }
+
+            let exported_segment = cached_segment
+                .as_ref()
+                .expect("Segment was loaded above if it wasn't cached yet; qed");
+
+            Piece::from(&exported_segment.pieces[piece_index.position() as usize])
+        };
+
+        if !is_piece_valid(
+            &self.kzg,
+            &piece,
+            &segment_header.segment_commitment(),
+            piece_index.position(),
+        ) {
+            return Err(
+                format!("Piece {piece_index} of segment {segment_index} is invalid").into(),
+            );
+        }
+
+        Ok(Some(piece))
+    }
+}
@@ -0,0 +1,304 @@
+use crate::archived_segments_files::{
+    confirm_segment_headers_with_blocks, read_exported_segment, read_segment_headers,
+    segment_file_path, verify_segment_headers_chain, write_exported_segment,
+    SegmentFilesPieceGetter,
+};
+use crate::sync_from_dsn::DsnSyncPieceGetter;
+use futures::executor::block_on;
+use parity_scale_codec::Encode;
+use sc_client_api::in_mem::Backend;
+use sc_consensus_subspace::archiver::{encode_block, SegmentHeadersStore};
+use sp_consensus_subspace::digests::CompatibleDigestItem;
+use sp_runtime::generic::SignedBlock;
+use sp_runtime::traits::Header as HeaderT;
+use sp_runtime::{Digest, DigestItem, OpaqueExtrinsic};
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+use subspace_archiving::archiver::{Archiver, ExportedSegment};
+use subspace_archiving::reconstructor::Reconstructor;
+use subspace_core_primitives::crypto::kzg::{embedded_kzg_settings, Kzg};
+use subspace_core_primitives::objects::BlockObjectMapping;
+use subspace_core_primitives::{
+    ArchivedHistorySegment, Blake3Hash, PieceIndex, SegmentCommitment, SegmentHeader, SegmentIndex,
+};
+use subspace_runtime_primitives::opaque::{Block, Header};
+use tempfile::TempDir;
+
+/// Size of extrinsic in each non-genesis block, such that segments are produced quickly
+const EXTRINSIC_SIZE: usize = 8 * 1024 * 1024;
+
+/// Create and archive a chain until `num_segments` segments are produced, segments are exported
+/// into `directory`.
+///
+/// Segment commitments are included in the block that follows the one which produced segment (as
+/// if confirmation depth was zero), the same way runtime does it.
+fn export_chain(
+    kzg: &Kzg,
+    directory: &Path,
+    num_segments: usize,
+) -> (Vec<SignedBlock<Block>>, Vec<SegmentHeader>) {
+    let mut archiver = Archiver::new(kzg.clone()).unwrap();
+    let mut blocks = Vec::<SignedBlock<Block>>::new();
+    let mut segment_headers = Vec::<SegmentHeader>::new();
+    let mut segment_headers_to_include = Vec::<SegmentHeader>::new();
+
+    while segment_headers.len() < num_segments {
+        let number = blocks.len() as u32;
+        let parent_hash = blocks
+            .last()
+            .map(|block| block.block.header.hash())
+            .unwrap_or_default();
+        let logs = segment_headers_to_include
+            .drain(..)
+            .map(|segment_header| {
+                DigestItem::segment_commitment(
+                    segment_header.segment_index(),
+                    segment_header.segment_commitment(),
+                )
+            })
+            .collect();
+        let extrinsics = if number == 0 {
+            Vec::new()
+        } else {
+            let extrinsic = vec![number as u8; EXTRINSIC_SIZE];
+            vec![OpaqueExtrinsic::from_bytes(&extrinsic.encode()).unwrap()]
+        };
+        let block = SignedBlock {
+            block: Block {
+                header: Header::new(
+                    number,
+                    Default::default(),
+                    Default::default(),
+                    parent_hash,
+                    Digest { logs },
+                ),
+                extrinsics,
+            },
+            justifications: None,
+        };
+
+        for archived_segment in archiver.add_block(
+            encode_block(block.clone()),
+            BlockObjectMapping::default(),
+            false,
+        ) {
+            let segment_header = archived_segment.segment_header;
+            write_exported_segment(
+                directory,
+                &ExportedSegment {
+                    segment_header,
+                    pieces: archived_segment.pieces,
+                },
+            )
+            .unwrap();
+
+            segment_headers.push(segment_header);
+            segment_headers_to_include.push(segment_header);
+        }
+
+        blocks.push(block);
+    }
+
+    (blocks, segment_headers)
+}
+
+fn modify_exported_segment<F>(directory: &Path, segment_index: SegmentIndex, modify: F)
+where
+    F: FnOnce(&mut ExportedSegment),
+{
+    let mut exported_segment = read_exported_segment(directory, segment_index).unwrap();
+    modify(&mut exported_segment);
+    fs::write(
+        segment_file_path(directory, segment_index),
+        exported_segment.encode(),
+    )
+    .unwrap();
+}
+
+fn with_segment_commitment(
+    segment_header: SegmentHeader,
+    segment_commitment: SegmentCommitment,
+    prev_segment_header_hash: Blake3Hash,
+) -> SegmentHeader {
+    SegmentHeader::V0 {
+        segment_index: segment_header.segment_index(),
+        segment_commitment,
+        prev_segment_header_hash,
+        last_archived_block: segment_header.last_archived_block(),
+    }
+}
+
+fn piece_getter(
+    directory: &Path,
+    segment_headers: &[SegmentHeader],
+    kzg: &Kzg,
+) -> SegmentFilesPieceGetter {
+    SegmentFilesPieceGetter {
+        directory: directory.to_path_buf(),
+        segment_headers: segment_headers.to_vec(),
+        kzg: kzg.clone(),
+        cached_segment: Default::default(),
+    }
+}
+
+#[test]
+fn export_import_round_trip() {
+    let directory = TempDir::new().unwrap();
+    let kzg = Kzg::new(embedded_kzg_settings());
+    let (blocks, segment_headers) = export_chain(&kzg, directory.path(), 3);
+
+    assert_eq!(
+        read_segment_headers(directory.path()).unwrap(),
+        segment_headers
+    );
+
+    let segment_headers_store =
+        SegmentHeadersStore::new(Arc::new(Backend::<Block>::new())).unwrap();
+    verify_segment_headers_chain(&segment_headers_store, &segment_headers).unwrap();
+    // Nothing is known yet, hence import must start with genesis segment
+    assert!(verify_segment_headers_chain(&segment_headers_store, &segment_headers[1..]).is_err());
+
+    // Commitment of the last segment is not included in any exported block yet
+    let confirmed_segment_headers = confirm_segment_headers_with_blocks::<Block, _>(
+        directory.path(),
+        &segment_headers_store,
+        &segment_headers,
+    )
+    .unwrap();
+    assert_eq!(confirmed_segment_headers, 2);
+
+    segment_headers_store
+        .add_segment_headers(&segment_headers[..confirmed_segment_headers])
+        .unwrap();
+
+    // Continuation of known segment headers passes verification too
+    verify_segment_headers_chain(&segment_headers_store, &segment_headers[1..]).unwrap();
+    verify_segment_headers_chain(&segment_headers_store, &segment_headers[2..]).unwrap();
+
+    // Segment header that doesn't chain to the last known segment header is rejected
+    let forged_segment_header_2 = with_segment_commitment(
+        segment_headers[2],
+        segment_headers[2].segment_commitment(),
+        segment_headers[0].hash(),
+    );
+    assert!(
+        verify_segment_headers_chain(&segment_headers_store, &[forged_segment_header_2]).is_err()
+    );
+
+    let piece_getter = piece_getter(
+        directory.path(),
+        &segment_headers[..confirmed_segment_headers],
+        &kzg,
+    );
+    let mut reconstructor = Reconstructor::new().unwrap();
+    let mut reconstructed_blocks = Vec::new();
+    for segment_index in [SegmentIndex::ZERO, SegmentIndex::ONE] {
+        let segment_pieces = segment_index
+            .segment_piece_indexes_source_first()
+            .into_iter()
+            .take(ArchivedHistorySegment::NUM_PIECES / 2)
+            .map(|piece_index| block_on(piece_getter.get_piece(piece_index)).unwrap())
+            .collect::<Vec<_>>();
+        assert!(segment_pieces.iter().all(Option::is_some));
+
+        // Source pieces are at even positions
+        let segment_pieces = (0..ArchivedHistorySegment::NUM_PIECES)
+            .map(|position| {
+                (position % 2 == 0)
+                    .then(|| segment_pieces[position / 2].clone())
+                    .flatten()
+            })
+            .collect::<Vec<_>>();
+        reconstructed_blocks.extend(reconstructor.add_segment(&segment_pieces).unwrap().blocks);
+    }
+
+    assert!(!reconstructed_blocks.is_empty());
+    for (block_number, block_bytes) in reconstructed_blocks {
+        assert_eq!(
+            block_bytes,
+            encode_block(blocks[block_number as usize].clone()),
+            "Block {block_number} must be the same after round trip"
+        );
+    }
+
+    // Unconfirmed segment is not known and its pieces are not served
+    assert!(
+        block_on(piece_getter.get_piece(SegmentIndex::from(2).first_piece_index()))
+            .unwrap()
+            .is_none()
+    );
+}
+
+#[test]
+fn tampered_segments_are_rejected() {
+    let directory = TempDir::new().unwrap();
+    let kzg = Kzg::new(embedded_kzg_settings());
+    let (_blocks, segment_headers) = export_chain(&kzg, directory.path(), 2);
+    let segment_headers_store =
+        SegmentHeadersStore::new(Arc::new(Backend::<Block>::new())).unwrap();
+
+    // Forged genesis segment header breaks the chain of segment headers
+    let forged_segment_commitment = SegmentCommitment::from([1; SegmentCommitment::SIZE]);
+    let forged_segment_header_0 = with_segment_commitment(
+        segment_headers[0],
+        forged_segment_commitment,
+        segment_headers[0].prev_segment_header_hash(),
+    );
+    modify_exported_segment(directory.path(), SegmentIndex::ZERO, |exported_segment| {
+        exported_segment.segment_header = forged_segment_header_0;
+    });
+    let forged_segment_headers = read_segment_headers(directory.path()).unwrap();
+    assert!(verify_segment_headers_chain(&segment_headers_store, &forged_segment_headers).is_err());
+
+    // Chain that was forged consistently still doesn't match exported blocks
+    let forged_segment_header_1 = with_segment_commitment(
+        segment_headers[1],
+        segment_headers[1].segment_commitment(),
+        forged_segment_header_0.hash(),
+    );
+    modify_exported_segment(directory.path(), SegmentIndex::ONE, |exported_segment| {
+        exported_segment.segment_header = forged_segment_header_1;
+    });
+    let forged_segment_headers = read_segment_headers(directory.path()).unwrap();
+    verify_segment_headers_chain(&segment_headers_store, &forged_segment_headers).unwrap();
+    assert!(confirm_segment_headers_with_blocks::<Block, _>(
+        directory.path(),
+        &segment_headers_store,
+        &forged_segment_headers,
+    )
+    .is_err());
+
+    // Restore original segment headers
+    for segment_header in &segment_headers {
+        modify_exported_segment(
+            directory.path(),
+            segment_header.segment_index(),
+            |exported_segment| {
+                exported_segment.segment_header = *segment_header;
+            },
+        );
+    }
+    segment_headers_store
+        .add_segment_headers(&segment_headers[..1])
+        .unwrap();
+
+    // Tampered piece doesn't match segment commitment
+    let piece_index = PieceIndex::from(2);
+    modify_exported_segment(directory.path(), SegmentIndex::ZERO, |exported_segment| {
+        exported_segment.pieces[piece_index.position() as usize].record_mut()[0][0] ^= 1;
+    });
+    let piece_getter = piece_getter(directory.path(), &segment_headers[..1], &kzg);
+    assert!(block_on(piece_getter.get_piece(piece_index)).is_err());
+    assert!(block_on(piece_getter.get_piece(PieceIndex::ZERO))
+        .unwrap()
+        .is_some());
+
+    // File with segment that has unexpected name
+    fs::copy(
+        segment_file_path(directory.path(), SegmentIndex::ZERO),
+        segment_file_path(directory.path(), SegmentIndex::from(2)),
+    )
+    .unwrap();
+    assert!(read_segment_headers(directory.path()).is_err());
+}
@@ -18,6 +18,7 @@ use std::ops::Deref;
 use std::path::PathBuf;
 use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
+use subspace_core_primitives::SegmentIndex;
 use subspace_networking::libp2p::Multiaddr;
 use subspace_networking::Node;
 use tokio::runtime::Handle;
@@ -230,6 +231,14 @@ pub enum SubspaceNetworking {
     },
 }
 
+/// Object mappings index configuration.
+#[derive(Debug, Clone, Copy)]
+pub struct ObjectMappingsConfig {
+    /// Segment index starting with which already archived segments will be re-created and
+    /// indexed, `None` means only newly archived segments will be indexed.
+    pub backfill_from: Option<SegmentIndex>,
+}
+
 /// Subspace-specific service configuration.
 #[derive(Debug)]
 pub struct SubspaceConfiguration {
@@ -248,6 +257,10 @@ pub struct SubspaceConfiguration {
     pub is_timekeeper: bool,
     /// CPU cores that timekeeper can use
     pub timekeeper_cpu_cores: HashSet<usize>,
+    /// Object mappings index configuration, `None` disables indexing
+    pub object_mappings: Option<ObjectMappingsConfig>,
+    /// Detect equivocations in imported and announced blocks, report them and store the evidence
+    pub equivocation_reporting: bool,
 }
 
 impl Deref for SubspaceConfiguration {
@@ -4,10 +4,11 @@ use std::fs;
 use std::num::NonZeroUsize;
 use std::path::PathBuf;
 use subspace_networking::libp2p::kad::Mode;
+use subspace_networking::libp2p::mdns::Config as MdnsConfig;
 use subspace_networking::libp2p::{identity, Multiaddr};
 use subspace_networking::utils::strip_peer_id;
 use subspace_networking::{
-    CreationError, KademliaMode, KnownPeersManager, KnownPeersManagerConfig,
+    gossipsub_config, CreationError, KademliaMode, KnownPeersManager, KnownPeersManagerConfig,
     KnownPeersManagerPersistenceError, Node, NodeRunner, PieceByIndexRequestHandler,
     SegmentHeaderBySegmentIndexesRequestHandler,
 };
@@ -66,6 +67,12 @@ pub struct DsnConfig {
 
     /// Defines whether we should run blocking Kademlia bootstrap() operation before other requests.
     pub disable_bootstrap_on_start: bool,
+
+    /// Discover peers in local network using mDNS.
+    pub lan_peer_discovery: bool,
+
+    /// Announce newly archived segment headers to other peers over gossipsub.
+    pub segment_header_announcements: bool,
 }
 
 pub(crate) fn create_dsn_instance(
@@ -119,6 +126,10 @@ pub(crate) fn create_dsn_instance(
         external_addresses: dsn_config.external_addresses,
         kademlia_mode: KademliaMode::Static(Mode::Client),
         disable_bootstrap_on_start: dsn_config.disable_bootstrap_on_start,
+        mdns: dsn_config.lan_peer_discovery.then(MdnsConfig::default),
+        gossipsub: dsn_config
+            .segment_header_announcements
+            .then(gossipsub_config),
 
         ..default_networking_config
     };
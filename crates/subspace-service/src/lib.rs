@@ -24,6 +24,7 @@
     type_changing_struct_update
 )]
 
+pub mod archived_segments_files;
 pub mod config;
 pub mod dsn;
 mod metrics;
@@ -34,14 +35,16 @@ pub mod transaction_pool;
 use crate::config::{SubspaceConfiguration, SubspaceNetworking};
 use crate::dsn::{create_dsn_instance, DsnConfigurationError};
 use crate::metrics::NodeMetrics;
-use crate::sync_from_dsn::piece_validator::SegmentCommitmentPieceValidator;
+use crate::sync_from_dsn::piece_validator::{
+    SegmentCommitmentPieceValidator, SegmentHeadersStoreCommitments,
+};
 use crate::transaction_pool::FullPool;
 use core::sync::atomic::{AtomicU32, Ordering};
 use cross_domain_message_gossip::xdm_gossip_peers_set_config;
 use domain_runtime_primitives::opaque::{Block as DomainBlock, Header as DomainHeader};
 use frame_system_rpc_runtime_api::AccountNonceApi;
-use futures::channel::oneshot;
-use futures::FutureExt;
+use futures::channel::{mpsc, oneshot};
+use futures::{FutureExt, StreamExt};
 use jsonrpsee::RpcModule;
 use pallet_transaction_payment_rpc_runtime_api::TransactionPaymentApi;
 use parking_lot::Mutex;
@@ -60,7 +63,14 @@ use sc_consensus_subspace::archiver::{
     create_subspace_archiver, ArchivedSegmentNotification, SegmentHeadersStore,
 };
 use sc_consensus_subspace::block_import::{BlockImportingNotification, SubspaceBlockImport};
+use sc_consensus_subspace::equivocation::{
+    create_equivocation_reporter, EquivocationBlockAnnounceValidator, EquivocationEvidenceStore,
+    ANNOUNCED_HEADERS_CHANNEL_CAPACITY,
+};
 use sc_consensus_subspace::notification::SubspaceNotificationStream;
+use sc_consensus_subspace::object_mappings::{
+    backfill_object_mappings, create_object_mappings_indexer, ObjectMappingsIndex,
+};
 use sc_consensus_subspace::slot_worker::{
     NewSlotNotification, RewardSigningNotification, SubspaceSlotWorker, SubspaceSlotWorkerOptions,
     SubspaceSyncOracle,
@@ -106,16 +116,20 @@ use sp_transaction_pool::runtime_api::TaggedTransactionQueue;
 use static_assertions::const_assert;
 use std::marker::PhantomData;
 use std::num::NonZeroUsize;
+use std::path::Path;
 use std::sync::Arc;
 use std::time::Duration;
 use subspace_core_primitives::crypto::kzg::{embedded_kzg_settings, Kzg};
 use subspace_core_primitives::{BlockNumber, PotSeed, REWARD_SIGNING_CONTEXT};
 use subspace_networking::libp2p::multiaddr::Protocol;
+use subspace_networking::utils::object_fetcher::{
+    ObjectFetcher, ObjectPieceGetter, MAX_OBJECT_SIZE,
+};
 use subspace_networking::utils::piece_provider::PieceProvider;
 use subspace_proof_of_space::Table;
 use subspace_runtime_primitives::opaque::Block;
 use subspace_runtime_primitives::{AccountId, Balance, Hash, Nonce};
-use tracing::{debug, error, info, Instrument};
+use tracing::{debug, error, info, warn, Instrument};
 
 // There are multiple places where it is assumed that node is running on 64-bit system, refuse to
 // compile otherwise
@@ -432,11 +446,31 @@ type PartialComponents<RuntimeApi> = sc_service::PartialComponents<
     OtherPartialComponents<RuntimeApi>,
 >;
 
+/// Options for [`new_partial()`].
+#[derive(Debug, Copy, Clone)]
+pub struct NewPartialOptions<'a> {
+    /// External entropy, used for initialization of proof of time
+    pub pot_external_entropy: &'a [u8],
+    /// Path where KZG fixed-base tables are cached, tables are computed from scratch on every
+    /// start if not specified
+    pub kzg_fixed_base_tables_path: Option<&'a Path>,
+}
+
+impl<'a> NewPartialOptions<'a> {
+    /// Create options with specified external entropy and defaults for everything else
+    pub fn new(pot_external_entropy: &'a [u8]) -> Self {
+        Self {
+            pot_external_entropy,
+            kzg_fixed_base_tables_path: None,
+        }
+    }
+}
+
 /// Creates `PartialComponents` for Subspace client.
 #[allow(clippy::type_complexity)]
 pub fn new_partial<PosTable, RuntimeApi>(
     config: &Configuration,
-    pot_external_entropy: &[u8],
+    options: NewPartialOptions<'_>,
 ) -> Result<PartialComponents<RuntimeApi>, ServiceError>
 where
     PosTable: Table,
@@ -455,6 +489,11 @@ where
         + MmrApi<Block, H256, NumberFor<Block>>
         + MessengerApi<Block, NumberFor<Block>>,
 {
+    let NewPartialOptions {
+        pot_external_entropy,
+        kzg_fixed_base_tables_path,
+    } = options;
+
     let telemetry = config
         .telemetry_endpoints
         .clone()
@@ -476,7 +515,13 @@ where
             executor.clone(),
         )?;
 
-    let kzg = tokio::task::block_in_place(|| Kzg::new(embedded_kzg_settings()));
+    let kzg = tokio::task::block_in_place(|| match kzg_fixed_base_tables_path {
+        Some(path) => Kzg::with_cached_fixed_base_tables(embedded_kzg_settings(), path),
+        None => Ok(Kzg::new(embedded_kzg_settings())),
+    })
+    .map_err(|error| {
+        ServiceError::Other(format!("Failed to load KZG fixed-base tables: {error}"))
+    })?;
 
     let client = Arc::new(client);
     let client_info = client.info();
@@ -485,6 +530,14 @@ where
         PotSeed::from_genesis(client_info.genesis_hash.as_ref(), pot_external_entropy),
         POT_VERIFIER_CACHE_SIZE,
     );
+    match pot_verifier.load_persisted_checkpoints(client.as_ref()) {
+        Ok(count) => {
+            debug!(%count, "Loaded persisted proof of time checkpoints");
+        }
+        Err(error) => {
+            warn!(%error, "Failed to load persisted proof of time checkpoints");
+        }
+    }
 
     let executor = Arc::new(executor);
 
@@ -701,11 +754,12 @@ where
     } = other;
 
     let offchain_indexing_enabled = config.offchain_worker.indexing_enabled;
-    let (node, bootstrap_nodes) = match config.subspace_networking {
+    let (node, bootstrap_nodes, segment_header_announcements) = match config.subspace_networking {
+        // Reused instance might not have gossipsub enabled
         SubspaceNetworking::Reuse {
             node,
             bootstrap_nodes,
-        } => (node, bootstrap_nodes),
+        } => (node, bootstrap_nodes, false),
         SubspaceNetworking::Create { config: dsn_config } => {
             let dsn_protocol_version = hex::encode(client.chain_info().genesis_hash);
 
@@ -748,7 +802,11 @@ where
                     ),
                 );
 
-            (node, dsn_config.bootstrap_nodes)
+            (
+                node,
+                dsn_config.bootstrap_nodes,
+                dsn_config.segment_header_announcements,
+            )
         }
     };
 
@@ -814,6 +872,18 @@ where
         pot_gossip_peers_set_config();
     net_config.add_notification_protocol(pot_gossip_notification_config);
     let pause_sync = Arc::clone(&net_config.network_config.pause_sync);
+    // Headers from block announcements are forwarded to equivocation reporter
+    let (maybe_announced_headers_sender, maybe_announced_headers_receiver) =
+        if config.equivocation_reporting {
+            let (announced_headers_sender, announced_headers_receiver) =
+                mpsc::channel(ANNOUNCED_HEADERS_CHANNEL_CAPACITY);
+            (
+                Some(announced_headers_sender),
+                Some(announced_headers_receiver),
+            )
+        } else {
+            (None, None)
+        };
     let (network_service, system_rpc_tx, tx_handler_controller, network_starter, sync_service) =
         sc_service::build_network(sc_service::BuildNetworkParams {
             config: &config.base,
@@ -822,7 +892,15 @@ where
             transaction_pool: transaction_pool.clone(),
             spawn_handle: task_manager.spawn_handle(),
             import_queue,
-            block_announce_validator_builder: None,
+            block_announce_validator_builder: maybe_announced_headers_sender.map(
+                |announced_headers_sender| {
+                    Box::new(move |_client: Arc<FullClient<RuntimeApi>>| {
+                        Box::new(EquivocationBlockAnnounceValidator::new(
+                            announced_headers_sender,
+                        )) as Box<_>
+                    }) as Box<_>
+                },
+            ),
             warp_sync_params: None,
             block_relay,
         })?;
@@ -855,6 +933,69 @@ where
         sync_service.clone(),
     );
 
+    // Indexer must be created before archiver to receive segments archived during its
+    // initialization
+    let object_mappings_index = config.object_mappings.map(|object_mappings_config| {
+        let object_mappings_index = ObjectMappingsIndex::new(client.clone());
+        let object_mappings_indexer =
+            create_object_mappings_indexer(object_mappings_index.clone(), &subspace_link);
+
+        task_manager.spawn_handle().spawn_blocking(
+            "subspace-object-mappings-indexer",
+            None,
+            Box::pin(async move {
+                if let Err(error) = object_mappings_indexer.await {
+                    error!(%error, "Object mappings indexer exited with error");
+                }
+            }),
+        );
+
+        if let Some(backfill_from) = object_mappings_config.backfill_from {
+            let object_mappings_index = object_mappings_index.clone();
+            let segment_headers_store = segment_headers_store.clone();
+            let client = client.clone();
+            let kzg = subspace_link.kzg().clone();
+
+            task_manager.spawn_handle().spawn_blocking(
+                "subspace-object-mappings-backfill",
+                None,
+                Box::pin(async move {
+                    if let Err(error) = backfill_object_mappings(
+                        &object_mappings_index,
+                        &segment_headers_store,
+                        client.as_ref(),
+                        kzg,
+                        backfill_from,
+                    ) {
+                        error!(%error, "Object mappings backfilling failed");
+                    }
+                }),
+            );
+        }
+
+        object_mappings_index
+    });
+
+    let equivocation_evidence_store =
+        maybe_announced_headers_receiver.map(|announced_headers_receiver| {
+            let equivocation_evidence_store = EquivocationEvidenceStore::new(client.clone());
+            let equivocation_reporter = create_equivocation_reporter(
+                equivocation_evidence_store.clone(),
+                client.clone(),
+                OffchainTransactionPoolFactory::new(transaction_pool.clone()),
+                sync_oracle.clone(),
+                announced_headers_receiver,
+            );
+
+            task_manager.spawn_handle().spawn(
+                "subspace-equivocation-reporter",
+                Some("subspace"),
+                Box::pin(equivocation_reporter),
+            );
+
+            equivocation_evidence_store
+        });
+
     let subspace_archiver = tokio::task::block_in_place(|| {
         create_subspace_archiver(
             segment_headers_store.clone(),
@@ -878,6 +1019,39 @@ where
             }),
         );
 
+    if segment_header_announcements {
+        task_manager.spawn_handle().spawn(
+            "segment-headers-announcer",
+            Some("subspace-networking"),
+            Box::pin({
+                let node = node.clone();
+                let mut archived_segment_notifications = subspace_link
+                    .archived_segment_notification_stream()
+                    .subscribe();
+
+                async move {
+                    while let Some(archived_segment_notification) =
+                        archived_segment_notifications.next().await
+                    {
+                        let segment_header = archived_segment_notification
+                            .archived_segment
+                            .segment_header;
+                        // Acknowledge right away, announcement must not slow down archiving
+                        drop(archived_segment_notification);
+
+                        if let Err(error) = node.publish_segment_header(&segment_header).await {
+                            debug!(
+                                %error,
+                                segment_index = %segment_header.segment_index(),
+                                "Failed to announce segment header on the DSN"
+                            );
+                        }
+                    }
+                }
+            }),
+        );
+    }
+
     network_wrapper.set(network_service.clone());
     if config.sync_from_dsn {
         let dsn_sync_piece_getter = config.dsn_piece_getter.unwrap_or_else(|| {
@@ -886,7 +1060,7 @@ where
                 Some(SegmentCommitmentPieceValidator::new(
                     node.clone(),
                     subspace_link.kzg().clone(),
-                    segment_headers_store.clone(),
+                    SegmentHeadersStoreCommitments(segment_headers_store.clone()),
                 )),
             ))
         });
@@ -981,6 +1155,7 @@ where
 
     let new_slot_notification_stream = subspace_link.new_slot_notification_stream();
     let reward_signing_notification_stream = subspace_link.reward_signing_notification_stream();
+    let solution_outcome_notification_stream = subspace_link.solution_outcome_notification_stream();
     let block_importing_notification_stream = subspace_link.block_importing_notification_stream();
     let archived_segment_notification_stream = subspace_link.archived_segment_notification_stream();
 
@@ -993,6 +1168,7 @@ where
         pot_gossip_notification_service,
         sync_service.clone(),
         sync_oracle.clone(),
+        config.base.prometheus_registry(),
     )
     .map_err(|error| Error::Other(error.into()))?;
 
@@ -1090,7 +1266,21 @@ where
             let client = client.clone();
             let new_slot_notification_stream = new_slot_notification_stream.clone();
             let reward_signing_notification_stream = reward_signing_notification_stream.clone();
+            let solution_outcome_notification_stream = solution_outcome_notification_stream.clone();
             let archived_segment_notification_stream = archived_segment_notification_stream.clone();
+            let object_mappings_index = object_mappings_index.clone();
+            let equivocation_evidence_store = equivocation_evidence_store.clone();
+            let object_fetcher = Arc::new(ObjectFetcher::new(
+                Arc::new(PieceProvider::new(
+                    node.clone(),
+                    Some(SegmentCommitmentPieceValidator::new(
+                        node.clone(),
+                        subspace_link.kzg().clone(),
+                        SegmentHeadersStoreCommitments(segment_headers_store.clone()),
+                    )),
+                )) as Arc<dyn ObjectPieceGetter + Send + Sync>,
+                MAX_OBJECT_SIZE,
+            ));
             let transaction_pool = transaction_pool.clone();
             let chain_spec = config.base.chain_spec.cloned_box();
             let backend = backend.clone();
@@ -1104,12 +1294,17 @@ where
                     subscription_executor,
                     new_slot_notification_stream: new_slot_notification_stream.clone(),
                     reward_signing_notification_stream: reward_signing_notification_stream.clone(),
+                    solution_outcome_notification_stream: solution_outcome_notification_stream
+                        .clone(),
                     archived_segment_notification_stream: archived_segment_notification_stream
                         .clone(),
                     dsn_bootstrap_nodes: dsn_bootstrap_nodes.clone(),
                     segment_headers_store: segment_headers_store.clone(),
                     sync_oracle: sync_oracle.clone(),
                     kzg: subspace_link.kzg().clone(),
+                    object_fetcher: Arc::clone(&object_fetcher),
+                    object_mappings_index: object_mappings_index.clone(),
+                    equivocation_evidence_store: equivocation_evidence_store.clone(),
                     backend: backend.clone(),
                 };
 
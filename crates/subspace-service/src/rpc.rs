@@ -26,9 +26,11 @@ use mmr_rpc::{Mmr, MmrApiServer};
 use pallet_transaction_payment_rpc::{TransactionPayment, TransactionPaymentApiServer};
 use sc_client_api::{AuxStore, BlockBackend};
 use sc_consensus_subspace::archiver::{ArchivedSegmentNotification, SegmentHeadersStore};
+use sc_consensus_subspace::equivocation::EquivocationEvidenceStore;
 use sc_consensus_subspace::notification::SubspaceNotificationStream;
+use sc_consensus_subspace::object_mappings::ObjectMappingsIndex;
 use sc_consensus_subspace::slot_worker::{
-    NewSlotNotification, RewardSigningNotification, SubspaceSyncOracle,
+    NewSlotNotification, RewardSigningNotification, SolutionOutcomeNotification, SubspaceSyncOracle,
 };
 use sc_consensus_subspace_rpc::{SubspaceRpc, SubspaceRpcApiServer, SubspaceRpcConfig};
 use sc_rpc::SubscriptionTaskExecutor;
@@ -45,6 +47,7 @@ use std::sync::Arc;
 use subspace_core_primitives::crypto::kzg::Kzg;
 use subspace_core_primitives::BlockNumber;
 use subspace_networking::libp2p::Multiaddr;
+use subspace_networking::utils::object_fetcher::{ObjectFetcher, ObjectPieceGetter};
 use subspace_runtime_primitives::opaque::Block;
 use subspace_runtime_primitives::{AccountId, Balance, Nonce};
 use substrate_frame_rpc_system::{System, SystemApiServer};
@@ -69,6 +72,9 @@ where
     /// A stream with notifications about headers that need to be signed with ability to send
     /// signature back.
     pub reward_signing_notification_stream: SubspaceNotificationStream<RewardSigningNotification>,
+    /// A stream with notifications about outcomes of solutions submitted by farmers.
+    pub solution_outcome_notification_stream:
+        SubspaceNotificationStream<SolutionOutcomeNotification>,
     /// A stream with notifications about archived segment creation.
     pub archived_segment_notification_stream:
         SubspaceNotificationStream<ArchivedSegmentNotification>,
@@ -80,6 +86,12 @@ where
     pub sync_oracle: SubspaceSyncOracle<SO>,
     /// Kzg instance.
     pub kzg: Kzg,
+    /// Object fetcher.
+    pub object_fetcher: Arc<ObjectFetcher<Arc<dyn ObjectPieceGetter + Send + Sync>>>,
+    /// Object mappings index, `None` if indexing is disabled.
+    pub object_mappings_index: Option<ObjectMappingsIndex<AS>>,
+    /// Equivocation evidence store, `None` if equivocation reporting is disabled.
+    pub equivocation_evidence_store: Option<EquivocationEvidenceStore<AS>>,
     /// Backend used by the node.
     pub backend: Arc<B>,
 }
@@ -117,11 +129,15 @@ where
         subscription_executor,
         new_slot_notification_stream,
         reward_signing_notification_stream,
+        solution_outcome_notification_stream,
         archived_segment_notification_stream,
         dsn_bootstrap_nodes,
         segment_headers_store,
         sync_oracle,
         kzg,
+        object_fetcher,
+        object_mappings_index,
+        equivocation_evidence_store,
         backend,
     } = deps;
 
@@ -139,11 +155,15 @@ where
             subscription_executor,
             new_slot_notification_stream,
             reward_signing_notification_stream,
+            solution_outcome_notification_stream,
             archived_segment_notification_stream,
             dsn_bootstrap_nodes,
             segment_headers_store,
             sync_oracle,
             kzg,
+            object_fetcher: Some(object_fetcher),
+            object_mappings_index,
+            equivocation_evidence_store,
             deny_unsafe,
         })?
         .into_rpc(),
@@ -1,10 +1,9 @@
 mod import_blocks;
 pub(super) mod piece_validator;
-mod segment_header_downloader;
 
-use crate::sync_from_dsn::import_blocks::import_blocks_from_dsn;
+use crate::sync_from_dsn::import_blocks::download_segment_headers;
 pub use crate::sync_from_dsn::import_blocks::DsnSyncPieceGetter;
-use crate::sync_from_dsn::segment_header_downloader::SegmentHeaderDownloader;
+pub(crate) use crate::sync_from_dsn::import_blocks::{import_blocks_from_dsn, SegmentHeaderSource};
 use futures::channel::mpsc;
 use futures::{select, FutureExt, StreamExt};
 use sc_client_api::{AuxStore, BlockBackend, BlockchainEvents};
@@ -20,7 +19,9 @@ use std::future::Future;
 use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
+use subspace_archiving::reconstructor::Reconstructor;
 use subspace_core_primitives::SegmentIndex;
+use subspace_networking::utils::segment_header_downloader::SegmentHeaderDownloader;
 use subspace_networking::Node;
 use tracing::{info, warn};
 
@@ -254,15 +255,25 @@ where
 
         info!(?reason, "Received notification to sync from DSN");
         // TODO: Maybe handle failed block imports, additional helpful logging
-        let import_froms_from_dsn_fut = import_blocks_from_dsn(
-            &segment_headers_store,
-            &segment_header_downloader,
-            client,
-            piece_getter,
-            import_queue_service,
-            &mut last_processed_segment_index,
-            &mut last_processed_block_number,
-        );
+        let import_froms_from_dsn_fut = async {
+            download_segment_headers(&segment_headers_store, &segment_header_downloader).await?;
+
+            let max_segment_index = segment_headers_store
+                .max_segment_index()
+                .expect("Exists, we have checked it before downloading segment headers above; qed");
+
+            import_blocks_from_dsn(
+                &segment_headers_store,
+                max_segment_index,
+                client,
+                piece_getter,
+                import_queue_service,
+                &mut Reconstructor::new().map_err(|error| error.to_string())?,
+                &mut last_processed_segment_index,
+                &mut last_processed_block_number,
+            )
+            .await
+        };
         let wait_almost_synced_fut = async {
             loop {
                 tokio::time::sleep(CHECK_ALMOST_SYNCED_INTERVAL).await;
@@ -14,10 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
-use crate::sync_from_dsn::segment_header_downloader::SegmentHeaderDownloader;
 use async_trait::async_trait;
-use futures::stream::FuturesUnordered;
-use futures::StreamExt;
 use sc_client_api::{AuxStore, BlockBackend, HeaderBackend};
 use sc_consensus::import_queue::ImportQueueService;
 use sc_consensus::IncomingBlock;
@@ -32,12 +29,11 @@ use std::fmt;
 use std::sync::Arc;
 use std::time::Duration;
 use subspace_archiving::reconstructor::Reconstructor;
-use subspace_core_primitives::{
-    ArchivedHistorySegment, BlockNumber, Piece, PieceIndex, RecordedHistorySegment, SegmentIndex,
+use subspace_core_primitives::{BlockNumber, Piece, PieceIndex, SegmentHeader, SegmentIndex};
+use subspace_networking::utils::piece_provider::{
+    download_segment_pieces, PieceProvider, PieceValidator,
 };
-use subspace_networking::utils::piece_provider::{PieceProvider, PieceValidator};
-use tokio::sync::Semaphore;
-use tracing::warn;
+use subspace_networking::utils::segment_header_downloader::SegmentHeaderDownloader;
 
 /// Trait representing a way to get pieces for DSN sync purposes
 #[async_trait]
@@ -74,66 +70,115 @@ where
     }
 }
 
+/// Source of segment headers of segments blocks are imported from
+pub(crate) trait SegmentHeaderSource {
+    /// Get segment header by segment index, if known
+    fn get_segment_header(&self, segment_index: SegmentIndex) -> Option<SegmentHeader>;
+}
+
+impl<AS> SegmentHeaderSource for SegmentHeadersStore<AS>
+where
+    AS: AuxStore,
+{
+    fn get_segment_header(&self, segment_index: SegmentIndex) -> Option<SegmentHeader> {
+        SegmentHeadersStore::get_segment_header(self, segment_index)
+    }
+}
+
+/// Segment headers sorted by segment index
+impl SegmentHeaderSource for [SegmentHeader] {
+    fn get_segment_header(&self, segment_index: SegmentIndex) -> Option<SegmentHeader> {
+        self.binary_search_by_key(&segment_index, SegmentHeader::segment_index)
+            .ok()
+            .map(|position| self[position])
+    }
+}
+
 /// How many blocks to queue before pausing and waiting for blocks to be imported, this is
 /// essentially used to ensure we use a bounded amount of RAM during sync process.
 const QUEUED_BLOCKS_LIMIT: BlockNumber = 500;
 /// Time to wait for blocks to import if import is too slow
 const WAIT_FOR_BLOCKS_TO_IMPORT: Duration = Duration::from_secs(1);
 
-/// Starts the process of importing blocks.
-///
-/// Returns number of downloaded blocks.
-pub(super) async fn import_blocks_from_dsn<Block, AS, Client, PG, IQS>(
+/// Download segment headers that are not known yet and add them to segment headers store.
+pub(super) async fn download_segment_headers<AS>(
     segment_headers_store: &SegmentHeadersStore<AS>,
     segment_header_downloader: &SegmentHeaderDownloader<'_>,
+) -> Result<(), sc_service::Error>
+where
+    AS: AuxStore + Send + Sync + 'static,
+{
+    let max_segment_index = segment_headers_store.max_segment_index().ok_or_else(|| {
+        sc_service::Error::Other(
+            "Archiver needs to be initialized before syncing from DSN to populate the very first \
+            segment"
+                .to_string(),
+        )
+    })?;
+    let new_segment_headers = segment_header_downloader
+        .get_segment_headers(max_segment_index)
+        .await
+        .map_err(|error| error.to_string())?;
+
+    debug!("Found {} new segment headers", new_segment_headers.len());
+
+    if !new_segment_headers.is_empty() {
+        segment_headers_store.add_segment_headers(&new_segment_headers)?;
+    }
+
+    Ok(())
+}
+
+/// Starts the process of importing blocks from segments up to `max_segment_index`, segment headers
+/// of all of these segments must already be present in segment header source.
+///
+/// `reconstructor` contains contents of the last processed segment, such that blocks that span
+/// across segments can be imported when import is split into multiple calls.
+///
+/// Returns number of downloaded blocks.
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn import_blocks_from_dsn<Block, SHS, Client, PG, IQS>(
+    segment_headers: &SHS,
+    max_segment_index: SegmentIndex,
     client: &Client,
     piece_getter: &PG,
     import_queue_service: &mut IQS,
+    reconstructor: &mut Reconstructor,
     last_processed_segment_index: &mut SegmentIndex,
     last_processed_block_number: &mut <Block::Header as Header>::Number,
 ) -> Result<u64, sc_service::Error>
 where
     Block: BlockT,
-    AS: AuxStore + Send + Sync + 'static,
+    SHS: SegmentHeaderSource + ?Sized,
     Client: HeaderBackend<Block> + BlockBackend<Block> + Send + Sync + 'static,
     PG: DsnSyncPieceGetter,
     IQS: ImportQueueService<Block> + ?Sized,
 {
+    if segment_headers
+        .get_segment_header(max_segment_index)
+        .is_none()
     {
-        let max_segment_index = segment_headers_store.max_segment_index().ok_or_else(|| {
-            sc_service::Error::Other(
-                "Archiver needs to be initialized before syncing from DSN to populate the very \
-                    first segment"
-                    .to_string(),
-            )
-        })?;
-        let new_segment_headers = segment_header_downloader
-            .get_segment_headers(max_segment_index)
-            .await
-            .map_err(|error| error.to_string())?;
-
-        debug!("Found {} new segment headers", new_segment_headers.len());
-
-        if !new_segment_headers.is_empty() {
-            segment_headers_store.add_segment_headers(&new_segment_headers)?;
-        }
+        return Err(sc_service::Error::Other(format!(
+            "Segment header {max_segment_index} is not known, can't import blocks"
+        )));
     }
 
     let mut downloaded_blocks = 0;
-    let mut reconstructor = Reconstructor::new().map_err(|error| error.to_string())?;
-    // Start from the first unprocessed segment and process all segments known so far
-    let segment_indices_iter = (*last_processed_segment_index + SegmentIndex::ONE)
-        ..=segment_headers_store
-            .max_segment_index()
-            .expect("Exists, we have inserted segment headers above; qed");
+    // Start from the first unprocessed segment and process all segments up to the requested one
+    let segment_indices_iter =
+        (*last_processed_segment_index + SegmentIndex::ONE)..=max_segment_index;
     let mut segment_indices_iter = segment_indices_iter.peekable();
 
     while let Some(segment_index) = segment_indices_iter.next() {
         debug!(%segment_index, "Processing segment");
 
-        let segment_header = segment_headers_store
+        let segment_header = segment_headers
             .get_segment_header(segment_index)
-            .expect("Statically guaranteed to exist, see checks above; qed");
+            .ok_or_else(|| {
+                sc_service::Error::Other(format!(
+                    "Segment header {segment_index} is not known, can't import blocks"
+                ))
+            })?;
 
         trace!(
             %segment_index,
@@ -155,7 +200,7 @@ where
         if last_archived_block <= *last_processed_block_number {
             *last_processed_segment_index = segment_index;
             // Reset reconstructor instance
-            reconstructor = Reconstructor::new().map_err(|error| error.to_string())?;
+            *reconstructor = Reconstructor::new().map_err(|error| error.to_string())?;
             continue;
         }
         // Just one partial unprocessed block and this was the last segment available, so nothing to
@@ -165,13 +210,12 @@ where
             && segment_indices_iter.peek().is_none()
         {
             // Reset reconstructor instance
-            reconstructor = Reconstructor::new().map_err(|error| error.to_string())?;
+            *reconstructor = Reconstructor::new().map_err(|error| error.to_string())?;
             continue;
         }
 
         let blocks =
-            download_and_reconstruct_blocks(segment_index, piece_getter, &mut reconstructor)
-                .await?;
+            download_and_reconstruct_blocks(segment_index, piece_getter, reconstructor).await?;
 
         let mut blocks_to_import = Vec::with_capacity(QUEUED_BLOCKS_LIMIT as usize);
 
@@ -285,82 +329,20 @@ async fn download_and_reconstruct_blocks<PG>(
 where
     PG: DsnSyncPieceGetter,
 {
-    debug!(%segment_index, "Retrieving pieces of the segment");
-
-    let semaphore = &Semaphore::new(RecordedHistorySegment::NUM_RAW_RECORDS);
-
-    let mut received_segment_pieces = segment_index
-        .segment_piece_indexes_source_first()
-        .into_iter()
-        .map(|piece_index| {
-            // Source pieces will acquire permit here right away
-            let maybe_permit = semaphore.try_acquire().ok();
-
-            async move {
-                let permit = match maybe_permit {
-                    Some(permit) => permit,
-                    None => {
-                        // Other pieces will acquire permit here instead
-                        match semaphore.acquire().await {
-                            Ok(permit) => permit,
-                            Err(error) => {
-                                warn!(
-                                    %piece_index,
-                                    %error,
-                                    "Semaphore was closed, interrupting piece retrieval"
-                                );
-                                return None;
-                            }
-                        }
-                    }
-                };
-                let maybe_piece = match piece_getter.get_piece(piece_index).await {
-                    Ok(maybe_piece) => maybe_piece,
-                    Err(error) => {
-                        trace!(
-                            %error,
-                            ?piece_index,
-                            "Piece request failed",
-                        );
-                        return None;
-                    }
-                };
-
+    let segment_pieces = download_segment_pieces(segment_index, |piece_index| async move {
+        match piece_getter.get_piece(piece_index).await {
+            Ok(maybe_piece) => maybe_piece,
+            Err(error) => {
                 trace!(
+                    %error,
                     ?piece_index,
-                    piece_found = maybe_piece.is_some(),
-                    "Piece request succeeded",
+                    "Piece request failed",
                 );
-
-                maybe_piece.map(|received_piece| {
-                    // Piece was received successfully, "remove" this slot from semaphore
-                    permit.forget();
-                    (piece_index, received_piece)
-                })
+                None
             }
-        })
-        .collect::<FuturesUnordered<_>>();
-
-    let mut segment_pieces = vec![None::<Piece>; ArchivedHistorySegment::NUM_PIECES];
-    let mut pieces_received = 0;
-
-    while let Some(maybe_result) = received_segment_pieces.next().await {
-        let Some((piece_index, piece)) = maybe_result else {
-            continue;
-        };
-
-        segment_pieces
-            .get_mut(piece_index.position() as usize)
-            .expect("Piece position is by definition within segment; qed")
-            .replace(piece);
-
-        pieces_received += 1;
-
-        if pieces_received >= RecordedHistorySegment::NUM_RAW_RECORDS {
-            trace!(%segment_index, "Received half of the segment.");
-            break;
         }
-    }
+    })
+    .await;
 
     let reconstructed_contents = reconstructor
         .add_segment(segment_pieces.as_ref())
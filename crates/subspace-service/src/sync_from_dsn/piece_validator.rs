@@ -1,87 +1,35 @@
 use async_trait::async_trait;
 use sc_client_api::AuxStore;
 use sc_consensus_subspace::archiver::SegmentHeadersStore;
-use subspace_archiving::archiver::is_piece_valid;
-use subspace_core_primitives::crypto::kzg::Kzg;
-use subspace_core_primitives::{Piece, PieceIndex};
-use subspace_networking::libp2p::PeerId;
-use subspace_networking::utils::piece_provider::PieceValidator;
-use subspace_networking::Node;
-use tracing::{error, warn};
+use subspace_core_primitives::{SegmentCommitment, SegmentIndex};
+use subspace_networking::utils::piece_validator::SegmentCommitmentSource;
+use tracing::error;
 
-pub(crate) struct SegmentCommitmentPieceValidator<AS> {
-    dsn_node: Node,
-    kzg: Kzg,
-    segment_headers_store: SegmentHeadersStore<AS>,
-}
+/// Validates pieces against segment commitments from segment headers store of the node.
+pub(crate) type SegmentCommitmentPieceValidator<AS> =
+    subspace_networking::utils::piece_validator::SegmentCommitmentPieceValidator<
+        SegmentHeadersStoreCommitments<AS>,
+    >;
 
-impl<AS> SegmentCommitmentPieceValidator<AS>
-where
-    AS: AuxStore + Send + Sync + 'static,
-{
-    /// Segment headers must be in order from 0 to the last one that exists
-    pub(crate) fn new(
-        dsn_node: Node,
-        kzg: Kzg,
-        segment_headers_store: SegmentHeadersStore<AS>,
-    ) -> Self {
-        Self {
-            dsn_node,
-            kzg,
-            segment_headers_store,
-        }
-    }
-}
+/// Segment commitments from segment headers store, segment headers must be in order from 0 to the
+/// last one that exists.
+pub(crate) struct SegmentHeadersStoreCommitments<AS>(pub(crate) SegmentHeadersStore<AS>);
 
 #[async_trait]
-impl<AS> PieceValidator for SegmentCommitmentPieceValidator<AS>
+impl<AS> SegmentCommitmentSource for SegmentHeadersStoreCommitments<AS>
 where
     AS: AuxStore + Send + Sync + 'static,
 {
-    async fn validate_piece(
-        &self,
-        source_peer_id: PeerId,
-        piece_index: PieceIndex,
-        piece: Piece,
-    ) -> Option<Piece> {
-        if source_peer_id == self.dsn_node.id() {
-            return Some(piece);
+    async fn segment_commitment(&self, segment_index: SegmentIndex) -> Option<SegmentCommitment> {
+        let maybe_segment_commitment = self
+            .0
+            .get_segment_header(segment_index)
+            .map(|segment_header| segment_header.segment_commitment());
+
+        if maybe_segment_commitment.is_none() {
+            error!(%segment_index, "No segment commitment in the cache.");
         }
 
-        let segment_index = piece_index.segment_index();
-
-        let maybe_segment_header = self.segment_headers_store.get_segment_header(segment_index);
-        let segment_commitment = match maybe_segment_header {
-            Some(segment_header) => segment_header.segment_commitment(),
-            None => {
-                error!(%segment_index, "No segment commitment in the cache.");
-
-                return None;
-            }
-        };
-
-        let is_valid_fut = tokio::task::spawn_blocking({
-            let kzg = self.kzg.clone();
-
-            move || {
-                is_piece_valid(&kzg, &piece, &segment_commitment, piece_index.position())
-                    .then_some(piece)
-            }
-        });
-
-        match is_valid_fut.await.unwrap_or_default() {
-            Some(piece) => Some(piece),
-            None => {
-                warn!(
-                    %piece_index,
-                    %source_peer_id,
-                    "Received invalid piece from peer"
-                );
-
-                // We don't care about result here
-                let _ = self.dsn_node.ban_peer(source_peer_id).await;
-                None
-            }
-        }
+        maybe_segment_commitment
     }
 }
@@ -0,0 +1,89 @@
+//! Offline simulator of Subspace consensus solution range adjustment.
+//!
+//! Drives solution range adjustment of `pallet-subspace` with a synthetic farmer population
+//! described in a JSON scenario file and outputs time series of solution ranges, block times and
+//! vote counts as CSV, which makes it possible to validate chain parameters before launch.
+//!
+//! Blocks are imported into the pallet, while votes are only sampled from the pallet's voting
+//! solution range and counted, they are not submitted to the pallet.
+//!
+//! Example scenario:
+//! ```json
+//! {
+//!   "chain": {
+//!     "eraDuration": 2016,
+//!     "slotProbability": [1, 6],
+//!     "enableSolutionRangeAdjustmentAt": 0
+//!   },
+//!   "slots": 1000000,
+//!   "farmers": [
+//!     { "sectors": 10000 },
+//!     { "sectors": 50000, "joinSlot": 200000, "plottingSlots": 100000, "leaveSlot": 700000 }
+//!   ]
+//! }
+//! ```
+
+mod runtime;
+mod scenario;
+mod simulation;
+
+use crate::scenario::Scenario;
+use crate::simulation::{simulate, BlockRecord};
+use clap::Parser;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::PathBuf;
+use tracing::{info, Level};
+use tracing_subscriber::fmt::Subscriber;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::EnvFilter;
+
+#[derive(Debug, Parser)]
+#[clap(about, version)]
+struct Cli {
+    /// Path to JSON file with simulation scenario
+    scenario: PathBuf,
+    /// Seed for random number generator, the same seed results in the same output
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+    /// Path to CSV file where produced blocks are written, stdout is used if not specified
+    #[arg(long)]
+    output: Option<PathBuf>,
+}
+
+fn main() -> anyhow::Result<()> {
+    Subscriber::builder()
+        .with_env_filter(
+            EnvFilter::builder()
+                .with_default_directive(Level::INFO.into())
+                .from_env_lossy(),
+        )
+        .with_writer(io::stderr)
+        .finish()
+        .init();
+
+    let cli = Cli::parse();
+
+    let scenario = Scenario::from_file(&cli.scenario)?;
+
+    let mut output: Box<dyn Write> = match &cli.output {
+        Some(path) => Box::new(BufWriter::new(File::create(path)?)),
+        None => Box::new(BufWriter::new(io::stdout().lock())),
+    };
+
+    writeln!(output, "{}", BlockRecord::CSV_HEADER)?;
+
+    let mut blocks = 0;
+    simulate(&scenario, cli.seed, |block_record| {
+        blocks += 1;
+        writeln!(output, "{}", block_record.to_csv())?;
+
+        Ok(())
+    })?;
+
+    output.flush()?;
+
+    info!(%blocks, slots = %scenario.slots, "Simulation finished");
+
+    Ok(())
+}
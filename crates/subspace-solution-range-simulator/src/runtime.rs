@@ -0,0 +1,257 @@
+//! Minimal runtime that drives `pallet-subspace` with chain parameters of the simulated scenario.
+
+use crate::scenario::ChainParameters;
+use anyhow::anyhow;
+use frame_support::parameter_types;
+use frame_support::traits::{ConstU32, ConstU64, Get, OnFinalize, OnInitialize};
+use pallet_subspace::{AllowAuthoringBy, EnableRewardsAt, NormalEraChange};
+use sp_consensus_slots::Slot;
+use sp_consensus_subspace::digests::{CompatibleDigestItem, PreDigest, PreDigestPotInfo};
+use sp_consensus_subspace::{FarmerPublicKey, SolutionRanges};
+use sp_core::crypto::UncheckedFrom;
+use sp_core::H256;
+use sp_io::TestExternalities;
+use sp_runtime::traits::{Header as _, IdentityLookup};
+use sp_runtime::{BuildStorage, Digest, DigestItem};
+use std::cell::RefCell;
+use std::marker::PhantomData;
+use std::num::{NonZeroU32, NonZeroU64};
+use subspace_core_primitives::{BlockNumber, HistorySize, SlotNumber, Solution, SolutionRange};
+
+type Block = frame_system::mocking::MockBlock<Runtime>;
+
+thread_local! {
+    static CHAIN_PARAMETERS: RefCell<ChainParameters> = RefCell::new(ChainParameters::default());
+}
+
+fn chain_parameters() -> ChainParameters {
+    CHAIN_PARAMETERS.with(|chain_parameters| *chain_parameters.borrow())
+}
+
+frame_support::construct_runtime!(
+    pub struct Runtime {
+        System: frame_system,
+        Subspace: pallet_subspace,
+    }
+);
+
+impl frame_system::Config for Runtime {
+    type BaseCallFilter = frame_support::traits::Everything;
+    type BlockWeights = ();
+    type BlockLength = ();
+    type DbWeight = ();
+    type RuntimeOrigin = RuntimeOrigin;
+    type RuntimeCall = RuntimeCall;
+    type RuntimeTask = RuntimeTask;
+    type Nonce = u64;
+    type Hash = H256;
+    type Hashing = sp_runtime::traits::BlakeTwo256;
+    type AccountId = u64;
+    type Lookup = IdentityLookup<Self::AccountId>;
+    type Block = Block;
+    type RuntimeEvent = RuntimeEvent;
+    type BlockHashCount = ConstU64<250>;
+    type Version = ();
+    type PalletInfo = PalletInfo;
+    type AccountData = ();
+    type OnNewAccount = ();
+    type OnKilledAccount = ();
+    type SystemWeightInfo = ();
+    type SS58Prefix = ();
+    type OnSetCode = ();
+    type MaxConsumers = ConstU32<16>;
+}
+
+parameter_types! {
+    pub const BlockAuthoringDelay: SlotNumber = 4;
+    pub const PotEntropyInjectionInterval: BlockNumber = 50;
+    pub const PotEntropyInjectionLookbackDepth: u8 = 2;
+    pub const PotEntropyInjectionDelay: SlotNumber = 15;
+    pub const ConfirmationDepthK: u32 = 100;
+    pub const RecentSegments: HistorySize = HistorySize::new(NonZeroU64::new(5).unwrap());
+    pub const RecentHistoryFraction: (HistorySize, HistorySize) = (
+        HistorySize::new(NonZeroU64::new(1).unwrap()),
+        HistorySize::new(NonZeroU64::new(10).unwrap()),
+    );
+    pub const MinSectorLifetime: HistorySize = HistorySize::new(NonZeroU64::new(4).unwrap());
+}
+
+/// Era duration of the simulated chain
+pub struct EraDuration;
+
+impl Get<u64> for EraDuration {
+    fn get() -> u64 {
+        chain_parameters().era_duration
+    }
+}
+
+/// Solution range of the first era of the simulated chain
+pub struct InitialSolutionRange;
+
+impl Get<SolutionRange> for InitialSolutionRange {
+    fn get() -> SolutionRange {
+        chain_parameters().initial_solution_range()
+    }
+}
+
+/// Slot probability of the simulated chain
+pub struct SlotProbability;
+
+impl Get<(u64, u64)> for SlotProbability {
+    fn get() -> (u64, u64) {
+        chain_parameters().slot_probability
+    }
+}
+
+/// Expected number of votes per block of the simulated chain
+pub struct ExpectedVotesPerBlock;
+
+impl Get<u32> for ExpectedVotesPerBlock {
+    fn get() -> u32 {
+        chain_parameters().expected_votes_per_block
+    }
+}
+
+/// Max number of pieces in a sector of the simulated chain
+pub struct MaxPiecesInSector;
+
+impl Get<u16> for MaxPiecesInSector {
+    fn get() -> u16 {
+        chain_parameters().max_pieces_in_sector
+    }
+}
+
+/// Whether solution range adjustment is enabled in genesis of the simulated chain
+pub struct ShouldAdjustSolutionRange;
+
+impl Get<bool> for ShouldAdjustSolutionRange {
+    fn get() -> bool {
+        chain_parameters().enable_solution_range_adjustment_at == Some(0)
+    }
+}
+
+impl pallet_subspace::Config for Runtime {
+    type RuntimeEvent = RuntimeEvent;
+    type BlockAuthoringDelay = BlockAuthoringDelay;
+    type PotEntropyInjectionInterval = PotEntropyInjectionInterval;
+    type PotEntropyInjectionLookbackDepth = PotEntropyInjectionLookbackDepth;
+    type PotEntropyInjectionDelay = PotEntropyInjectionDelay;
+    type EraDuration = EraDuration;
+    type InitialSolutionRange = InitialSolutionRange;
+    type SlotProbability = SlotProbability;
+    type ConfirmationDepthK = ConfirmationDepthK;
+    type RecentSegments = RecentSegments;
+    type RecentHistoryFraction = RecentHistoryFraction;
+    type MinSectorLifetime = MinSectorLifetime;
+    type ExpectedVotesPerBlock = ExpectedVotesPerBlock;
+    type MaxPiecesInSector = MaxPiecesInSector;
+    type ShouldAdjustSolutionRange = ShouldAdjustSolutionRange;
+    type SolutionRangeHistoryLength = ConstU32<0>;
+    type EraChangeTrigger = NormalEraChange;
+    type HandleEquivocation = ();
+    type WeightInfo = ();
+}
+
+/// Chain state of the simulation.
+///
+/// Chain parameters are stored per thread, so only one instance should be used on a thread at a
+/// time.
+pub(crate) struct SimulatedChain {
+    externalities: TestExternalities,
+}
+
+impl SimulatedChain {
+    /// Create chain at genesis with provided parameters
+    pub(crate) fn new(chain_parameters: ChainParameters) -> anyhow::Result<Self> {
+        CHAIN_PARAMETERS.with(|parameters| {
+            *parameters.borrow_mut() = chain_parameters;
+        });
+
+        let mut storage = frame_system::GenesisConfig::<Runtime>::default()
+            .build_storage()
+            .map_err(|error| anyhow!("Failed to build system genesis storage: {error}"))?;
+
+        pallet_subspace::GenesisConfig::<Runtime> {
+            enable_rewards_at: EnableRewardsAt::Manually,
+            allow_authoring_by: AllowAuthoringBy::Anyone,
+            pot_slot_iterations: NonZeroU32::MIN,
+            phantom: PhantomData,
+        }
+        .assimilate_storage(&mut storage)
+        .map_err(|error| anyhow!("Failed to build Subspace genesis storage: {error}"))?;
+
+        Ok(Self {
+            externalities: TestExternalities::from(storage),
+        })
+    }
+
+    /// Solution ranges that will be used for the next block
+    pub(crate) fn next_solution_ranges(&mut self) -> (SolutionRange, SolutionRange) {
+        self.externalities.execute_with(|| {
+            let SolutionRanges {
+                current,
+                next,
+                voting_current,
+                voting_next,
+            } = Subspace::solution_ranges();
+
+            (
+                next.unwrap_or(current),
+                voting_next.unwrap_or(voting_current),
+            )
+        })
+    }
+
+    /// Finalize the current block and initialize the next one claimed at `slot`, returns solution
+    /// ranges of the new block
+    pub(crate) fn import_block(
+        &mut self,
+        block_number: u64,
+        slot: Slot,
+    ) -> anyhow::Result<(SolutionRange, SolutionRange)> {
+        self.externalities.execute_with(|| {
+            Subspace::on_finalize(System::block_number());
+
+            let parent_hash = if System::block_number() > 0 {
+                System::finalize().hash()
+            } else {
+                System::parent_hash()
+            };
+
+            let pre_digest = Digest {
+                logs: vec![DigestItem::subspace_pre_digest(&PreDigest::V0 {
+                    slot,
+                    solution: Solution::genesis_solution(
+                        FarmerPublicKey::unchecked_from([0; 32]),
+                        0,
+                    ),
+                    pot_info: PreDigestPotInfo::V0 {
+                        proof_of_time: Default::default(),
+                        future_proof_of_time: Default::default(),
+                    },
+                })],
+            };
+
+            System::reset_events();
+            System::initialize(&block_number, &parent_hash, &pre_digest);
+            Subspace::on_initialize(block_number);
+
+            if chain_parameters().enable_solution_range_adjustment_at == Some(block_number) {
+                Subspace::enable_solution_range_adjustment(RuntimeOrigin::root(), None, None)
+                    .map_err(|error| {
+                        anyhow!("Failed to enable solution range adjustment: {error:?}")
+                    })?;
+            }
+
+            let solution_ranges = Subspace::solution_ranges();
+
+            Ok((solution_ranges.current, solution_ranges.voting_current))
+        })
+    }
+
+    /// Space pledged to the network, in bytes, that corresponds to provided solution range
+    pub(crate) fn solution_range_to_space(&mut self, solution_range: SolutionRange) -> u128 {
+        self.externalities
+            .execute_with(|| Subspace::solution_range_to_space(solution_range))
+    }
+}
@@ -0,0 +1,153 @@
+//! Description of the simulated chain and farmer population.
+
+use anyhow::anyhow;
+use serde::Deserialize;
+use std::fs;
+use std::path::Path;
+use subspace_core_primitives::{sectors_to_solution_range, SolutionRange};
+
+/// Chain parameters that affect solution range adjustment, defaults match the Subspace runtime
+#[derive(Debug, Copy, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", default, deny_unknown_fields)]
+pub(crate) struct ChainParameters {
+    /// Duration of one era in blocks
+    pub(crate) era_duration: u64,
+    /// How often blocks are expected to be produced on average, (numerator, denominator)
+    pub(crate) slot_probability: (u64, u64),
+    /// Expected number of votes per block on average
+    pub(crate) expected_votes_per_block: u32,
+    /// Max number of pieces in a sector
+    pub(crate) max_pieces_in_sector: u16,
+    /// Duration of one slot in milliseconds
+    pub(crate) slot_duration_ms: u64,
+    /// Solution range of the first era, defaults to solution range of a single sector
+    pub(crate) initial_solution_range: Option<SolutionRange>,
+    /// Block at which solution range adjustment is enabled by sudo, `0` means it is enabled in
+    /// genesis, never enabled if not specified
+    pub(crate) enable_solution_range_adjustment_at: Option<u64>,
+}
+
+impl Default for ChainParameters {
+    fn default() -> Self {
+        Self {
+            era_duration: 2016,
+            slot_probability: (1, 6),
+            expected_votes_per_block: 9,
+            max_pieces_in_sector: 1000,
+            slot_duration_ms: 1000,
+            initial_solution_range: None,
+            enable_solution_range_adjustment_at: None,
+        }
+    }
+}
+
+impl ChainParameters {
+    /// Solution range of the first era
+    pub(crate) fn initial_solution_range(&self) -> SolutionRange {
+        self.initial_solution_range.unwrap_or_else(|| {
+            sectors_to_solution_range(1, self.slot_probability, self.max_pieces_in_sector)
+        })
+    }
+}
+
+/// Group of farmers that pledge space to the network together
+#[derive(Debug, Copy, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub(crate) struct FarmerGroup {
+    /// Number of sectors pledged by the group once fully plotted
+    pub(crate) sectors: u64,
+    /// Slot at which the group starts plotting
+    #[serde(default)]
+    pub(crate) join_slot: u64,
+    /// Number of slots it takes to plot all sectors, sectors are added linearly during this time
+    #[serde(default)]
+    pub(crate) plotting_slots: u64,
+    /// Slot at which the group stops farming
+    #[serde(default)]
+    pub(crate) leave_slot: Option<u64>,
+}
+
+impl FarmerGroup {
+    /// Number of sectors the group farms at specified slot
+    pub(crate) fn sectors_at(&self, slot: u64) -> u64 {
+        if slot < self.join_slot || self.leave_slot.is_some_and(|leave_slot| slot >= leave_slot) {
+            return 0;
+        }
+
+        let plotted_slots = slot - self.join_slot;
+        if plotted_slots >= self.plotting_slots {
+            self.sectors
+        } else {
+            (u128::from(self.sectors) * u128::from(plotted_slots) / u128::from(self.plotting_slots))
+                as u64
+        }
+    }
+}
+
+/// Simulation scenario
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub(crate) struct Scenario {
+    /// Chain parameters
+    #[serde(default)]
+    pub(crate) chain: ChainParameters,
+    /// Number of slots to simulate
+    pub(crate) slots: u64,
+    /// Farmer population
+    pub(crate) farmers: Vec<FarmerGroup>,
+}
+
+impl Scenario {
+    /// Read scenario from JSON file
+    pub(crate) fn from_file(path: &Path) -> anyhow::Result<Self> {
+        let scenario = serde_json::from_slice::<Self>(&fs::read(path)?)
+            .map_err(|error| anyhow!("Failed to parse scenario {}: {error}", path.display()))?;
+        scenario.validate()?;
+
+        Ok(scenario)
+    }
+
+    /// Check that scenario can be simulated
+    pub(crate) fn validate(&self) -> anyhow::Result<()> {
+        let chain = &self.chain;
+
+        if chain.era_duration == 0 {
+            return Err(anyhow!("Era duration must not be zero"));
+        }
+        if chain.slot_probability.0 == 0 || chain.slot_probability.0 > chain.slot_probability.1 {
+            return Err(anyhow!(
+                "Slot probability {:?} must be within (0, 1]",
+                chain.slot_probability
+            ));
+        }
+        if chain.max_pieces_in_sector == 0 {
+            return Err(anyhow!("Max pieces in sector must not be zero"));
+        }
+        if chain.initial_solution_range == Some(0) {
+            return Err(anyhow!("Initial solution range must not be zero"));
+        }
+        if self.slots == 0 {
+            return Err(anyhow!("At least one slot must be simulated"));
+        }
+        for (index, farmer_group) in self.farmers.iter().enumerate() {
+            if let Some(leave_slot) = farmer_group.leave_slot {
+                if leave_slot <= farmer_group.join_slot {
+                    return Err(anyhow!(
+                        "Farmer group {index} leaves at slot {leave_slot} before joining at slot {}",
+                        farmer_group.join_slot
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Total number of sectors farmed at specified slot
+    pub(crate) fn sectors_at(&self, slot: u64) -> u64 {
+        self.farmers
+            .iter()
+            .map(|farmer_group| farmer_group.sectors_at(slot))
+            .sum()
+    }
+}
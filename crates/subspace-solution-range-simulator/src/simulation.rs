@@ -0,0 +1,168 @@
+//! Slot by slot simulation of block and vote production.
+//!
+//! Farmers are not simulated individually. Instead, the number of solutions in each slot is
+//! sampled from the Poisson distribution whose rate follows from pledged space and current
+//! solution ranges, while solution ranges themselves are adjusted by `pallet-subspace`.
+//!
+//! Votes are modelled outside of the pallet: they are sampled using voting solution range of the
+//! pallet, but never submitted to it. Votes in the pallet only affect rewards and not solution
+//! range adjustment, while submitting them would require real solutions that pass verification.
+
+#[cfg(test)]
+mod tests;
+
+use crate::runtime::SimulatedChain;
+use crate::scenario::Scenario;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::f64::consts::PI;
+use subspace_core_primitives::{audited_chunks_per_sector, Piece, SolutionRange};
+use tracing::info;
+
+/// Above this rate Poisson distribution is approximated with normal distribution
+const POISSON_NORMAL_APPROXIMATION_THRESHOLD: f64 = 30.0;
+
+/// State of the chain after block import
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct BlockRecord {
+    /// Block number
+    pub(crate) block_number: u64,
+    /// Slot at which block was produced
+    pub(crate) slot: u64,
+    /// Time since genesis in milliseconds
+    pub(crate) time_ms: u64,
+    /// Time since parent block in milliseconds
+    pub(crate) block_time_ms: u64,
+    /// Solution range of the block
+    pub(crate) solution_range: SolutionRange,
+    /// Voting solution range of the block
+    pub(crate) voting_solution_range: SolutionRange,
+    /// Number of votes produced since parent block, sampled outside of `pallet-subspace`
+    pub(crate) votes: u64,
+    /// Space actually pledged to the network, in bytes
+    pub(crate) pledged_space: u128,
+    /// Space pledged to the network estimated from solution range, in bytes
+    pub(crate) estimated_space: u128,
+}
+
+impl BlockRecord {
+    /// Header of CSV output
+    pub(crate) const CSV_HEADER: &'static str = "block_number,slot,time_ms,block_time_ms,\
+        solution_range,voting_solution_range,votes,pledged_space,estimated_space";
+
+    /// Format record as a CSV line
+    pub(crate) fn to_csv(&self) -> String {
+        format!(
+            "{},{},{},{},{},{},{},{},{}",
+            self.block_number,
+            self.slot,
+            self.time_ms,
+            self.block_time_ms,
+            self.solution_range,
+            self.voting_solution_range,
+            self.votes,
+            self.pledged_space,
+            self.estimated_space,
+        )
+    }
+}
+
+/// Run simulation of the scenario, calling `on_block` for every produced block
+pub(crate) fn simulate<F>(scenario: &Scenario, seed: u64, mut on_block: F) -> anyhow::Result<()>
+where
+    F: FnMut(BlockRecord) -> anyhow::Result<()>,
+{
+    let chain_parameters = scenario.chain;
+    let chunks_per_sector = audited_chunks_per_sector(chain_parameters.max_pieces_in_sector);
+    let sector_size = u128::from(chain_parameters.max_pieces_in_sector) * Piece::SIZE as u128;
+
+    let mut chain = SimulatedChain::new(chain_parameters)?;
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+
+    let mut block_number = 0;
+    let mut parent_slot = 0;
+    let mut pending_votes = 0;
+
+    // Slot 0 is reserved for genesis
+    for slot in 1..=scenario.slots {
+        let audited_chunks = scenario.sectors_at(slot).saturating_mul(chunks_per_sector) as f64;
+        let (solution_range, voting_solution_range) = chain.next_solution_ranges();
+
+        // Probability of a chunk to be within solution range is `solution_range / MAX`
+        let block_rate = audited_chunks * solution_range as f64 / SolutionRange::MAX as f64;
+        // Block solutions are also valid votes, but they are used for block production instead
+        let vote_rate = audited_chunks
+            * voting_solution_range.saturating_sub(solution_range) as f64
+            / SolutionRange::MAX as f64;
+
+        pending_votes += sample_poisson(&mut rng, vote_rate);
+
+        if sample_poisson(&mut rng, block_rate) == 0 {
+            continue;
+        }
+
+        block_number += 1;
+        let (solution_range, voting_solution_range) =
+            chain.import_block(block_number, slot.into())?;
+
+        let block_record = BlockRecord {
+            block_number,
+            slot,
+            time_ms: slot * chain_parameters.slot_duration_ms,
+            block_time_ms: (slot - parent_slot) * chain_parameters.slot_duration_ms,
+            solution_range,
+            voting_solution_range,
+            votes: pending_votes,
+            pledged_space: u128::from(scenario.sectors_at(slot)) * sector_size,
+            estimated_space: chain.solution_range_to_space(solution_range),
+        };
+
+        if block_number % chain_parameters.era_duration == 0 {
+            info!(
+                %block_number,
+                %slot,
+                %solution_range,
+                pledged_space = %block_record.pledged_space,
+                estimated_space = %block_record.estimated_space,
+                "Era finished"
+            );
+        }
+
+        on_block(block_record)?;
+
+        parent_slot = slot;
+        pending_votes = 0;
+    }
+
+    Ok(())
+}
+
+/// Sample a value from Poisson distribution with provided rate
+fn sample_poisson<R>(rng: &mut R, rate: f64) -> u64
+where
+    R: Rng,
+{
+    if rate <= 0.0 {
+        return 0;
+    }
+
+    if rate >= POISSON_NORMAL_APPROXIMATION_THRESHOLD {
+        // Box-Muller transform
+        let u1 = 1.0 - rng.gen::<f64>();
+        let u2 = rng.gen::<f64>();
+        let z = (-2.0 * u1.ln()).sqrt() * (2.0 * PI * u2).cos();
+
+        return (rate + rate.sqrt() * z).round().max(0.0) as u64;
+    }
+
+    // Knuth's algorithm
+    let limit = (-rate).exp();
+    let mut count = 0;
+    let mut product = rng.gen::<f64>();
+    while product > limit {
+        count += 1;
+        product *= rng.gen::<f64>();
+    }
+
+    count
+}
@@ -0,0 +1,96 @@
+use crate::scenario::{ChainParameters, FarmerGroup, Scenario};
+use crate::simulation::{sample_poisson, simulate, BlockRecord};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+const ERA_DURATION: u64 = 100;
+
+fn scenario(enable_solution_range_adjustment_at: Option<u64>) -> Scenario {
+    Scenario {
+        chain: ChainParameters {
+            era_duration: ERA_DURATION,
+            enable_solution_range_adjustment_at,
+            ..ChainParameters::default()
+        },
+        slots: 20_000,
+        farmers: vec![FarmerGroup {
+            sectors: 1_000,
+            join_slot: 0,
+            plotting_slots: 0,
+            leave_slot: None,
+        }],
+    }
+}
+
+fn run(scenario: &Scenario) -> Vec<BlockRecord> {
+    let mut blocks = Vec::new();
+    simulate(scenario, 0, |block_record| {
+        blocks.push(block_record);
+        Ok(())
+    })
+    .unwrap();
+
+    blocks
+}
+
+#[test]
+fn poisson_sampling_mean() {
+    let mut rng = ChaCha8Rng::seed_from_u64(0);
+    let samples = 10_000;
+
+    for rate in [0.0, 0.5, 5.0, 100.0] {
+        let mean = (0..samples)
+            .map(|_| sample_poisson(&mut rng, rate))
+            .sum::<u64>() as f64
+            / samples as f64;
+
+        assert!(
+            (mean - rate).abs() <= rate * 0.05 + 0.01,
+            "Mean {mean} is too far from rate {rate}"
+        );
+    }
+}
+
+#[test]
+fn solution_range_is_constant_without_adjustment() {
+    let scenario = scenario(None);
+    let blocks = run(&scenario);
+
+    let initial_solution_range = scenario.chain.initial_solution_range();
+    assert!(!blocks.is_empty());
+    assert!(blocks
+        .iter()
+        .all(|block_record| block_record.solution_range == initial_solution_range));
+}
+
+#[test]
+fn solution_range_converges_to_pledged_space() {
+    let scenario = scenario(Some(0));
+    let blocks = run(&scenario);
+
+    let last_era = &blocks[blocks.len() - ERA_DURATION as usize..];
+    let average_block_time_ms = last_era
+        .iter()
+        .map(|block_record| block_record.block_time_ms)
+        .sum::<u64>()
+        / ERA_DURATION;
+    let (slot_probability_numerator, slot_probability_denominator) =
+        scenario.chain.slot_probability;
+    let expected_block_time_ms =
+        scenario.chain.slot_duration_ms * slot_probability_denominator / slot_probability_numerator;
+    assert!(
+        average_block_time_ms.abs_diff(expected_block_time_ms) <= expected_block_time_ms / 4,
+        "Average block time {average_block_time_ms}ms is too far from {expected_block_time_ms}ms"
+    );
+
+    let last_block = blocks.last().unwrap();
+    assert!(
+        last_block
+            .estimated_space
+            .abs_diff(last_block.pledged_space)
+            <= last_block.pledged_space / 4,
+        "Estimated space {} is too far from pledged space {}",
+        last_block.estimated_space,
+        last_block.pledged_space
+    );
+}
@@ -39,7 +39,7 @@ use subspace_core_primitives::{
 use subspace_proof_of_space::Table;
 
 /// Errors encountered by the Subspace consensus primitives.
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Clone, Eq, PartialEq)]
 #[cfg_attr(feature = "thiserror", derive(thiserror::Error))]
 pub enum Error {
     /// Invalid piece offset
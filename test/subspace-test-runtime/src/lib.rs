@@ -47,8 +47,8 @@ use scale_info::TypeInfo;
 use sp_api::impl_runtime_apis;
 use sp_consensus_slots::{Slot, SlotDuration};
 use sp_consensus_subspace::{
-    ChainConstants, EquivocationProof, FarmerPublicKey, PotParameters, SignedVote, SolutionRanges,
-    Vote,
+    ChainConstants, EquivocationProof, FarmerPublicKey, PotEntropyInjectionParameters,
+    PotParameters, SignedVote, SolutionRangeHistory, SolutionRanges, Vote,
 };
 use sp_core::crypto::{ByteArray, KeyTypeId};
 use sp_core::{OpaqueMetadata, H256};
@@ -112,7 +112,7 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
     //   `spec_version`, and `authoring_version` are the same between Wasm and native.
     // This value is set to 100 to notify Polkadot-JS App (https://polkadot.js.org/apps) to use
     //   the compatible custom types.
-    spec_version: 100,
+    spec_version: 101,
     impl_version: 1,
     apis: RUNTIME_API_VERSIONS,
     transaction_version: 1,
@@ -175,6 +175,9 @@ const_assert!(POT_ENTROPY_INJECTION_DELAY > BLOCK_AUTHORING_DELAY + 1);
 /// Era duration in blocks.
 const ERA_DURATION_IN_BLOCKS: BlockNumber = 2016;
 
+/// Number of most recent eras for which solution ranges are kept in history.
+const SOLUTION_RANGE_HISTORY_LENGTH: u32 = 256;
+
 const EQUIVOCATION_REPORT_LONGEVITY: BlockNumber = 256;
 
 /// Any solution range is valid in the test environment.
@@ -291,6 +294,7 @@ impl pallet_subspace::Config for Runtime {
     type ExpectedVotesPerBlock = ExpectedVotesPerBlock;
     type MaxPiecesInSector = ConstU16<{ MAX_PIECES_IN_SECTOR }>;
     type ShouldAdjustSolutionRange = ShouldAdjustSolutionRange;
+    type SolutionRangeHistoryLength = ConstU32<SOLUTION_RANGE_HISTORY_LENGTH>;
     type EraChangeTrigger = pallet_subspace::NormalEraChange;
 
     type HandleEquivocation = pallet_subspace::equivocation::EquivocationHandler<
@@ -1192,6 +1196,18 @@ impl_runtime_apis! {
                 min_sector_lifetime: MinSectorLifetime::get(),
             }
         }
+
+        fn solution_range_history() -> SolutionRangeHistory<BlockNumber> {
+            Subspace::solution_range_history()
+        }
+
+        fn pot_entropy_injection_parameters() -> PotEntropyInjectionParameters<BlockNumber> {
+            PotEntropyInjectionParameters::V0 {
+                interval: PotEntropyInjectionInterval::get(),
+                lookback_depth: PotEntropyInjectionLookbackDepth::get(),
+                delay: Slot::from(PotEntropyInjectionDelay::get()),
+            }
+        }
     }
 
     impl sp_domains::DomainsApi<Block, DomainHeader> for Runtime {